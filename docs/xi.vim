@@ -38,23 +38,24 @@ JavaHiLink javaError2 javaError
 " keyword definitions
 syn keyword javaExternal	native package
 syn match javaExternal		"\<import\>\(\s\+static\>\)\?"
-syn keyword javaConditional	if else
-syn keyword javaRepeat		do while
+syn keyword javaConditional	if else match
+syn keyword javaRepeat		do while for
 syn keyword javaBoolean		true false
 syn keyword javaConstant	null
 syn keyword javaTypedef		this super
-syn keyword javaOperator	new
-syn keyword javaType		bool int
+syn keyword javaOperator	new instanceof as
+syn keyword javaType		bool int fn
 syn keyword javaType		void
 syn keyword javaStatement	return use
-syn keyword javaClassDecl	extends interface template final
+syn keyword javaExceptions	throw try catch finally
+syn keyword javaClassDecl	extends implements interface template final enum
 " to differentiate the keyword class from MyClass.class we use a match here
 syn match   javaTypedef		"\.\s*\<class\>"ms=s+1
 syn match   javaClassDecl	"^class\>"
 syn match   javaClassDecl	"[^.]\s*\<class\>"ms=s+1
 syn match   javaAnnotation      "@[_$a-zA-Z][_$a-zA-Z0-9_]*\>"
 syn match   javaClassDecl       "@interface\>"
-syn keyword javaBranch		break nextgroup=javaUserLabelRef skipwhite
+syn keyword javaBranch		break continue nextgroup=javaUserLabelRef skipwhite
 syn match   javaUserLabelRef	"\k\+" contained
 syn match   javaVarArg		"\.\.\."
 syn keyword javaScopeDecl	public private abstract
@@ -44,6 +44,16 @@ void XI_BUILTIN(out_of_bounds)(void) {
     abort();
 }
 
+void XI_BUILTIN(bad_cast)(void) {
+    fprintf(stderr, "Object is not an instance of the target class\n");
+    abort();
+}
+
+void XI_BUILTIN(uncaught)(void* exception) {
+    fprintf(stderr, "Uncaught exception %p\n", exception);
+    abort();
+}
+
 // Internal helper for making arrays
 static void* mkArray(int bytes, int cells) {
     xiint *memory = XI_BUILTIN(alloc)(bytes + sizeof(xiint));
@@ -31,13 +31,16 @@
 //! ```
 
 pub mod class;
+pub mod demangle;
 pub mod mangle;
 
+use crate::data::asm;
 use crate::data::operand::Immediate;
 use crate::data::operand::Memory;
 use crate::data::operand::Register;
 use crate::data::operand::Temporary;
 use crate::data::operand::Unary;
+use crate::data::token::Size;
 
 pub const WORD: i64 = 8;
 
@@ -46,8 +49,15 @@ pub const XI_INIT_CLASSES: &str = "_Iinit_classes";
 pub const XI_MAIN: &str = "_Imain_paai";
 pub const XI_ALLOC: &str = "_xi_alloc";
 pub const XI_OUT_OF_BOUNDS: &str = "_xi_out_of_bounds";
+pub const XI_BAD_CAST: &str = "_xi_bad_cast";
 pub const XI_CONCAT: &str = "_xi_concat";
+pub const XI_CONCAT_8: &str = "_xi_concat_8";
+pub const XI_CONCAT_16: &str = "_xi_concat_16";
+pub const XI_CONCAT_32: &str = "_xi_concat_32";
 pub const XI_MEMDUP: &str = "_xi_memdup";
+pub const XI_INTERFACE_LOOKUP: &str = "_xi_interface_lookup";
+pub const XI_INSTANCEOF: &str = "_xi_instanceof";
+pub const XI_IMPLEMENTS: &str = "_xi_implements";
 pub const XI_PRINT: &str = "_Iprint_pai";
 pub const XI_PRINTLN: &str = "_Iprintln_pai";
 pub const XI_READLN: &str = "_Ireadln_ai";
@@ -57,6 +67,30 @@ pub const XI_UNPARSE_INT: &str = "_IunparseInt_aii";
 pub const XI_PARSE_INT: &str = "_IparseInt_t2ibai";
 pub const XI_ASSERT: &str = "_Iassert_pb";
 
+// Exceptions are propagated by storing the thrown instance in this word, which
+// callers check after every call that might throw returns. An uncaught exception is passed to
+// `XI_UNCAUGHT`, which aborts the program.
+pub const XI_EXCEPTION: &str = "_xi_exception";
+pub const XI_UNCAUGHT: &str = "_xi_uncaught";
+
+/// Built-in base class of every class that can be thrown
+pub const EXCEPTION: &str = "Exception";
+
+/// Built-in exception class thrown by out-of-bounds array accesses
+pub const OUT_OF_BOUNDS: &str = "OutOfBounds";
+
+// Packed array elements narrower than a word are accessed through these
+// intrinsics, which are inlined during tiling and never actually called.
+pub const XI_LOAD_I8: &str = "_xi_load_i8";
+pub const XI_LOAD_U8: &str = "_xi_load_u8";
+pub const XI_LOAD_I16: &str = "_xi_load_i16";
+pub const XI_LOAD_U16: &str = "_xi_load_u16";
+pub const XI_LOAD_I32: &str = "_xi_load_i32";
+pub const XI_LOAD_U32: &str = "_xi_load_u32";
+pub const XI_STORE_8: &str = "_xi_store_8";
+pub const XI_STORE_16: &str = "_xi_store_16";
+pub const XI_STORE_32: &str = "_xi_store_32";
+
 pub const CALLEE_SAVED: &[Register] = &[
     Register::rsp(),
     Register::Rbx,
@@ -109,6 +143,76 @@ pub enum FramePointer {
     Omit,
 }
 
+/// Intrinsic taking an address and returning the sign- or zero-extended
+/// narrow integer stored there.
+pub fn load(signed: bool, size: Size) -> &'static str {
+    match (signed, size) {
+        (true, Size::_8) => XI_LOAD_I8,
+        (false, Size::_8) => XI_LOAD_U8,
+        (true, Size::_16) => XI_LOAD_I16,
+        (false, Size::_16) => XI_LOAD_U16,
+        (true, Size::_32) => XI_LOAD_I32,
+        (false, Size::_32) => XI_LOAD_U32,
+        (_, Size::_64) => panic!("[INTERNAL ERROR]: no intrinsic for 64-bit load"),
+    }
+}
+
+/// Intrinsic taking an address and a value, and storing the truncated value there.
+pub fn store(size: Size) -> &'static str {
+    match size {
+        Size::_8 => XI_STORE_8,
+        Size::_16 => XI_STORE_16,
+        Size::_32 => XI_STORE_32,
+        Size::_64 => panic!("[INTERNAL ERROR]: no intrinsic for 64-bit store"),
+    }
+}
+
+/// Library function for concatenating arrays with elements of the given width.
+pub fn concat(size: Size) -> &'static str {
+    match size {
+        Size::_8 => XI_CONCAT_8,
+        Size::_16 => XI_CONCAT_16,
+        Size::_32 => XI_CONCAT_32,
+        Size::_64 => XI_CONCAT,
+    }
+}
+
+/// Assembly instruction that an intrinsic should be inlined as.
+pub fn intrinsic(name: &str) -> Option<asm::Binary> {
+    let binary = match name {
+        XI_LOAD_I8 => asm::Binary::Load {
+            signed: true,
+            size: Size::_8,
+        },
+        XI_LOAD_U8 => asm::Binary::Load {
+            signed: false,
+            size: Size::_8,
+        },
+        XI_LOAD_I16 => asm::Binary::Load {
+            signed: true,
+            size: Size::_16,
+        },
+        XI_LOAD_U16 => asm::Binary::Load {
+            signed: false,
+            size: Size::_16,
+        },
+        XI_LOAD_I32 => asm::Binary::Load {
+            signed: true,
+            size: Size::_32,
+        },
+        XI_LOAD_U32 => asm::Binary::Load {
+            signed: false,
+            size: Size::_32,
+        },
+        XI_STORE_8 => asm::Binary::Store(Size::_8),
+        XI_STORE_16 => asm::Binary::Store(Size::_16),
+        XI_STORE_32 => asm::Binary::Store(Size::_32),
+        _ => return None,
+    };
+
+    Some(binary)
+}
+
 /// Total stack size. Guaranteed to align to 16 bytes if there is a function call.
 pub fn stack_size(
     frame_pointer: FramePointer,
@@ -54,6 +54,39 @@ use crate::Set;
 /// Here, invoking methods on `super` requires there to be a virtual table.
 /// But we can still omit any methods that aren't defined in superclass(es)
 /// from the virtual table and dispatch those statically at type `Class`.
+///
+/// The private slot of the root class points at the class's type descriptor, which
+/// is used to test the class of an instance at runtime and to call methods through
+/// interfaces:
+///
+/// ```text
+///             +---------------+    +-------------------------+    +------------------------+
+/// instance -> | virtual table | -> | root class private slot | -> | superclass descriptor  |
+///             |---------------|    |-------------------------|    |------------------------|
+///             |       .       |    |            .            |    | number of interfaces   |
+///             |       .       |    |            .            |    |------------------------|
+///             |       .       |    |            .            |    | interface identifier   |
+///             +---------------+    +-------------------------+    | interface method table | -+
+///                                                                 |           .            |  |
+///                                                                 |           .            |  |
+///                                                                 |           .            |  |
+///                                                                 |------------------------|  |
+///                                                                 | interface methods      | <+
+///                                                                 |           .            |
+///                                                                 |           .            |
+///                                                                 |           .            |
+///                                                                 +------------------------+
+/// ```
+///
+/// Following the chain of superclass descriptors (terminated by a null pointer) tests
+/// whether an instance belongs to a class. Each interface is identified by the address
+/// of a unique symbol, so calling a method through an interface searches the table for
+/// the identifier, and then indexes the corresponding method table in declaration order
+/// (see `protocol_method_index`).
+///
+/// Classes that implement interfaces, directly or through their superclasses, always keep
+/// their virtual table, even if they are `final`, since the interface table is only
+/// reachable through it.
 pub struct Layout {
     /// First interface-only class in this class's inheritance hierarchy.
     ///
@@ -69,6 +102,13 @@ pub struct Layout {
     /// `Some(size)` of this class's virtual table in words, or `None` if
     /// it does not need a virtual table.
     slots: Option<usize>,
+
+    /// Map from interfaces implemented by this class or its ancestors
+    /// to the offset of their method tables in the type descriptor.
+    protocols: Map<Symbol, usize>,
+
+    /// Size of this class's type descriptor in words.
+    descriptor_slots: usize,
 }
 
 impl Layout {
@@ -131,16 +171,31 @@ impl Layout {
             }
         }
 
+        let implements = context.get_implements_inclusive(class);
+        let mut protocols = Map::default();
+        let mut descriptor_slots = 2 + implements.len() * 2;
+
+        for protocol in implements {
+            let methods = context
+                .get_class(&protocol)
+                .expect("[INTERNAL ERROR]: unbound interface")
+                .len();
+            protocols.insert(protocol, descriptor_slots);
+            descriptor_slots += methods;
+        }
+
         Self {
             interface,
             fields,
             methods,
-            slots: if r#final && slots == 1 {
+            slots: if r#final && slots == 1 && protocols.is_empty() {
                 log::debug!("Omitted virtual table for class {}", class);
                 None
             } else {
                 Some(slots)
             },
+            protocols,
+            descriptor_slots,
         }
     }
 
@@ -188,4 +243,28 @@ impl Layout {
     pub fn virtual_table_len(&self) -> Option<usize> {
         self.slots
     }
+
+    /// Interfaces implemented by this class or its ancestors, and the offset
+    /// of their method tables in the type descriptor.
+    pub fn protocols(&self) -> impl Iterator<Item = (&Symbol, &usize)> + '_ {
+        self.protocols.iter()
+    }
+
+    /// `Some(size)` of type descriptor in words, or `None` if this class
+    /// does not need a virtual table to reach it from.
+    pub fn type_descriptor_len(&self) -> Option<usize> {
+        self.slots.map(|_| self.descriptor_slots)
+    }
+}
+
+/// Index of `method` in the method table of interface `protocol`.
+pub fn protocol_method_index(
+    context: &Context,
+    protocol: &Symbol,
+    method: &Symbol,
+) -> Option<usize> {
+    context
+        .get_class(protocol)?
+        .iter()
+        .position(|(identifier, _)| identifier.symbol == *method)
 }
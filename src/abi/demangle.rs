@@ -0,0 +1,405 @@
+//! Inverts every mangling scheme in `abi::mangle`, so that symbols can be
+//! reported in terms of Xi source.
+//!
+//! Mangling escapes both `'` and `_` in identifiers as `__`, so primes are
+//! demangled as underscores.
+
+use std::fmt::Write as _;
+
+use crate::abi;
+use crate::data::r#type;
+use crate::data::symbol;
+use crate::data::token::Size;
+
+/// Demangles `symbol` into a readable description, or returns `None` if it
+/// wasn't produced by `abi::mangle`. For example:
+///
+/// ```text
+/// _IparseInt_t2ibai       parseInt(int[]): int, bool
+/// _I_Point_move_pii       Point.move(int, int)
+/// _I_global_count_i       count: int
+/// _It8identity1i_ii       identity::<int>(int): int
+/// _I_vt_Point             vtable for Point
+/// _I_closure0__Imain_paai closure 0 in main(int[][])
+/// ```
+pub fn demangle(symbol: &str) -> Option<String> {
+    match symbol {
+        abi::XI_INIT_GLOBALS => return Some(String::from("initializer for globals")),
+        abi::XI_INIT_CLASSES => return Some(String::from("initializer for classes")),
+        _ => (),
+    }
+
+    let mangled = symbol.strip_prefix("_I")?;
+
+    match mangled.strip_prefix('_') {
+        None => function(None, mangled),
+        Some(mangled) => metadata(mangled)
+            .or_else(|| global(mangled))
+            .or_else(|| closure(mangled))
+            .or_else(|| global_initialization(mangled))
+            .or_else(|| {
+                let (class, mangled) = identifier(mangled)?;
+                function(Some(&name(&class)), mangled)
+            }),
+    }
+}
+
+/// Replaces every demangleable symbol in `text`, such as the output of `nm`,
+/// `objdump`, or a linker, leaving everything else untouched.
+pub fn demangle_text(text: &str) -> String {
+    let mut filtered = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("_I") {
+        // Symbols must begin at a word boundary
+        let boundary = !rest[..start].ends_with(is_symbol);
+
+        let end = rest[start..]
+            .find(|character| !is_symbol(character))
+            .map_or(rest.len(), |end| start + end);
+
+        filtered.push_str(&rest[..start]);
+
+        match demangle(&rest[start..end]).filter(|_| boundary) {
+            Some(demangled) => filtered.push_str(&demangled),
+            None => filtered.push_str(&rest[start..end]),
+        }
+
+        rest = &rest[end..];
+    }
+
+    filtered.push_str(rest);
+    filtered
+}
+
+fn is_symbol(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_'
+}
+
+/// Demangles `<name>_<signature>`, where `name` may be a template instantiation.
+fn function(class: Option<&str>, mangled: &str) -> Option<String> {
+    let (function, mangled) = identifier(mangled)?;
+    let mut demangler = Demangler { mangled };
+    let (parameters, returns) = demangler.signature(None)?;
+    demangler.finish()?;
+
+    let mut demangled = String::new();
+
+    if let Some(class) = class {
+        write!(demangled, "{}.", class).unwrap();
+    }
+
+    write!(demangled, "{}(", name(&function)).unwrap();
+
+    for (index, parameter) in parameters.iter().enumerate() {
+        if index > 0 {
+            demangled.push_str(", ");
+        }
+        write!(demangled, "{}", parameter).unwrap();
+    }
+
+    demangled.push(')');
+
+    for (index, r#return) in returns.iter().enumerate() {
+        let separator = if index == 0 { ": " } else { ", " };
+        write!(demangled, "{}{}", separator, r#return).unwrap();
+    }
+
+    Some(demangled)
+}
+
+/// Demangles class and interface metadata, such as `vt_Point`.
+fn metadata(mangled: &str) -> Option<String> {
+    let (kind, class) = [
+        ("size_", "size for"),
+        ("vt_", "vtable for"),
+        ("td_", "type descriptor for"),
+        ("iid_", "interface identifier for"),
+        ("init_", "initializer for"),
+    ]
+    .into_iter()
+    .find_map(|(prefix, kind)| Some((kind, mangled.strip_prefix(prefix)?)))?;
+
+    Some(format!("{} {}", kind, name(&unescape(class)?)))
+}
+
+/// Demangles `global_<name>_<type>`.
+fn global(mangled: &str) -> Option<String> {
+    let (global, mangled) = identifier(mangled.strip_prefix("global_")?)?;
+    let mut demangler = Demangler { mangled };
+    let r#type = demangler.r#type()?;
+    demangler.finish()?;
+    Some(format!("{}: {}", global, r#type))
+}
+
+/// Demangles `closure<index>_<function>` and `closure_<function>`, where
+/// `function` is the mangled name of the enclosing or adapted function.
+fn closure(mangled: &str) -> Option<String> {
+    let mangled = mangled.strip_prefix("closure")?;
+    let end = mangled.find(|character: char| !character.is_ascii_digit())?;
+    let function = mangled[end..].strip_prefix('_')?;
+    let function = demangle(function).unwrap_or_else(|| String::from(function));
+
+    match &mangled[..end] {
+        "" => Some(format!("closure adapter for {}", function)),
+        index => Some(format!("closure {} in {}", index, function)),
+    }
+}
+
+/// Demangles `init_global` followed by `_<name>_<type>` for each global
+/// declared by the same statement.
+fn global_initialization(mangled: &str) -> Option<String> {
+    let mut demangler = Demangler {
+        mangled: mangled.strip_prefix("init_global")?,
+    };
+    let mut globals = Vec::new();
+
+    while let Some(mangled) = demangler.mangled.strip_prefix('_') {
+        let (global, mangled) = identifier(mangled)?;
+        demangler.mangled = mangled;
+        globals.push(format!("{}: {}", global, demangler.r#type()?));
+    }
+
+    demangler.finish()?;
+
+    match globals.is_empty() {
+        true => None,
+        false => Some(format!("initializer for {}", globals.join(", "))),
+    }
+}
+
+/// Renders an unescaped name, which may be a mangled template instantiation.
+fn name(name: &str) -> String {
+    let mut demangler = Demangler { mangled: name };
+    demangler
+        .template()
+        .filter(|_| demangler.mangled.is_empty())
+        .unwrap_or_else(|| String::from(name))
+}
+
+/// Splits an escaped identifier from the `_` that terminates it.
+fn identifier(mangled: &str) -> Option<(String, &str)> {
+    let mut identifier = String::new();
+    let mut characters = mangled.char_indices().peekable();
+
+    while let Some((index, character)) = characters.next() {
+        match character {
+            '_' if characters.peek().map(|(_, next)| *next) == Some('_') => {
+                characters.next();
+                identifier.push('_');
+            }
+            '_' if identifier.is_empty() => return None,
+            '_' => return Some((identifier, &mangled[index + 1..])),
+            _ => identifier.push(character),
+        }
+    }
+
+    None
+}
+
+/// Unescapes an entire identifier, which must not contain a lone `_`.
+fn unescape(mangled: &str) -> Option<String> {
+    match identifier(&format!("{}_", mangled)) {
+        Some((identifier, "")) => Some(identifier),
+        _ => None,
+    }
+}
+
+struct Demangler<'a> {
+    mangled: &'a str,
+}
+
+impl<'a> Demangler<'a> {
+    fn finish(&self) -> Option<()> {
+        self.mangled.is_empty().then_some(())
+    }
+
+    /// Parses return types, followed by `parameters` parameter types, or every
+    /// remaining type if `parameters` is `None`.
+    #[allow(clippy::type_complexity)]
+    fn signature(
+        &mut self,
+        parameters: Option<usize>,
+    ) -> Option<(Vec<r#type::Expression>, Vec<r#type::Expression>)> {
+        let returns = match self.peek()? {
+            'p' => {
+                self.next();
+                Vec::new()
+            }
+            't' => {
+                self.next();
+                let count = self.number()?;
+                self.types(count, Self::r#type)?
+            }
+            _ => vec![self.r#type()?],
+        };
+
+        let parameters = match parameters {
+            Some(count) => self.types(count, Self::r#type)?,
+            None => {
+                let mut parameters = Vec::new();
+                while !self.mangled.is_empty() {
+                    parameters.push(self.r#type()?);
+                }
+                parameters
+            }
+        };
+
+        Some((parameters, returns))
+    }
+
+    fn types(
+        &mut self,
+        count: usize,
+        mut r#type: impl FnMut(&mut Self) -> Option<r#type::Expression>,
+    ) -> Option<Vec<r#type::Expression>> {
+        (0..count).map(|_| r#type(self)).collect()
+    }
+
+    /// Parses a type as mangled by `mangle_type`, where class names are escaped.
+    fn r#type(&mut self) -> Option<r#type::Expression> {
+        match self.next()? {
+            'i' => Some(self.integer(true)),
+            'u' => Some(self.integer(false)),
+            'b' => Some(r#type::Expression::Boolean),
+            'a' => self.r#type().map(Box::new).map(r#type::Expression::Array),
+            'o' => {
+                let class = unescape(self.take()?)?;
+                Some(r#type::Expression::Class(symbol::intern(name(&class))))
+            }
+            'f' => {
+                let count = self.number()?;
+                let (parameters, returns) = self.signature(Some(count))?;
+                Some(r#type::Expression::Function(parameters, returns))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses `t<length><name><count>` followed by `count` type arguments,
+    /// as mangled by `mangle_template`.
+    fn template(&mut self) -> Option<String> {
+        if self.next()? != 't' {
+            return None;
+        }
+
+        let template = unescape(self.take()?)?;
+        let count = self.number()?;
+        let generics = self.types(count, Self::generic)?;
+
+        let mut demangled = format!("{}::<", template);
+
+        for (index, generic) in generics.iter().enumerate() {
+            if index > 0 {
+                demangled.push_str(", ");
+            }
+            write!(demangled, "{}", generic).unwrap();
+        }
+
+        demangled.push('>');
+        Some(demangled)
+    }
+
+    /// Parses a type argument as mangled by `mangle_type_ast`, where class names
+    /// are escaped unless they are nested template instantiations.
+    fn generic(&mut self) -> Option<r#type::Expression> {
+        match self.peek()? {
+            'o' => {
+                self.next();
+                let class = self.take()?;
+                let mut nested = Demangler { mangled: class };
+                let class = match nested.template() {
+                    Some(template) if nested.mangled.is_empty() => template,
+                    _ => unescape(class)?,
+                };
+                Some(r#type::Expression::Class(symbol::intern(class)))
+            }
+            't' => self
+                .template()
+                .map(symbol::intern)
+                .map(r#type::Expression::Class),
+            'a' => {
+                self.next();
+                self.generic().map(Box::new).map(r#type::Expression::Array)
+            }
+            'f' => {
+                self.next();
+                let count = self.number()?;
+
+                let returns = match self.peek()? {
+                    'p' => {
+                        self.next();
+                        Vec::new()
+                    }
+                    // Note: `t` begins either a tuple of return types or a
+                    // single template instantiation, so try both.
+                    't' => {
+                        let backtrack = self.mangled;
+                        self.next();
+                        match self
+                            .number()
+                            .and_then(|count| self.types(count, Self::generic))
+                        {
+                            Some(returns) => returns,
+                            None => {
+                                self.mangled = backtrack;
+                                vec![self.generic()?]
+                            }
+                        }
+                    }
+                    _ => vec![self.generic()?],
+                };
+
+                let parameters = self.types(count, Self::generic)?;
+                Some(r#type::Expression::Function(parameters, returns))
+            }
+            _ => self.r#type(),
+        }
+    }
+
+    /// Sized integers are followed by their width in bits, which is omitted for 64 bits.
+    fn integer(&mut self, signed: bool) -> r#type::Expression {
+        for size in [Size::_8, Size::_16, Size::_32] {
+            if let Some(mangled) = self.mangled.strip_prefix(&*size.to_string()) {
+                self.mangled = mangled;
+                return r#type::Expression::Sized { signed, size };
+            }
+        }
+
+        match signed {
+            true => r#type::Expression::Integer,
+            false => r#type::Expression::Sized {
+                signed,
+                size: Size::_64,
+            },
+        }
+    }
+
+    /// Takes a length-prefixed string.
+    fn take(&mut self) -> Option<&'a str> {
+        let length = self.number()?;
+        let taken = self.mangled.get(..length)?;
+        self.mangled = &self.mangled[length..];
+        Some(taken)
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let end = self
+            .mangled
+            .find(|character: char| !character.is_ascii_digit())
+            .unwrap_or(self.mangled.len());
+        let number = self.mangled[..end].parse().ok()?;
+        self.mangled = &self.mangled[end..];
+        Some(number)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.mangled.chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let next = self.peek()?;
+        self.mangled = &self.mangled[next.len_utf8()..];
+        Some(next)
+    }
+}
@@ -5,6 +5,7 @@ use crate::data::ast;
 use crate::data::r#type;
 use crate::data::symbol;
 use crate::data::symbol::Symbol;
+use crate::data::token::Size;
 
 pub fn template<T>(name: &Symbol, generics: &[ast::Type<T>]) -> Symbol {
     let mut mangled = String::new();
@@ -20,6 +21,15 @@ pub fn class_virtual_table(class: &Symbol) -> Symbol {
     symbol::intern(&format!("_I_vt_{}", escape(class)))
 }
 
+pub fn class_type_descriptor(class: &Symbol) -> Symbol {
+    symbol::intern(&format!("_I_td_{}", escape(class)))
+}
+
+/// Symbol whose address uniquely identifies interface `protocol` across modules.
+pub fn protocol_identifier(protocol: &Symbol) -> Symbol {
+    symbol::intern(&format!("_I_iid_{}", escape(protocol)))
+}
+
 pub fn class_initialization(class: &Symbol) -> Symbol {
     symbol::intern(&format!("_I_init_{}", escape(class)))
 }
@@ -30,7 +40,7 @@ where
 {
     let mut mangled = String::from("_I_init_global");
     for (name, r#type) in initialization {
-        write!(&mut mangled, "_{}", escape(name)).unwrap();
+        write!(&mut mangled, "_{}_", escape(name)).unwrap();
         mangle_type(r#type, &mut mangled).unwrap();
     }
     symbol::intern(mangled)
@@ -63,6 +73,17 @@ pub fn function(
     symbol::intern(mangled)
 }
 
+/// Name of the `index`-th anonymous function defined in `function`.
+pub fn closure(function: &Symbol, index: usize) -> Symbol {
+    symbol::intern(&format!("_I_closure{}_{}", index, function))
+}
+
+/// Name of the adapter that lets a named function be called as a closure,
+/// which ignores the closure environment passed as its first argument.
+pub fn closure_adapter(function: &Symbol) -> Symbol {
+    symbol::intern(&format!("_I_closure_{}", function))
+}
+
 fn mangle_function(
     name: &Symbol,
     parameters: &[r#type::Expression],
@@ -70,7 +91,14 @@ fn mangle_function(
     mangled: &mut String,
 ) -> fmt::Result {
     write!(mangled, "{}_", escape(name))?;
+    mangle_signature(parameters, returns, mangled)
+}
 
+fn mangle_signature(
+    parameters: &[r#type::Expression],
+    returns: &[r#type::Expression],
+    mangled: &mut String,
+) -> fmt::Result {
     match returns {
         [] => mangled.push('p'),
         [r#type] => {
@@ -94,10 +122,11 @@ fn mangle_function(
 
 fn mangle_type(r#type: &r#type::Expression, mangled: &mut String) -> fmt::Result {
     match r#type {
-        r#type::Expression::Any | r#type::Expression::Null | r#type::Expression::Function(_, _) => {
+        r#type::Expression::Any | r#type::Expression::Null | r#type::Expression::Poison => {
             panic!("[INTERNAL ERROR]: `{}` type in IR", r#type)
         }
         r#type::Expression::Integer => mangled.push('i'),
+        &r#type::Expression::Sized { signed, size } => mangle_integer(signed, size, mangled)?,
         r#type::Expression::Boolean => mangled.push('b'),
         r#type::Expression::Class(class) => {
             let name = escape(class);
@@ -107,11 +136,26 @@ fn mangle_type(r#type: &r#type::Expression, mangled: &mut String) -> fmt::Result
             mangled.push('a');
             mangle_type(&*r#type, mangled)?;
         }
+        // Note: the parameter count is required to find where the signature ends
+        r#type::Expression::Function(parameters, returns) => {
+            write!(mangled, "f{}", parameters.len())?;
+            mangle_signature(parameters, returns, mangled)?;
+        }
     }
 
     Ok(())
 }
 
+/// Sized integer types are mangled as `i` or `u` followed by their width in bits,
+/// which is omitted for `uint` (and `int`, which is mangled as `i` above).
+fn mangle_integer(signed: bool, size: Size, mangled: &mut String) -> fmt::Result {
+    mangled.push(if signed { 'i' } else { 'u' });
+    if size < Size::_64 {
+        write!(mangled, "{}", size)?;
+    }
+    Ok(())
+}
+
 fn mangle_template<T>(
     name: &Symbol,
     generics: &[ast::Type<T>],
@@ -157,6 +201,7 @@ fn mangle_template<T>(
 fn mangle_type_ast<T>(r#type: &ast::Type<T>, mangled: &mut String) -> Result<(), fmt::Error> {
     match r#type {
         ast::Type::Int(_) => mangled.push('i'),
+        &ast::Type::Sized { signed, size, .. } => mangle_integer(signed, size, mangled)?,
         ast::Type::Bool(_) => mangled.push('b'),
         ast::Type::Class(ast::Variable {
             name,
@@ -177,6 +222,24 @@ fn mangle_type_ast<T>(r#type: &ast::Type<T>, mangled: &mut String) -> Result<(),
             mangled.push('a');
             mangle_type_ast(&*r#type, mangled)?;
         }
+        ast::Type::Function(parameters, returns, _) => {
+            write!(mangled, "f{}", parameters.len())?;
+
+            match returns.as_slice() {
+                [] => mangled.push('p'),
+                [r#type] => mangle_type_ast(r#type, mangled)?,
+                types => {
+                    write!(mangled, "t{}", types.len())?;
+                    for r#type in types {
+                        mangle_type_ast(r#type, mangled)?;
+                    }
+                }
+            }
+
+            for parameter in parameters {
+                mangle_type_ast(parameter, mangled)?;
+            }
+        }
     }
 
     Ok(())
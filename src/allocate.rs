@@ -1,3 +1,4 @@
+mod coloring;
 mod linear;
 mod trivial;
 
@@ -9,8 +10,10 @@ use crate::analyze::analyze_default;
 use crate::analyze::LiveRanges;
 use crate::analyze::LiveVariables;
 use crate::asm;
+use crate::cfg;
 use crate::cfg::Cfg;
 use crate::data::asm;
+use crate::data::debug::Debug;
 use crate::data::operand;
 use crate::data::operand::Immediate;
 use crate::data::operand::Memory;
@@ -116,6 +119,52 @@ pub fn allocate_linear(mut function: Cfg<asm::Function<Temporary>>) -> asm::Func
     }
 }
 
+pub fn allocate_coloring(mut function: Cfg<asm::Function<Temporary>>) -> asm::Function<Register> {
+    log::info!(
+        "[{}] Allocating {} using graph coloring algorithm...",
+        std::any::type_name::<Cfg<asm::Function<Temporary>>>(),
+        function.name(),
+    );
+    util::time!(
+        "[{}] Done allocating {}",
+        std::any::type_name::<Cfg<asm::Function<Temporary>>>(),
+        function.name(),
+    );
+
+    let frame_pointer = match function[function.enter()].first() {
+        Some(asm::Statement::Unary(
+            asm::Unary::Push,
+            operand::Unary::R(Temporary::Register(Register::Rbp)),
+        )) => abi::FramePointer::Keep,
+        _ => abi::FramePointer::Omit,
+    };
+
+    let registers = abi::CALLER_SAVED
+        .iter()
+        .chain(abi::CALLEE_SAVED)
+        .copied()
+        .filter(|register| *register != Register::rsp())
+        // If omitting frame pointer, then we can use it during register allocation
+        .filter(|register| *register != Register::Rbp || frame_pointer == abi::FramePointer::Omit)
+        .collect::<Vec<_>>();
+
+    let live_variables = analyze_default::<LiveVariables<_>, _>(&function);
+    optimize::eliminate_dead_code_assembly(&live_variables, &mut function);
+
+    let (allocated, spilled) = coloring::allocate(&mut function, registers);
+    let function = cfg::destruct_cfg(function);
+
+    log::debug!(
+        "Allocated {} with {} spilled temporaries",
+        function.name,
+        spilled.len()
+    );
+
+    // Spill code is rewritten to only use valid addressing modes
+    allocate(frame_pointer, &[], allocated, spilled, &function)
+        .expect("[INTERNAL ERROR]: graph coloring register allocator requires shuttle registers")
+}
+
 struct Allocator<'a> {
     callee_arguments: Option<usize>,
     callee_returns: Option<usize>,
@@ -222,9 +271,9 @@ impl<'a> Allocator<'a> {
         let statement = match statement {
             // Since the linear scan allocator is based on live variable analysis,
             // it doesn't allocate registers for dead variables. This is only allowed
-            // for `mov` and `lea` statements, since they don't read their destinations.
+            // for `mov`, `lea`, and narrow load statements, since they don't read their destinations.
             asm::Statement::Binary(
-                asm::Binary::Mov | asm::Binary::Lea,
+                asm::Binary::Mov | asm::Binary::Lea | asm::Binary::Load { .. },
                 operand::Binary::RI { destination, .. }
                 | operand::Binary::RM { destination, .. }
                 | operand::Binary::RR { destination, .. },
@@ -282,6 +331,31 @@ impl<'a> Allocator<'a> {
                 }
             }
 
+            // Special case: narrow loads can only take a register destination, so we need
+            // to shuttle any memory destinations.
+            asm::Statement::Binary(
+                load @ asm::Binary::Load { .. },
+                operand::Binary::RM {
+                    destination,
+                    source,
+                },
+            ) => {
+                let source = self.allocate_memory(source)?;
+                match self.allocate(destination) {
+                    Or::L(register) => {
+                        asm::Statement::Binary(*load, operand::Binary::from((register, source)))
+                    }
+                    Or::R(memory) => {
+                        let shuttle = self.shuttle_unused.next()?;
+                        self.statements.push(asm::Statement::Binary(
+                            *load,
+                            operand::Binary::from((shuttle, source)),
+                        ));
+                        asm!((mov memory, shuttle))
+                    }
+                }
+            }
+
             asm::Statement::Binary(binary, operands) => {
                 asm::Statement::Binary(*binary, self.allocate_binary(operands)?)
             }
@@ -292,6 +366,21 @@ impl<'a> Allocator<'a> {
             asm::Statement::Label(label) => asm::Statement::Label(*label),
             asm::Statement::Jmp(label) => asm::Statement::Jmp(*label),
             asm::Statement::Jcc(condition, label) => asm::Statement::Jcc(*condition, *label),
+
+            // Variables whose temporaries were never allocated have been optimized out
+            asm::Statement::Debug(Debug::Variable(_, _, operand::Unary::R(temporary)))
+                if !matches!(temporary, Temporary::Register(_))
+                    && !self.allocated.contains_key(temporary)
+                    && !self.spilled.contains_key(temporary) =>
+            {
+                return Some(());
+            }
+            asm::Statement::Debug(debug) => asm::Statement::Debug(match debug {
+                Debug::Location(span) => Debug::Location(*span),
+                Debug::Variable(name, r#type, operand) => {
+                    Debug::Variable(*name, r#type.clone(), self.allocate_unary(operand)?)
+                }
+            }),
         };
 
         match statement {
@@ -542,7 +631,8 @@ fn rewrite_rbp(
         | asm::Statement::Nullary(_)
         | asm::Statement::Label(_)
         | asm::Statement::Jmp(_)
-        | asm::Statement::Jcc(_, _) => return,
+        | asm::Statement::Jcc(_, _)
+        | asm::Statement::Debug(_) => return,
         #[rustfmt::skip]
         asm::Statement::Binary(_, operand::Binary::MI { destination: memory, .. })
         | asm::Statement::Binary( _, operand::Binary::MR { destination: memory, .. })
@@ -0,0 +1,804 @@
+use crate::abi;
+use crate::analyze::analyze_default;
+use crate::analyze::Analysis as _;
+use crate::analyze::LiveVariables;
+use crate::asm;
+use crate::cfg::Cfg;
+use crate::data::asm;
+use crate::data::debug::Debug;
+use crate::data::operand;
+use crate::data::operand::Immediate;
+use crate::data::operand::Label;
+use crate::data::operand::Memory;
+use crate::data::operand::Register;
+use crate::data::operand::Temporary;
+use crate::data::symbol;
+use crate::util::Or;
+use crate::Map;
+use crate::Set;
+
+/// Requires that dead code elimination has been run on `function`, since
+/// every temporary that appears in a statement is assigned a location.
+///
+/// Rewrites `function` in place with spill code, and returns the registers
+/// and stack slots assigned to each temporary.
+pub fn allocate(
+    function: &mut Cfg<asm::Function<Temporary>>,
+    registers: Vec<Register>,
+) -> (Map<Temporary, Register>, Map<Temporary, usize>) {
+    let mut spilled = Map::default();
+    let mut unspillable = Set::default();
+
+    // Also rewrites `imul` instructions with memory destinations
+    rewrite(function, &spilled, &mut unspillable);
+
+    loop {
+        let mut coloring = Coloring::new(&registers, &spilled, &unspillable, function);
+        coloring.color();
+
+        if coloring.spilled_nodes.is_empty() {
+            return (coloring.allocated(), spilled);
+        }
+
+        for temporary in coloring.spilled_nodes {
+            let index = spilled.len();
+            spilled.insert(temporary, index);
+        }
+
+        rewrite(function, &spilled, &mut unspillable);
+    }
+}
+
+fn rewrite(
+    function: &mut Cfg<asm::Function<Temporary>>,
+    spilled: &Map<Temporary, usize>,
+    unspillable: &mut Set<Temporary>,
+) {
+    for (_, statements) in function.blocks_mut() {
+        let mut rewriter = Rewriter {
+            spilled,
+            unspillable,
+            statements: Vec::with_capacity(statements.len()),
+        };
+
+        for statement in statements.iter() {
+            rewriter.rewrite(statement);
+        }
+
+        *statements = rewriter.statements;
+    }
+}
+
+/// This register allocator is based on the research paper
+/// "Iterated register coalescing" by George and Appel:
+///
+/// https://dl.acm.org/doi/10.1145/229542.229546
+///
+/// There were some challenges when adapting the algorithm
+/// to work with the x86-64 ISA:
+///
+/// - Representing temporaries bound to fixed registers
+///   (e.g. passing arguments, `imul` operands)
+///
+/// These are the precolored nodes of the interference graph. Instructions
+/// that implicitly clobber registers (calls, `cqo`, `div`, and co.) define
+/// those registers, so every temporary live across them interferes with them.
+/// Moves into and out of argument and return registers are coalesced like any
+/// other move, as long as the register is available for allocation.
+///
+/// - Maintaining valid addressing modes when spilling
+///   temporaries to the stack
+///
+/// Spilled temporaries are left in place wherever x86-64 accepts a memory
+/// operand, and are later rewritten as `[rsp + offset]`. Everywhere else
+/// (memory operand bases and indices, register-only destinations, and
+/// instructions with two memory operands) they are loaded into or stored from
+/// short-lived temporaries, which are never spilled themselves.
+struct Coloring<'a> {
+    registers: &'a [Register],
+    spilled: &'a Map<Temporary, usize>,
+    unspillable: &'a Set<Temporary>,
+
+    // Interference graph
+    adjacent: Set<(Temporary, Temporary)>,
+    adjacency: Map<Temporary, Set<Temporary>>,
+    degree: Map<Temporary, usize>,
+    uses: Map<Temporary, usize>,
+
+    // Move instructions and the temporaries that they involve
+    moves: Vec<(Temporary, Temporary)>,
+    states: Vec<Move>,
+    node_moves: Map<Temporary, Set<usize>>,
+    worklist_moves: Set<usize>,
+
+    simplify_worklist: Set<Temporary>,
+    freeze_worklist: Set<Temporary>,
+    spill_worklist: Set<Temporary>,
+    spilled_nodes: Vec<Temporary>,
+    coalesced_nodes: Set<Temporary>,
+
+    select_stack: Vec<Temporary>,
+    selected: Set<Temporary>,
+    alias: Map<Temporary, Temporary>,
+    colors: Map<Temporary, Register>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Move {
+    Worklist,
+    Active,
+    Coalesced,
+    Constrained,
+    Frozen,
+}
+
+impl<'a> Coloring<'a> {
+    fn new(
+        registers: &'a [Register],
+        spilled: &'a Map<Temporary, usize>,
+        unspillable: &'a Set<Temporary>,
+        function: &Cfg<asm::Function<Temporary>>,
+    ) -> Self {
+        let mut coloring = Coloring {
+            registers,
+            spilled,
+            unspillable,
+            adjacent: Set::default(),
+            adjacency: Map::default(),
+            degree: Map::default(),
+            uses: Map::default(),
+            moves: Vec::new(),
+            states: Vec::new(),
+            node_moves: Map::default(),
+            worklist_moves: Set::default(),
+            simplify_worklist: Set::default(),
+            freeze_worklist: Set::default(),
+            spill_worklist: Set::default(),
+            spilled_nodes: Vec::new(),
+            coalesced_nodes: Set::default(),
+            select_stack: Vec::new(),
+            selected: Set::default(),
+            alias: Map::default(),
+            colors: Map::default(),
+        };
+
+        coloring.build(function);
+        coloring.make_worklist();
+        coloring
+    }
+
+    fn build(&mut self, function: &Cfg<asm::Function<Temporary>>) {
+        let live_variables = analyze_default::<LiveVariables<_>, _>(function);
+
+        for (label, statements) in function.blocks() {
+            let mut live = live_variables.inputs[label].clone();
+
+            for statement in statements.iter().rev() {
+                if let asm::Statement::Debug(_) = statement {
+                    continue;
+                }
+
+                visit(statement, |temporary| {
+                    if self.is_node(temporary) {
+                        *self.uses.entry(*temporary).or_default() += 1;
+                        self.adjacency.entry(*temporary).or_default();
+                        self.degree.entry(*temporary).or_default();
+                    }
+                });
+
+                // Moves don't make their source and destination interfere, since
+                // the two hold the same value and can share a register.
+                let source = match statement {
+                    asm::Statement::Binary(
+                        asm::Binary::Mov,
+                        operand::Binary::RR {
+                            destination,
+                            source,
+                        },
+                    ) if self.is_candidate(destination) && self.is_candidate(source) => {
+                        let index = self.moves.len();
+                        self.moves.push((*destination, *source));
+                        self.states.push(Move::Worklist);
+                        self.worklist_moves.insert(index);
+                        self.node_moves
+                            .entry(*destination)
+                            .or_default()
+                            .insert(index);
+                        self.node_moves.entry(*source).or_default().insert(index);
+                        Some(*source)
+                    }
+                    _ => None,
+                };
+
+                for definition in definitions(statement) {
+                    for temporary in &live {
+                        if Some(*temporary) != source {
+                            self.add_edge(definition, *temporary);
+                        }
+                    }
+                }
+
+                live_variables.analysis.transfer(statement, &mut live);
+            }
+        }
+    }
+
+    fn make_worklist(&mut self) {
+        for temporary in self.adjacency.keys().copied().collect::<Vec<_>>() {
+            if self.degree[&temporary] >= self.registers.len() {
+                self.spill_worklist.insert(temporary);
+            } else if self.is_move_related(&temporary) {
+                self.freeze_worklist.insert(temporary);
+            } else {
+                self.simplify_worklist.insert(temporary);
+            }
+        }
+    }
+
+    fn color(&mut self) {
+        loop {
+            if let Some(temporary) = self.simplify_worklist.pop() {
+                self.simplify(temporary);
+            } else if let Some(index) = self.worklist_moves.pop() {
+                self.coalesce(index);
+            } else if let Some(temporary) = self.freeze_worklist.pop() {
+                self.freeze(temporary);
+            } else if !self.spill_worklist.is_empty() {
+                self.select_spill();
+            } else {
+                break;
+            }
+        }
+
+        self.assign_colors();
+    }
+
+    fn simplify(&mut self, temporary: Temporary) {
+        self.select_stack.push(temporary);
+        self.selected.insert(temporary);
+
+        for adjacent in self.adjacent(&temporary) {
+            self.decrement_degree(adjacent);
+        }
+    }
+
+    fn decrement_degree(&mut self, temporary: Temporary) {
+        if is_precolored(&temporary) {
+            return;
+        }
+
+        let degree = self.degree[&temporary];
+        self.degree[&temporary] = degree - 1;
+
+        if degree != self.registers.len() {
+            return;
+        }
+
+        let mut enable = self.adjacent(&temporary);
+        enable.push(temporary);
+        self.enable_moves(enable);
+
+        self.spill_worklist.shift_remove(&temporary);
+
+        if self.is_move_related(&temporary) {
+            self.freeze_worklist.insert(temporary);
+        } else {
+            self.simplify_worklist.insert(temporary);
+        }
+    }
+
+    fn enable_moves(&mut self, temporaries: Vec<Temporary>) {
+        for temporary in temporaries {
+            for index in self.active_moves(&temporary) {
+                if self.states[index] == Move::Active {
+                    self.states[index] = Move::Worklist;
+                    self.worklist_moves.insert(index);
+                }
+            }
+        }
+    }
+
+    fn coalesce(&mut self, index: usize) {
+        let (destination, source) = self.moves[index];
+        let destination = self.get_alias(destination);
+        let source = self.get_alias(source);
+
+        let (u, v) = if is_precolored(&source) {
+            (source, destination)
+        } else {
+            (destination, source)
+        };
+
+        if u == v {
+            self.states[index] = Move::Coalesced;
+            self.add_worklist(u);
+        } else if is_precolored(&v) || self.adjacent.contains(&(u, v)) {
+            self.states[index] = Move::Constrained;
+            self.add_worklist(u);
+            self.add_worklist(v);
+        } else if match u {
+            // George's heuristic: every neighbor of `v` either already interferes
+            // with `u`, or is insignificant and can be simplified away.
+            Temporary::Register(_) => self
+                .adjacent(&v)
+                .iter()
+                .all(|temporary| self.is_ok(temporary, &u)),
+            // Briggs' heuristic: the combined node has fewer than K neighbors of
+            // significant degree.
+            _ => {
+                let mut adjacent = self.adjacent(&u).into_iter().collect::<Set<_>>();
+                adjacent.extend(self.adjacent(&v));
+                self.is_conservative(&adjacent)
+            }
+        } {
+            self.states[index] = Move::Coalesced;
+            self.combine(u, v);
+            self.add_worklist(u);
+        } else {
+            self.states[index] = Move::Active;
+        }
+    }
+
+    fn add_worklist(&mut self, temporary: Temporary) {
+        if !is_precolored(&temporary)
+            && !self.is_move_related(&temporary)
+            && self.degree[&temporary] < self.registers.len()
+        {
+            self.freeze_worklist.shift_remove(&temporary);
+            self.simplify_worklist.insert(temporary);
+        }
+    }
+
+    fn is_ok(&self, temporary: &Temporary, register: &Temporary) -> bool {
+        is_precolored(temporary)
+            || self.degree[temporary] < self.registers.len()
+            || self.adjacent.contains(&(*temporary, *register))
+    }
+
+    fn is_conservative(&self, temporaries: &Set<Temporary>) -> bool {
+        temporaries
+            .iter()
+            .filter(|temporary| {
+                is_precolored(temporary) || self.degree[*temporary] >= self.registers.len()
+            })
+            .count()
+            < self.registers.len()
+    }
+
+    fn combine(&mut self, u: Temporary, v: Temporary) {
+        if !self.freeze_worklist.shift_remove(&v) {
+            self.spill_worklist.shift_remove(&v);
+        }
+
+        self.coalesced_nodes.insert(v);
+        self.alias.insert(v, u);
+
+        let moves = self.node_moves.get(&v).cloned().unwrap_or_default();
+        self.node_moves.entry(u).or_default().extend(moves);
+        self.enable_moves(vec![v]);
+
+        for temporary in self.adjacent(&v) {
+            self.add_edge(temporary, u);
+            self.decrement_degree(temporary);
+        }
+
+        if !is_precolored(&u)
+            && self.degree[&u] >= self.registers.len()
+            && self.freeze_worklist.shift_remove(&u)
+        {
+            self.spill_worklist.insert(u);
+        }
+    }
+
+    fn freeze(&mut self, temporary: Temporary) {
+        self.simplify_worklist.insert(temporary);
+        self.freeze_moves(temporary);
+    }
+
+    fn freeze_moves(&mut self, temporary: Temporary) {
+        for index in self.active_moves(&temporary) {
+            let (destination, source) = self.moves[index];
+
+            let other = if self.get_alias(source) == self.get_alias(temporary) {
+                self.get_alias(destination)
+            } else {
+                self.get_alias(source)
+            };
+
+            self.states[index] = Move::Frozen;
+            self.worklist_moves.shift_remove(&index);
+
+            if !is_precolored(&other)
+                && self.active_moves(&other).is_empty()
+                && self.degree[&other] < self.registers.len()
+                && self.freeze_worklist.shift_remove(&other)
+            {
+                self.simplify_worklist.insert(other);
+            }
+        }
+    }
+
+    fn select_spill(&mut self) {
+        // Spill the temporary with the fewest uses per interference, preferring
+        // never to spill the temporaries introduced by rewriting earlier spills.
+        let (index, _) = self
+            .spill_worklist
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, temporary)| {
+                (
+                    self.unspillable.contains(*temporary),
+                    self.uses[*temporary] * 1024 / self.degree[*temporary].max(1),
+                )
+            })
+            .unwrap();
+
+        let temporary = self.spill_worklist.shift_remove_index(index).unwrap();
+        self.simplify_worklist.insert(temporary);
+        self.freeze_moves(temporary);
+    }
+
+    fn assign_colors(&mut self) {
+        while let Some(temporary) = self.select_stack.pop() {
+            let mut available = self.registers.to_vec();
+
+            for adjacent in &self.adjacency[&temporary] {
+                let adjacent = self.get_alias(*adjacent);
+                let color = match adjacent {
+                    Temporary::Register(register) => register,
+                    _ => match self.colors.get(&adjacent) {
+                        Some(register) => *register,
+                        None => continue,
+                    },
+                };
+                available.retain(|register| *register != color);
+            }
+
+            match available.first() {
+                Some(register) => {
+                    self.colors.insert(temporary, *register);
+                }
+                None => self.spilled_nodes.push(temporary),
+            }
+        }
+
+        for temporary in &self.coalesced_nodes {
+            match self.get_alias(*temporary) {
+                Temporary::Register(register) => {
+                    self.colors.insert(*temporary, register);
+                }
+                alias => {
+                    if let Some(register) = self.colors.get(&alias).copied() {
+                        self.colors.insert(*temporary, register);
+                    }
+                }
+            }
+        }
+    }
+
+    fn allocated(self) -> Map<Temporary, Register> {
+        self.colors
+    }
+
+    fn add_edge(&mut self, u: Temporary, v: Temporary) {
+        let is_vertex = |temporary| is_precolored(temporary) || self.is_node(temporary);
+
+        if u == v || !is_vertex(&u) || !is_vertex(&v) || !self.adjacent.insert((u, v)) {
+            return;
+        }
+
+        self.adjacent.insert((v, u));
+
+        for (u, v) in [(u, v), (v, u)] {
+            if !is_precolored(&u) {
+                self.adjacency.entry(u).or_default().insert(v);
+                *self.degree.entry(u).or_default() += 1;
+            }
+        }
+    }
+
+    /// Neighbors of `temporary` that are still in the interference graph.
+    fn adjacent(&self, temporary: &Temporary) -> Vec<Temporary> {
+        self.adjacency
+            .get(temporary)
+            .into_iter()
+            .flatten()
+            .filter(|temporary| {
+                !self.selected.contains(*temporary) && !self.coalesced_nodes.contains(*temporary)
+            })
+            .copied()
+            .collect()
+    }
+
+    fn active_moves(&self, temporary: &Temporary) -> Vec<usize> {
+        self.node_moves
+            .get(temporary)
+            .into_iter()
+            .flatten()
+            .filter(|index| matches!(self.states[**index], Move::Worklist | Move::Active))
+            .copied()
+            .collect()
+    }
+
+    fn is_move_related(&self, temporary: &Temporary) -> bool {
+        !self.active_moves(temporary).is_empty()
+    }
+
+    fn get_alias(&self, mut temporary: Temporary) -> Temporary {
+        while let Some(alias) = self.alias.get(&temporary) {
+            temporary = *alias;
+        }
+        temporary
+    }
+
+    /// Whether `temporary` should be assigned a register by this allocator.
+    fn is_node(&self, temporary: &Temporary) -> bool {
+        !is_precolored(temporary) && !self.spilled.contains_key(temporary)
+    }
+
+    /// Whether `temporary` can participate in a coalesced move.
+    fn is_candidate(&self, temporary: &Temporary) -> bool {
+        match temporary {
+            Temporary::Register(register) => self.registers.contains(register),
+            temporary => self.is_node(temporary),
+        }
+    }
+}
+
+fn is_precolored(temporary: &Temporary) -> bool {
+    matches!(temporary, Temporary::Register(_))
+}
+
+fn visit<F: FnMut(&Temporary)>(statement: &asm::Statement<Temporary>, mut visit: F) {
+    match statement {
+        asm::Statement::Binary(_, operands) => {
+            match operands.destination() {
+                Or::L(temporary) => visit(&temporary),
+                Or::R(memory) => {
+                    memory.map(&mut visit);
+                }
+            }
+            operands.source().map(&mut visit);
+        }
+        asm::Statement::Unary(_, operand) => {
+            operand.map(&mut visit);
+        }
+        asm::Statement::Nullary(_)
+        | asm::Statement::Label(_)
+        | asm::Statement::Jmp(_)
+        | asm::Statement::Jcc(_, _)
+        | asm::Statement::Debug(_) => (),
+    }
+}
+
+/// Temporaries written by `statement`, including implicitly clobbered registers.
+fn definitions(statement: &asm::Statement<Temporary>) -> Vec<Temporary> {
+    match statement {
+        asm::Statement::Binary(asm::Binary::Cmp | asm::Binary::Store(_), _) => Vec::new(),
+        asm::Statement::Binary(_, operands) => match operands.destination() {
+            Or::L(temporary) => vec![temporary],
+            Or::R(_) => Vec::new(),
+        },
+        asm::Statement::Unary(asm::Unary::Neg | asm::Unary::Pop, operand::Unary::R(temporary)) => {
+            vec![*temporary]
+        }
+
+        // Special case: `_xi_out_of_bounds` diverges, so nothing after is live.
+        asm::Statement::Unary(
+            asm::Unary::Call { .. },
+            operand::Unary::I(Immediate::Label(Label::Fixed(label))),
+        ) if symbol::resolve(*label) == abi::XI_OUT_OF_BOUNDS => Vec::new(),
+        asm::Statement::Unary(asm::Unary::Call { .. }, _) => abi::CALLER_SAVED
+            .iter()
+            .copied()
+            .map(Temporary::Register)
+            .collect(),
+
+        asm::Statement::Unary(
+            asm::Unary::Hul
+            | asm::Unary::Div
+            | asm::Unary::Mod
+            | asm::Unary::UHul
+            | asm::Unary::UDiv
+            | asm::Unary::UMod,
+            _,
+        ) => vec![
+            Temporary::Register(Register::Rax),
+            Temporary::Register(Register::Rdx),
+        ],
+        asm::Statement::Nullary(asm::Nullary::Cqo) => vec![Temporary::Register(Register::Rdx)],
+
+        asm::Statement::Unary(_, _)
+        | asm::Statement::Nullary(_)
+        | asm::Statement::Label(_)
+        | asm::Statement::Jmp(_)
+        | asm::Statement::Jcc(_, _)
+        | asm::Statement::Debug(_) => Vec::new(),
+    }
+}
+
+struct Rewriter<'a> {
+    spilled: &'a Map<Temporary, usize>,
+    unspillable: &'a mut Set<Temporary>,
+    statements: Vec<asm::Statement<Temporary>>,
+}
+
+impl Rewriter<'_> {
+    fn rewrite(&mut self, statement: &asm::Statement<Temporary>) {
+        let mut stores = Vec::new();
+
+        let statement = match statement {
+            asm::Statement::Binary(binary, operands) => {
+                let (read, write) = match binary {
+                    asm::Binary::Mov | asm::Binary::Lea | asm::Binary::Load { .. } => (false, true),
+                    asm::Binary::Cmp => (true, false),
+                    _ => (true, true),
+                };
+
+                // These instructions can only take a register destination
+                let register = matches!(
+                    binary,
+                    asm::Binary::Mul | asm::Binary::Lea | asm::Binary::Load { .. }
+                );
+
+                let operands = match *operands {
+                    operand::Binary::RI {
+                        destination,
+                        source,
+                    } if self.is_spilled(&destination)
+                        && (register
+                            || matches!(binary, asm::Binary::Mov) && source.is_64_bit()) =>
+                    {
+                        operand::Binary::RI {
+                            destination: self.register(destination, read, write, &mut stores),
+                            source,
+                        }
+                    }
+                    operand::Binary::RI { .. } => *operands,
+                    // Special case: `imul` can only take a register destination, so we
+                    // need to load and store memory destinations through a temporary.
+                    operand::Binary::MI {
+                        destination,
+                        source,
+                    } if register => operand::Binary::RI {
+                        destination: self.shuttle(&destination, &mut stores),
+                        source,
+                    },
+                    operand::Binary::MR {
+                        destination,
+                        source,
+                    } if register => operand::Binary::RR {
+                        destination: self.shuttle(&destination, &mut stores),
+                        source,
+                    },
+                    operand::Binary::MI {
+                        destination,
+                        source,
+                    } => operand::Binary::MI {
+                        destination: self.memory(&destination),
+                        source,
+                    },
+                    operand::Binary::MR {
+                        destination,
+                        source,
+                    } => operand::Binary::MR {
+                        destination: self.memory(&destination),
+                        source: self.load(source),
+                    },
+                    operand::Binary::RM {
+                        destination,
+                        source,
+                    } => {
+                        let source = self.memory(&source);
+                        operand::Binary::RM {
+                            destination: self.register(destination, read, write, &mut stores),
+                            source,
+                        }
+                    }
+                    operand::Binary::RR {
+                        destination,
+                        source,
+                    } if register && self.is_spilled(&destination) => operand::Binary::RR {
+                        destination: self.register(destination, read, write, &mut stores),
+                        source,
+                    },
+                    operand::Binary::RR {
+                        destination,
+                        source,
+                    } if self.is_spilled(&destination) && self.is_spilled(&source) => {
+                        operand::Binary::RR {
+                            destination,
+                            source: self.load(source),
+                        }
+                    }
+                    operand::Binary::RR { .. } => *operands,
+                };
+
+                asm::Statement::Binary(*binary, operands)
+            }
+            asm::Statement::Unary(unary, operand::Unary::M(memory)) => {
+                asm::Statement::Unary(*unary, operand::Unary::M(self.memory(memory)))
+            }
+
+            // Variables stored at addresses computed from spilled temporaries
+            // can't be described without shuttle registers, so drop them.
+            asm::Statement::Debug(Debug::Variable(_, _, operand::Unary::M(memory))) => {
+                let mut spilled = false;
+                memory.map(|temporary| spilled |= self.is_spilled(temporary));
+                if spilled {
+                    return;
+                }
+                statement.clone()
+            }
+            statement => statement.clone(),
+        };
+
+        self.statements.push(statement);
+        self.statements.extend(stores);
+    }
+
+    /// Loads `temporary` into a fresh temporary before the current statement.
+    fn load(&mut self, temporary: Temporary) -> Temporary {
+        if !self.is_spilled(&temporary) {
+            return temporary;
+        }
+
+        let fresh = self.fresh();
+        self.statements.push(asm!((mov fresh, temporary)));
+        fresh
+    }
+
+    /// Replaces `temporary` with a fresh temporary, loading it before and storing
+    /// it after the current statement as necessary.
+    fn register(
+        &mut self,
+        temporary: Temporary,
+        read: bool,
+        write: bool,
+        stores: &mut Vec<asm::Statement<Temporary>>,
+    ) -> Temporary {
+        if !self.is_spilled(&temporary) {
+            return temporary;
+        }
+
+        let fresh = self.fresh();
+
+        if read {
+            self.statements.push(asm!((mov fresh, temporary)));
+        }
+
+        if write {
+            stores.push(asm!((mov temporary, fresh)));
+        }
+
+        fresh
+    }
+
+    /// Loads `memory` into a fresh temporary before the current statement,
+    /// and stores it back after.
+    fn shuttle(
+        &mut self,
+        memory: &Memory<Temporary>,
+        stores: &mut Vec<asm::Statement<Temporary>>,
+    ) -> Temporary {
+        let memory = self.memory(memory);
+        let fresh = self.fresh();
+        self.statements.push(asm!((mov fresh, memory)));
+        stores.push(asm!((mov memory, fresh)));
+        fresh
+    }
+
+    fn memory(&mut self, memory: &Memory<Temporary>) -> Memory<Temporary> {
+        memory.map(|temporary| self.load(*temporary))
+    }
+
+    fn fresh(&mut self) -> Temporary {
+        let temporary = Temporary::fresh("spill");
+        self.unspillable.insert(temporary);
+        temporary
+    }
+
+    fn is_spilled(&self, temporary: &Temporary) -> bool {
+        self.spilled.contains_key(temporary)
+    }
+}
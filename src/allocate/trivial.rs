@@ -23,7 +23,8 @@ impl Trivial {
             asm::Statement::Nullary(_)
             | asm::Statement::Label(_)
             | asm::Statement::Jmp(_)
-            | asm::Statement::Jcc(_, _) => {}
+            | asm::Statement::Jcc(_, _)
+            | asm::Statement::Debug(_) => {}
             asm::Statement::Binary(_, operands) => self.allocate_binary(operands),
             asm::Statement::Unary(_, operand) => self.allocate_unary(operand),
         }
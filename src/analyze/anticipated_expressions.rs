@@ -16,7 +16,7 @@ impl<T: lir::Target> Analysis<lir::Function<T>> for AnticipatedExpressions {
 
     fn transfer(&self, statement: &lir::Statement<T>, output: &mut Self::Data) {
         match statement {
-            lir::Statement::Jump(_) | lir::Statement::Label(_) => (),
+            lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => (),
             lir::Statement::CJump {
                 condition: _,
                 left,
@@ -69,6 +69,7 @@ impl<T: lir::Target> Analysis<lir::Function<T>> for AvailableExpressions<T> {
             lir::Statement::Jump(_)
             | lir::Statement::CJump { .. }
             | lir::Statement::Label(_)
+            | lir::Statement::Debug(_)
             | lir::Statement::Return(_) => (),
             lir::Statement::Call(_, _, returns) => {
                 for r#return in returns {
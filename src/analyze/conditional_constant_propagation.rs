@@ -11,12 +11,15 @@ use crate::Map;
 
 pub struct ConditionalConstantPropagation {
     enter: Label,
+    arguments: Vec<Temporary>,
 }
 
 impl ConditionalConstantPropagation {
     pub fn new<T: lir::Target>(cfg: &Cfg<lir::Function<T>>) -> Self {
+        let (arguments, _, _) = cfg.metadata();
         ConditionalConstantPropagation {
             enter: *cfg.enter(),
+            arguments: arguments.clone(),
         }
     }
 }
@@ -65,6 +68,7 @@ impl<T: lir::Target> Analysis<lir::Function<T>> for ConditionalConstantPropagati
                     lir::Statement::Jump(_)
                     | lir::Statement::Call(_, _, _)
                     | lir::Statement::Label(_)
+                    | lir::Statement::Debug(_)
                     | lir::Statement::Move { .. }
                     | lir::Statement::Return(_) => Reachable::Linear(false),
                 };
@@ -75,6 +79,7 @@ impl<T: lir::Target> Analysis<lir::Function<T>> for ConditionalConstantPropagati
         match statement {
             lir::Statement::Jump(_)
             | lir::Statement::Label(_)
+            | lir::Statement::Debug(_)
             | lir::Statement::Move {
                 destination: lir::Expression::Memory(_),
                 source: _,
@@ -145,7 +150,13 @@ impl<T: lir::Target> Analysis<lir::Function<T>> for ConditionalConstantPropagati
         I: Iterator<Item = (&'a Edge, Option<&'a Self::Data>)>,
         Self::Data: 'a,
     {
-        input.constants.clear();
+        // Arguments are never defined within the function, so they would
+        // otherwise be mistaken for constants that haven't been reached yet.
+        input.constants = self
+            .arguments
+            .iter()
+            .map(|argument| (*argument, Constant::Overdefined))
+            .collect();
 
         let reachable = match &mut input.reachable {
             Reachable::Linear(reachable) => reachable,
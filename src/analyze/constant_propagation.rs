@@ -48,7 +48,7 @@ impl Analysis<asm::Function<Temporary>> for ConstantPropagation {
                 }
                 operand::Binary::MR { .. } | operand::Binary::MI { .. } => (),
             },
-            asm::Statement::Binary(Lea, operands) => {
+            asm::Statement::Binary(Lea | Load { .. }, operands) => {
                 if let util::Or::L(temporary) = operands.destination() {
                     output.remove(&temporary);
                 }
@@ -88,8 +88,8 @@ impl Analysis<asm::Function<Temporary>> for ConstantPropagation {
                     operand::Binary::MI { .. } | operand::Binary::MR { .. } => (),
                 }
             }
-            asm::Statement::Binary(Cmp, _) => (),
-            asm::Statement::Unary(unary @ (Hul | Div | Mod), operand) => {
+            asm::Statement::Binary(Cmp | Store(_), _) => (),
+            asm::Statement::Unary(unary @ (Hul | Div | Mod | UHul | UDiv | UMod), operand) => {
                 let immediate = match operand {
                     operand::Unary::I(immediate) => Some(immediate),
                     operand::Unary::R(temporary) => output.get(temporary),
@@ -143,7 +143,8 @@ impl Analysis<asm::Function<Temporary>> for ConstantPropagation {
             asm::Statement::Nullary(Nop | Ret(_))
             | asm::Statement::Label(_)
             | asm::Statement::Jmp(_)
-            | asm::Statement::Jcc(_, _) => (),
+            | asm::Statement::Jcc(_, _)
+            | asm::Statement::Debug(_) => (),
         }
     }
 
@@ -190,7 +191,11 @@ fn fold_binary(
         asm::Binary::And => destination & source,
         asm::Binary::Or => destination | source,
         asm::Binary::Xor => destination ^ source,
-        asm::Binary::Cmp | asm::Binary::Mov | asm::Binary::Lea => return None,
+        asm::Binary::Cmp
+        | asm::Binary::Mov
+        | asm::Binary::Lea
+        | asm::Binary::Load { .. }
+        | asm::Binary::Store(_) => return None,
     };
 
     Some(Immediate::Integer(integer))
@@ -216,6 +221,15 @@ fn fold_unary(
         ),
         asm::Unary::Div | asm::Unary::Mod if source == 0 => return None,
         asm::Unary::Div | asm::Unary::Mod => (destination / source, destination % source),
+        asm::Unary::UHul => (
+            destination.wrapping_mul(source),
+            (((destination as u64 as u128) * (source as u64 as u128)) >> 64) as i64,
+        ),
+        asm::Unary::UDiv | asm::Unary::UMod if source == 0 => return None,
+        asm::Unary::UDiv | asm::Unary::UMod => (
+            ((destination as u64) / (source as u64)) as i64,
+            ((destination as u64) % (source as u64)) as i64,
+        ),
     };
 
     Some((Immediate::Integer(rax), Immediate::Integer(rdx)))
@@ -29,6 +29,7 @@ impl<T: lir::Target> Analysis<lir::Function<T>> for CopyPropagation {
             lir::Statement::Jump(_)
             | lir::Statement::CJump { .. }
             | lir::Statement::Label(_)
+            | lir::Statement::Debug(_)
             | lir::Statement::Return(_) => (),
             lir::Statement::Call(_, _, returns) => {
                 for r#return in returns {
@@ -95,13 +96,17 @@ impl Analysis<asm::Function<Temporary>> for CopyPropagation {
                 }
                 operand::Binary::MR { .. } | operand::Binary::MI { .. } => (),
             },
-            asm::Statement::Binary(Lea | Add | Sub | Shl | Mul | And | Or | Xor, operands) => {
+            asm::Statement::Binary(
+                Lea | Load { .. } | Add | Sub | Shl | Mul | And | Or | Xor,
+                operands,
+            ) => {
                 if let util::Or::L(temporary) = operands.destination() {
                     remove(output, &temporary);
                 }
             }
-            asm::Statement::Binary(Cmp, _) => (),
-            asm::Statement::Unary(Hul | Div | Mod, _) | asm::Statement::Nullary(Cqo) => {
+            asm::Statement::Binary(Cmp | Store(_), _) => (),
+            asm::Statement::Unary(Hul | Div | Mod | UHul | UDiv | UMod, _)
+            | asm::Statement::Nullary(Cqo) => {
                 remove(output, &Temporary::Register(Register::Rax));
                 remove(output, &Temporary::Register(Register::Rdx));
             }
@@ -133,7 +138,8 @@ impl Analysis<asm::Function<Temporary>> for CopyPropagation {
             asm::Statement::Nullary(Nop | Ret(_))
             | asm::Statement::Label(_)
             | asm::Statement::Jmp(_)
-            | asm::Statement::Jcc(_, _) => (),
+            | asm::Statement::Jcc(_, _)
+            | asm::Statement::Debug(_) => (),
         }
     }
 
@@ -132,8 +132,11 @@ impl LiveRanges {
                         operand::Unary::I(Immediate::Label(Label::Fixed(label))),
                     ) if symbol::resolve(*label) == abi::XI_OUT_OF_BOUNDS => Clobbered::None,
                     asm::Statement::Unary(asm::Unary::Call { .. }, _) => Clobbered::Caller,
-                    asm::Statement::Unary(asm::Unary::Div, _) => Clobbered::Rdx,
-                    asm::Statement::Unary(asm::Unary::Hul | asm::Unary::Mod, _) => Clobbered::Rax,
+                    asm::Statement::Unary(asm::Unary::Div | asm::Unary::UDiv, _) => Clobbered::Rdx,
+                    asm::Statement::Unary(
+                        asm::Unary::Hul | asm::Unary::Mod | asm::Unary::UHul | asm::Unary::UMod,
+                        _,
+                    ) => Clobbered::Rax,
                     asm::Statement::Nullary(asm::Nullary::Cqo) => Clobbered::Rdx,
                     _ => Clobbered::None,
                 };
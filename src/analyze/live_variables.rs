@@ -72,7 +72,10 @@ trait Function: cfg::Function {
 impl Function for asm::Function<Temporary> {
     fn transfer(statement: &Self::Statement, output: &mut Set<Temporary>) {
         match statement {
-            asm::Statement::Label(_) | asm::Statement::Jmp(_) | asm::Statement::Jcc(_, _) => {}
+            asm::Statement::Label(_)
+            | asm::Statement::Jmp(_)
+            | asm::Statement::Jcc(_, _)
+            | asm::Statement::Debug(_) => {}
             asm::Statement::Nullary(asm::Nullary::Nop) => {}
 
             asm::Statement::Nullary(asm::Nullary::Cqo) if dead_assembly(Register::Rdx, output) => {
@@ -97,6 +100,7 @@ impl Function for asm::Function<Temporary> {
             asm::Statement::Binary(
                 asm::Binary::Mov
                 | asm::Binary::Lea
+                | asm::Binary::Load { .. }
                 | asm::Binary::Add
                 | asm::Binary::Sub
                 | asm::Binary::Shl
@@ -108,7 +112,10 @@ impl Function for asm::Function<Temporary> {
             ) if dead_assembly(operands.destination(), output) => (),
             asm::Statement::Binary(binary, operands) => {
                 match (binary, operands.destination()) {
-                    (asm::Binary::Mov | asm::Binary::Lea, Or::L(temporary)) => {
+                    (
+                        asm::Binary::Mov | asm::Binary::Lea | asm::Binary::Load { .. },
+                        Or::L(temporary),
+                    ) => {
                         output.remove(&temporary);
                     }
                     (
@@ -125,6 +132,9 @@ impl Function for asm::Function<Temporary> {
                         // Both uses and defines `temporary`
                         output.insert(temporary);
                     }
+                    (asm::Binary::Store(_), Or::L(_)) => {
+                        unreachable!("[INTERNAL ERROR]: narrow store into register")
+                    }
                     (_, Or::R(memory)) => {
                         memory.map(|temporary| output.insert(*temporary));
                     }
@@ -159,19 +169,31 @@ impl Function for asm::Function<Temporary> {
             }
 
             // We don't check `div` and `mod` as they can have side effects (x / 0, x % 0)
-            asm::Statement::Unary(asm::Unary::Hul, _) if dead_assembly(Register::Rdx, output) => (),
+            asm::Statement::Unary(asm::Unary::Hul | asm::Unary::UHul, _)
+                if dead_assembly(Register::Rdx, output) => {}
             asm::Statement::Unary(
-                unary @ (asm::Unary::Hul | asm::Unary::Div | asm::Unary::Mod),
+                unary @ (asm::Unary::Hul
+                | asm::Unary::Div
+                | asm::Unary::Mod
+                | asm::Unary::UHul
+                | asm::Unary::UDiv
+                | asm::Unary::UMod),
                 operand,
             ) => {
-                if matches!(unary, asm::Unary::Hul | asm::Unary::Mod) {
+                if matches!(
+                    unary,
+                    asm::Unary::Hul | asm::Unary::Mod | asm::Unary::UHul | asm::Unary::UMod
+                ) {
                     output.remove(&Temporary::Register(Register::Rdx));
                 }
 
                 // Both uses and defines `rax`
                 output.insert(Temporary::Register(Register::Rax));
 
-                if matches!(unary, asm::Unary::Div | asm::Unary::Mod) {
+                if matches!(
+                    unary,
+                    asm::Unary::Div | asm::Unary::Mod | asm::Unary::UDiv | asm::Unary::UMod
+                ) {
                     output.insert(Temporary::Register(Register::Rdx));
                 }
 
@@ -212,7 +234,7 @@ fn dead_assembly<I: Into<operand::Unary<Temporary>>>(
 impl<T: lir::Target> Function for lir::Function<T> {
     fn transfer(statement: &Self::Statement, output: &mut Set<Temporary>) {
         match statement {
-            lir::Statement::Jump(_) | lir::Statement::Label(_) => (),
+            lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => (),
             lir::Statement::CJump {
                 condition: _,
                 left,
@@ -46,7 +46,7 @@ impl<T: lir::Target> Analysis<lir::Function<T>> for PostponableExpressions<T> {
         output.extend(self.earliest[label][index].iter().cloned());
 
         match statement {
-            lir::Statement::Jump(_) | lir::Statement::Label(_) => (),
+            lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => (),
             lir::Statement::CJump {
                 condition: _,
                 left,
@@ -44,7 +44,7 @@ impl<T: lir::Target> Analysis<lir::Function<T>> for UsedExpressions<T> {
         output: &mut Self::Data,
     ) {
         match statement {
-            lir::Statement::Jump(_) | lir::Statement::Label(_) => (),
+            lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => (),
             lir::Statement::CJump {
                 condition: _,
                 left,
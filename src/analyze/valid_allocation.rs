@@ -36,7 +36,10 @@ impl<'a> Analysis<asm::Function<Temporary>> for ValidAllocation<'a> {
 
     fn transfer(&self, statement: &asm::Statement<Temporary>, output: &mut Self::Data) {
         match statement {
-            asm::Statement::Label(_) | asm::Statement::Jmp(_) | asm::Statement::Jcc(_, _) => (),
+            asm::Statement::Label(_)
+            | asm::Statement::Jmp(_)
+            | asm::Statement::Jcc(_, _)
+            | asm::Statement::Debug(_) => (),
             asm::Statement::Binary(binary, operands) => {
                 let access = match binary {
                     asm::Binary::Add
@@ -47,7 +50,10 @@ impl<'a> Analysis<asm::Function<Temporary>> for ValidAllocation<'a> {
                     | asm::Binary::Xor
                     | asm::Binary::Shl => Access::ReadWrite,
                     asm::Binary::Cmp => Access::Read,
-                    asm::Binary::Mov | asm::Binary::Lea => Access::Write,
+                    asm::Binary::Mov
+                    | asm::Binary::Lea
+                    | asm::Binary::Load { .. }
+                    | asm::Binary::Store(_) => Access::Write,
                 };
 
                 self.transfer_binary(output, access, operands)
@@ -86,7 +92,12 @@ impl<'a> Analysis<asm::Function<Temporary>> for ValidAllocation<'a> {
                         );
                     }
                 }
-                asm::Unary::Hul | asm::Unary::Mod | asm::Unary::Div => {
+                asm::Unary::Hul
+                | asm::Unary::Mod
+                | asm::Unary::Div
+                | asm::Unary::UHul
+                | asm::Unary::UMod
+                | asm::Unary::UDiv => {
                     self.transfer_unary(
                         output,
                         Access::Read,
@@ -100,8 +111,10 @@ impl<'a> Analysis<asm::Function<Temporary>> for ValidAllocation<'a> {
                     );
 
                     let (written, clobbered) = match unary {
-                        asm::Unary::Div => (Register::Rax, Register::Rdx),
-                        asm::Unary::Hul | asm::Unary::Mod => (Register::Rdx, Register::Rax),
+                        asm::Unary::Div | asm::Unary::UDiv => (Register::Rax, Register::Rdx),
+                        asm::Unary::Hul | asm::Unary::Mod | asm::Unary::UHul | asm::Unary::UMod => {
+                            (Register::Rdx, Register::Rax)
+                        }
                         _ => unreachable!(),
                     };
 
@@ -1,5 +1,11 @@
+mod att;
+mod dwarf;
+mod elf;
+mod encode;
 mod print;
 mod tile;
 
+pub(crate) use att::Att;
+pub(crate) use elf::elf;
 pub(crate) use print::Intel;
 pub use tile::tile;
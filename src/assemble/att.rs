@@ -0,0 +1,362 @@
+use std::fmt;
+use std::iter;
+
+use crate::abi;
+use crate::assemble::dwarf;
+use crate::data::asm;
+use crate::data::asm::Directive;
+use crate::data::asm::Statement;
+use crate::data::debug::Debug;
+use crate::data::ir;
+use crate::data::operand::Binary;
+use crate::data::operand::Immediate;
+use crate::data::operand::Label;
+use crate::data::operand::Memory;
+use crate::data::operand::Operand;
+use crate::data::operand::Unary;
+use crate::data::token::Size;
+
+/// AT&T syntax printer, which mirrors `print::Intel` with operands reversed
+/// and explicit size suffixes.
+pub struct Att<T>(pub T);
+
+impl<T: Operand> fmt::Display for Att<&asm::Unit<T>> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(fmt, "{}\n", Directive::Att)?;
+
+        let files = dwarf::Files::new(self.0.functions.values());
+
+        for file in files.directives() {
+            writeln!(fmt, "{}", file)?;
+        }
+
+        if !files.is_empty() {
+            writeln!(fmt)?;
+        }
+
+        writeln!(fmt, "{}\n", Directive::Data)?;
+        writeln!(fmt, "{}", Directive::Align(abi::WORD as usize))?;
+
+        for (label, data) in &self.0.data {
+            writeln!(fmt, "{}", Directive::Linkage(ir::Linkage::Local, *label))?;
+            writeln!(fmt, "{}", Att(&Statement::<T>::Label(*label)))?;
+            writeln!(fmt, "{}\n", Directive::Quad(data.clone()))?;
+        }
+
+        writeln!(fmt, "{}\n", Directive::Bss)?;
+        writeln!(fmt, "{}", Directive::Align(abi::WORD as usize))?;
+
+        for (symbol, (linkage, size)) in &self.0.bss {
+            writeln!(
+                fmt,
+                "{}",
+                Directive::Linkage(*linkage, Label::Fixed(*symbol))
+            )?;
+            writeln!(
+                fmt,
+                "{}",
+                Att(&Statement::<T>::Label(Label::Fixed(*symbol))),
+            )?;
+            writeln!(fmt, "{}\n", Directive::Space(*size * abi::WORD as usize),)?;
+        }
+
+        for (name, priority) in [(abi::XI_INIT_CLASSES, 65534), (abi::XI_INIT_GLOBALS, 65533)] {
+            writeln!(fmt, "{}", Directive::Ctors(priority))?;
+            writeln!(fmt, "{}", Directive::Align(abi::WORD as usize))?;
+            writeln!(fmt, "{}\n", Directive::Quad(vec![Immediate::from(name)]))?;
+        }
+
+        writeln!(fmt, "{}\n", Directive::Text)?;
+
+        for (name, function) in &self.0.functions {
+            writeln!(
+                fmt,
+                "{}",
+                Directive::Linkage(function.linkage, Label::Fixed(*name))
+            )?;
+            writeln!(fmt, "{}", Att((function, &files)))?;
+        }
+
+        if !files.is_empty() {
+            write!(fmt, "{}", dwarf::Dwarf(self.0))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Att<&asm::Function<T>> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let files = dwarf::Files::new(iter::once(self.0));
+        write!(fmt, "{}", Att((self.0, &files)))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Att<(&asm::Function<T>, &dwarf::Files)> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let (function, files) = self.0;
+
+        writeln!(fmt, "{}:", function.name)?;
+
+        assert!(
+            matches!(function.statements.first(), Some(Statement::Label(label)) if *label == function.enter)
+        );
+
+        let prologue = dwarf::prologue(function);
+
+        if let Some(location) = prologue.and_then(|span| files.location(span)) {
+            writeln!(fmt, "  {}", location)?;
+        }
+
+        for statement in function.statements.iter().skip(1) {
+            match statement {
+                Statement::Label(_) => (),
+                Statement::Debug(Debug::Location(span)) => {
+                    if let Some(location) = files.location(span) {
+                        writeln!(fmt, "  {}", location)?;
+                        continue;
+                    }
+                    write!(fmt, "  ")?;
+                }
+                _ => write!(fmt, "  ")?,
+            }
+
+            writeln!(fmt, "{}", Att(statement))?;
+        }
+
+        if prologue.is_some() {
+            writeln!(fmt, "{}:", dwarf::End(function.name))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Att<&Statement<T>> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Statement::Binary(
+                asm::Binary::Load { signed, size },
+                Binary::RM {
+                    destination,
+                    source,
+                },
+            ) => {
+                let (binary, bits) = match (signed, size) {
+                    (true, Size::_8) => ("movsbq", 64),
+                    (true, Size::_16) => ("movswq", 64),
+                    (true, Size::_32) => ("movslq", 64),
+                    (false, Size::_8) => ("movzbq", 64),
+                    (false, Size::_16) => ("movzwq", 64),
+                    // Writing to a 32-bit register implicitly zeroes the upper 32 bits
+                    (false, Size::_32) => ("movl", 32),
+                    (_, Size::_64) => ("movq", 64),
+                };
+
+                write!(
+                    fmt,
+                    "{} {}, {}",
+                    binary,
+                    Att(source),
+                    Register(destination, bits),
+                )
+            }
+            Statement::Binary(
+                asm::Binary::Store(size),
+                Binary::MR {
+                    destination,
+                    source,
+                },
+            ) => write!(
+                fmt,
+                "mov{} {}, {}",
+                Suffix(*size),
+                Register(source, size.bits() as usize),
+                Att(destination),
+            ),
+            Statement::Binary(
+                asm::Binary::Store(size),
+                Binary::MI {
+                    destination,
+                    source,
+                },
+            ) => write!(
+                fmt,
+                "mov{} {}, {}",
+                Suffix(*size),
+                Att(source),
+                Att(destination),
+            ),
+            Statement::Binary(asm::Binary::Load { .. } | asm::Binary::Store(_), _) => {
+                panic!("[INTERNAL ERROR]: invalid operands for narrow memory access")
+            }
+            Statement::Binary(
+                asm::Binary::Mov,
+                Binary::RI {
+                    destination,
+                    source: source @ Immediate::Integer(_),
+                },
+            ) if source.is_64_bit() => {
+                write!(
+                    fmt,
+                    "movabsq {}, {}",
+                    Att(source),
+                    Register(destination, 64)
+                )
+            }
+            Statement::Binary(binary, operands) => {
+                write!(fmt, "{}q {}", binary, Att(operands))
+            }
+            Statement::Unary(
+                unary @ asm::Unary::Call { .. },
+                Unary::I(Immediate::Label(label)),
+            ) => {
+                write!(fmt, "{} {}", unary, label)
+            }
+            Statement::Unary(unary @ asm::Unary::Call { .. }, operand) => {
+                write!(fmt, "{} *{}", unary, Att(operand))
+            }
+            Statement::Unary(unary, operand) => {
+                write!(fmt, "{}q {}", unary, Att(operand))
+            }
+            Statement::Nullary(asm::Nullary::Cqo) => write!(fmt, "cqto"),
+            Statement::Nullary(nullary) => write!(fmt, "{}", nullary),
+            Statement::Label(label) => write!(fmt, "{}:", label),
+            Statement::Jmp(label) => write!(fmt, "jmp {}", label),
+            Statement::Jcc(condition, label) => write!(fmt, "j{} {}", condition, label),
+            Statement::Debug(debug) => write!(fmt, "{}", dwarf::Comment(debug)),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Att<&Binary<T>> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Binary::RI {
+                destination,
+                source,
+            } => write!(fmt, "{}, {}", Att(source), Register(destination, 64)),
+            Binary::MI {
+                destination,
+                source,
+            } => write!(fmt, "{}, {}", Att(source), Att(destination)),
+            Binary::MR {
+                destination,
+                source,
+            } => write!(fmt, "{}, {}", Register(source, 64), Att(destination)),
+            Binary::RM {
+                destination,
+                source,
+            } => write!(fmt, "{}, {}", Att(source), Register(destination, 64)),
+            Binary::RR {
+                destination,
+                source,
+            } => write!(
+                fmt,
+                "{}, {}",
+                Register(source, 64),
+                Register(destination, 64)
+            ),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Att<&Unary<T>> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Unary::I(immediate) => write!(fmt, "{}", Att(immediate)),
+            Unary::R(register) => write!(fmt, "{}", Register(register, 64)),
+            Unary::M(memory) => write!(fmt, "{}", Att(memory)),
+        }
+    }
+}
+
+impl fmt::Display for Att<&Immediate> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "${}", self.0)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Att<&Memory<T>> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Memory::B { base } => write!(fmt, "({})", Register(base, 64)),
+            Memory::O { offset } => write!(fmt, "{}", offset),
+            Memory::BI { base, index } => {
+                write!(fmt, "({}, {})", Register(base, 64), Register(index, 64))
+            }
+            Memory::BO { base, offset } => {
+                write!(fmt, "{}({})", offset, Register(base, 64))
+            }
+            Memory::BIO {
+                base,
+                index,
+                offset,
+            } => {
+                write!(
+                    fmt,
+                    "{}({}, {})",
+                    offset,
+                    Register(base, 64),
+                    Register(index, 64)
+                )
+            }
+            Memory::BIS { base, index, scale } => {
+                write!(
+                    fmt,
+                    "({}, {}, {})",
+                    Register(base, 64),
+                    Register(index, 64),
+                    scale
+                )
+            }
+            Memory::ISO {
+                index,
+                scale,
+                offset,
+            } => {
+                write!(fmt, "{}(, {}, {})", offset, Register(index, 64), scale)
+            }
+            Memory::BISO {
+                base,
+                index,
+                scale,
+                offset,
+            } => {
+                write!(
+                    fmt,
+                    "{}({}, {}, {})",
+                    offset,
+                    Register(base, 64),
+                    Register(index, 64),
+                    scale
+                )
+            }
+        }
+    }
+}
+
+/// Register operand with the given width in bits, which may be a temporary
+/// before register allocation.
+struct Register<'a, T>(&'a T, usize);
+
+impl<T: fmt::Display> fmt::Display for Register<'_, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "%{:.*}", self.1, self.0)
+    }
+}
+
+struct Suffix(Size);
+
+impl fmt::Display for Suffix {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let suffix = match self.0 {
+            Size::_8 => 'b',
+            Size::_16 => 'w',
+            Size::_32 => 'l',
+            Size::_64 => 'q',
+        };
+
+        write!(fmt, "{}", suffix)
+    }
+}
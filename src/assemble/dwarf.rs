@@ -0,0 +1,481 @@
+//! DWARF debugging information for units compiled with `-g`, emitted as
+//! assembler directives. The assembler builds the line table itself from
+//! `.file` and `.loc` directives, while `.debug_info` describes each function
+//! and the variables it keeps in registers or spill slots.
+//!
+//! Variables are given a single location for their whole function, so a
+//! register shared between several variables with disjoint live ranges shows
+//! whichever one was most recently written.
+
+use std::fmt;
+use std::fmt::Write as _;
+
+use crate::abi;
+use crate::data::asm;
+use crate::data::asm::Directive;
+use crate::data::asm::Statement;
+use crate::data::debug::Debug;
+use crate::data::ir;
+use crate::data::operand::Immediate;
+use crate::data::operand::Memory;
+use crate::data::operand::Operand;
+use crate::data::operand::Unary;
+use crate::data::r#type;
+use crate::data::span::Span;
+use crate::data::symbol;
+use crate::data::symbol::Symbol;
+use crate::data::token::Size;
+use crate::Set;
+
+// Xi has no DWARF language code, but its types are all expressible in C.
+const DW_LANG_C99: u16 = 0x0c;
+
+const DW_TAG_COMPILE_UNIT: u8 = 0x11;
+const DW_TAG_SUBPROGRAM: u8 = 0x2e;
+const DW_TAG_VARIABLE: u8 = 0x34;
+const DW_TAG_BASE_TYPE: u8 = 0x24;
+const DW_TAG_POINTER_TYPE: u8 = 0x0f;
+const DW_TAG_STRUCTURE_TYPE: u8 = 0x13;
+
+const DW_AT_LOCATION: u8 = 0x02;
+const DW_AT_NAME: u8 = 0x03;
+const DW_AT_BYTE_SIZE: u8 = 0x0b;
+const DW_AT_STMT_LIST: u8 = 0x10;
+const DW_AT_LOW_PC: u8 = 0x11;
+const DW_AT_HIGH_PC: u8 = 0x12;
+const DW_AT_LANGUAGE: u8 = 0x13;
+const DW_AT_PRODUCER: u8 = 0x25;
+const DW_AT_DECLARATION: u8 = 0x3c;
+const DW_AT_ENCODING: u8 = 0x3e;
+const DW_AT_EXTERNAL: u8 = 0x3f;
+const DW_AT_TYPE: u8 = 0x49;
+const DW_AT_RANGES: u8 = 0x55;
+const DW_AT_LINKAGE_NAME: u8 = 0x6e;
+
+const DW_FORM_ADDR: u8 = 0x01;
+const DW_FORM_DATA1: u8 = 0x0b;
+const DW_FORM_DATA2: u8 = 0x05;
+const DW_FORM_DATA8: u8 = 0x07;
+const DW_FORM_STRING: u8 = 0x08;
+const DW_FORM_REF4: u8 = 0x13;
+const DW_FORM_SEC_OFFSET: u8 = 0x17;
+const DW_FORM_EXPRLOC: u8 = 0x18;
+const DW_FORM_FLAG_PRESENT: u8 = 0x19;
+
+const DW_ATE_BOOLEAN: u8 = 0x02;
+const DW_ATE_SIGNED: u8 = 0x05;
+const DW_ATE_UNSIGNED: u8 = 0x08;
+
+const DW_OP_REG0: u8 = 0x50;
+const DW_OP_BREG0: u8 = 0x70;
+
+/// Abbreviation codes, which must match `ABBREVIATIONS`.
+#[derive(Copy, Clone)]
+enum Abbreviation {
+    CompileUnit = 1,
+    Subprogram,
+    LocalSubprogram,
+    Variable,
+    BaseType,
+    PointerType,
+    VoidPointerType,
+    StructureType,
+}
+
+/// Attribute names and forms of an abbreviation.
+type Attributes = &'static [(u8, u8)];
+
+/// Tag, whether it has children, and attributes of each abbreviation.
+#[rustfmt::skip]
+const ABBREVIATIONS: &[(u8, bool, Attributes)] = &[
+    (DW_TAG_COMPILE_UNIT, true, &[
+        (DW_AT_PRODUCER, DW_FORM_STRING),
+        (DW_AT_LANGUAGE, DW_FORM_DATA2),
+        (DW_AT_NAME, DW_FORM_STRING),
+        (DW_AT_LOW_PC, DW_FORM_ADDR),
+        (DW_AT_RANGES, DW_FORM_SEC_OFFSET),
+        (DW_AT_STMT_LIST, DW_FORM_SEC_OFFSET),
+    ]),
+    (DW_TAG_SUBPROGRAM, true, &[
+        (DW_AT_NAME, DW_FORM_STRING),
+        (DW_AT_LINKAGE_NAME, DW_FORM_STRING),
+        (DW_AT_EXTERNAL, DW_FORM_FLAG_PRESENT),
+        (DW_AT_LOW_PC, DW_FORM_ADDR),
+        (DW_AT_HIGH_PC, DW_FORM_DATA8),
+    ]),
+    (DW_TAG_SUBPROGRAM, true, &[
+        (DW_AT_NAME, DW_FORM_STRING),
+        (DW_AT_LINKAGE_NAME, DW_FORM_STRING),
+        (DW_AT_LOW_PC, DW_FORM_ADDR),
+        (DW_AT_HIGH_PC, DW_FORM_DATA8),
+    ]),
+    (DW_TAG_VARIABLE, false, &[
+        (DW_AT_NAME, DW_FORM_STRING),
+        (DW_AT_TYPE, DW_FORM_REF4),
+        (DW_AT_LOCATION, DW_FORM_EXPRLOC),
+    ]),
+    (DW_TAG_BASE_TYPE, false, &[
+        (DW_AT_NAME, DW_FORM_STRING),
+        (DW_AT_ENCODING, DW_FORM_DATA1),
+        (DW_AT_BYTE_SIZE, DW_FORM_DATA1),
+    ]),
+    (DW_TAG_POINTER_TYPE, false, &[
+        (DW_AT_BYTE_SIZE, DW_FORM_DATA1),
+        (DW_AT_TYPE, DW_FORM_REF4),
+    ]),
+    (DW_TAG_POINTER_TYPE, false, &[
+        (DW_AT_BYTE_SIZE, DW_FORM_DATA1),
+    ]),
+    (DW_TAG_STRUCTURE_TYPE, false, &[
+        (DW_AT_NAME, DW_FORM_STRING),
+        (DW_AT_DECLARATION, DW_FORM_FLAG_PRESENT),
+    ]),
+];
+
+/// Source files referenced by source locations, numbered from 1.
+pub(super) struct Files(Set<Symbol>);
+
+impl Files {
+    pub(super) fn new<'a, T: 'a, I>(functions: I) -> Self
+    where
+        I: IntoIterator<Item = &'a asm::Function<T>>,
+    {
+        Files(
+            functions
+                .into_iter()
+                .flat_map(|function| &function.statements)
+                .filter_map(|statement| match statement {
+                    Statement::Debug(Debug::Location(span)) => span.path(),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(super) fn directives(&self) -> impl Iterator<Item = Directive> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(index, path)| Directive::File(index + 1, *path))
+    }
+
+    /// Location directive for `span`, unless it doesn't point into a file.
+    pub(super) fn location(&self, span: &Span) -> Option<Directive> {
+        let index = self.0.get_index_of(&span.path()?)?;
+        Some(Directive::Location(index + 1, *span))
+    }
+}
+
+/// Source location of a function's first statement, which it also attributes
+/// its prologue to. Functions without one have no debugging information.
+pub(super) fn prologue<T>(function: &asm::Function<T>) -> Option<&Span> {
+    function
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            Statement::Debug(Debug::Location(span)) => Some(span),
+            _ => None,
+        })
+}
+
+/// Local label marking the end of a function with debugging information.
+pub(super) struct End(pub(super) Symbol);
+
+impl fmt::Display for End {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, ".L{}_end", self.0)
+    }
+}
+
+/// Comment describing a debugging marker, which has no encoding of its own.
+///
+/// Uses C-style delimiters because assembly files are run through the C
+/// preprocessor, which would interpret a line starting with `#`.
+pub(super) struct Comment<'a, T>(pub(super) &'a Debug<T>);
+
+impl<T: fmt::Display> fmt::Display for Comment<'_, Unary<T>> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Debug::Location(span) => match span.path() {
+                Some(path) => write!(fmt, "/* {}:{} */", path, span),
+                None => write!(fmt, "/* {} */", span),
+            },
+            Debug::Variable(name, r#type, Unary::R(register)) => {
+                write!(fmt, "/* {}: {} in {} */", name, r#type, register)
+            }
+            Debug::Variable(name, r#type, _) => {
+                write!(fmt, "/* {}: {} in memory */", name, r#type)
+            }
+        }
+    }
+}
+
+/// The `.debug_abbrev`, `.debug_info`, `.debug_ranges`, and `.debug_line`
+/// sections of a unit.
+pub(super) struct Dwarf<'a, T>(pub(super) &'a asm::Unit<T>);
+
+impl<T: Operand> fmt::Display for Dwarf<'_, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let functions = self
+            .0
+            .functions
+            .values()
+            .filter(|function| prologue(function).is_some())
+            .collect::<Vec<_>>();
+
+        writeln!(fmt, ".section .debug_abbrev,\"\",@progbits")?;
+        writeln!(fmt, ".Ldebug_abbrev0:")?;
+
+        for (code, (tag, children, attributes)) in ABBREVIATIONS.iter().enumerate() {
+            writeln!(fmt, "  .uleb128 {}", code + 1)?;
+            writeln!(fmt, "  .uleb128 {:#x}", tag)?;
+            writeln!(fmt, "  .byte {}", *children as u8)?;
+            for (attribute, form) in *attributes {
+                writeln!(fmt, "  .uleb128 {:#x}", attribute)?;
+                writeln!(fmt, "  .uleb128 {:#x}", form)?;
+            }
+            writeln!(fmt, "  .byte 0\n  .byte 0")?;
+        }
+
+        writeln!(fmt, "  .byte 0\n")?;
+
+        writeln!(fmt, ".section .debug_info,\"\",@progbits")?;
+        writeln!(fmt, ".Ldebug_info0:")?;
+        writeln!(fmt, "  .long .Ldebug_info1 - .Ldebug_info0 - 4")?;
+        writeln!(fmt, "  .value 4")?;
+        writeln!(fmt, "  .long .Ldebug_abbrev0")?;
+        writeln!(fmt, "  .byte {}", abi::WORD)?;
+
+        Abbreviation::CompileUnit.fmt(fmt)?;
+        Text(&format!("xic {}", env!("CARGO_PKG_VERSION"))).fmt(fmt)?;
+        writeln!(fmt, "  .value {:#x}", DW_LANG_C99)?;
+        Text(symbol::resolve(self.0.name)).fmt(fmt)?;
+        writeln!(fmt, "  .quad 0")?;
+        writeln!(fmt, "  .long .Ldebug_ranges0")?;
+        writeln!(fmt, "  .long .Ldebug_line0")?;
+
+        let mut types = Set::default();
+
+        for function in &functions {
+            let name = symbol::resolve(function.name);
+            let demangled = abi::demangle::demangle(name);
+
+            // Debuggers look functions up by their source name, without the signature
+            let source = demangled
+                .as_deref()
+                .and_then(|demangled| demangled.split('(').next())
+                .unwrap_or(name);
+
+            match function.linkage {
+                ir::Linkage::Local => Abbreviation::LocalSubprogram.fmt(fmt)?,
+                ir::Linkage::Global | ir::Linkage::LinkOnceOdr => {
+                    Abbreviation::Subprogram.fmt(fmt)?
+                }
+            }
+
+            Text(source).fmt(fmt)?;
+            Text(name).fmt(fmt)?;
+            writeln!(fmt, "  .quad {}", name)?;
+            writeln!(fmt, "  .quad {} - {}", End(function.name), name)?;
+
+            for statement in &function.statements {
+                let (name, r#type, operand) = match statement {
+                    Statement::Debug(Debug::Variable(name, r#type, operand)) => {
+                        (name, r#type, operand)
+                    }
+                    _ => continue,
+                };
+
+                let location = match location(operand) {
+                    Some(location) => location,
+                    None => continue,
+                };
+
+                Abbreviation::Variable.fmt(fmt)?;
+                Text(symbol::resolve(*name)).fmt(fmt)?;
+                writeln!(
+                    fmt,
+                    "  .long {}",
+                    Type(insert(&mut types, Die::Type(r#type.clone())))
+                )?;
+                writeln!(fmt, "  .uleb128 {}", location.len())?;
+                Bytes(&location).fmt(fmt)?;
+            }
+
+            writeln!(fmt, "  .byte 0")?;
+        }
+
+        // Note: describing a type may insert its referents, so this can't be an iterator
+        let mut index = 0;
+        while let Some(r#type) = types.get_index(index).cloned() {
+            writeln!(fmt, ".Ldebug_type{}:", index)?;
+
+            match r#type {
+                Die::Type(r#type::Expression::Integer) => {
+                    Abbreviation::BaseType.fmt(fmt)?;
+                    Text("int").fmt(fmt)?;
+                    writeln!(fmt, "  .byte {:#x}", DW_ATE_SIGNED)?;
+                    writeln!(fmt, "  .byte {}", abi::WORD)?;
+                }
+                Die::Type(r#type @ r#type::Expression::Sized { signed, size }) => {
+                    Abbreviation::BaseType.fmt(fmt)?;
+                    Text(&r#type.to_string()).fmt(fmt)?;
+                    match signed {
+                        true => writeln!(fmt, "  .byte {:#x}", DW_ATE_SIGNED)?,
+                        false => writeln!(fmt, "  .byte {:#x}", DW_ATE_UNSIGNED)?,
+                    }
+                    writeln!(fmt, "  .byte {}", size.bytes())?;
+                }
+                Die::Type(r#type::Expression::Boolean) => {
+                    Abbreviation::BaseType.fmt(fmt)?;
+                    Text("bool").fmt(fmt)?;
+                    writeln!(fmt, "  .byte {:#x}", DW_ATE_BOOLEAN)?;
+                    writeln!(fmt, "  .byte {}", Size::_8.bytes())?;
+                }
+                // Arrays point to their first element, with the length stored just before it
+                Die::Type(r#type::Expression::Array(element)) => {
+                    Abbreviation::PointerType.fmt(fmt)?;
+                    writeln!(fmt, "  .byte {}", abi::WORD)?;
+                    writeln!(
+                        fmt,
+                        "  .long {}",
+                        Type(insert(&mut types, Die::Type(*element)))
+                    )?;
+                }
+                Die::Type(r#type::Expression::Class(class)) => {
+                    Abbreviation::PointerType.fmt(fmt)?;
+                    writeln!(fmt, "  .byte {}", abi::WORD)?;
+                    writeln!(
+                        fmt,
+                        "  .long {}",
+                        Type(insert(&mut types, Die::Structure(class)))
+                    )?;
+                }
+                // Closures point to their environment
+                Die::Type(
+                    r#type::Expression::Function(_, _)
+                    | r#type::Expression::Any
+                    | r#type::Expression::Null
+                    | r#type::Expression::Poison,
+                ) => {
+                    Abbreviation::VoidPointerType.fmt(fmt)?;
+                    writeln!(fmt, "  .byte {}", abi::WORD)?;
+                }
+                Die::Structure(class) => {
+                    Abbreviation::StructureType.fmt(fmt)?;
+                    Text(symbol::resolve(class)).fmt(fmt)?;
+                }
+            }
+
+            index += 1;
+        }
+
+        writeln!(fmt, "  .byte 0")?;
+        writeln!(fmt, ".Ldebug_info1:\n")?;
+
+        writeln!(fmt, ".section .debug_ranges,\"\",@progbits")?;
+        writeln!(fmt, ".Ldebug_ranges0:")?;
+        for function in &functions {
+            writeln!(fmt, "  .quad {0}, {1}", function.name, End(function.name))?;
+        }
+        writeln!(fmt, "  .quad 0, 0\n")?;
+
+        // The assembler appends the line table generated from `.loc` directives here
+        writeln!(fmt, ".section .debug_line,\"\",@progbits")?;
+        writeln!(fmt, ".Ldebug_line0:")
+    }
+}
+
+impl Abbreviation {
+    fn fmt(self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(fmt, "  .uleb128 {}", self as u8)
+    }
+}
+
+/// Location expression for a variable stored in `operand`, if it's a register
+/// or a fixed offset from one.
+fn location<T: Operand>(operand: &Unary<T>) -> Option<Vec<u8>> {
+    match operand {
+        Unary::R(register) => Some(vec![DW_OP_REG0 + register.register()?.dwarf()]),
+        Unary::M(Memory::BO {
+            base,
+            offset: Immediate::Integer(offset),
+        }) => {
+            let mut location = vec![DW_OP_BREG0 + base.register()?.dwarf()];
+            sleb128(&mut location, *offset);
+            Some(location)
+        }
+        Unary::M(_) | Unary::I(_) => None,
+    }
+}
+
+fn sleb128(bytes: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+            bytes.push(byte);
+            return;
+        }
+
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// Type described by a DIE in `.debug_info`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Die {
+    Type(r#type::Expression),
+
+    /// Opaque structure that instances of a class point to
+    Structure(Symbol),
+}
+
+fn insert(types: &mut Set<Die>, die: Die) -> usize {
+    types.insert_full(die).0
+}
+
+/// Reference to the DIE describing a type, relative to the start of the unit.
+struct Type(usize);
+
+impl fmt::Display for Type {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, ".Ldebug_type{} - .Ldebug_info0", self.0)
+    }
+}
+
+struct Text<'a>(&'a str);
+
+impl Text<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let mut escaped = String::new();
+        for byte in self.0.bytes() {
+            match byte {
+                b'"' | b'\\' => write!(escaped, "\\{}", byte as char)?,
+                b' '..=b'~' => escaped.push(byte as char),
+                _ => write!(escaped, "\\{:03o}", byte)?,
+            }
+        }
+        writeln!(fmt, "  .string \"{}\"", escaped)
+    }
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl Bytes<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "  .byte")?;
+        for (index, byte) in self.0.iter().enumerate() {
+            match index {
+                0 => write!(fmt, " {:#x}", byte)?,
+                _ => write!(fmt, ", {:#x}", byte)?,
+            }
+        }
+        writeln!(fmt)
+    }
+}
@@ -0,0 +1,631 @@
+//! Writes relocatable ELF64 object files for x86-64, with the same sections and
+//! symbols that `as` would produce from the output of `print::Intel`.
+//!
+//! `ir::Linkage::LinkOnceOdr` definitions are weak, and are additionally placed
+//! in their own COMDAT group so that the linker keeps only one copy.
+//!
+//! https://refspecs.linuxfoundation.org/elf/gabi4+/ch4.intro.html
+
+use crate::abi;
+use crate::assemble::encode;
+use crate::assemble::encode::Code;
+use crate::assemble::encode::Kind;
+use crate::data::asm;
+use crate::data::ir;
+use crate::data::operand::Immediate;
+use crate::data::operand::Label;
+use crate::data::operand::Register;
+use crate::Map;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+const SHT_NOBITS: u32 = 8;
+const SHT_GROUP: u32 = 17;
+
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const SHF_INFO_LINK: u64 = 0x40;
+const SHF_GROUP: u64 = 0x200;
+
+const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STB_WEAK: u8 = 2;
+
+const STT_NOTYPE: u8 = 0;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+const STT_SECTION: u8 = 3;
+
+const R_X86_64_64: u32 = 1;
+const R_X86_64_PC32: u32 = 2;
+const R_X86_64_PLT32: u32 = 4;
+const R_X86_64_32S: u32 = 11;
+
+const GRP_COMDAT: u32 = 1;
+
+const HEADER_SIZE: usize = 64;
+const SECTION_HEADER_SIZE: usize = 64;
+const SYMBOL_SIZE: usize = 24;
+const RELOCATION_SIZE: usize = 24;
+
+pub(crate) fn elf(unit: &asm::Unit<Register>) -> Vec<u8> {
+    let mut sections = Vec::new();
+    let mut definitions = Map::default();
+
+    sections.push(Section::text(
+        String::from(".text"),
+        None,
+        unit.functions
+            .values()
+            .filter(|function| function.linkage != ir::Linkage::LinkOnceOdr),
+    ));
+
+    let mut data = Section::new(".data", SHT_PROGBITS, SHF_WRITE | SHF_ALLOC, None);
+    for (label, immediates) in &unit.data {
+        data.code.labels.push((*label, data.code.bytes.len()));
+        encode::encode_quads(&mut data.code, immediates);
+    }
+    sections.push(data);
+
+    let mut bss = Section::new(".bss", SHT_NOBITS, SHF_WRITE | SHF_ALLOC, None);
+    for (symbol, (_, size)) in unit
+        .bss
+        .iter()
+        .filter(|(_, (linkage, _))| *linkage != ir::Linkage::LinkOnceOdr)
+    {
+        bss.code.labels.push((Label::Fixed(*symbol), bss.size));
+        bss.size += size * abi::WORD as usize;
+    }
+    sections.push(bss);
+
+    for (symbol, (_, size)) in unit
+        .bss
+        .iter()
+        .filter(|(_, (linkage, _))| *linkage == ir::Linkage::LinkOnceOdr)
+    {
+        let label = Label::Fixed(*symbol);
+        let mut bss = Section::new(
+            format!(".bss.{}", symbol),
+            SHT_NOBITS,
+            SHF_WRITE | SHF_ALLOC,
+            Some(label),
+        );
+        bss.code.labels.push((label, 0));
+        bss.size = size * abi::WORD as usize;
+        sections.push(bss);
+    }
+
+    for (name, priority) in [(abi::XI_INIT_CLASSES, 65534), (abi::XI_INIT_GLOBALS, 65533)] {
+        let mut ctors = Section::new(
+            format!(".ctors.{}", priority),
+            SHT_PROGBITS,
+            SHF_WRITE | SHF_ALLOC,
+            None,
+        );
+        encode::encode_quads(&mut ctors.code, &[Immediate::from(name)]);
+        sections.push(ctors);
+    }
+
+    for (name, function) in unit
+        .functions
+        .iter()
+        .filter(|(_, function)| function.linkage == ir::Linkage::LinkOnceOdr)
+    {
+        sections.push(Section::text(
+            format!(".text.{}", name),
+            Some(Label::Fixed(*name)),
+            [function],
+        ));
+    }
+
+    // Collect symbol definitions from every section
+    for (index, section) in sections.iter().enumerate() {
+        let end = section.code.bytes.len().max(section.size);
+
+        for (position, (label, offset)) in section.code.labels.iter().enumerate() {
+            let (binding, r#type, size) = match label {
+                Label::Fixed(symbol) if unit.functions.contains_key(symbol) => {
+                    // Note: functions are followed by local labels, then the next function
+                    let next = section.code.labels[position + 1..]
+                        .iter()
+                        .find(|(label, _)| {
+                            matches!(label, Label::Fixed(symbol) if unit.functions.contains_key(symbol))
+                        })
+                        .map_or(end, |(_, offset)| *offset);
+
+                    (unit.functions[symbol].linkage, STT_FUNC, next - offset)
+                }
+                Label::Fixed(symbol) if unit.bss.contains_key(symbol) => {
+                    let (linkage, size) = unit.bss[symbol];
+                    (linkage, STT_OBJECT, size * abi::WORD as usize)
+                }
+                label if unit.data.contains_key(label) => (
+                    ir::Linkage::Local,
+                    STT_OBJECT,
+                    unit.data[label].len() * abi::WORD as usize,
+                ),
+                _ => (ir::Linkage::Local, STT_NOTYPE, 0),
+            };
+
+            definitions.insert(
+                *label,
+                Definition {
+                    section: index,
+                    offset: *offset,
+                    binding,
+                    r#type,
+                    size,
+                },
+            );
+        }
+    }
+
+    let mut relocations = Vec::with_capacity(sections.len());
+
+    for (index, section) in sections.iter_mut().enumerate() {
+        relocations.push(section.resolve(index, &definitions));
+    }
+
+    Object::new(sections, relocations, definitions).write()
+}
+
+struct Definition {
+    section: usize,
+    offset: usize,
+    binding: ir::Linkage,
+    r#type: u8,
+    size: usize,
+}
+
+struct Section {
+    name: String,
+    r#type: u32,
+    flags: u64,
+    code: Code,
+
+    /// Size of `SHT_NOBITS` sections, which have no contents
+    size: usize,
+
+    /// Signature symbol of this section's COMDAT group
+    group: Option<Label>,
+}
+
+impl Section {
+    fn new<S: Into<String>>(name: S, r#type: u32, flags: u64, group: Option<Label>) -> Self {
+        Section {
+            name: name.into(),
+            r#type,
+            flags: flags | if group.is_some() { SHF_GROUP } else { 0 },
+            code: Code::default(),
+            size: 0,
+            group,
+        }
+    }
+
+    fn text<'a, I>(name: String, group: Option<Label>, functions: I) -> Self
+    where
+        I: IntoIterator<Item = &'a asm::Function<Register>>,
+    {
+        let mut text = Section::new(name, SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, group);
+        text.code = encode::encode(functions);
+        text
+    }
+
+    fn size(&self) -> usize {
+        match self.r#type {
+            SHT_NOBITS => self.size,
+            _ => self.code.bytes.len(),
+        }
+    }
+
+    fn align(&self) -> u64 {
+        match self.flags & SHF_EXECINSTR {
+            0 => abi::WORD as u64,
+            _ => 1,
+        }
+    }
+
+    /// Resolves relative references to local labels in this section, like `as`,
+    /// and returns the remaining relocations.
+    ///
+    /// References to other local labels are relative to their section instead.
+    fn resolve(&mut self, index: usize, definitions: &Map<Label, Definition>) -> Vec<Relocation> {
+        let mut relocations = Vec::new();
+
+        for relocation in &self.code.relocations {
+            let local = definitions
+                .get(&relocation.label)
+                .filter(|definition| definition.binding == ir::Linkage::Local);
+
+            let (target, r#type, addend) = match (local, relocation.kind) {
+                (Some(definition), Kind::Relative32) if definition.section == index => {
+                    let displacement =
+                        definition.offset as i64 + relocation.addend - relocation.offset as i64;
+                    let displacement = i32::try_from(displacement)
+                        .expect("[INTERNAL ERROR]: displacement does not fit in 32 bits");
+                    self.code.bytes[relocation.offset..][..4]
+                        .copy_from_slice(&displacement.to_le_bytes());
+                    continue;
+                }
+                (Some(definition), kind) => (
+                    Target::Section(definition.section),
+                    match kind {
+                        Kind::Absolute32 => R_X86_64_32S,
+                        Kind::Absolute64 => R_X86_64_64,
+                        Kind::Relative32 => R_X86_64_PC32,
+                    },
+                    relocation.addend + definition.offset as i64,
+                ),
+                (None, kind) => (
+                    Target::Symbol(relocation.label),
+                    match kind {
+                        Kind::Absolute32 => R_X86_64_32S,
+                        Kind::Absolute64 => R_X86_64_64,
+                        Kind::Relative32 => R_X86_64_PLT32,
+                    },
+                    relocation.addend,
+                ),
+            };
+
+            relocations.push(Relocation {
+                offset: relocation.offset,
+                target,
+                r#type,
+                addend,
+            });
+        }
+
+        relocations
+    }
+}
+
+struct Relocation {
+    offset: usize,
+    target: Target,
+    r#type: u32,
+    addend: i64,
+}
+
+#[derive(Copy, Clone)]
+enum Target {
+    Section(usize),
+    Symbol(Label),
+}
+
+struct Symbol {
+    name: u32,
+    binding: u8,
+    r#type: u8,
+    section: u16,
+    value: u64,
+    size: u64,
+}
+
+/// Section header, along with its contents.
+struct Header {
+    name: u32,
+    r#type: u32,
+    flags: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entry_size: u64,
+    size: usize,
+    contents: Vec<u8>,
+}
+
+struct Object {
+    headers: Vec<Header>,
+    names: Strings,
+}
+
+impl Object {
+    fn new(
+        sections: Vec<Section>,
+        relocations: Vec<Vec<Relocation>>,
+        definitions: Map<Label, Definition>,
+    ) -> Self {
+        // Assign section header indices: the null section, then each section
+        // preceded by its group and followed by its relocations.
+        let mut next = 1;
+        let mut indices = Vec::with_capacity(sections.len());
+
+        for (section, relocations) in sections.iter().zip(&relocations) {
+            next += section.group.is_some() as usize;
+            indices.push(next);
+            next += 1 + !relocations.is_empty() as usize;
+        }
+
+        let stack = next;
+        let symtab = stack + 1;
+        let strtab = symtab + 1;
+        let shstrtab = strtab + 1;
+
+        // Local symbols must precede global symbols
+        let mut strings = Strings::default();
+        let mut symbols = vec![Symbol {
+            name: 0,
+            binding: STB_LOCAL,
+            r#type: STT_NOTYPE,
+            section: 0,
+            value: 0,
+            size: 0,
+        }];
+
+        for index in &indices {
+            symbols.push(Symbol {
+                name: 0,
+                binding: STB_LOCAL,
+                r#type: STT_SECTION,
+                section: *index as u16,
+                value: 0,
+                size: 0,
+            });
+        }
+
+        let mut symbol_indices = Map::default();
+
+        for local in [true, false] {
+            for (label, definition) in &definitions {
+                if (definition.binding == ir::Linkage::Local) != local {
+                    continue;
+                }
+
+                symbol_indices.insert(*label, symbols.len());
+                symbols.push(Symbol {
+                    name: strings.insert(&label.to_string()),
+                    binding: match definition.binding {
+                        ir::Linkage::Local => STB_LOCAL,
+                        ir::Linkage::Global => STB_GLOBAL,
+                        ir::Linkage::LinkOnceOdr => STB_WEAK,
+                    },
+                    r#type: definition.r#type,
+                    section: indices[definition.section] as u16,
+                    value: definition.offset as u64,
+                    size: definition.size as u64,
+                });
+            }
+        }
+
+        let locals = symbols.len()
+            - definitions
+                .values()
+                .filter(|definition| definition.binding != ir::Linkage::Local)
+                .count();
+
+        // Remaining references are to undefined global symbols
+        for relocation in relocations.iter().flatten() {
+            if let Target::Symbol(label) = relocation.target {
+                symbol_indices.entry(label).or_insert_with(|| {
+                    symbols.push(Symbol {
+                        name: strings.insert(&label.to_string()),
+                        binding: STB_GLOBAL,
+                        r#type: STT_NOTYPE,
+                        section: 0,
+                        value: 0,
+                        size: 0,
+                    });
+                    symbols.len() - 1
+                });
+            }
+        }
+
+        let mut names = Strings::default();
+        let mut headers = vec![Header {
+            name: 0,
+            r#type: 0,
+            flags: 0,
+            link: 0,
+            info: 0,
+            align: 0,
+            entry_size: 0,
+            size: 0,
+            contents: Vec::new(),
+        }];
+
+        for (index, (section, relocations)) in sections.into_iter().zip(relocations).enumerate() {
+            if let Some(signature) = section.group {
+                let mut contents = Vec::new();
+                contents.extend(GRP_COMDAT.to_le_bytes());
+                contents.extend((indices[index] as u32).to_le_bytes());
+                if !relocations.is_empty() {
+                    contents.extend((indices[index] as u32 + 1).to_le_bytes());
+                }
+
+                headers.push(Header {
+                    name: names.insert(".group"),
+                    r#type: SHT_GROUP,
+                    flags: 0,
+                    link: symtab as u32,
+                    info: symbol_indices[&signature] as u32,
+                    align: 4,
+                    entry_size: 4,
+                    size: contents.len(),
+                    contents,
+                });
+            }
+
+            headers.push(Header {
+                name: names.insert(&section.name),
+                r#type: section.r#type,
+                flags: section.flags,
+                link: 0,
+                info: 0,
+                align: section.align(),
+                entry_size: 0,
+                size: section.size(),
+                contents: section.code.bytes,
+            });
+
+            if relocations.is_empty() {
+                continue;
+            }
+
+            let mut contents = Vec::with_capacity(relocations.len() * RELOCATION_SIZE);
+
+            for relocation in relocations {
+                let symbol = match relocation.target {
+                    Target::Section(section) => 1 + section,
+                    Target::Symbol(label) => symbol_indices[&label],
+                };
+
+                contents.extend((relocation.offset as u64).to_le_bytes());
+                contents.extend(((symbol as u64) << 32 | relocation.r#type as u64).to_le_bytes());
+                contents.extend(relocation.addend.to_le_bytes());
+            }
+
+            headers.push(Header {
+                name: names.insert(&format!(".rela{}", section.name)),
+                r#type: SHT_RELA,
+                flags: SHF_INFO_LINK | (section.flags & SHF_GROUP),
+                link: symtab as u32,
+                info: indices[index] as u32,
+                align: 8,
+                entry_size: RELOCATION_SIZE as u64,
+                size: contents.len(),
+                contents,
+            });
+        }
+
+        // Mark the stack as non-executable, which `as` does on request
+        headers.push(Header {
+            name: names.insert(".note.GNU-stack"),
+            r#type: SHT_PROGBITS,
+            flags: 0,
+            link: 0,
+            info: 0,
+            align: 1,
+            entry_size: 0,
+            size: 0,
+            contents: Vec::new(),
+        });
+
+        let mut contents = Vec::with_capacity(symbols.len() * SYMBOL_SIZE);
+
+        for symbol in &symbols {
+            contents.extend(symbol.name.to_le_bytes());
+            contents.push(symbol.binding << 4 | symbol.r#type);
+            contents.push(0);
+            contents.extend(symbol.section.to_le_bytes());
+            contents.extend(symbol.value.to_le_bytes());
+            contents.extend(symbol.size.to_le_bytes());
+        }
+
+        headers.push(Header {
+            name: names.insert(".symtab"),
+            r#type: SHT_SYMTAB,
+            flags: 0,
+            link: strtab as u32,
+            info: locals as u32,
+            align: 8,
+            entry_size: SYMBOL_SIZE as u64,
+            size: contents.len(),
+            contents,
+        });
+
+        headers.push(Header {
+            name: names.insert(".strtab"),
+            r#type: SHT_STRTAB,
+            flags: 0,
+            link: 0,
+            info: 0,
+            align: 1,
+            entry_size: 0,
+            size: strings.0.len(),
+            contents: strings.0,
+        });
+
+        headers.push(Header {
+            name: names.insert(".shstrtab"),
+            r#type: SHT_STRTAB,
+            flags: 0,
+            link: 0,
+            info: 0,
+            align: 1,
+            entry_size: 0,
+            size: 0,
+            contents: Vec::new(),
+        });
+
+        debug_assert_eq!(headers.len(), shstrtab + 1);
+
+        Object { headers, names }
+    }
+
+    fn write(mut self) -> Vec<u8> {
+        let shstrtab = self.headers.len() - 1;
+        self.headers[shstrtab].size = self.names.0.len();
+        self.headers[shstrtab].contents = std::mem::take(&mut self.names.0);
+
+        let mut offsets = Vec::with_capacity(self.headers.len());
+        let mut object = vec![0; HEADER_SIZE];
+
+        for header in &self.headers {
+            let align = header.align.max(1) as usize;
+            object.resize(object.len().next_multiple_of(align), 0);
+            offsets.push(object.len());
+            object.extend(&header.contents);
+        }
+
+        object.resize(object.len().next_multiple_of(8), 0);
+        let section_headers = object.len();
+
+        for (header, offset) in self.headers.iter().zip(offsets) {
+            object.extend(header.name.to_le_bytes());
+            object.extend(header.r#type.to_le_bytes());
+            object.extend(header.flags.to_le_bytes());
+            object.extend(0u64.to_le_bytes());
+            object.extend((offset as u64).to_le_bytes());
+            object.extend((header.size as u64).to_le_bytes());
+            object.extend(header.link.to_le_bytes());
+            object.extend(header.info.to_le_bytes());
+            object.extend(header.align.to_le_bytes());
+            object.extend(header.entry_size.to_le_bytes());
+        }
+
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+
+        // Magic number, 64-bit, little-endian, version 1, System V ABI
+        header.extend(b"\x7fELF\x02\x01\x01\x00");
+        header.extend([0; 8]);
+
+        // Relocatable file for x86-64, version 1, no entry point or program headers
+        header.extend(1u16.to_le_bytes());
+        header.extend(62u16.to_le_bytes());
+        header.extend(1u32.to_le_bytes());
+        header.extend(0u64.to_le_bytes());
+        header.extend(0u64.to_le_bytes());
+        header.extend((section_headers as u64).to_le_bytes());
+        header.extend(0u32.to_le_bytes());
+        header.extend((HEADER_SIZE as u16).to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend((SECTION_HEADER_SIZE as u16).to_le_bytes());
+        header.extend((self.headers.len() as u16).to_le_bytes());
+        header.extend((shstrtab as u16).to_le_bytes());
+
+        object[..HEADER_SIZE].copy_from_slice(&header);
+        object
+    }
+}
+
+/// String table, which begins with the empty string.
+struct Strings(Vec<u8>);
+
+impl Default for Strings {
+    fn default() -> Self {
+        Strings(vec![0])
+    }
+}
+
+impl Strings {
+    fn insert(&mut self, string: &str) -> u32 {
+        let index = self.0.len() as u32;
+        self.0.extend(string.as_bytes());
+        self.0.push(0);
+        index
+    }
+}
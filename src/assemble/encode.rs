@@ -0,0 +1,783 @@
+//! Encodes x86-64 assembly as machine code, without an external assembler.
+//!
+//! Instruction selection follows GNU `as` on the output of `print::Intel`, so
+//! that disassembling either object file produces the same listing.
+
+use std::collections::BTreeMap;
+
+use crate::data::asm;
+use crate::data::asm::Statement;
+use crate::data::operand::Binary;
+use crate::data::operand::Immediate;
+use crate::data::operand::Label;
+use crate::data::operand::Memory;
+use crate::data::operand::Register;
+use crate::data::operand::Scale;
+use crate::data::operand::Unary;
+use crate::data::token::Size;
+
+/// Machine code for a single section.
+#[derive(Debug, Default)]
+pub(super) struct Code {
+    pub(super) bytes: Vec<u8>,
+
+    /// Offsets of labels defined in this section, in order of definition
+    pub(super) labels: Vec<(Label, usize)>,
+
+    /// References to labels, which may or may not be defined in this section
+    pub(super) relocations: Vec<Relocation>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) struct Relocation {
+    pub(super) offset: usize,
+    pub(super) kind: Kind,
+    pub(super) label: Label,
+    pub(super) addend: i64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Kind {
+    /// Sign-extended 32-bit address
+    Absolute32,
+
+    /// 64-bit address
+    Absolute64,
+
+    /// 32-bit displacement from the end of a call or jump
+    Relative32,
+}
+
+/// Encodes `functions` back-to-back into a single section.
+pub(super) fn encode<'a, I>(functions: I) -> Code
+where
+    I: IntoIterator<Item = &'a asm::Function<Register>>,
+{
+    let mut fragments = Vec::new();
+
+    for function in functions {
+        fragments.push(Fragment::Label(Label::Fixed(function.name), true));
+
+        assert!(
+            matches!(function.statements.first(), Some(Statement::Label(label)) if *label == function.enter)
+        );
+
+        // Note: the entry label isn't printed, so it shouldn't be a symbol
+        fragments.push(Fragment::Label(function.enter, false));
+        fragments.extend(function.statements.iter().skip(1).map(fragment));
+    }
+
+    relax(fragments)
+}
+
+/// Encodes `data` as a sequence of quad words.
+pub(super) fn encode_quads(code: &mut Code, data: &[Immediate]) {
+    for immediate in data {
+        match immediate {
+            Immediate::Integer(integer) => code.bytes.extend(integer.to_le_bytes()),
+            Immediate::Label(label) => {
+                code.relocations.push(Relocation {
+                    offset: code.bytes.len(),
+                    kind: Kind::Absolute64,
+                    label: *label,
+                    addend: 0,
+                });
+                code.bytes.extend([0; 8]);
+            }
+        }
+    }
+}
+
+enum Fragment {
+    /// Label definition, and whether it should be visible as a symbol
+    Label(Label, bool),
+    Instruction(Instruction),
+    Jump(Option<asm::Condition>, Label),
+}
+
+/// Chooses between short and long jumps, the same way as `as`: every jump starts
+/// short, and grows until all displacements fit.
+fn relax(fragments: Vec<Fragment>) -> Code {
+    let mut long = vec![false; fragments.len()];
+
+    let labels = loop {
+        let mut labels = BTreeMap::new();
+        let mut offsets = Vec::with_capacity(fragments.len());
+        let mut offset = 0;
+
+        for (index, fragment) in fragments.iter().enumerate() {
+            offsets.push(offset);
+            offset += match fragment {
+                Fragment::Label(label, _) => {
+                    labels.insert(*label, offset);
+                    0
+                }
+                Fragment::Instruction(instruction) => instruction.bytes.len(),
+                Fragment::Jump(condition, _) => jump_size(*condition, long[index]),
+            };
+        }
+
+        let mut changed = false;
+
+        for (index, fragment) in fragments.iter().enumerate() {
+            let label = match fragment {
+                Fragment::Jump(_, label) if !long[index] => label,
+                _ => continue,
+            };
+
+            let fits = labels.get(label).is_some_and(|target| {
+                let displacement = *target as i64 - (offsets[index] + 2) as i64;
+                i8::try_from(displacement).is_ok()
+            });
+
+            if !fits {
+                long[index] = true;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break labels;
+        }
+    };
+
+    let mut code = Code::default();
+
+    for (fragment, long) in fragments.into_iter().zip(long) {
+        match fragment {
+            Fragment::Label(label, symbol) => {
+                if symbol {
+                    code.labels.push((label, code.bytes.len()));
+                }
+            }
+            Fragment::Instruction(instruction) => {
+                let start = code.bytes.len();
+                code.bytes.extend(instruction.bytes);
+                code.relocations
+                    .extend(
+                        instruction
+                            .relocations
+                            .into_iter()
+                            .map(|relocation| Relocation {
+                                offset: start + relocation.offset,
+                                ..relocation
+                            }),
+                    );
+            }
+            Fragment::Jump(condition, label) => {
+                let end = code.bytes.len() + jump_size(condition, long);
+
+                match (condition, long) {
+                    (None, false) => code.bytes.push(0xeb),
+                    (None, true) => code.bytes.push(0xe9),
+                    (Some(condition), false) => code.bytes.push(0x70 | condition_code(condition)),
+                    (Some(condition), true) => {
+                        code.bytes.extend([0x0f, 0x80 | condition_code(condition)])
+                    }
+                }
+
+                match (labels.get(&label), long) {
+                    (Some(target), false) => {
+                        code.bytes.push((*target as i64 - end as i64) as i8 as u8)
+                    }
+                    (Some(target), true) => code
+                        .bytes
+                        .extend((*target as i64 - end as i64).to_le_bytes()[..4].iter()),
+                    (None, _) => {
+                        code.relocations.push(Relocation {
+                            offset: code.bytes.len(),
+                            kind: Kind::Relative32,
+                            label,
+                            addend: -4,
+                        });
+                        code.bytes.extend([0; 4]);
+                    }
+                }
+            }
+        }
+    }
+
+    code
+}
+
+fn jump_size(condition: Option<asm::Condition>, long: bool) -> usize {
+    match (condition, long) {
+        (_, false) => 2,
+        (None, true) => 5,
+        (Some(_), true) => 6,
+    }
+}
+
+fn condition_code(condition: asm::Condition) -> u8 {
+    match condition {
+        asm::Condition::B => 0x2,
+        asm::Condition::Ae => 0x3,
+        asm::Condition::E => 0x4,
+        asm::Condition::Ne => 0x5,
+        asm::Condition::Be => 0x6,
+        asm::Condition::A => 0x7,
+        asm::Condition::L => 0xc,
+        asm::Condition::Ge => 0xd,
+        asm::Condition::Le => 0xe,
+        asm::Condition::G => 0xf,
+    }
+}
+
+fn fragment(statement: &Statement<Register>) -> Fragment {
+    let mut instruction = Instruction::default();
+
+    match statement {
+        Statement::Label(label) => return Fragment::Label(*label, true),
+        Statement::Jmp(label) => return Fragment::Jump(None, *label),
+        Statement::Jcc(condition, label) => return Fragment::Jump(Some(*condition), *label),
+        Statement::Binary(binary, operands) => instruction.binary(*binary, operands),
+        Statement::Unary(unary, operand) => instruction.unary(*unary, operand),
+        Statement::Nullary(asm::Nullary::Nop) => instruction.bytes.push(0x90),
+        Statement::Nullary(asm::Nullary::Cqo) => instruction.bytes.extend([0x48, 0x99]),
+        Statement::Nullary(asm::Nullary::Ret(_)) => instruction.bytes.push(0xc3),
+        // Note: debugging information is only emitted by the assembly printers
+        Statement::Debug(_) => (),
+    }
+
+    Fragment::Instruction(instruction)
+}
+
+#[derive(Default)]
+struct Instruction {
+    bytes: Vec<u8>,
+    relocations: Vec<Relocation>,
+}
+
+#[derive(Copy, Clone)]
+enum Operand<'a> {
+    Register(Register),
+    Memory(&'a Memory<Register>),
+}
+
+impl Instruction {
+    fn binary(&mut self, binary: asm::Binary, operands: &Binary<Register>) {
+        use Operand::Memory as M;
+        use Operand::Register as R;
+
+        match (binary, operands) {
+            (
+                asm::Binary::Add
+                | asm::Binary::Or
+                | asm::Binary::And
+                | asm::Binary::Sub
+                | asm::Binary::Xor
+                | asm::Binary::Cmp,
+                operands,
+            ) => {
+                let extension = match binary {
+                    asm::Binary::Add => 0,
+                    asm::Binary::Or => 1,
+                    asm::Binary::And => 4,
+                    asm::Binary::Sub => 5,
+                    asm::Binary::Xor => 6,
+                    asm::Binary::Cmp => 7,
+                    _ => unreachable!(),
+                };
+
+                match operands {
+                    Binary::RI {
+                        destination,
+                        source,
+                    } => self.arithmetic(extension, R(*destination), source),
+                    Binary::MI {
+                        destination,
+                        source,
+                    } => self.arithmetic(extension, M(destination), source),
+                    Binary::MR {
+                        destination,
+                        source,
+                    } => self.modrm(
+                        true,
+                        false,
+                        &[extension << 3 | 1],
+                        number(*source),
+                        M(destination),
+                    ),
+                    Binary::RM {
+                        destination,
+                        source,
+                    } => self.modrm(
+                        true,
+                        false,
+                        &[extension << 3 | 3],
+                        number(*destination),
+                        M(source),
+                    ),
+                    Binary::RR {
+                        destination,
+                        source,
+                    } => self.modrm(
+                        true,
+                        false,
+                        &[extension << 3 | 1],
+                        number(*source),
+                        R(*destination),
+                    ),
+                }
+            }
+
+            (
+                asm::Binary::Mov,
+                Binary::RI {
+                    destination,
+                    source: Immediate::Integer(integer),
+                },
+            ) if i32::try_from(*integer).is_err() => {
+                self.rex(true, false, 0, None, Some(number(*destination)));
+                self.bytes.push(0xb8 | number(*destination) & 0b111);
+                self.bytes.extend(integer.to_le_bytes());
+            }
+            (
+                asm::Binary::Mov,
+                Binary::RI {
+                    destination,
+                    source,
+                },
+            ) => {
+                self.modrm(true, false, &[0xc7], 0, R(*destination));
+                self.immediate(source, Size::_32);
+            }
+            (
+                asm::Binary::Mov,
+                Binary::MI {
+                    destination,
+                    source,
+                },
+            ) => {
+                self.modrm(true, false, &[0xc7], 0, M(destination));
+                self.immediate(source, Size::_32);
+            }
+            (
+                asm::Binary::Mov,
+                Binary::MR {
+                    destination,
+                    source,
+                },
+            ) => self.modrm(true, false, &[0x89], number(*source), M(destination)),
+            (
+                asm::Binary::Mov,
+                Binary::RM {
+                    destination,
+                    source,
+                },
+            ) => self.modrm(true, false, &[0x8b], number(*destination), M(source)),
+            (
+                asm::Binary::Mov,
+                Binary::RR {
+                    destination,
+                    source,
+                },
+            ) => self.modrm(true, false, &[0x89], number(*source), R(*destination)),
+
+            (
+                asm::Binary::Lea,
+                Binary::RM {
+                    destination,
+                    source,
+                },
+            ) => self.modrm(true, false, &[0x8d], number(*destination), M(source)),
+
+            (
+                asm::Binary::Mul,
+                Binary::RI {
+                    destination,
+                    source: source @ Immediate::Integer(integer),
+                },
+            ) => match i8::try_from(*integer) {
+                Ok(byte) => {
+                    self.modrm(true, false, &[0x6b], number(*destination), R(*destination));
+                    self.bytes.push(byte as u8);
+                }
+                Err(_) => {
+                    self.modrm(true, false, &[0x69], number(*destination), R(*destination));
+                    self.immediate(source, Size::_32);
+                }
+            },
+            (
+                asm::Binary::Mul,
+                Binary::RM {
+                    destination,
+                    source,
+                },
+            ) => self.modrm(true, false, &[0x0f, 0xaf], number(*destination), M(source)),
+            (
+                asm::Binary::Mul,
+                Binary::RR {
+                    destination,
+                    source,
+                },
+            ) => self.modrm(true, false, &[0x0f, 0xaf], number(*destination), R(*source)),
+
+            (
+                asm::Binary::Shl,
+                Binary::RI {
+                    destination,
+                    source: Immediate::Integer(integer),
+                },
+            ) => self.shift(R(*destination), *integer),
+            (
+                asm::Binary::Shl,
+                Binary::MI {
+                    destination,
+                    source: Immediate::Integer(integer),
+                },
+            ) => self.shift(M(destination), *integer),
+
+            (
+                asm::Binary::Load { signed, size },
+                Binary::RM {
+                    destination,
+                    source,
+                },
+            ) => {
+                let (wide, opcode): (bool, &[u8]) = match (signed, size) {
+                    (true, Size::_8) => (true, &[0x0f, 0xbe]),
+                    (true, Size::_16) => (true, &[0x0f, 0xbf]),
+                    (true, Size::_32) => (true, &[0x63]),
+                    (false, Size::_8) => (true, &[0x0f, 0xb6]),
+                    (false, Size::_16) => (true, &[0x0f, 0xb7]),
+                    // Writing to a 32-bit register implicitly zeroes the upper 32 bits
+                    (false, Size::_32) => (false, &[0x8b]),
+                    (_, Size::_64) => (true, &[0x8b]),
+                };
+
+                self.modrm(wide, false, opcode, number(*destination), M(source));
+            }
+
+            (
+                asm::Binary::Store(size),
+                Binary::MR {
+                    destination,
+                    source,
+                },
+            ) => {
+                let opcode = match size {
+                    Size::_8 => 0x88,
+                    Size::_16 => {
+                        self.bytes.push(0x66);
+                        0x89
+                    }
+                    Size::_32 | Size::_64 => 0x89,
+                };
+
+                // Note: `spl`, `bpl`, `sil`, and `dil` are only addressable with a REX prefix
+                let byte = size == Size::_8 && (4..8).contains(&number(*source));
+
+                self.modrm(
+                    size == Size::_64,
+                    byte,
+                    &[opcode],
+                    number(*source),
+                    M(destination),
+                );
+            }
+            (
+                asm::Binary::Store(size),
+                Binary::MI {
+                    destination,
+                    source,
+                },
+            ) => {
+                let opcode = match size {
+                    Size::_8 => 0xc6,
+                    Size::_16 => {
+                        self.bytes.push(0x66);
+                        0xc7
+                    }
+                    Size::_32 | Size::_64 => 0xc7,
+                };
+
+                self.modrm(size == Size::_64, false, &[opcode], 0, M(destination));
+                self.immediate(
+                    source,
+                    match size {
+                        Size::_64 => Size::_32,
+                        size => size,
+                    },
+                );
+            }
+
+            (binary, operands) => panic!(
+                "[INTERNAL ERROR]: cannot encode {:?} with operands {:?}",
+                binary, operands
+            ),
+        }
+    }
+
+    fn unary(&mut self, unary: asm::Unary, operand: &Unary<Register>) {
+        match (unary, operand) {
+            (asm::Unary::Push, Unary::R(register)) => {
+                self.rex(false, false, 0, None, Some(number(*register)));
+                self.bytes.push(0x50 | number(*register) & 0b111);
+            }
+            (asm::Unary::Push, Unary::I(Immediate::Integer(integer)))
+                if i8::try_from(*integer).is_ok() =>
+            {
+                self.bytes.extend([0x6a, *integer as i8 as u8]);
+            }
+            (asm::Unary::Push, Unary::I(immediate)) => {
+                self.bytes.push(0x68);
+                self.immediate(immediate, Size::_32);
+            }
+            (asm::Unary::Push, Unary::M(memory)) => {
+                self.modrm(false, false, &[0xff], 6, Operand::Memory(memory))
+            }
+            (asm::Unary::Pop, Unary::R(register)) => {
+                self.rex(false, false, 0, None, Some(number(*register)));
+                self.bytes.push(0x58 | number(*register) & 0b111);
+            }
+            (asm::Unary::Pop, Unary::M(memory)) => {
+                self.modrm(false, false, &[0x8f], 0, Operand::Memory(memory))
+            }
+
+            (asm::Unary::Call { .. }, Unary::I(Immediate::Label(label))) => {
+                self.bytes.push(0xe8);
+                self.relocations.push(Relocation {
+                    offset: self.bytes.len(),
+                    kind: Kind::Relative32,
+                    label: *label,
+                    addend: -4,
+                });
+                self.bytes.extend([0; 4]);
+            }
+            (asm::Unary::Call { .. }, Unary::R(register)) => {
+                self.modrm(false, false, &[0xff], 2, Operand::Register(*register))
+            }
+            (asm::Unary::Call { .. }, Unary::M(memory)) => {
+                self.modrm(false, false, &[0xff], 2, Operand::Memory(memory))
+            }
+
+            (
+                asm::Unary::Neg
+                | asm::Unary::Hul
+                | asm::Unary::Div
+                | asm::Unary::Mod
+                | asm::Unary::UHul
+                | asm::Unary::UDiv
+                | asm::Unary::UMod,
+                Unary::R(_) | Unary::M(_),
+            ) => {
+                let extension = match unary {
+                    asm::Unary::Neg => 3,
+                    asm::Unary::UHul => 4,
+                    asm::Unary::Hul => 5,
+                    asm::Unary::UDiv | asm::Unary::UMod => 6,
+                    asm::Unary::Div | asm::Unary::Mod => 7,
+                    _ => unreachable!(),
+                };
+
+                let operand = match operand {
+                    Unary::R(register) => Operand::Register(*register),
+                    Unary::M(memory) => Operand::Memory(memory),
+                    Unary::I(_) => unreachable!(),
+                };
+
+                self.modrm(true, false, &[0xf7], extension, operand);
+            }
+
+            (unary, operand) => panic!(
+                "[INTERNAL ERROR]: cannot encode {:?} with operand {:?}",
+                unary, operand
+            ),
+        }
+    }
+
+    /// Encodes `add`, `or`, `and`, `sub`, `xor`, or `cmp` with an immediate source.
+    fn arithmetic(&mut self, extension: u8, destination: Operand, source: &Immediate) {
+        match (destination, source) {
+            (_, Immediate::Integer(integer)) if i8::try_from(*integer).is_ok() => {
+                self.modrm(true, false, &[0x83], extension, destination);
+                self.bytes.push(*integer as i8 as u8);
+            }
+            // Note: `as` prefers the shorter encoding specialized for `rax`
+            (Operand::Register(Register::Rax), _) => {
+                self.rex(true, false, 0, None, None);
+                self.bytes.push(extension << 3 | 5);
+                self.immediate(source, Size::_32);
+            }
+            (_, _) => {
+                self.modrm(true, false, &[0x81], extension, destination);
+                self.immediate(source, Size::_32);
+            }
+        }
+    }
+
+    fn shift(&mut self, destination: Operand, amount: i64) {
+        match amount {
+            1 => self.modrm(true, false, &[0xd1], 4, destination),
+            _ => {
+                self.modrm(true, false, &[0xc1], 4, destination);
+                self.bytes.push(amount as u8);
+            }
+        }
+    }
+
+    /// Encodes an immediate operand of at most 32 bits, which is sign-extended
+    /// to 64 bits by the processor if necessary.
+    fn immediate(&mut self, immediate: &Immediate, size: Size) {
+        match immediate {
+            Immediate::Integer(integer) => {
+                let bytes = integer.to_le_bytes();
+                match size {
+                    Size::_8 => self.bytes.push(bytes[0]),
+                    Size::_16 => self.bytes.extend(&bytes[..2]),
+                    Size::_32 if i32::try_from(*integer).is_ok() => self.bytes.extend(&bytes[..4]),
+                    Size::_32 | Size::_64 => panic!(
+                        "[INTERNAL ERROR]: immediate {} does not fit in 32 bits",
+                        integer
+                    ),
+                }
+            }
+            Immediate::Label(label) => {
+                assert_eq!(size, Size::_32);
+                self.relocations.push(Relocation {
+                    offset: self.bytes.len(),
+                    kind: Kind::Absolute32,
+                    label: *label,
+                    addend: 0,
+                });
+                self.bytes.extend([0; 4]);
+            }
+        }
+    }
+
+    /// Encodes an optional REX prefix, which is required for 64-bit operands,
+    /// registers `r8` through `r15`, and byte registers `spl` through `dil`.
+    fn rex(&mut self, wide: bool, byte: bool, reg: u8, index: Option<u8>, base: Option<u8>) {
+        let rex = (wide as u8) << 3
+            | (reg >> 3) << 2
+            | (index.unwrap_or(0) >> 3) << 1
+            | base.unwrap_or(0) >> 3;
+
+        if rex != 0 || byte {
+            self.bytes.push(0x40 | rex);
+        }
+    }
+
+    /// Encodes `opcode` followed by a ModRM byte, and SIB byte and displacement
+    /// if necessary, where `reg` is either a register or an opcode extension.
+    fn modrm(&mut self, wide: bool, byte: bool, opcode: &[u8], reg: u8, rm: Operand) {
+        let memory = match rm {
+            Operand::Register(register) => {
+                let rm = number(register);
+                self.rex(wide, byte, reg, None, Some(rm));
+                self.bytes.extend(opcode);
+                self.bytes.push(0b11 << 6 | (reg & 0b111) << 3 | rm & 0b111);
+                return;
+            }
+            Operand::Memory(memory) => memory,
+        };
+
+        let (base, index, offset) = match *memory {
+            Memory::B { base } => (Some(base), None, None),
+            Memory::O { offset } => (None, None, Some(offset)),
+            Memory::BI { base, index } => (Some(base), Some((index, Scale::_1)), None),
+            Memory::BO { base, offset } => (Some(base), None, Some(offset)),
+            Memory::BIO {
+                base,
+                index,
+                offset,
+            } => (Some(base), Some((index, Scale::_1)), Some(offset)),
+            Memory::BIS { base, index, scale } => (Some(base), Some((index, scale)), None),
+            Memory::ISO {
+                index,
+                scale,
+                offset,
+            } => (None, Some((index, scale)), Some(offset)),
+            Memory::BISO {
+                base,
+                index,
+                scale,
+                offset,
+            } => (Some(base), Some((index, scale)), Some(offset)),
+        };
+
+        let base = base.map(number);
+        let index = index.map(|(index, scale)| (number(index), scale));
+
+        self.rex(wide, byte, reg, index.map(|(index, _)| index), base);
+        self.bytes.extend(opcode);
+
+        let displacement = match (base, offset) {
+            // Absolute addresses and indices without a base always take 32 bits
+            (None, offset) => Displacement::Dword(offset.unwrap_or(Immediate::Integer(0))),
+            // Note: `rbp` and `r13` as a base require a displacement
+            (Some(base), None | Some(Immediate::Integer(0))) if base & 0b111 == 0b101 => {
+                Displacement::Byte(0)
+            }
+            (Some(_), None | Some(Immediate::Integer(0))) => Displacement::None,
+            (Some(_), Some(Immediate::Integer(integer))) => match i8::try_from(integer) {
+                Ok(byte) => Displacement::Byte(byte),
+                Err(_) => Displacement::Dword(Immediate::Integer(integer)),
+            },
+            (Some(_), Some(label @ Immediate::Label(_))) => Displacement::Dword(label),
+        };
+
+        let mode = match (base, displacement) {
+            (None, _) | (Some(_), Displacement::None) => 0b00,
+            (Some(_), Displacement::Byte(_)) => 0b01,
+            (Some(_), Displacement::Dword(_)) => 0b10,
+        };
+
+        // Note: `rsp` and `r12` as a base require a SIB byte
+        match (base, index) {
+            (Some(base), None) if base & 0b111 != 0b100 => {
+                self.bytes
+                    .push(mode << 6 | (reg & 0b111) << 3 | base & 0b111);
+            }
+            (base, index) => {
+                let (index, scale) = index.unwrap_or((0b100, Scale::_1));
+                let scale = match scale {
+                    Scale::_1 => 0b00,
+                    Scale::_2 => 0b01,
+                    Scale::_4 => 0b10,
+                    Scale::_8 => 0b11,
+                };
+
+                self.bytes.push(mode << 6 | (reg & 0b111) << 3 | 0b100);
+                self.bytes
+                    .push(scale << 6 | (index & 0b111) << 3 | base.unwrap_or(0b101) & 0b111);
+            }
+        }
+
+        match displacement {
+            Displacement::None => (),
+            Displacement::Byte(byte) => self.bytes.push(byte as u8),
+            Displacement::Dword(offset) => self.immediate(&offset, Size::_32),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Displacement {
+    None,
+    Byte(i8),
+    Dword(Immediate),
+}
+
+fn number(register: Register) -> u8 {
+    match register {
+        Register::Rax => 0,
+        Register::Rcx => 1,
+        Register::Rdx => 2,
+        Register::Rbx => 3,
+        Register::Rsp(true) => 4,
+        // Note: placeholder is printed as `rbp`, see `Register::Rsp`
+        Register::Rsp(false) | Register::Rbp => 5,
+        Register::Rsi => 6,
+        Register::Rdi => 7,
+        Register::R8 => 8,
+        Register::R9 => 9,
+        Register::R10 => 10,
+        Register::R11 => 11,
+        Register::R12 => 12,
+        Register::R13 => 13,
+        Register::R14 => 14,
+        Register::R15 => 15,
+    }
+}
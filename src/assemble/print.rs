@@ -1,22 +1,37 @@
 use std::fmt;
+use std::iter;
 
 use crate::abi;
+use crate::assemble::dwarf;
 use crate::data::asm;
 use crate::data::asm::Directive;
 use crate::data::asm::Statement;
+use crate::data::debug::Debug;
 use crate::data::ir;
 use crate::data::operand::Binary;
 use crate::data::operand::Immediate;
 use crate::data::operand::Label;
 use crate::data::operand::Memory;
+use crate::data::operand::Operand;
 use crate::data::operand::Unary;
+use crate::data::token::Size;
 
 pub struct Intel<T>(pub T);
 
-impl<T: fmt::Display> fmt::Display for Intel<&asm::Unit<T>> {
+impl<T: Operand> fmt::Display for Intel<&asm::Unit<T>> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         writeln!(fmt, "{}\n", Directive::Intel)?;
 
+        let files = dwarf::Files::new(self.0.functions.values());
+
+        for file in files.directives() {
+            writeln!(fmt, "{}", file)?;
+        }
+
+        if !files.is_empty() {
+            writeln!(fmt)?;
+        }
+
         writeln!(fmt, "{}\n", Directive::Data)?;
         writeln!(fmt, "{}", Directive::Align(abi::WORD as usize))?;
 
@@ -59,7 +74,11 @@ impl<T: fmt::Display> fmt::Display for Intel<&asm::Unit<T>> {
                 "{}",
                 Directive::Linkage(function.linkage, Label::Fixed(*name))
             )?;
-            writeln!(fmt, "{}", Intel(function))?;
+            writeln!(fmt, "{}", Intel((function, &files)))?;
+        }
+
+        if !files.is_empty() {
+            write!(fmt, "{}", dwarf::Dwarf(self.0))?;
         }
 
         Ok(())
@@ -68,19 +87,47 @@ impl<T: fmt::Display> fmt::Display for Intel<&asm::Unit<T>> {
 
 impl<T: fmt::Display> fmt::Display for Intel<&asm::Function<T>> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(fmt, "{}:", self.0.name)?;
+        let files = dwarf::Files::new(iter::once(self.0));
+        write!(fmt, "{}", Intel((self.0, &files)))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Intel<(&asm::Function<T>, &dwarf::Files)> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let (function, files) = self.0;
+
+        writeln!(fmt, "{}:", function.name)?;
 
         assert!(
-            matches!(self.0.statements.first(), Some(Statement::Label(label)) if *label == self.0.enter)
+            matches!(function.statements.first(), Some(Statement::Label(label)) if *label == function.enter)
         );
 
-        for statement in self.0.statements.iter().skip(1) {
-            if !matches!(statement, Statement::Label(_)) {
-                write!(fmt, "  ")?;
+        let prologue = dwarf::prologue(function);
+
+        if let Some(location) = prologue.and_then(|span| files.location(span)) {
+            writeln!(fmt, "  {}", location)?;
+        }
+
+        for statement in function.statements.iter().skip(1) {
+            match statement {
+                Statement::Label(_) => (),
+                Statement::Debug(Debug::Location(span)) => {
+                    if let Some(location) = files.location(span) {
+                        writeln!(fmt, "  {}", location)?;
+                        continue;
+                    }
+                    write!(fmt, "  ")?;
+                }
+                _ => write!(fmt, "  ")?,
             }
 
             writeln!(fmt, "{}", Intel(statement))?;
         }
+
+        if prologue.is_some() {
+            writeln!(fmt, "{}:", dwarf::End(function.name))?;
+        }
+
         Ok(())
     }
 }
@@ -88,6 +135,61 @@ impl<T: fmt::Display> fmt::Display for Intel<&asm::Function<T>> {
 impl<T: fmt::Display> fmt::Display for Intel<&Statement<T>> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match &self.0 {
+            Statement::Binary(
+                binary @ asm::Binary::Load { signed, size },
+                Binary::RM {
+                    destination,
+                    source,
+                },
+            ) => {
+                // Writing to a 32-bit register implicitly zeroes the upper 32 bits
+                let bits = match (signed, size) {
+                    (false, Size::_32) => 32,
+                    _ => 64,
+                };
+
+                write!(
+                    fmt,
+                    "{} {:.*}, {} ptr {}",
+                    binary,
+                    bits,
+                    destination,
+                    Pointer(*size),
+                    Intel(source),
+                )
+            }
+            Statement::Binary(
+                binary @ asm::Binary::Store(size),
+                Binary::MR {
+                    destination,
+                    source,
+                },
+            ) => write!(
+                fmt,
+                "{} {} ptr {}, {:.*}",
+                binary,
+                Pointer(*size),
+                Intel(destination),
+                size.bits() as usize,
+                source,
+            ),
+            Statement::Binary(
+                binary @ asm::Binary::Store(size),
+                Binary::MI {
+                    destination,
+                    source,
+                },
+            ) => write!(
+                fmt,
+                "{} {} ptr {}, {}",
+                binary,
+                Pointer(*size),
+                Intel(destination),
+                source,
+            ),
+            Statement::Binary(asm::Binary::Load { .. } | asm::Binary::Store(_), _) => {
+                panic!("[INTERNAL ERROR]: invalid operands for narrow memory access")
+            }
             Statement::Binary(binary, operands) => {
                 write!(fmt, "{} {}", binary, Intel(operands))
             }
@@ -96,6 +198,7 @@ impl<T: fmt::Display> fmt::Display for Intel<&Statement<T>> {
             Statement::Label(label) => write!(fmt, "{}:", label),
             Statement::Jmp(label) => write!(fmt, "jmp {}", label),
             Statement::Jcc(condition, label) => write!(fmt, "j{} {}", condition, label),
+            Statement::Debug(debug) => write!(fmt, "{}", dwarf::Comment(debug)),
         }
     }
 }
@@ -185,10 +288,26 @@ impl<T: fmt::Display> fmt::Display for Intel<&Memory<T>> {
     }
 }
 
+struct Pointer(Size);
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let pointer = match self.0 {
+            Size::_8 => "byte",
+            Size::_16 => "word",
+            Size::_32 => "dword",
+            Size::_64 => "qword",
+        };
+
+        write!(fmt, "{}", pointer)
+    }
+}
+
 impl fmt::Display for Directive {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Directive::Intel => write!(fmt, ".intel_syntax noprefix"),
+            Directive::Att => write!(fmt, ".att_syntax prefix"),
             Directive::Align(alignment) => write!(fmt, ".align {}", alignment),
             Directive::Linkage(ir::Linkage::Local, label) => {
                 write!(fmt, ".local {}", label)
@@ -221,6 +340,10 @@ impl fmt::Display for Directive {
             Directive::Bss => write!(fmt, ".section .bss"),
             Directive::Ctors(priority) => write!(fmt, ".section .ctors.{}", priority),
             Directive::Text => write!(fmt, ".section .text"),
+            Directive::File(index, path) => write!(fmt, ".file {} {:?}", index, path.to_string()),
+            Directive::Location(index, span) => {
+                write!(fmt, ".loc {} {} {}", index, span.lo.row(), span.lo.column())
+            }
         }
     }
 }
@@ -238,6 +361,27 @@ impl fmt::Display for asm::Binary {
             asm::Binary::Cmp => "cmp",
             asm::Binary::Mov => "mov",
             asm::Binary::Lea => "lea",
+            asm::Binary::Load {
+                signed: true,
+                size: Size::_32,
+            } => "movsxd",
+            asm::Binary::Load {
+                signed: false,
+                size: Size::_32 | Size::_64,
+            }
+            | asm::Binary::Load {
+                signed: true,
+                size: Size::_64,
+            }
+            | asm::Binary::Store(_) => "mov",
+            asm::Binary::Load {
+                signed: true,
+                size: Size::_8 | Size::_16,
+            } => "movsx",
+            asm::Binary::Load {
+                signed: false,
+                size: Size::_8 | Size::_16,
+            } => "movzx",
         };
 
         write!(fmt, "{}", binary)
@@ -254,6 +398,8 @@ impl fmt::Display for asm::Unary {
             } => "call",
             asm::Unary::Hul => "imul",
             asm::Unary::Div | asm::Unary::Mod => "idiv",
+            asm::Unary::UHul => "mul",
+            asm::Unary::UDiv | asm::Unary::UMod => "div",
             asm::Unary::Push => "push",
             asm::Unary::Pop => "pop",
         };
@@ -272,6 +418,9 @@ impl fmt::Display for asm::Condition {
             asm::Condition::L => "l",
             asm::Condition::Le => "le",
             asm::Condition::Ae => "ae",
+            asm::Condition::B => "b",
+            asm::Condition::Be => "be",
+            asm::Condition::A => "a",
         };
 
         write!(fmt, "{}", condition)
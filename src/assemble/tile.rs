@@ -5,9 +5,11 @@ use crate::data::ir;
 use crate::data::lir;
 use crate::data::operand;
 use crate::data::operand::Immediate;
+use crate::data::operand::Label;
 use crate::data::operand::Memory;
 use crate::data::operand::Register;
 use crate::data::operand::Temporary;
+use crate::data::symbol;
 use crate::util;
 use crate::util::Or;
 
@@ -22,6 +24,13 @@ enum Mutate {
     No,
 }
 
+/// How to initialize `rdx` before a (high) multiplication or division.
+enum Extend {
+    No,
+    Sign,
+    Zero,
+}
+
 pub fn tile(
     frame_pointer: abi::FramePointer,
     function: &lir::Function<lir::Fallthrough>,
@@ -128,6 +137,9 @@ impl Tiler {
     fn tile_statement(&mut self, statement: &lir::Statement<lir::Fallthrough>) {
         match statement {
             lir::Statement::Label(label) => self.push(asm::Statement::Label(*label)),
+            lir::Statement::Debug(debug) => self.push(asm::Statement::Debug(
+                debug.map(|temporary| operand::Unary::R(*temporary)),
+            )),
             lir::Statement::Return(returns) => {
                 for (index, r#return) in returns.iter().enumerate() {
                     self.tile_binary(
@@ -180,6 +192,15 @@ impl Tiler {
                 }
                 source => self.tile_binary(asm::Binary::Mov, destination, source),
             },
+            // Special case: narrow loads and stores are inlined.
+            lir::Statement::Call(
+                lir::Expression::Immediate(Immediate::Label(Label::Fixed(name))),
+                arguments,
+                returns,
+            ) if abi::intrinsic(symbol::resolve(*name)).is_some() => {
+                let intrinsic = abi::intrinsic(symbol::resolve(*name)).unwrap();
+                self.tile_intrinsic(intrinsic, arguments, returns);
+            }
             lir::Statement::Call(function, arguments, returns) => {
                 let offset = if returns.len() > 2 {
                     self.tile_binary(
@@ -217,6 +238,38 @@ impl Tiler {
         }
     }
 
+    fn tile_intrinsic(
+        &mut self,
+        intrinsic: asm::Binary,
+        arguments: &[lir::Expression],
+        returns: &[Temporary],
+    ) {
+        let memory = match self.tile_memory(&arguments[0]) {
+            operand::Unary::M(memory) => memory,
+            _ => unreachable!(),
+        };
+
+        let operands = match (intrinsic, arguments, returns) {
+            (asm::Binary::Load { .. }, [_], [destination]) => operand::Binary::RM {
+                destination: *destination,
+                source: memory,
+            },
+            (asm::Binary::Store(_), [_, source], []) => match self.tile_expression(source) {
+                operand::Unary::I(source) => operand::Binary::MI {
+                    destination: memory,
+                    source,
+                },
+                source => operand::Binary::MR {
+                    destination: memory,
+                    source: self.shuttle(source),
+                },
+            },
+            _ => panic!("[INTERNAL ERROR]: malformed call to intrinsic"),
+        };
+
+        self.push(asm::Statement::Binary(intrinsic, operands));
+    }
+
     fn tile_expression(&mut self, expression: &lir::Expression) -> operand::Unary<Temporary> {
         let (binary, left, right) = match expression {
             lir::Expression::Immediate(immediate) => {
@@ -287,11 +340,19 @@ impl Tiler {
             | ir::Binary::Xor => {
                 self.tile_binary(asm::Binary::from(*binary), destination, right);
             }
-            ir::Binary::Hul | ir::Binary::Div | ir::Binary::Mod => {
-                let (cqo, unary, register) = match binary {
-                    ir::Binary::Hul => (false, asm::Unary::Hul, Register::Rdx),
-                    ir::Binary::Div => (true, asm::Unary::Div, Register::Rax),
-                    ir::Binary::Mod => (true, asm::Unary::Mod, Register::Rdx),
+            ir::Binary::Hul
+            | ir::Binary::Div
+            | ir::Binary::Mod
+            | ir::Binary::UHul
+            | ir::Binary::UDiv
+            | ir::Binary::UMod => {
+                let (extend, unary, register) = match binary {
+                    ir::Binary::Hul => (Extend::No, asm::Unary::Hul, Register::Rdx),
+                    ir::Binary::Div => (Extend::Sign, asm::Unary::Div, Register::Rax),
+                    ir::Binary::Mod => (Extend::Sign, asm::Unary::Mod, Register::Rdx),
+                    ir::Binary::UHul => (Extend::No, asm::Unary::UHul, Register::Rdx),
+                    ir::Binary::UDiv => (Extend::Zero, asm::Unary::UDiv, Register::Rax),
+                    ir::Binary::UMod => (Extend::Zero, asm::Unary::UMod, Register::Rdx),
                     _ => unreachable!(),
                 };
 
@@ -301,8 +362,10 @@ impl Tiler {
                 let source = self.shuttle(source);
 
                 self.tile_binary(asm::Binary::Mov, Register::Rax, destination);
-                if cqo {
-                    self.push(asm!((cqo)));
+                match extend {
+                    Extend::No => (),
+                    Extend::Sign => self.push(asm!((cqo))),
+                    Extend::Zero => self.push(asm!((mov rdx, 0))),
                 }
                 self.tile_unary(unary, source);
                 self.tile_binary(asm::Binary::Mov, destination, register);
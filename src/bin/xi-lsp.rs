@@ -0,0 +1,36 @@
+use std::io;
+use std::path::PathBuf;
+
+use clap::Parser;
+use clap::ValueHint;
+use xic::api;
+
+#[derive(Debug, Parser)]
+#[clap(
+    name = "xi-lsp",
+    about = "Language server for the Xi programming language, over stdin and stdout."
+)]
+struct Command {
+    /// Directory to search for library files
+    ///
+    /// Defaults to the same directory as the source file using the library.
+    #[clap(long = "libpath", value_hint = ValueHint::DirPath)]
+    directory_library: Option<PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    // Logs go to `stderr`, since `stdout` is reserved for the protocol
+    pretty_env_logger::init_timed();
+
+    let command = Command::parse();
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    api::serve(
+        command.directory_library.as_deref(),
+        stdin.lock(),
+        stdout.lock(),
+    )?;
+
+    Ok(())
+}
@@ -1,14 +1,21 @@
 mod clean;
 mod construct;
 mod destruct;
+mod dominator;
 mod dot;
+mod r#loop;
 mod split;
+mod ssa;
 
 pub use clean::clean_cfg;
 pub use construct::construct_cfg;
 pub use destruct::destruct_cfg;
+pub use dominator::Dominators;
 pub(crate) use dot::Dot;
+pub use r#loop::Loops;
 pub use split::split_cfg;
+pub use ssa::construct_ssa;
+pub use ssa::destruct_ssa;
 
 use std::fmt;
 use std::fmt::Write as _;
@@ -83,6 +90,16 @@ impl<T: Function> Cfg<T> {
         }
     }
 
+    /// Computes the dominator tree and dominance frontiers of this control flow graph.
+    pub fn dominators(&self) -> Dominators {
+        Dominators::new(self)
+    }
+
+    /// Computes the natural loops of this control flow graph.
+    pub fn loops(&self) -> Loops {
+        Loops::new(self, &self.dominators())
+    }
+
     pub fn blocks(&self) -> impl Iterator<Item = (&Label, &[T::Statement])> {
         self.blocks
             .iter()
@@ -255,37 +272,7 @@ impl<T: lir::Target> Function for lir::Function<T> {
     ) -> Self::Fallthrough {
         lir::Function {
             name,
-            statements: statements
-                .into_iter()
-                .map(|statement| match statement {
-                    lir::Statement::Jump(label) => lir::Statement::Jump(label),
-                    lir::Statement::Return(returns) => lir::Statement::Return(returns),
-                    lir::Statement::Label(label) => lir::Statement::Label(label),
-                    lir::Statement::Call(function, arguments, returns) => {
-                        lir::Statement::Call(function, arguments, returns)
-                    }
-                    lir::Statement::Move {
-                        destination,
-                        source,
-                    } => lir::Statement::Move {
-                        destination,
-                        source,
-                    },
-                    lir::Statement::CJump {
-                        condition,
-                        left,
-                        right,
-                        r#true,
-                        r#false: _,
-                    } => lir::Statement::CJump {
-                        condition,
-                        left,
-                        right,
-                        r#true,
-                        r#false: lir::Fallthrough,
-                    },
-                })
-                .collect(),
+            statements: statements.into_iter().map(fallthrough).collect(),
             arguments,
             returns,
             linkage,
@@ -342,6 +329,7 @@ impl<T: lir::Target> Function for lir::Function<T> {
                 source: _,
             } => None,
             lir::Statement::Return(_) => Some(Terminator::Return),
+            lir::Statement::Debug(_) => None,
         }
     }
 
@@ -363,6 +351,38 @@ impl<T: lir::Target> Function for lir::Function<T> {
     }
 }
 
+pub(super) fn fallthrough<T>(statement: lir::Statement<T>) -> lir::Statement<lir::Fallthrough> {
+    match statement {
+        lir::Statement::Jump(label) => lir::Statement::Jump(label),
+        lir::Statement::Return(returns) => lir::Statement::Return(returns),
+        lir::Statement::Label(label) => lir::Statement::Label(label),
+        lir::Statement::Debug(debug) => lir::Statement::Debug(debug),
+        lir::Statement::Call(function, arguments, returns) => {
+            lir::Statement::Call(function, arguments, returns)
+        }
+        lir::Statement::Move {
+            destination,
+            source,
+        } => lir::Statement::Move {
+            destination,
+            source,
+        },
+        lir::Statement::CJump {
+            condition,
+            left,
+            right,
+            r#true,
+            r#false: _,
+        } => lir::Statement::CJump {
+            condition,
+            left,
+            right,
+            r#true,
+            r#false: lir::Fallthrough,
+        },
+    }
+}
+
 impl<T: Clone> Function for asm::Function<T> {
     type Statement = asm::Statement<T>;
     type Metadata = (usize, usize, ir::Linkage);
@@ -425,6 +445,7 @@ impl<T: Clone> Function for asm::Function<T> {
             | asm::Statement::Nullary(asm::Nullary::Ret(_)) => None,
             asm::Statement::Binary(_, _) => None,
             asm::Statement::Unary(_, _) => None,
+            asm::Statement::Debug(_) => None,
             asm::Statement::Label(label) => Some(Terminator::Label(*label)),
             asm::Statement::Jmp(label) => Some(Terminator::Jump(*label)),
             asm::Statement::Jcc(_, label) => Some(Terminator::CJump {
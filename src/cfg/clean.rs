@@ -74,12 +74,17 @@ pub fn clean_cfg<T: Function>(cfg: &mut Cfg<T>) {
                             cfg.graph
                                 .add_edge(predecessor, target, Edge::Conditional(branch));
 
+                            // Note: both branches can share a single edge if they jump to
+                            // the same block, so we rewrite any branch that targets `label`.
                             match cfg.get_terminator_mut(&predecessor).unwrap() {
                                 TerminatorMut::Jump(_) => unreachable!(),
                                 TerminatorMut::CJump { r#true, r#false } => {
-                                    if branch {
+                                    if *r#true == label {
                                         *r#true = target;
-                                    } else if let Some(r#false) = r#false {
+                                    }
+                                    if let Some(r#false) =
+                                        r#false.filter(|r#false| **r#false == label)
+                                    {
                                         *r#false = target;
                                     }
                                 }
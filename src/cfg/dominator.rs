@@ -0,0 +1,104 @@
+use petgraph::algo::dominators;
+
+use crate::cfg::Cfg;
+use crate::cfg::Function;
+use crate::data::operand::Label;
+use crate::Map;
+use crate::Set;
+
+/// Dominator tree and dominance frontiers of a control flow graph.
+///
+/// Block `a` dominates block `b` if every path from the entry block to `b` passes
+/// through `a`. The dominance frontier of `a` contains the blocks where that dominance
+/// ends: blocks that `a` doesn't strictly dominate, but that have a predecessor `a`
+/// dominates. Blocks that are unreachable from the entry block are not in the tree.
+#[derive(Clone, Debug)]
+pub struct Dominators {
+    enter: Label,
+    immediate: Map<Label, Label>,
+    children: Map<Label, Vec<Label>>,
+    frontiers: Map<Label, Set<Label>>,
+}
+
+impl Dominators {
+    pub fn new<T: Function>(cfg: &Cfg<T>) -> Self {
+        let enter = *cfg.enter();
+        let dominators = dominators::simple_fast(&cfg.graph, enter);
+
+        let mut immediate = Map::default();
+        let mut children = Map::<_, Vec<_>>::default();
+
+        for label in cfg.blocks.keys() {
+            if let Some(dominator) = dominators.immediate_dominator(*label) {
+                immediate.insert(*label, dominator);
+                children.entry(dominator).or_default().push(*label);
+            }
+        }
+
+        // Cooper, Harvey, and Kennedy's algorithm for computing dominance frontiers:
+        // https://www.cs.rice.edu/~keith/EMBED/dom.pdf
+        let mut frontiers = Map::<_, Set<_>>::default();
+
+        for label in cfg.blocks.keys() {
+            if *label != enter && !immediate.contains_key(label) {
+                continue;
+            }
+
+            for predecessor in cfg.incoming(label) {
+                let mut runner = Some(predecessor).filter(|predecessor| {
+                    *predecessor == enter || immediate.contains_key(predecessor)
+                });
+
+                while let Some(dominator) =
+                    runner.filter(|runner| immediate.get(label) != Some(runner))
+                {
+                    frontiers.entry(dominator).or_default().insert(*label);
+                    runner = immediate.get(&dominator).copied();
+                }
+            }
+        }
+
+        Dominators {
+            enter,
+            immediate,
+            children,
+            frontiers,
+        }
+    }
+
+    /// Returns whether `label` is reachable from the entry block.
+    pub fn contains(&self, label: &Label) -> bool {
+        *label == self.enter || self.immediate.contains_key(label)
+    }
+
+    /// Returns the immediate dominator of `label`, or `None` for the entry block.
+    pub fn immediate(&self, label: &Label) -> Option<&Label> {
+        self.immediate.get(label)
+    }
+
+    /// Returns the blocks that `label` immediately dominates.
+    pub fn children(&self, label: &Label) -> &[Label] {
+        self.children
+            .get(label)
+            .map(|children| children.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn frontier(&self, label: &Label) -> impl Iterator<Item = &Label> + '_ {
+        self.frontiers.get(label).into_iter().flatten()
+    }
+
+    /// Returns whether `dominator` dominates `label`. Every block dominates itself.
+    pub fn dominates(&self, dominator: &Label, label: &Label) -> bool {
+        let mut runner = Some(label);
+
+        while let Some(label) = runner {
+            if label == dominator {
+                return true;
+            }
+            runner = self.immediate.get(label);
+        }
+
+        false
+    }
+}
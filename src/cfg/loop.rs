@@ -0,0 +1,164 @@
+use std::cmp;
+use std::ops;
+
+use crate::cfg::split::redirect_edge;
+use crate::cfg::Cfg;
+use crate::cfg::Dominators;
+use crate::cfg::Edge;
+use crate::cfg::Function;
+use crate::data::operand::Label;
+use crate::Set;
+
+/// Natural loops of a control flow graph, organized into a loop nesting forest.
+///
+/// Each back edge `n -> h`, where the header `h` dominates `n`, induces a natural loop
+/// containing `h` and every block that can reach `n` without passing through `h`.
+/// Back edges that share a header are merged into a single loop. Natural loops are
+/// either disjoint or nested, so each loop's parent is the smallest loop containing it.
+#[derive(Clone, Debug)]
+pub struct Loops {
+    loops: Vec<Loop>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Loop {
+    header: Label,
+    body: Set<Label>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    depth: usize,
+}
+
+impl Loops {
+    pub fn new<T: Function>(cfg: &Cfg<T>, dominators: &Dominators) -> Self {
+        let mut loops = Vec::new();
+
+        for header in cfg.blocks.keys() {
+            let mut stack = cfg
+                .incoming(header)
+                .filter(|predecessor| dominators.dominates(header, predecessor))
+                .collect::<Vec<_>>();
+
+            if stack.is_empty() {
+                continue;
+            }
+
+            let mut body = Set::default();
+            body.insert(*header);
+
+            while let Some(label) = stack.pop() {
+                if body.insert(label) {
+                    stack.extend(
+                        cfg.incoming(&label)
+                            .filter(|predecessor| dominators.contains(predecessor)),
+                    );
+                }
+            }
+
+            loops.push(Loop {
+                header: *header,
+                body,
+                parent: None,
+                children: Vec::new(),
+                depth: 0,
+            });
+        }
+
+        // Visit outer loops before inner ones, so parents have their depth
+        // computed before their children.
+        loops.sort_by_key(|r#loop| cmp::Reverse(r#loop.body.len()));
+
+        for inner in 0..loops.len() {
+            let parent = (0..inner)
+                .rev()
+                .find(|outer| loops[*outer].body.contains(&loops[inner].header));
+
+            if let Some(parent) = parent {
+                loops[inner].parent = Some(parent);
+                loops[inner].depth = loops[parent].depth + 1;
+                loops[parent].children.push(inner);
+            }
+        }
+
+        Loops { loops }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Loop> + '_ {
+        self.loops.iter()
+    }
+
+    /// Returns the indices of all loops, with inner loops before the loops containing them.
+    pub fn postorder(&self) -> Vec<usize> {
+        let mut postorder = (0..self.loops.len()).collect::<Vec<_>>();
+        postorder.sort_by_key(|index| cmp::Reverse(self.loops[*index].depth));
+        postorder
+    }
+
+    /// Inserts a preheader for the loop at `index`: a block that jumps unconditionally to
+    /// the loop header, and that every edge entering the loop from outside is redirected to.
+    ///
+    /// Enclosing loops are updated to contain the preheader.
+    pub fn insert_preheader<T: Function>(&mut self, cfg: &mut Cfg<T>, index: usize) -> Label {
+        let preheader = Label::fresh("preheader");
+        let header = self.loops[index].header;
+
+        let predecessors = cfg
+            .incoming(&header)
+            .filter(|predecessor| !self.loops[index].body.contains(predecessor))
+            .collect::<Vec<_>>();
+
+        cfg.blocks.insert(preheader, vec![T::jump(header)]);
+        cfg.graph.add_edge(preheader, header, Edge::Unconditional);
+
+        for predecessor in predecessors {
+            redirect_edge(cfg, predecessor, header, preheader);
+        }
+
+        if cfg.enter == header {
+            cfg.enter = preheader;
+        }
+
+        let mut parent = self.loops[index].parent;
+        while let Some(index) = parent {
+            self.loops[index].body.insert(preheader);
+            parent = self.loops[index].parent;
+        }
+
+        preheader
+    }
+}
+
+impl ops::Index<usize> for Loops {
+    type Output = Loop;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.loops[index]
+    }
+}
+
+impl Loop {
+    pub fn header(&self) -> &Label {
+        &self.header
+    }
+
+    /// Returns the blocks in this loop, including the header and any nested loops.
+    pub fn body(&self) -> &Set<Label> {
+        &self.body
+    }
+
+    pub fn contains(&self, label: &Label) -> bool {
+        self.body.contains(label)
+    }
+
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[usize] {
+        &self.children
+    }
+
+    /// Returns the number of loops enclosing this one.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
@@ -35,49 +35,52 @@ pub fn split_cfg<T: Function>(cfg: &mut Cfg<T>) {
                     .count()
                     > 1
         })
-        .map(|(predecessor, successor, edge)| (predecessor, successor, *edge))
+        .map(|(predecessor, successor, _)| (predecessor, successor))
         .collect::<Vec<_>>();
 
-    for (predecessor, successor, edge) in split {
-        let split = Label::fresh("split");
+    for (predecessor, successor) in split {
+        split_edge(cfg, predecessor, successor);
+    }
+}
 
-        cfg.blocks.insert(split, vec![T::jump(successor)]);
-        cfg.graph.add_edge(predecessor, split, edge);
-        cfg.graph.add_edge(split, successor, Edge::Unconditional);
-        cfg.graph.remove_edge(predecessor, successor);
+/// Inserts an empty block along the edge from `predecessor` to `successor`, and
+/// returns its label.
+pub(super) fn split_edge<T: Function>(
+    cfg: &mut Cfg<T>,
+    predecessor: Label,
+    successor: Label,
+) -> Label {
+    let split = Label::fresh("split");
 
-        let terminator = cfg
-            .blocks
-            .get_mut(&predecessor)
-            .and_then(|statements| statements.last_mut())
-            .and_then(T::to_terminator_mut)
-            .unwrap();
+    cfg.blocks.insert(split, vec![T::jump(successor)]);
+    cfg.graph.add_edge(split, successor, Edge::Unconditional);
+    redirect_edge(cfg, predecessor, successor, split);
 
-        match (edge, terminator) {
-            (Edge::Unconditional, TerminatorMut::Jump(target)) => {
-                *target = split;
-            }
-            (Edge::Conditional(true), TerminatorMut::CJump { r#true, r#false: _ }) => {
-                *r#true = split;
+    split
+}
+
+/// Redirects the edge from `predecessor` to `successor` so that it targets `target` instead.
+pub(super) fn redirect_edge<T: Function>(
+    cfg: &mut Cfg<T>,
+    predecessor: Label,
+    successor: Label,
+    target: Label,
+) {
+    let edge = cfg.graph.remove_edge(predecessor, successor).unwrap();
+    cfg.graph.add_edge(predecessor, target, edge);
+
+    match cfg.get_terminator_mut(&predecessor).unwrap() {
+        TerminatorMut::Jump(label) => *label = target,
+
+        // Note: both branches can share a single edge if they jump to
+        // the same block, so we rewrite any branch that targets `successor`.
+        TerminatorMut::CJump { r#true, r#false } => {
+            if *r#true == successor {
+                *r#true = target;
             }
-            (
-                Edge::Conditional(false),
-                TerminatorMut::CJump {
-                    r#true: _,
-                    r#false: Some(r#false),
-                },
-            ) => {
-                *r#false = split;
+            if let Some(r#false) = r#false.filter(|r#false| **r#false == successor) {
+                *r#false = target;
             }
-            (
-                Edge::Conditional(false),
-                TerminatorMut::CJump {
-                    r#true: _,
-                    r#false: None,
-                },
-            ) => (),
-            (Edge::Unconditional, TerminatorMut::CJump { .. })
-            | (Edge::Conditional(_), TerminatorMut::Jump(_)) => unreachable!(),
         }
     }
 }
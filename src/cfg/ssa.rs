@@ -0,0 +1,549 @@
+use std::mem;
+
+use crate::cfg::fallthrough;
+use crate::cfg::split::split_edge;
+use crate::cfg::Cfg;
+use crate::cfg::Edge;
+use crate::cfg::Function;
+use crate::cfg::Terminator;
+use crate::cfg::TerminatorMut;
+use crate::data::debug::Debug;
+use crate::data::ir;
+use crate::data::lir;
+use crate::data::operand::Label;
+use crate::data::operand::Temporary;
+use crate::data::ssa;
+use crate::data::symbol::Symbol;
+use crate::util;
+use crate::Map;
+use crate::Set;
+
+impl<T: lir::Target> Function for ssa::Function<T> {
+    type Statement = ssa::Statement<T>;
+    type Metadata = (Vec<Temporary>, usize, ir::Linkage);
+    type Fallthrough = ssa::Function<lir::Fallthrough>;
+
+    fn new(
+        name: Symbol,
+        statements: Vec<Self::Statement>,
+        (arguments, returns, linkage): Self::Metadata,
+        enter: Label,
+        exit: Label,
+    ) -> Self::Fallthrough {
+        ssa::Function {
+            name,
+            statements: statements
+                .into_iter()
+                .map(|statement| match statement {
+                    ssa::Statement::Phi {
+                        destination,
+                        sources,
+                    } => ssa::Statement::Phi {
+                        destination,
+                        sources,
+                    },
+                    ssa::Statement::Lir(statement) => ssa::Statement::Lir(fallthrough(statement)),
+                })
+                .collect(),
+            arguments,
+            returns,
+            linkage,
+            enter,
+            exit,
+        }
+    }
+
+    fn name(&self) -> Symbol {
+        self.name
+    }
+
+    fn metadata(&self) -> Self::Metadata {
+        (self.arguments.clone(), self.returns, self.linkage)
+    }
+
+    fn statements(&mut self) -> Vec<Self::Statement> {
+        mem::take(&mut self.statements)
+    }
+
+    fn enter(&self) -> Option<&Label> {
+        T::access(&self.enter)
+    }
+
+    fn exit(&self) -> Option<&Label> {
+        T::access(&self.exit)
+    }
+
+    fn jump(label: Label) -> Self::Statement {
+        ssa::Statement::Lir(lir::Statement::Jump(label))
+    }
+
+    fn label(label: Label) -> Self::Statement {
+        ssa::Statement::Lir(lir::Statement::Label(label))
+    }
+
+    fn to_terminator(statement: &Self::Statement) -> Option<Terminator> {
+        match statement {
+            ssa::Statement::Phi { .. } => None,
+            ssa::Statement::Lir(statement) => lir::Function::<T>::to_terminator(statement),
+        }
+    }
+
+    fn to_terminator_mut(statement: &mut Self::Statement) -> Option<TerminatorMut<'_>> {
+        match statement {
+            ssa::Statement::Phi { .. } => None,
+            ssa::Statement::Lir(statement) => lir::Function::<T>::to_terminator_mut(statement),
+        }
+    }
+}
+
+/// Converts `cfg` into static single assignment (SSA) form.
+///
+/// Places φ-functions for temporaries that are live across blocks at the iterated
+/// dominance frontiers of their definitions (semi-pruned SSA), and then renames each
+/// definition to a fresh temporary while walking the dominator tree. Blocks that are
+/// unreachable from the entry block are removed, and temporaries that reach a
+/// φ-function undefined are initialized to zero on entry.
+///
+/// Physical registers are never renamed.
+pub fn construct_ssa<T: lir::Target>(mut cfg: Cfg<lir::Function<T>>) -> Cfg<ssa::Function<T>> {
+    log::info!(
+        "[{}] Constructing SSA for {}...",
+        std::any::type_name::<Cfg<lir::Function<T>>>(),
+        cfg.name(),
+    );
+    util::time!(
+        "[{}] Done constructing SSA for {}",
+        std::any::type_name::<Cfg<lir::Function<T>>>(),
+        cfg.name(),
+    );
+
+    // φ-functions at the entry block would need a source for the
+    // implicit edge from the caller, so give it a fresh predecessor.
+    if cfg.incoming(&cfg.enter).next().is_some() {
+        let enter = Label::fresh("enter");
+        cfg.blocks
+            .insert(enter, vec![lir::Statement::Jump(cfg.enter)]);
+        cfg.graph.add_edge(enter, cfg.enter, Edge::Unconditional);
+        cfg.enter = enter;
+    }
+
+    let dominators = cfg.dominators();
+
+    let unreachable = cfg
+        .blocks
+        .keys()
+        .filter(|label| **label != cfg.exit && !dominators.contains(label))
+        .copied()
+        .collect::<Vec<_>>();
+
+    for label in unreachable {
+        cfg.graph.remove_node(label);
+        cfg.blocks.remove(&label);
+    }
+
+    let (arguments, _, _) = cfg.metadata();
+    let arguments = arguments.clone();
+
+    let mut blocks = mem::take(&mut cfg.blocks)
+        .into_iter()
+        .map(|(label, statements)| {
+            let statements = statements
+                .into_iter()
+                .map(ssa::Statement::Lir)
+                .collect::<Vec<_>>();
+            (label, statements)
+        })
+        .collect::<Map<_, _>>();
+
+    // Temporaries used before being defined in some block, and the
+    // blocks that define each temporary.
+    let mut globals = Set::default();
+    let mut definitions = Map::<Temporary, Set<Label>>::default();
+
+    for argument in &arguments {
+        definitions.entry(*argument).or_default().insert(cfg.enter);
+    }
+
+    let mut defined = Set::default();
+
+    for (label, statements) in &mut blocks {
+        defined.clear();
+
+        for statement in statements {
+            if let ssa::Statement::Lir(statement) = statement {
+                visit_uses(statement, &mut |temporary| {
+                    if !defined.contains(temporary) {
+                        globals.insert(*temporary);
+                    }
+                });
+
+                visit_definitions(statement, &mut |temporary| {
+                    defined.insert(*temporary);
+                    definitions.entry(*temporary).or_default().insert(*label);
+                });
+            }
+        }
+    }
+
+    let mut phis = Map::<Label, Vec<Temporary>>::default();
+    let mut worklist = Vec::new();
+    let mut inserted = Set::default();
+
+    for temporary in &globals {
+        worklist.extend(definitions.get(temporary).into_iter().flatten().copied());
+        inserted.clear();
+
+        while let Some(label) = worklist.pop() {
+            for frontier in dominators.frontier(&label) {
+                if inserted.insert(*frontier) {
+                    phis.entry(*frontier).or_default().push(*temporary);
+                    worklist.push(*frontier);
+                }
+            }
+        }
+    }
+
+    for (label, temporaries) in phis {
+        let predecessors = cfg.incoming(&label).collect::<Vec<_>>();
+        blocks[&label].splice(
+            0..0,
+            temporaries
+                .into_iter()
+                .map(|temporary| ssa::Statement::Phi {
+                    destination: temporary,
+                    sources: predecessors
+                        .iter()
+                        .map(|predecessor| (*predecessor, temporary))
+                        .collect(),
+                }),
+        );
+    }
+
+    // Stack of renamed definitions for each original temporary. Arguments are
+    // defined on entry, and keep their original names.
+    let mut stacks = arguments
+        .iter()
+        .map(|argument| (*argument, vec![*argument]))
+        .collect::<Map<_, _>>();
+
+    // Temporaries that reach a φ-function without being defined. Copying them out of
+    // SSA would read uninitialized temporaries, so they're defined on entry instead.
+    let mut undefined = Set::default();
+    let mut walk = vec![Walk::Enter(cfg.enter)];
+
+    while let Some(event) = walk.pop() {
+        let label = match event {
+            Walk::Enter(label) => label,
+            Walk::Exit(renamed) => {
+                for temporary in renamed {
+                    stacks[&temporary].pop();
+                }
+                continue;
+            }
+        };
+
+        let mut renamed = Vec::new();
+
+        for statement in &mut blocks[&label] {
+            match statement {
+                ssa::Statement::Phi {
+                    destination,
+                    sources: _,
+                } => define(&mut stacks, &mut renamed, destination),
+                ssa::Statement::Lir(lir::Statement::Debug(Debug::Variable(_, _, temporary))) => {
+                    r#use(&stacks, temporary)
+                }
+                ssa::Statement::Lir(statement) => {
+                    visit_uses(statement, &mut |temporary| r#use(&stacks, temporary));
+                    visit_definitions(statement, &mut |temporary| {
+                        define(&mut stacks, &mut renamed, temporary)
+                    });
+                }
+            }
+        }
+
+        for successor in cfg.graph.neighbors(label) {
+            for statement in &mut blocks[&successor] {
+                let sources = match statement {
+                    ssa::Statement::Phi {
+                        destination: _,
+                        sources,
+                    } => sources,
+                    ssa::Statement::Lir(_) => break,
+                };
+
+                // Each source is renamed exactly once, so it still refers to the
+                // original temporary here.
+                let (_, source) = sources
+                    .iter_mut()
+                    .find(|(predecessor, _)| *predecessor == label)
+                    .unwrap();
+
+                match stacks.get(source).and_then(|stack| stack.last()) {
+                    Some(current) => *source = *current,
+                    None => {
+                        undefined.insert(*source);
+                    }
+                }
+            }
+        }
+
+        walk.push(Walk::Exit(renamed));
+        walk.extend(
+            dominators
+                .children(&label)
+                .iter()
+                .rev()
+                .copied()
+                .map(Walk::Enter),
+        );
+    }
+
+    blocks[&cfg.enter].splice(
+        0..0,
+        undefined.into_iter().map(|temporary| {
+            ssa::Statement::Lir(lir::Statement::Move {
+                destination: lir::Expression::Temporary(temporary),
+                source: lir::ZERO,
+            })
+        }),
+    );
+
+    Cfg {
+        name: cfg.name,
+        metadata: cfg.metadata,
+        enter: cfg.enter,
+        exit: cfg.exit,
+        graph: cfg.graph,
+        blocks,
+    }
+}
+
+enum Walk {
+    Enter(Label),
+    Exit(Vec<Temporary>),
+}
+
+fn r#use(stacks: &Map<Temporary, Vec<Temporary>>, temporary: &mut Temporary) {
+    if let Some(current) = stacks.get(temporary).and_then(|stack| stack.last()) {
+        *temporary = *current;
+    }
+}
+
+fn define(
+    stacks: &mut Map<Temporary, Vec<Temporary>>,
+    renamed: &mut Vec<Temporary>,
+    temporary: &mut Temporary,
+) {
+    let fresh = Temporary::fresh("ssa");
+    stacks.entry(*temporary).or_default().push(fresh);
+    renamed.push(*temporary);
+    *temporary = fresh;
+}
+
+/// Converts `cfg` out of static single assignment (SSA) form.
+///
+/// Replaces each φ-function with copies along its incoming edges, splitting edges
+/// from blocks with multiple successors. The copies along each edge happen in
+/// parallel, so they're sequentialized with extra temporaries to break cycles.
+pub fn destruct_ssa<T: lir::Target>(mut cfg: Cfg<ssa::Function<T>>) -> Cfg<lir::Function<T>> {
+    log::info!(
+        "[{}] Destructing SSA for {}...",
+        std::any::type_name::<Cfg<ssa::Function<T>>>(),
+        cfg.name(),
+    );
+    util::time!(
+        "[{}] Done destructing SSA for {}",
+        std::any::type_name::<Cfg<ssa::Function<T>>>(),
+        cfg.name(),
+    );
+
+    let joins = cfg
+        .blocks
+        .iter()
+        .filter(|(_, statements)| matches!(statements.first(), Some(ssa::Statement::Phi { .. })))
+        .map(|(label, _)| *label)
+        .collect::<Vec<_>>();
+
+    let mut copies = Map::<Label, Vec<(Temporary, Temporary)>>::default();
+
+    for join in joins {
+        let predecessors = cfg.incoming(&join).collect::<Vec<_>>();
+
+        for predecessor in predecessors {
+            // Copies can only be placed before an unconditional jump.
+            let block = match cfg.get_terminator(&predecessor) {
+                Some(Terminator::Jump(_)) => predecessor,
+                _ => split_edge(&mut cfg, predecessor, join),
+            };
+
+            let copies = copies.entry(block).or_default();
+
+            for statement in &mut cfg.blocks[&join] {
+                let (destination, sources) = match statement {
+                    ssa::Statement::Phi {
+                        destination,
+                        sources,
+                    } => (destination, sources),
+                    ssa::Statement::Lir(_) => break,
+                };
+
+                for (label, source) in sources {
+                    if *label == predecessor {
+                        *label = block;
+                        copies.push((*destination, *source));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut blocks = cfg
+        .blocks
+        .into_iter()
+        .map(|(label, statements)| {
+            let statements = statements
+                .into_iter()
+                .filter_map(|statement| match statement {
+                    ssa::Statement::Phi { .. } => None,
+                    ssa::Statement::Lir(statement) => Some(statement),
+                })
+                .collect::<Vec<_>>();
+            (label, statements)
+        })
+        .collect::<Map<_, _>>();
+
+    for (label, copies) in copies {
+        let statements = &mut blocks[&label];
+        let jump = statements.pop().unwrap();
+
+        statements.extend(
+            sequentialize(copies)
+                .into_iter()
+                .map(|(destination, source)| lir::Statement::Move {
+                    destination: lir::Expression::Temporary(destination),
+                    source: lir::Expression::Temporary(source),
+                }),
+        );
+        statements.push(jump);
+    }
+
+    Cfg {
+        name: cfg.name,
+        metadata: cfg.metadata,
+        enter: cfg.enter,
+        exit: cfg.exit,
+        graph: cfg.graph,
+        blocks,
+    }
+}
+
+/// Orders the parallel `copies` so that no destination is overwritten before it's
+/// read, saving a destination in a fresh temporary when the copies form a cycle.
+fn sequentialize(mut copies: Vec<(Temporary, Temporary)>) -> Vec<(Temporary, Temporary)> {
+    let mut sequential = Vec::with_capacity(copies.len());
+
+    copies.retain(|(destination, source)| destination != source);
+
+    while !copies.is_empty() {
+        let ready = copies
+            .iter()
+            .position(|(destination, _)| copies.iter().all(|(_, source)| source != destination));
+
+        let index = match ready {
+            Some(index) => index,
+            None => {
+                let (destination, _) = copies[0];
+                let saved = Temporary::fresh("swap");
+
+                sequential.push((saved, destination));
+
+                for (_, source) in &mut copies {
+                    if *source == destination {
+                        *source = saved;
+                    }
+                }
+
+                0
+            }
+        };
+
+        sequential.push(copies.remove(index));
+    }
+
+    sequential
+}
+
+fn visit_uses<T, F: FnMut(&mut Temporary)>(statement: &mut lir::Statement<T>, apply: &mut F) {
+    match statement {
+        lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => (),
+        lir::Statement::CJump {
+            condition: _,
+            left,
+            right,
+            r#true: _,
+            r#false: _,
+        } => {
+            visit_expression(left, apply);
+            visit_expression(right, apply);
+        }
+        lir::Statement::Call(function, arguments, _) => {
+            visit_expression(function, apply);
+            for argument in arguments {
+                visit_expression(argument, apply);
+            }
+        }
+        lir::Statement::Move {
+            destination,
+            source,
+        } => {
+            if let lir::Expression::Memory(address) = destination {
+                visit_expression(address, apply);
+            }
+            visit_expression(source, apply);
+        }
+        lir::Statement::Return(returns) => {
+            for r#return in returns {
+                visit_expression(r#return, apply);
+            }
+        }
+    }
+}
+
+fn visit_expression<F: FnMut(&mut Temporary)>(expression: &mut lir::Expression, apply: &mut F) {
+    match expression {
+        lir::Expression::Immediate(_) | lir::Expression::Temporary(Temporary::Register(_)) => (),
+        lir::Expression::Temporary(temporary) => apply(temporary),
+        lir::Expression::Memory(address) => visit_expression(address, apply),
+        lir::Expression::Binary(_, left, right) => {
+            visit_expression(left, apply);
+            visit_expression(right, apply);
+        }
+    }
+}
+
+fn visit_definitions<T, F: FnMut(&mut Temporary)>(
+    statement: &mut lir::Statement<T>,
+    apply: &mut F,
+) {
+    match statement {
+        lir::Statement::Call(_, _, returns) => returns
+            .iter_mut()
+            .filter(|r#return| !matches!(r#return, Temporary::Register(_)))
+            .for_each(apply),
+        lir::Statement::Move {
+            destination: lir::Expression::Temporary(Temporary::Register(_)),
+            source: _,
+        } => (),
+        lir::Statement::Move {
+            destination: lir::Expression::Temporary(temporary),
+            source: _,
+        } => apply(temporary),
+        lir::Statement::Jump(_)
+        | lir::Statement::CJump { .. }
+        | lir::Statement::Label(_)
+        | lir::Statement::Move { .. }
+        | lir::Statement::Return(_)
+        | lir::Statement::Debug(_) => (),
+    }
+}
@@ -3,10 +3,16 @@
 mod check;
 mod context;
 mod error;
+mod exhaustive;
+mod infer;
+mod lint;
 mod load;
 mod monomorphize;
+mod warning;
 
 pub use check::check;
+pub use check::check_warnings;
+pub use check::check_without_interface;
 pub(crate) use context::Context;
 pub(crate) use context::Entry;
 pub(crate) use context::GlobalScope;
@@ -14,3 +20,8 @@ pub(crate) use context::LocalScope;
 pub(crate) use context::Scope;
 pub(crate) use error::Error;
 pub(crate) use error::ErrorKind;
+pub use warning::Level;
+pub use warning::Lint;
+pub use warning::Lints;
+pub use warning::Warning;
+pub(crate) use warning::WarningKind;
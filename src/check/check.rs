@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::path::Path;
 
 use crate::abi;
@@ -5,14 +6,20 @@ use crate::check::context::Entry;
 use crate::check::context::GlobalScope;
 use crate::check::context::LeastUpperBound;
 use crate::check::context::LocalScope;
+use crate::check::exhaustive;
 use crate::check::Context;
 use crate::check::Error;
 use crate::check::ErrorKind;
 use crate::check::Scope;
+use crate::check::Warning;
+use crate::check::WarningKind;
 use crate::data::ast;
 use crate::data::r#type;
+use crate::data::span::Span;
+use crate::data::symbol;
 use crate::data::symbol::Symbol;
 use crate::util;
+use crate::Map;
 use crate::Set;
 
 macro_rules! bail {
@@ -44,8 +51,40 @@ macro_rules! expected {
 pub fn check(
     directory_library: Option<&Path>,
     path: &Path,
-    mut program: ast::Program<()>,
+    program: ast::Program<()>,
 ) -> Result<(ast::Program<r#type::Expression>, Context), crate::Error> {
+    check_warnings(directory_library, path, program).map(|(program, context, _)| (program, context))
+}
+
+/// Type checks a program, additionally returning every warning found.
+///
+/// Warnings are reported regardless of their configured `Level`, which is left to the caller.
+pub fn check_warnings(
+    directory_library: Option<&Path>,
+    path: &Path,
+    program: ast::Program<()>,
+) -> Result<(ast::Program<r#type::Expression>, Context, Vec<Warning>), crate::Error> {
+    check_implicit(directory_library, path, program, true)
+}
+
+/// Type checks a program like `check_warnings`, but without loading its own
+/// interface file, which may be missing or out of date when generating a new one.
+///
+/// Functions aren't linted as unused, since the generated interface exports them.
+pub fn check_without_interface(
+    directory_library: Option<&Path>,
+    path: &Path,
+    program: ast::Program<()>,
+) -> Result<(ast::Program<r#type::Expression>, Context, Vec<Warning>), crate::Error> {
+    check_implicit(directory_library, path, program, false)
+}
+
+fn check_implicit(
+    directory_library: Option<&Path>,
+    path: &Path,
+    mut program: ast::Program<()>,
+    implicit: bool,
+) -> Result<(ast::Program<r#type::Expression>, Context, Vec<Warning>), crate::Error> {
     log::info!(
         "[{}] Type checking {}...",
         std::any::type_name::<ast::Program<()>>(),
@@ -60,15 +99,49 @@ pub fn check(
     let mut checker = Checker {
         context: Context::new(),
         used: Set::default(),
+        exports: Map::default(),
+        referenced: RefCell::default(),
+        captures: Vec::new(),
+        instantiations: Map::default(),
+        provenance: Vec::new(),
+        instantiated: Vec::new(),
+        errors: Vec::new(),
+        warnings: Vec::new(),
     };
 
     let directory_library = directory_library.unwrap_or_else(|| path.parent().unwrap());
 
-    checker.load_program(directory_library, path, &program)?;
+    checker.load_program(directory_library, path, &program, implicit)?;
+
+    // Note: must run before monomorphization, which rewrites template names
+    let references = checker.collect_references(&mut program);
+
+    checker.check_templates(&program);
     checker.monomorphize_program(&mut program)?;
-    let program = checker.check_program(program)?;
+    let mut program = checker.check_program(program);
 
-    Ok((program, checker.context))
+    if !checker.errors.is_empty() {
+        // Note: recovery can discover errors out of order, e.g. in the body
+        // of an `if` statement before its condition.
+        checker.errors.sort_by_key(Error::span);
+        return Err(checker.errors.into());
+    }
+
+    checker.lint_uses(&program, &references);
+
+    // Note: without its own interface, every function is about to be exported
+    // by the one generated from the program, so none of them are unused.
+    if implicit {
+        checker.lint_functions(&program);
+    }
+
+    checker.lint_stores(&mut program);
+
+    // Note: template instantiations share spans, so they can report the same warning
+    checker.warnings.sort_by_key(Warning::span);
+    checker.warnings.dedup();
+
+    Ok((program, checker.context, checker.warnings))
 }
 
 pub(super) struct Checker {
@@ -76,39 +149,160 @@ pub(super) struct Checker {
 
     /// Set of unique interfaces in the use tree
     pub(super) used: Set<Symbol>,
+
+    /// Names of items made visible by each interface, including transitively used ones
+    pub(super) exports: Map<Symbol, Set<Symbol>>,
+
+    /// Spans of declarations that have been read from
+    pub(super) referenced: RefCell<Set<Span>>,
+
+    /// Local variables captured by each enclosing closure, innermost last
+    captures: Vec<Set<Symbol>>,
+
+    /// Template name and type arguments of each class and enum instantiation
+    pub(super) instantiations: Map<Symbol, (Symbol, Vec<ast::Type<()>>)>,
+
+    /// Chain of template instantiations leading to the item being checked
+    pub(super) provenance: Vec<Span>,
+
+    /// Instantiations created while checking calls with inferred type arguments,
+    /// which have not been checked yet
+    instantiated: Vec<ast::Item<()>>,
+
+    /// Errors recovered from so far while checking the program
+    errors: Vec<Error>,
+
+    /// Warnings found so far while checking the program
+    pub(super) warnings: Vec<Warning>,
 }
 
 impl Checker {
-    fn check_program(
-        &mut self,
-        program: ast::Program<()>,
-    ) -> Result<ast::Program<r#type::Expression>, Error> {
-        let items = program
+    fn check_program(&mut self, program: ast::Program<()>) -> ast::Program<r#type::Expression> {
+        let mut items = program
             .items
             .into_iter()
-            .map(|item| match item {
-                ast::Item::Global(global) => self.check_global(global).map(ast::Item::Global),
-                ast::Item::Class(class) => {
-                    let provenance = class.provenance.clone();
-                    self.check_class(class)
-                        .map_err(|error| error.with_provenance(provenance))
-                        .map(ast::Item::Class)
-                }
-                ast::Item::ClassTemplate(_) => unreachable!(),
-                ast::Item::Function(function) => {
-                    let provenance = function.provenance.clone();
-                    self.check_function(GlobalScope::Global, function)
-                        .map_err(|error| error.with_provenance(provenance))
-                        .map(ast::Item::Function)
-                }
-                ast::Item::FunctionTemplate(_) => unreachable!(),
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+            .filter_map(|item| self.check_item(item))
+            .collect::<Vec<_>>();
 
-        Ok(ast::Program {
+        // Note: checking an instantiation can infer further instantiations
+        while !self.instantiated.is_empty() {
+            for item in std::mem::take(&mut self.instantiated) {
+                items.extend(self.check_item(item));
+            }
+        }
+
+        ast::Program {
             uses: program.uses,
             items,
-        })
+        }
+    }
+
+    /// Checks the body of each template against the bounds on its parameters.
+    ///
+    /// The instantiations created for checking are discarded afterward, along with
+    /// any changes they made to the context, so that they aren't compiled.
+    fn check_templates(&mut self, program: &ast::Program<()>) {
+        for item in &program.items {
+            if !matches!(
+                item,
+                ast::Item::ClassTemplate(_)
+                    | ast::Item::EnumTemplate(_)
+                    | ast::Item::FunctionTemplate(_)
+            ) {
+                continue;
+            }
+
+            let context = self.context.clone();
+            let instantiations = self.instantiations.clone();
+            let warnings = self.warnings.len();
+
+            match self.instantiate_bounds(item) {
+                None => continue,
+                Some(Err(error)) => self.errors.push(error),
+                Some(Ok(items)) => {
+                    for item in items {
+                        self.check_item(item);
+                    }
+
+                    while !self.instantiated.is_empty() {
+                        for item in std::mem::take(&mut self.instantiated) {
+                            self.check_item(item);
+                        }
+                    }
+                }
+            }
+
+            self.context = context;
+            self.instantiations = instantiations;
+            self.warnings.truncate(warnings);
+        }
+    }
+
+    fn check_item(&mut self, item: ast::Item<()>) -> Option<ast::Item<r#type::Expression>> {
+        match item {
+            ast::Item::Global(global) => {
+                let global = self.check_global(global);
+                self.recover(global).map(ast::Item::Global)
+            }
+            ast::Item::Class(class) => {
+                let provenance = class.provenance.clone();
+                self.with_provenance(provenance, |checker| Ok(checker.check_class(class)))
+                    .map(ast::Item::Class)
+            }
+            ast::Item::ClassTemplate(_) => unreachable!(),
+            ast::Item::Protocol(protocol) => {
+                let protocol = self.check_protocol(protocol);
+                self.recover(protocol).map(ast::Item::Protocol)
+            }
+            ast::Item::Enum(r#enum) => {
+                let provenance = r#enum.provenance.clone();
+                self.with_provenance(provenance, |checker| checker.check_enum(r#enum))
+                    .map(ast::Item::Enum)
+            }
+            ast::Item::EnumTemplate(_) => unreachable!(),
+            ast::Item::Function(function) => {
+                let provenance = function.provenance.clone();
+                self.with_provenance(provenance, |checker| {
+                    checker.check_function(GlobalScope::Global, function)
+                })
+                .map(ast::Item::Function)
+            }
+            ast::Item::FunctionTemplate(_) => unreachable!(),
+        }
+    }
+
+    /// Records the error in `result`, if any, so that checking can continue
+    /// with the next item or statement.
+    fn recover<T>(&mut self, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.errors.push(error);
+                None
+            }
+        }
+    }
+
+    /// Runs `check`, attributing every error it produces to the template
+    /// instantiation chain `provenance`.
+    fn with_provenance<T, F>(&mut self, provenance: Vec<Span>, check: F) -> Option<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        let start = self.errors.len();
+        let enclosing = std::mem::replace(&mut self.provenance, provenance.clone());
+        let result = check(self);
+        let checked = self.recover(result);
+        self.provenance = enclosing;
+
+        let errors = self.errors.split_off(start);
+        self.errors.extend(
+            errors
+                .into_iter()
+                .map(|error| error.with_provenance(provenance.clone())),
+        );
+
+        checked
     }
 
     fn check_global(
@@ -130,6 +324,7 @@ impl Checker {
         class: &ast::ClassSignature<()>,
     ) -> Result<(), Error> {
         let _ = self.check_class_like(class)?;
+        let _ = self.check_class_implements(class)?;
 
         for method in &class.methods {
             let _ = self.check_function_like(method)?;
@@ -138,11 +333,11 @@ impl Checker {
         Ok(())
     }
 
-    fn check_class(
-        &mut self,
-        class: ast::Class<()>,
-    ) -> Result<ast::Class<r#type::Expression>, Error> {
-        let extends = self.check_class_like(&class)?;
+    fn check_class(&mut self, class: ast::Class<()>) -> ast::Class<r#type::Expression> {
+        let extends = self.check_class_like(&class);
+        let extends = self.recover(extends).flatten();
+        let implements = self.check_class_implements(&class);
+        let implements = self.recover(implements).unwrap_or_default();
 
         // Classes must implement at least the methods declared in its interface
         if let Some(span) = self
@@ -155,20 +350,24 @@ impl Checker {
                 _ => None,
             })
         {
-            bail!(
+            self.errors.push(Error::new(
                 class.span,
-                ErrorKind::ClassIncomplete(class.name.symbol, span)
-            );
+                ErrorKind::ClassIncomplete(class.name.symbol, span),
+            ));
         }
 
+        self.check_class_complete(&class);
+
         let items = class
             .items
             .into_iter()
-            .map(|item| {
+            .filter_map(|item| {
                 match item {
-                    ast::ClassItem::Field(declaration) => self
-                        .check_declaration(GlobalScope::Class(class.name.symbol), declaration)
-                        .map(ast::ClassItem::Field),
+                    ast::ClassItem::Field(declaration) => {
+                        let declaration = self
+                            .check_declaration(GlobalScope::Class(class.name.symbol), declaration);
+                        self.recover(declaration).map(ast::ClassItem::Field)
+                    }
                     ast::ClassItem::Method(method) => {
                         // Check if method is declared or defined by an ancestor
                         if let Some((
@@ -198,26 +397,31 @@ impl Checker {
                             if !self.context.all_subtype(old_parameters, new_parameters)
                                 || !self.context.all_subtype(new_returns, old_returns)
                             {
-                                bail!(*method.name.span, ErrorKind::SignatureMismatch(*span));
+                                self.errors.push(Error::new(
+                                    *method.name.span,
+                                    ErrorKind::SignatureMismatch(*span),
+                                ));
                             }
                         }
 
-                        self.check_function(GlobalScope::Class(class.name.symbol), method)
-                            .map(ast::ClassItem::Method)
+                        let method =
+                            self.check_function(GlobalScope::Class(class.name.symbol), method);
+                        self.recover(method).map(ast::ClassItem::Method)
                     }
                 }
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect();
 
-        Ok(ast::Class {
+        ast::Class {
             r#final: class.r#final,
             name: class.name,
             extends,
+            implements,
             items,
             provenance: class.provenance,
             declared: class.declared.clone(),
             span: class.span,
-        })
+        }
     }
 
     fn check_class_like<C: ast::ClassLike<()>>(
@@ -226,6 +430,18 @@ impl Checker {
     ) -> Result<Option<ast::Variable<r#type::Expression>>, Error> {
         if let Some(supertype) = class.extends() {
             let supertype = self.check_variable(supertype.clone())?;
+            if self.context.get_protocol(&supertype.name).is_some() {
+                bail!(
+                    supertype.span,
+                    ErrorKind::ExtendsInterface(supertype.name.symbol)
+                );
+            }
+            if self.context.get_enum(&supertype.name).is_some() {
+                bail!(
+                    supertype.span,
+                    ErrorKind::EnumNotClass(supertype.name.symbol)
+                );
+            }
             if let Some(span) = self.context.get_final(&supertype.name.symbol) {
                 bail!(
                     supertype.span,
@@ -239,22 +455,216 @@ impl Checker {
         }
     }
 
+    fn check_class_implements<C: ast::ClassLike<()>>(
+        &mut self,
+        class: &C,
+    ) -> Result<Vec<ast::Variable<r#type::Expression>>, Error> {
+        class
+            .implements()
+            .iter()
+            .map(|protocol| {
+                let protocol = self.check_variable(protocol.clone())?;
+                if self.context.get_protocol(&protocol.name).is_none() {
+                    bail!(protocol.span, ErrorKind::NotInterface(protocol.name.symbol));
+                }
+                Ok(protocol)
+            })
+            .collect()
+    }
+
+    /// Checks that `class` or its ancestors implement every method of the
+    /// interfaces that `class` implements, with compatible signatures.
+    fn check_class_complete(&mut self, class: &ast::Class<()>) {
+        let protocols = self
+            .context
+            .get_implements(&class.name.symbol)
+            .filter(|protocol| self.context.get_protocol(protocol).is_some())
+            .collect::<Vec<_>>();
+
+        for protocol in protocols {
+            for (method, entry) in self.context.get_class(&protocol).unwrap() {
+                let (parameters, returns) = match entry {
+                    Entry::Signature(parameters, returns) => (parameters, returns),
+                    Entry::Variable(_) | Entry::Function(_, _) => unreachable!(),
+                };
+
+                let error = match self
+                    .context
+                    .get_full(GlobalScope::Class(class.name.symbol), method)
+                {
+                    Some((
+                        span,
+                        Entry::Function(new_parameters, new_returns)
+                        | Entry::Signature(new_parameters, new_returns),
+                    )) => {
+                        if self.context.all_subtype(parameters, new_parameters)
+                            && self.context.all_subtype(new_returns, returns)
+                        {
+                            continue;
+                        }
+                        Error::new(*span, ErrorKind::SignatureMismatch(*method.span))
+                    }
+                    Some((_, Entry::Variable(_))) | None => Error::new(
+                        class.span,
+                        ErrorKind::ClassIncomplete(class.name.symbol, *method.span),
+                    ),
+                };
+
+                self.errors.push(error);
+            }
+        }
+    }
+
+    pub(super) fn check_protocol(
+        &mut self,
+        protocol: ast::Protocol<()>,
+    ) -> Result<ast::Protocol<r#type::Expression>, Error> {
+        let methods = protocol
+            .methods
+            .into_iter()
+            .map(|method| {
+                let parameters = method
+                    .parameters
+                    .into_iter()
+                    .map(|ast::SingleDeclaration { name, r#type, span }| {
+                        Ok(ast::SingleDeclaration {
+                            name,
+                            r#type: Box::new(self.check_type(*r#type)?),
+                            span,
+                        })
+                    })
+                    .collect::<Result<_, Error>>()?;
+
+                let returns = method
+                    .returns
+                    .into_iter()
+                    .map(|r#return| self.check_type(r#return))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(ast::FunctionSignature {
+                    name: method.name,
+                    parameters,
+                    returns,
+                    span: method.span,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(ast::Protocol {
+            name: protocol.name,
+            methods,
+            span: protocol.span,
+        })
+    }
+
+    fn check_enum(
+        &mut self,
+        r#enum: ast::Enum<()>,
+    ) -> Result<ast::Enum<r#type::Expression>, Error> {
+        let variants = r#enum
+            .variants
+            .into_iter()
+            .map(|variant| {
+                let payload = variant
+                    .payload
+                    .into_iter()
+                    .map(|r#type| self.check_type(r#type))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(ast::Variant {
+                    name: variant.name,
+                    payload,
+                    span: variant.span,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(ast::Enum {
+            name: r#enum.name,
+            variants,
+            provenance: r#enum.provenance,
+            span: r#enum.span,
+        })
+    }
+
+    /// Checks that `expression` could evaluate to an instance of class or interface `r#type`.
+    ///
+    /// Conversions between unrelated classes can never succeed, but a class that isn't
+    /// `final` may have a subclass that implements any interface. Enums can't be
+    /// extended, so they're treated like `final` classes.
+    fn check_class_test(
+        &mut self,
+        expression: ast::Expression<()>,
+        r#type: ast::Type<()>,
+    ) -> Result<
+        (
+            ast::Expression<r#type::Expression>,
+            ast::Type<r#type::Expression>,
+        ),
+        Error,
+    > {
+        let expression = self.check_expression(expression)?;
+        let r#type = self.check_type(r#type)?;
+
+        let possible = match (expression.r#type(), r#type.r#type()) {
+            (r#type::Expression::Poison, _) | (_, r#type::Expression::Poison) => true,
+            (
+                from @ (r#type::Expression::Null | r#type::Expression::Class(_)),
+                to @ r#type::Expression::Class(_),
+            ) if self.context.is_subtype(&from, &to) || self.context.is_subtype(&to, &from) => true,
+            (r#type::Expression::Class(from), r#type::Expression::Class(to)) => {
+                let extensible = |class| {
+                    self.context.get_final(class).is_none()
+                        && self.context.get_enum(class).is_none()
+                };
+                (self.context.get_protocol(&to).is_some() && extensible(&from))
+                    || (self.context.get_protocol(&from).is_some() && extensible(&to))
+            }
+            _ => false,
+        };
+
+        if !possible {
+            bail!(
+                expression.span(),
+                ErrorKind::ImpossibleCast(expression.r#type(), r#type.r#type())
+            );
+        }
+
+        Ok((expression, r#type))
+    }
+
     fn check_function(
         &mut self,
         scope: GlobalScope,
         function: ast::Function<()>,
     ) -> Result<ast::Function<r#type::Expression>, Error> {
-        let returns = self.check_function_like(&function)?;
+        let start = self.errors.len();
+
+        // Note: errors in return types are recorded after checking parameters
+        // below, so that diagnostics are reported in source order.
+        let returns = function
+            .returns
+            .into_iter()
+            .map(|r#return| self.check_type(r#return))
+            .collect::<Vec<_>>();
 
         let is_procedure = returns.is_empty();
+        let return_types = returns
+            .iter()
+            .map(|r#return| match r#return {
+                Ok(r#return) => r#return.r#type(),
+                Err(_) => r#type::Expression::Poison,
+            })
+            .collect();
+
         let scope = match scope {
             GlobalScope::Class(class) => LocalScope::Method {
                 class,
                 this: None,
-                returns: returns.iter().map(|r#return| r#return.r#type()).collect(),
+                returns: return_types,
             },
             GlobalScope::Global => LocalScope::Function {
-                returns: returns.iter().map(|r#return| r#return.r#type()).collect(),
+                returns: return_types,
             },
         };
 
@@ -263,17 +673,28 @@ impl Checker {
         let parameters = function
             .parameters
             .into_iter()
-            .map(|parameter| self.check_single_declaration(Scope::Local, parameter))
-            .collect::<Result<Vec<_>, _>>()?;
+            .filter_map(|parameter| {
+                let parameter = self.check_single_declaration(Scope::Local, parameter);
+                self.recover(parameter)
+            })
+            .collect();
+
+        let returns = returns
+            .into_iter()
+            .filter_map(|r#return| self.recover(r#return))
+            .collect();
 
-        let (statements, statements_type) = self.check_statement(function.statements)?;
+        let checked = self.check_statement(function.statements);
+        self.pop();
+        let (statements, statements_type) = checked?;
 
-        if statements_type != r#type::Statement::Void && !is_procedure {
+        // Note: an earlier error may have hidden a return statement, so only
+        // report a missing return if the rest of the function checked cleanly.
+        if statements_type != r#type::Statement::Void && !is_procedure && self.errors.len() == start
+        {
             bail!(function.span, ErrorKind::MissingReturn);
         }
 
-        self.context.pop();
-
         Ok(ast::Function {
             name: function.name,
             parameters,
@@ -316,13 +737,20 @@ impl Checker {
                 let left_span = left.span();
                 let right_span = right.span();
 
-                let left = self.check_expression(*left).map(Box::new)?;
+                // Note: writing to a variable doesn't count as using it
+                let left = match *left {
+                    ast::Expression::Variable(variable, ()) => {
+                        self.check_variable_expression(variable, false)
+                    }
+                    left => self.check_expression(left),
+                }
+                .map(Box::new)?;
                 let left_type = left.r#type();
 
-                let right = self.check_expression(*right).map(Box::new)?;
+                let mut right = self.check_expression(*right).map(Box::new)?;
                 let right_type = right.r#type();
 
-                if self.context.is_subtype(&right_type, &left_type) {
+                if self.coerce(&mut right, &left_type) {
                     Ok((
                         ast::Statement::Assignment(left, right, span),
                         r#type::Statement::Unit,
@@ -335,7 +763,12 @@ impl Checker {
                 let call = self.check_call(call)?;
 
                 match call.function.r#type() {
-                    r#type::Expression::Function(_, returns) if returns.is_empty() => {
+                    r#type::Expression::Function(_, returns)
+                        if returns.is_empty() || returns == [r#type::Expression::Poison] =>
+                    {
+                        Ok((ast::Statement::Call(call), r#type::Statement::Unit))
+                    }
+                    r#type::Expression::Poison => {
                         Ok((ast::Statement::Call(call), r#type::Statement::Unit))
                     }
                     r#type::Expression::Function(_, _) => bail!(call.span, ErrorKind::NotProcedure),
@@ -355,7 +788,7 @@ impl Checker {
                 ))
             }
             ast::Statement::Return(returns, span) => {
-                let returns = returns
+                let mut returns = returns
                     .into_iter()
                     .map(|r#return| self.check_expression(r#return))
                     .collect::<Result<Vec<_>, _>>()?;
@@ -365,12 +798,12 @@ impl Checker {
                     .get_scoped_returns()
                     .expect("[INTERNAL PARSER ERROR]: return outside function scope");
 
-                let actual = returns
-                    .iter()
-                    .map(|r#return| r#return.r#type())
-                    .collect::<Vec<_>>();
-
-                if self.context.all_subtype(&actual, expected) {
+                if returns.len() == expected.len()
+                    && returns
+                        .iter_mut()
+                        .zip(expected)
+                        .all(|(r#return, expected)| self.coerce(r#return, expected))
+                {
                     Ok((
                         ast::Statement::Return(returns, span),
                         r#type::Statement::Void,
@@ -383,33 +816,42 @@ impl Checker {
                 self.context.push(LocalScope::Block);
 
                 let mut r#type = r#type::Statement::Unit;
+                let mut unreachable = false;
+                let mut poisoned = false;
 
                 let statements = statements
                     .into_iter()
-                    .map(|statement| {
-                        let (statement, statement_type) = self.check_statement(statement)?;
+                    .filter_map(|statement| {
+                        let checked = self.check_statement(statement);
+                        let (statement, statement_type) = self.recover(checked)?;
                         if r#type == r#type::Statement::Void {
-                            bail!(statement.span(), ErrorKind::Unreachable)
+                            // Only report the first unreachable statement in each block,
+                            // and none after a syntax error, since those statements
+                            // aren't actually unreachable
+                            if !unreachable && !poisoned {
+                                unreachable = true;
+                                self.errors
+                                    .push(Error::new(statement.span(), ErrorKind::Unreachable));
+                            }
                         } else if statement_type == r#type::Statement::Void {
                             r#type = r#type::Statement::Void;
+                            poisoned = matches!(statement, ast::Statement::Error(_));
                         }
-                        Ok(statement)
+                        Some(statement)
                     })
-                    .collect::<Result<Vec<_>, _>>()?;
+                    .collect();
 
-                self.context.pop();
+                self.pop();
                 Ok((ast::Statement::Sequence(statements, span), r#type))
             }
             ast::Statement::If(condition, r#if, None, span) => {
-                let condition = self.check_expression(*condition).map(Box::new)?;
-                match condition.r#type() {
-                    r#type::Expression::Boolean => (),
-                    r#type => expected!(r#type::Expression::Boolean, condition.span(), r#type),
-                }
+                let condition = self.check_condition(*condition);
 
                 self.context.push(LocalScope::If);
-                let (r#if, _) = self.check_statement(*r#if)?;
-                self.context.pop();
+                let r#if = self.check_statement(*r#if);
+                self.pop();
+
+                let (condition, (r#if, _)) = self.join(condition, r#if)?;
 
                 Ok((
                     ast::Statement::If(condition, Box::new(r#if), None, span),
@@ -417,50 +859,279 @@ impl Checker {
                 ))
             }
             ast::Statement::If(condition, r#if, Some(r#else), span) => {
-                let condition = self.check_expression(*condition).map(Box::new)?;
-                match condition.r#type() {
-                    r#type::Expression::Boolean => (),
-                    r#type => expected!(r#type::Expression::Boolean, condition.span(), r#type),
-                }
+                let condition = self.check_condition(*condition);
 
                 self.context.push(LocalScope::If);
-                let (r#if, if_type) = self.check_statement(*r#if)?;
-                self.context.pop();
+                let r#if = self.check_statement(*r#if);
+                self.pop();
 
                 self.context.push(LocalScope::Else);
-                let (r#else, else_type) = self.check_statement(*r#else)?;
-                self.context.pop();
+                let r#else = self.check_statement(*r#else);
+                self.pop();
+
+                let branches = self.join(r#if, r#else);
+                let (condition, ((r#if, if_type), (r#else, else_type))) =
+                    self.join(condition, branches)?;
 
                 Ok((
                     ast::Statement::If(condition, Box::new(r#if), Some(Box::new(r#else)), span),
                     if_type.least_upper_bound(&else_type),
                 ))
             }
-            ast::Statement::While(r#do, condition, body, span) => {
-                let condition = self.check_expression(*condition).map(Box::new)?;
-                match condition.r#type() {
-                    r#type::Expression::Boolean => (),
-                    r#type => expected!(r#type::Expression::Boolean, condition.span(), r#type),
-                }
+            ast::Statement::While(r#do, label, condition, body, span) => {
+                let condition = self.check_condition(*condition);
+
+                self.context.push(LocalScope::While {
+                    label: label.as_ref().map(|label| label.symbol),
+                    targets: None,
+                });
+                let body = self.check_statement(*body);
+                self.pop();
 
-                self.context.push(LocalScope::While(None));
-                let (body, _) = self.check_statement(*body)?;
-                self.context.pop();
+                let (condition, (body, _)) = self.join(condition, body)?;
 
                 Ok((
-                    ast::Statement::While(r#do, condition, Box::new(body), span),
+                    ast::Statement::While(r#do, label, condition, Box::new(body), span),
                     r#type::Statement::Unit,
                 ))
             }
-            ast::Statement::Break(span) => match self.context.get_scoped_while() {
-                None => bail!(span, ErrorKind::NotInWhile),
-                Some(_) => Ok((ast::Statement::Break(span), r#type::Statement::Void)),
+            ast::Statement::For(r#for) => {
+                let ast::For {
+                    label,
+                    initialization,
+                    condition,
+                    update,
+                    statements,
+                    span,
+                } = *r#for;
+
+                // Note: variables declared in the initialization are scoped to the loop
+                self.context.push(LocalScope::Block);
+
+                let initialization = initialization
+                    .map(|initialization| self.check_statement(*initialization))
+                    .transpose();
+                let condition = condition
+                    .map(|condition| self.check_condition(*condition))
+                    .transpose();
+
+                self.context.push(LocalScope::While {
+                    label: label.as_ref().map(|label| label.symbol),
+                    targets: None,
+                });
+                let statements = self.check_statement(*statements);
+                self.pop();
+
+                let update = update
+                    .map(|update| self.check_statement(*update))
+                    .transpose();
+
+                self.pop();
+
+                let header = self.join(initialization, condition);
+                let header = self.join(header, update);
+                let (((initialization, condition), update), (statements, _)) =
+                    self.join(header, statements)?;
+
+                Ok((
+                    ast::Statement::For(Box::new(ast::For {
+                        label,
+                        initialization: initialization
+                            .map(|(initialization, _)| Box::new(initialization)),
+                        condition,
+                        update: update.map(|(update, _)| Box::new(update)),
+                        statements: Box::new(statements),
+                        span,
+                    })),
+                    r#type::Statement::Unit,
+                ))
+            }
+            ast::Statement::Match(r#match) => {
+                let r#match = self.check_match(*r#match, Self::check_statement)?;
+
+                // Note: every value is matched by some arm, so the match only
+                // returns if every arm does.
+                let r#type = r#match
+                    .arms
+                    .iter()
+                    .map(|arm| arm.body.1)
+                    .reduce(|left, right| left.least_upper_bound(&right))
+                    .unwrap_or(r#type::Statement::Unit);
+
+                let arms = r#match
+                    .arms
+                    .into_iter()
+                    .map(|arm| ast::Arm {
+                        pattern: arm.pattern,
+                        body: arm.body.0,
+                        span: arm.span,
+                    })
+                    .collect();
+
+                Ok((
+                    ast::Statement::Match(Box::new(ast::Match {
+                        scrutinee: r#match.scrutinee,
+                        arms,
+                        span: r#match.span,
+                    })),
+                    r#type,
+                ))
+            }
+            // Note: the statement may have hidden a return, so assume it diverges
+            ast::Statement::Error(span) => {
+                Ok((ast::Statement::Error(span), r#type::Statement::Void))
+            }
+            ast::Statement::Throw(expression, span) => {
+                let expression = self.check_expression(*expression)?;
+                self.check_exception(expression.span(), &expression.r#type())?;
+                Ok((
+                    ast::Statement::Throw(Box::new(expression), span),
+                    r#type::Statement::Void,
+                ))
+            }
+            ast::Statement::Try(r#try) => {
+                let ast::Try {
+                    statements,
+                    catches,
+                    finally,
+                    span,
+                } = *r#try;
+
+                let statements = self.check_statement(statements);
+
+                let catches = catches
+                    .into_iter()
+                    .map(|catch| {
+                        self.context.push(LocalScope::Block);
+                        let checked = self.check_catch(catch);
+                        self.pop();
+                        checked
+                    })
+                    .collect::<Vec<_>>();
+
+                let finally = finally
+                    .map(|finally| self.check_statement(finally))
+                    .transpose();
+
+                // Note: join every `catch` block instead of short-circuiting,
+                // so that errors from each of them are reported.
+                let mut joined = Ok(Vec::new());
+                for catch in catches {
+                    joined = self.join(joined, catch).map(|(mut catches, catch)| {
+                        catches.push(catch);
+                        catches
+                    });
+                }
+
+                let handlers = self.join(joined, finally);
+                let (statements, (catches, finally)) = self.join(statements, handlers)?;
+
+                // Note: control only leaves normally through the `try` block or a
+                // `catch` block, unless the `finally` block never completes.
+                let r#type = match &finally {
+                    Some((_, r#type::Statement::Void)) => r#type::Statement::Void,
+                    _ => catches
+                        .iter()
+                        .map(|(_, r#type)| *r#type)
+                        .fold(statements.1, |left, right| left.least_upper_bound(&right)),
+                };
+
+                Ok((
+                    ast::Statement::Try(Box::new(ast::Try {
+                        statements: statements.0,
+                        catches: catches.into_iter().map(|(catch, _)| catch).collect(),
+                        finally: finally.map(|(finally, _)| finally),
+                        span,
+                    })),
+                    r#type,
+                ))
+            }
+            ast::Statement::Break(label, span) => {
+                self.check_jump(label.as_ref(), span)?;
+                Ok((ast::Statement::Break(label, span), r#type::Statement::Void))
+            }
+            ast::Statement::Continue(label, span) => {
+                self.check_jump(label.as_ref(), span)?;
+                Ok((
+                    ast::Statement::Continue(label, span),
+                    r#type::Statement::Void,
+                ))
+            }
+        }
+    }
+
+    /// Checks a `catch` block in a new scope containing the caught exception.
+    fn check_catch(
+        &mut self,
+        catch: ast::Catch<()>,
+    ) -> Result<(ast::Catch<r#type::Expression>, r#type::Statement), Error> {
+        let declaration = self.check_single_declaration(Scope::Local, catch.declaration)?;
+        self.check_exception(declaration.r#type.span(), &declaration.r#type.r#type())?;
+        let (statements, r#type) = self.check_statement(catch.statements)?;
+
+        Ok((
+            ast::Catch {
+                declaration,
+                statements,
+                span: catch.span,
             },
+            r#type,
+        ))
+    }
+
+    /// Checks that `r#type` is a subclass of the built-in exception class.
+    fn check_exception(&self, span: Span, r#type: &r#type::Expression) -> Result<(), Error> {
+        let exception = r#type::Expression::Class(symbol::intern_static(abi::EXCEPTION));
+        match r#type {
+            r#type::Expression::Poison => Ok(()),
+            r#type::Expression::Class(_) if self.context.is_subtype(r#type, &exception) => Ok(()),
+            _ => bail!(span, ErrorKind::NotException(r#type.clone())),
+        }
+    }
+
+    /// Checks that a `break` or `continue` statement is inside a (matching) loop.
+    fn check_jump(&self, label: Option<&ast::Identifier>, span: Span) -> Result<(), Error> {
+        match (
+            label,
+            self.context
+                .get_scoped_while(label.map(|label| label.symbol)),
+        ) {
+            (_, Some(_)) => Ok(()),
+            (Some(label), None) => bail!(*label.span, ErrorKind::UnboundLabel(label.symbol)),
+            (None, None) => bail!(span, ErrorKind::NotInWhile),
+        }
+    }
+
+    fn check_condition(
+        &mut self,
+        condition: ast::Expression<()>,
+    ) -> Result<Box<ast::Expression<r#type::Expression>>, Error> {
+        let condition = self.check_expression(condition).map(Box::new)?;
+        match condition.r#type() {
+            r#type::Expression::Boolean | r#type::Expression::Poison => Ok(condition),
+            r#type => expected!(r#type::Expression::Boolean, condition.span(), r#type),
+        }
+    }
+
+    /// Combines two independently checked results. If both failed, the first
+    /// error is recorded so that it isn't lost.
+    fn join<L, R>(
+        &mut self,
+        left: Result<L, Error>,
+        right: Result<R, Error>,
+    ) -> Result<(L, R), Error> {
+        match (left, right) {
+            (Ok(left), Ok(right)) => Ok((left, right)),
+            (Err(error), Ok(_)) | (Ok(_), Err(error)) => Err(error),
+            (Err(left), Err(right)) => {
+                self.errors.push(left);
+                Err(right)
+            }
         }
     }
 
     fn check_expression(
-        &self,
+        &mut self,
         expression: ast::Expression<()>,
     ) -> Result<ast::Expression<r#type::Expression>, Error> {
         match expression {
@@ -489,27 +1160,8 @@ impl Checker {
                 .map(r#type::Expression::Class)
                 .map(|r#type| ast::Expression::Super(r#type, span))
                 .map_err(|kind| Error::new(span, kind)),
-            ast::Expression::Variable(
-                ast::Variable {
-                    name,
-                    generics,
-                    span,
-                },
-                (),
-            ) => {
-                assert!(generics.is_none());
-                match self.context.get(Scope::Local, &name) {
-                    Some(Entry::Variable(r#type)) => Ok(ast::Expression::Variable(
-                        ast::Variable {
-                            name,
-                            generics: None,
-                            span,
-                        },
-                        r#type.clone(),
-                    )),
-                    Some(_) => bail!(*name.span, ErrorKind::NotVariable(name.symbol)),
-                    None => bail!(*name.span, ErrorKind::UnboundVariable(name.symbol)),
-                }
+            ast::Expression::Variable(variable, ()) => {
+                self.check_variable_expression(variable, true)
             }
 
             ast::Expression::Array(expressions, (), span) => {
@@ -554,11 +1206,25 @@ impl Checker {
                 let left_span = left.span();
                 let right_span = right.span();
 
-                let left = self.check_expression(*left).map(Box::new)?;
-                let left_type = left.r#type();
+                let mut left = self.check_expression(*left).map(Box::new)?;
+                let mut right = self.check_expression(*right).map(Box::new)?;
 
-                let right = self.check_expression(*right).map(Box::new)?;
-                let right_type = right.r#type();
+                // Literals take on the type of the other operand, if in range
+                let (left_type, right_type) = match (left.r#type(), right.r#type()) {
+                    (left_type, right_type)
+                        if !self.context.is_subtype(&left_type, &right_type)
+                            && self.coerce(&mut left, &right_type) =>
+                    {
+                        (right_type.clone(), right_type)
+                    }
+                    (left_type, right_type)
+                        if !self.context.is_subtype(&right_type, &left_type)
+                            && self.coerce(&mut right, &left_type) =>
+                    {
+                        (left_type.clone(), left_type)
+                    }
+                    (left_type, right_type) => (left_type, right_type),
+                };
 
                 let (parameter, r#return) = match binary {
                     // Note: array concatenation handled specially below
@@ -594,6 +1260,21 @@ impl Checker {
                     }
                 };
 
+                if left_type == r#type::Expression::Poison
+                    || right_type == r#type::Expression::Poison
+                {
+                    return Ok(ast::Expression::Binary(
+                        binary,
+                        left,
+                        right,
+                        match r#return {
+                            r#type::Expression::Integer => r#type::Expression::Poison,
+                            r#return => r#return,
+                        },
+                        span,
+                    ));
+                }
+
                 if let (
                     ast::Binary::Add | ast::Binary::Cat,
                     r#type::Expression::Array(_),
@@ -608,6 +1289,28 @@ impl Checker {
                     };
                 }
 
+                if let (r#type::Expression::Integer, Some(_), Some(_)) =
+                    (&parameter, left_type.integer(), right_type.integer())
+                {
+                    let r#type = match self.context.least_upper_bound(&left_type, &right_type) {
+                        None => expected!(left_span, left_type, right_span, right_type),
+                        Some(LeastUpperBound::Left(r#type) | LeastUpperBound::Right(r#type)) => {
+                            r#type
+                        }
+                    };
+
+                    return Ok(ast::Expression::Binary(
+                        binary,
+                        left,
+                        right,
+                        match r#return {
+                            r#type::Expression::Integer => r#type,
+                            r#return => r#return,
+                        },
+                        span,
+                    ));
+                }
+
                 if self.context.is_subtype(&left_type, &parameter)
                     && self.context.is_subtype(&right_type, &parameter)
                 {
@@ -622,24 +1325,46 @@ impl Checker {
             ast::Expression::Unary(ast::Unary::Neg, expression, (), span) => {
                 let expression = self.check_expression(*expression).map(Box::new)?;
                 match expression.r#type() {
-                    r#type::Expression::Integer => Ok(ast::Expression::Unary(
-                        ast::Unary::Neg,
-                        expression,
-                        r#type::Expression::Integer,
-                        span,
-                    )),
+                    r#type
+                        if r#type.integer().is_some() || r#type == r#type::Expression::Poison =>
+                    {
+                        Ok(ast::Expression::Unary(
+                            ast::Unary::Neg,
+                            expression,
+                            r#type,
+                            span,
+                        ))
+                    }
                     r#type => expected!(r#type::Expression::Integer, expression.span(), r#type),
                 }
             }
+            ast::Expression::InstanceOf(expression, r#type, span) => {
+                let (expression, r#type) = self.check_class_test(*expression, *r#type)?;
+                Ok(ast::Expression::InstanceOf(
+                    Box::new(expression),
+                    Box::new(r#type),
+                    span,
+                ))
+            }
+            ast::Expression::As(expression, r#type, span) => {
+                let (expression, r#type) = self.check_class_test(*expression, *r#type)?;
+                Ok(ast::Expression::As(
+                    Box::new(expression),
+                    Box::new(r#type),
+                    span,
+                ))
+            }
             ast::Expression::Unary(ast::Unary::Not, expression, (), span) => {
                 let expression = self.check_expression(*expression).map(Box::new)?;
                 match expression.r#type() {
-                    r#type::Expression::Boolean => Ok(ast::Expression::Unary(
-                        ast::Unary::Not,
-                        expression,
-                        r#type::Expression::Boolean,
-                        span,
-                    )),
+                    r#type::Expression::Boolean | r#type::Expression::Poison => {
+                        Ok(ast::Expression::Unary(
+                            ast::Unary::Not,
+                            expression,
+                            r#type::Expression::Boolean,
+                            span,
+                        ))
+                    }
                     r#type => expected!(r#type::Expression::Boolean, expression.span(), r#type),
                 }
             }
@@ -648,12 +1373,23 @@ impl Checker {
                 let array = self.check_expression(*array).map(Box::new)?;
                 let index = self.check_expression(*index).map(Box::new)?;
                 match (array.r#type(), index.r#type()) {
-                    (r#type::Expression::Array(r#type), r#type::Expression::Integer)
-                        if *r#type == r#type::Expression::Any =>
+                    (r#type::Expression::Poison, _) => Ok(ast::Expression::Index(
+                        array,
+                        index,
+                        r#type::Expression::Poison,
+                        span,
+                    )),
+                    (r#type::Expression::Array(r#type), r#type::Expression::Poison) => {
+                        Ok(ast::Expression::Index(array, index, *r#type, span))
+                    }
+                    (r#type::Expression::Array(r#type), index_type)
+                        if index_type.integer().is_some() && *r#type == r#type::Expression::Any =>
                     {
                         bail!(span, ErrorKind::IndexEmpty)
                     }
-                    (r#type::Expression::Array(r#type), r#type::Expression::Integer) => {
+                    (r#type::Expression::Array(r#type), index_type)
+                        if index_type.integer().is_some() =>
+                    {
                         Ok(ast::Expression::Index(array, index, *r#type, span))
                     }
                     (r#type::Expression::Array(r#type), _) => {
@@ -671,7 +1407,9 @@ impl Checker {
             ast::Expression::Length(array, span) => {
                 let array = self.check_expression(*array).map(Box::new)?;
                 match array.r#type() {
-                    r#type::Expression::Array(_) => Ok(ast::Expression::Length(array, span)),
+                    r#type::Expression::Array(_) | r#type::Expression::Poison => {
+                        Ok(ast::Expression::Length(array, span))
+                    }
                     r#type => expected!(
                         r#type::Expression::Array(Box::new(r#type::Expression::Any)),
                         span,
@@ -679,11 +1417,35 @@ impl Checker {
                     ),
                 }
             }
+            ast::Expression::Cast(r#type, expression, span) => {
+                let r#type = self.check_type(*r#type).map(Box::new)?;
+                let expression = self.check_expression(*expression).map(Box::new)?;
+                match expression.r#type() {
+                    r#type_
+                        if r#type_.integer().is_some() || r#type_ == r#type::Expression::Poison =>
+                    {
+                        Ok(ast::Expression::Cast(r#type, expression, span))
+                    }
+                    r#type => expected!(r#type::Expression::Integer, expression.span(), r#type),
+                }
+            }
 
+            ast::Expression::Dot(receiver, variant, (), span) if self.is_enum(&receiver) => self
+                .check_construct(*receiver, variant, Vec::new(), span)
+                .map(Box::new)
+                .map(ast::Expression::Construct),
             ast::Expression::Dot(receiver, field, (), span) => {
                 let receiver = self.check_expression(*receiver).map(Box::new)?;
                 let class = match receiver.r#type() {
                     r#type::Expression::Class(class) => class,
+                    r#type::Expression::Poison => {
+                        return Ok(ast::Expression::Dot(
+                            receiver,
+                            field,
+                            r#type::Expression::Poison,
+                            span,
+                        ))
+                    }
                     _ => bail!(receiver.span(), ErrorKind::NotClass),
                 };
 
@@ -705,6 +1467,16 @@ impl Checker {
             ) => {
                 assert!(generics.is_none());
                 match self.context.get_class_implementation(&name) {
+                    _ if symbol::resolve(name.symbol) == r#type::POISON => {
+                        Ok(ast::Expression::New(
+                            ast::Variable {
+                                name,
+                                generics: None,
+                                span: span_,
+                            },
+                            span,
+                        ))
+                    }
                     Some(_) => Ok(ast::Expression::New(
                         ast::Variable {
                             name,
@@ -713,6 +1485,12 @@ impl Checker {
                         },
                         span,
                     )),
+                    None if self.context.get_protocol(&name).is_some() => {
+                        bail!(span, ErrorKind::NewInterface(name.symbol))
+                    }
+                    None if self.context.get_enum(&name).is_some() => {
+                        bail!(span, ErrorKind::EnumNotClass(name.symbol))
+                    }
                     None if self.context.get_class(&name).is_some() => {
                         bail!(span, ErrorKind::NotInClassModule(name.symbol))
                     }
@@ -721,25 +1499,367 @@ impl Checker {
                     }
                 }
             }
+            ast::Expression::Closure(closure) => self
+                .check_closure(*closure)
+                .map(Box::new)
+                .map(ast::Expression::Closure),
+            ast::Expression::Call(ast::Call {
+                function,
+                arguments,
+                span,
+            }) if self.is_construct(&function) => {
+                let (receiver, variant) = match *function {
+                    ast::Expression::Dot(receiver, variant, (), _) => (receiver, variant),
+                    _ => unreachable!(),
+                };
+                self.check_construct(*receiver, variant, arguments, span)
+                    .map(Box::new)
+                    .map(ast::Expression::Construct)
+            }
+            // Note: the parser produces variant construction as a dot or call
+            ast::Expression::Construct(_) => unreachable!(),
+            ast::Expression::Match(r#match, ()) => {
+                let r#match = self.check_match(*r#match, Self::check_expression)?;
+
+                let mut bound = r#type::Expression::Any;
+                let mut bound_span = None;
+
+                for arm in &r#match.arms {
+                    let r#type = arm.body.r#type();
+                    match self.context.least_upper_bound(&bound, &r#type) {
+                        None => expected!(bound_span.unwrap(), bound, arm.body.span(), r#type),
+                        Some(LeastUpperBound::Left(_bound)) => bound = _bound,
+                        Some(LeastUpperBound::Right(_bound)) => {
+                            bound = _bound;
+                            bound_span = Some(arm.body.span());
+                        }
+                    }
+                }
+
+                Ok(ast::Expression::Match(Box::new(r#match), bound))
+            }
             ast::Expression::Call(call) => {
                 let call = self.check_call(call)?;
                 match call.function.r#type() {
                     r#type::Expression::Function(_, returns) if returns.len() == 1 => {
                         Ok(ast::Expression::Call(call))
                     }
+                    r#type::Expression::Poison => Ok(ast::Expression::Call(call)),
                     _ => bail!(call.span, ErrorKind::NotExp),
                 }
             }
         }
     }
 
-    fn check_call(&self, call: ast::Call<()>) -> Result<ast::Call<r#type::Expression>, Error> {
+    /// Whether `receiver` names an enum, rather than a variable, in which case
+    /// the dot operator selects one of its variants.
+    fn is_enum(&self, receiver: &ast::Expression<()>) -> bool {
+        match receiver {
+            ast::Expression::Variable(variable, ()) => {
+                variable.generics.is_none()
+                    && self.context.get(Scope::Local, &variable.name).is_none()
+                    && self.context.get_enum(&variable.name).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether calling `function` constructs an enum variant.
+    fn is_construct(&self, function: &ast::Expression<()>) -> bool {
+        matches!(function, ast::Expression::Dot(receiver, _, _, _) if self.is_enum(receiver))
+    }
+
+    fn check_construct(
+        &mut self,
+        receiver: ast::Expression<()>,
+        variant: ast::Identifier,
+        arguments: Vec<ast::Expression<()>>,
+        span: Span,
+    ) -> Result<ast::Construct<r#type::Expression>, Error> {
+        let r#enum = match receiver {
+            ast::Expression::Variable(variable, ()) => variable.name,
+            _ => unreachable!(),
+        };
+
+        let payload = match self.context.get_variant(&r#enum.symbol, &variant) {
+            Some((_, payload)) => payload.to_vec(),
+            None => bail!(
+                *variant.span,
+                ErrorKind::UnboundVariant(r#enum.symbol, variant.symbol)
+            ),
+        };
+
+        let arguments = self.check_arguments(arguments, &payload, span)?;
+
+        Ok(ast::Construct {
+            r#enum,
+            variant,
+            arguments,
+            span,
+        })
+    }
+
+    /// Checks the scrutinee and arms of `r#match`, where `check_body` checks each
+    /// arm's body in a new scope containing the variables bound by its pattern.
+    fn check_match<B, C, F>(
+        &mut self,
+        r#match: ast::Match<(), B>,
+        mut check_body: F,
+    ) -> Result<ast::Match<r#type::Expression, C>, Error>
+    where
+        F: FnMut(&mut Self, B) -> Result<C, Error>,
+    {
+        let start = self.errors.len();
+        let scrutinee = self.check_expression(*r#match.scrutinee).map(Box::new)?;
+        let r#type = scrutinee.r#type();
+
+        let arms = r#match
+            .arms
+            .into_iter()
+            .filter_map(|arm| {
+                self.context.push(LocalScope::Block);
+                let checked = self
+                    .check_pattern(arm.pattern, &r#type)
+                    .and_then(|pattern| Ok((pattern, check_body(self, arm.body)?)));
+                self.pop();
+
+                let (pattern, body) = self.recover(checked)?;
+                Some(ast::Arm {
+                    pattern,
+                    body,
+                    span: arm.span,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Note: a pattern or arm that failed to check could make the rest look
+        // redundant or non-exhaustive.
+        if self.errors.len() == start {
+            let patterns = arms.iter().map(|arm| &arm.pattern).collect::<Vec<_>>();
+            exhaustive::check(&self.context, &r#type, &patterns, r#match.span)?;
+        }
+
+        Ok(ast::Match {
+            scrutinee,
+            arms,
+            span: r#match.span,
+        })
+    }
+
+    /// Checks that `pattern` can match values of type `r#type`, binding its variables
+    /// in the current scope.
+    fn check_pattern(
+        &mut self,
+        pattern: ast::Pattern<()>,
+        r#type: &r#type::Expression,
+    ) -> Result<ast::Pattern<r#type::Expression>, Error> {
+        match pattern {
+            ast::Pattern::Wildcard(span) => Ok(ast::Pattern::Wildcard(span)),
+            ast::Pattern::Binding(name, ()) => {
+                // Note: payload-less variants are written without parentheses
+                if let r#type::Expression::Class(r#enum) = r#type {
+                    if let Some((_, payload)) = self.context.get_variant(r#enum, &name) {
+                        if !payload.is_empty() {
+                            bail!(
+                                *name.span,
+                                ErrorKind::VariantArity(name.symbol, payload.len())
+                            );
+                        }
+                        let span = *name.span;
+                        return Ok(ast::Pattern::Variant(
+                            name,
+                            Vec::new(),
+                            r#type.clone(),
+                            span,
+                        ));
+                    }
+                }
+
+                if let Some((span, _)) =
+                    self.context
+                        .insert(Scope::Local, name.clone(), Entry::Variable(r#type.clone()))
+                {
+                    bail!(*name.span, ErrorKind::NameClash(span));
+                }
+
+                Ok(ast::Pattern::Binding(name, r#type.clone()))
+            }
+            ast::Pattern::Variant(name, patterns, (), span) => {
+                let payload = match r#type {
+                    r#type::Expression::Poison => vec![r#type::Expression::Poison; patterns.len()],
+                    r#type::Expression::Class(r#enum)
+                        if self.context.get_enum(r#enum).is_some() =>
+                    {
+                        match self.context.get_variant(r#enum, &name) {
+                            Some((_, payload)) => payload.to_vec(),
+                            None => {
+                                bail!(*name.span, ErrorKind::UnboundVariant(*r#enum, name.symbol))
+                            }
+                        }
+                    }
+                    _ => bail!(span, ErrorKind::PatternMismatch(r#type.clone())),
+                };
+
+                if payload.len() != patterns.len() {
+                    bail!(span, ErrorKind::VariantArity(name.symbol, payload.len()));
+                }
+
+                let patterns = patterns
+                    .into_iter()
+                    .zip(&payload)
+                    .map(|(pattern, r#type)| self.check_pattern(pattern, r#type))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(ast::Pattern::Variant(name, patterns, r#type.clone(), span))
+            }
+            ast::Pattern::Boolean(boolean, span) => match r#type {
+                r#type::Expression::Boolean | r#type::Expression::Poison => {
+                    Ok(ast::Pattern::Boolean(boolean, span))
+                }
+                _ => bail!(span, ErrorKind::PatternMismatch(r#type.clone())),
+            },
+            ast::Pattern::Integer(integer, span) => {
+                if r#type.contains(integer) || *r#type == r#type::Expression::Poison {
+                    Ok(ast::Pattern::Integer(integer, span))
+                } else {
+                    bail!(span, ErrorKind::PatternMismatch(r#type.clone()))
+                }
+            }
+            ast::Pattern::Character(character, span) => {
+                if r#type.contains(character as i64) || *r#type == r#type::Expression::Poison {
+                    Ok(ast::Pattern::Character(character, span))
+                } else {
+                    bail!(span, ErrorKind::PatternMismatch(r#type.clone()))
+                }
+            }
+        }
+    }
+
+    /// Checks a variable expression, recording its declaration as used if `read` is set.
+    ///
+    /// Reading a local variable from inside a closure captures it, and a global
+    /// function can be read as a function value.
+    fn check_variable_expression(
+        &mut self,
+        ast::Variable {
+            name,
+            generics,
+            span,
+        }: ast::Variable<()>,
+        read: bool,
+    ) -> Result<ast::Expression<r#type::Expression>, Error> {
+        assert!(generics.is_none());
+        let r#type = match self.context.get_full(Scope::Local, &name) {
+            Some((declaration, Entry::Variable(r#type))) => {
+                if read {
+                    self.referenced.borrow_mut().insert(*declaration);
+                }
+                r#type.clone()
+            }
+            Some((
+                declaration,
+                Entry::Function(parameters, returns) | Entry::Signature(parameters, returns),
+            )) if read && self.context.get(GlobalScope::Global, &name).is_some() => {
+                self.referenced.borrow_mut().insert(*declaration);
+                r#type::Expression::Function(parameters.clone(), returns.clone())
+            }
+            Some(_) => bail!(*name.span, ErrorKind::NotVariable(name.symbol)),
+            None => bail!(*name.span, ErrorKind::UnboundVariable(name.symbol)),
+        };
+
+        match self.context.get_closure_depth(&name) {
+            None | Some(0) => (),
+            Some(_) if !read => bail!(*name.span, ErrorKind::AssignCaptured(name.symbol)),
+            Some(depth) => {
+                let len = self.captures.len();
+                for captures in &mut self.captures[len - depth..] {
+                    captures.insert(name.symbol);
+                }
+            }
+        }
+
+        Ok(ast::Expression::Variable(
+            ast::Variable {
+                name,
+                generics: None,
+                span,
+            },
+            r#type,
+        ))
+    }
+
+    fn check_closure(
+        &mut self,
+        closure: ast::Closure<()>,
+    ) -> Result<ast::Closure<r#type::Expression>, Error> {
+        let start = self.errors.len();
+
+        let returns = closure
+            .returns
+            .into_iter()
+            .map(|r#return| self.check_type(r#return))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.context.push(LocalScope::Closure {
+            this: None,
+            returns: returns.iter().map(ast::Type::r#type).collect(),
+        });
+        self.captures.push(Set::default());
+
+        let parameters = closure
+            .parameters
+            .into_iter()
+            .filter_map(|parameter| {
+                let parameter = self.check_single_declaration(Scope::Local, parameter);
+                self.recover(parameter)
+            })
+            .collect();
+
+        let checked = self.check_statement(closure.statements);
+        let captures = self.captures.pop().unwrap();
+        self.pop();
+        let (statements, statements_type) = checked?;
+
+        if statements_type != r#type::Statement::Void
+            && !returns.is_empty()
+            && self.errors.len() == start
+        {
+            bail!(closure.span, ErrorKind::MissingReturn);
+        }
+
+        Ok(ast::Closure {
+            parameters,
+            returns,
+            statements,
+            captures: captures.into_iter().collect(),
+            span: closure.span,
+        })
+    }
+
+    fn check_call(&mut self, call: ast::Call<()>) -> Result<ast::Call<r#type::Expression>, Error> {
         let (scope, function, function_name, function_span): (
             _,
             Box<dyn FnOnce(r#type::Expression) -> ast::Expression<r#type::Expression>>,
             _,
             _,
         ) = match *call.function {
+            // Note: calls through local and global variables are indirect
+            ast::Expression::Variable(variable, ())
+                if matches!(
+                    self.context.get(Scope::Local, &variable.name),
+                    Some(Entry::Variable(_)),
+                ) =>
+            {
+                let function = self.check_variable_expression(variable, true)?;
+                return self.check_indirect_call(function, call.arguments, call.span);
+            }
+            ast::Expression::Variable(variable, ())
+                if variable.generics.is_none()
+                    && self.context.get(Scope::Local, &variable.name).is_none()
+                    && self.context.get_function_template(&variable.name).is_some() =>
+            {
+                return self.check_inferred_call(variable, call.arguments, call.span);
+            }
             ast::Expression::Variable(
                 ast::Variable {
                     name,
@@ -773,9 +1893,38 @@ impl Checker {
                 let receiver = self.check_expression(*receiver).map(Box::new)?;
                 let class = match receiver.r#type() {
                     r#type::Expression::Class(class) => class,
+                    // Note: the method's signature is unknown, so we can only check
+                    // the arguments and assume the call produces a poisoned value.
+                    r#type::Expression::Poison => {
+                        let arguments = call
+                            .arguments
+                            .into_iter()
+                            .map(|argument| self.check_expression(argument))
+                            .collect::<Result<Vec<_>, _>>()?;
+
+                        let r#type = r#type::Expression::Function(
+                            arguments.iter().map(|argument| argument.r#type()).collect(),
+                            vec![r#type::Expression::Poison],
+                        );
+
+                        return Ok(ast::Call {
+                            function: Box::new(ast::Expression::Dot(receiver, name, r#type, span)),
+                            arguments,
+                            span: call.span,
+                        });
+                    }
                     _ => bail!(receiver.span(), ErrorKind::NotClass),
                 };
 
+                // Note: calls through fields are indirect
+                if let Some((declaration, Entry::Variable(r#type))) =
+                    self.context.get_full(GlobalScope::Class(class), &name)
+                {
+                    self.referenced.borrow_mut().insert(*declaration);
+                    let function = ast::Expression::Dot(receiver, name, r#type.clone(), span);
+                    return self.check_indirect_call(function, call.arguments, call.span);
+                }
+
                 let function_name = name.symbol;
                 let function_span = span;
 
@@ -786,47 +1935,156 @@ impl Checker {
                     function_span,
                 )
             }
-            expression => bail!(expression.span(), ErrorKind::NotFun(None)),
+            expression => {
+                let function = self.check_expression(expression)?;
+                return self.check_indirect_call(function, call.arguments, call.span);
+            }
         };
 
-        let (parameters, returns) = match self.context.get(scope, &function_name) {
-            Some(Entry::Signature(parameters, returns))
-            | Some(Entry::Function(parameters, returns)) => (parameters, returns),
+        let (parameters, returns) = match self.context.get_full(scope, &function_name) {
+            Some((declaration, Entry::Signature(parameters, returns)))
+            | Some((declaration, Entry::Function(parameters, returns))) => {
+                self.referenced.borrow_mut().insert(*declaration);
+                (parameters.clone(), returns.clone())
+            }
             Some(_) => bail!(function_span, ErrorKind::NotFun(Some(function_name))),
             None => bail!(function_span, ErrorKind::UnboundFun(function_name)),
         };
 
-        if call.arguments.len() != parameters.len() {
-            bail!(call.span, ErrorKind::CallLength);
-        }
+        let arguments = self.check_arguments(call.arguments, &parameters, call.span)?;
+
+        Ok(ast::Call {
+            function: Box::new(function(r#type::Expression::Function(parameters, returns))),
+            arguments,
+            span: call.span,
+        })
+    }
+
+    /// Checks a call through a function value, rather than to a named function or method.
+    fn check_indirect_call(
+        &mut self,
+        function: ast::Expression<r#type::Expression>,
+        arguments: Vec<ast::Expression<()>>,
+        span: Span,
+    ) -> Result<ast::Call<r#type::Expression>, Error> {
+        let arguments = match function.r#type() {
+            r#type::Expression::Function(parameters, _) => {
+                self.check_arguments(arguments, &parameters, span)?
+            }
+            // Note: the signature is unknown, so we can only check the arguments
+            r#type::Expression::Poison => arguments
+                .into_iter()
+                .map(|argument| self.check_expression(argument))
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => bail!(function.span(), ErrorKind::NotFun(None)),
+        };
+
+        Ok(ast::Call {
+            function: Box::new(function),
+            arguments,
+            span,
+        })
+    }
 
-        let arguments = call
-            .arguments
+    /// Checks a call to a function template without type arguments, which are
+    /// inferred from the types of its arguments.
+    fn check_inferred_call(
+        &mut self,
+        mut variable: ast::Variable<()>,
+        arguments: Vec<ast::Expression<()>>,
+        span: Span,
+    ) -> Result<ast::Call<r#type::Expression>, Error> {
+        let arguments = arguments
             .into_iter()
-            .zip(parameters)
-            .map(|(argument, parameter)| {
-                let argument = self.check_expression(argument)?;
-                let r#type = argument.r#type();
+            .map(|argument| self.check_expression(argument))
+            .collect::<Result<Vec<_>, _>>()?;
 
-                if self.context.is_subtype(&r#type, parameter) {
-                    Ok(argument)
-                } else {
-                    // TODO: attach span to parameters
-                    expected!(parameter.clone(), argument.span(), r#type)
-                }
-            })
+        let types = arguments
+            .iter()
+            .map(|argument| argument.r#type())
+            .collect::<Vec<_>>();
+
+        // Note: an argument failed to check, so inference would only report spurious errors
+        if types.contains(&r#type::Expression::Poison) {
+            let r#type = r#type::Expression::Function(types, vec![r#type::Expression::Poison]);
+            let variable = ast::Variable {
+                name: variable.name,
+                generics: None,
+                span: variable.span,
+            };
+
+            return Ok(ast::Call {
+                function: Box::new(ast::Expression::Variable(variable, r#type)),
+                arguments,
+                span,
+            });
+        }
+
+        variable.generics = Some(self.infer_template_arguments(&variable, &arguments, span)?);
+
+        let instantiations = self.monomorphize_call(&mut variable)?;
+        self.instantiated.extend(instantiations);
+
+        let (parameters, returns) = match self.context.get(GlobalScope::Global, &variable.name) {
+            Some(Entry::Function(parameters, returns)) => (parameters.clone(), returns.clone()),
+            _ => unreachable!(),
+        };
+
+        let arguments = arguments
+            .into_iter()
+            .zip(&parameters)
+            .map(|(argument, parameter)| self.coerce_argument(argument, parameter))
             .collect::<Result<Vec<_>, _>>()?;
 
+        let r#type = r#type::Expression::Function(parameters, returns);
+        let variable = ast::Variable {
+            name: variable.name,
+            generics: None,
+            span: variable.span,
+        };
+
         Ok(ast::Call {
-            function: Box::new(function(r#type::Expression::Function(
-                parameters.clone(),
-                returns.clone(),
-            ))),
+            function: Box::new(ast::Expression::Variable(variable, r#type)),
             arguments,
-            span: call.span,
+            span,
         })
     }
 
+    fn check_arguments(
+        &mut self,
+        arguments: Vec<ast::Expression<()>>,
+        parameters: &[r#type::Expression],
+        span: Span,
+    ) -> Result<Vec<ast::Expression<r#type::Expression>>, Error> {
+        if arguments.len() != parameters.len() {
+            bail!(span, ErrorKind::CallLength);
+        }
+
+        arguments
+            .into_iter()
+            .zip(parameters)
+            .map(|(argument, parameter)| {
+                let argument = self.check_expression(argument)?;
+                self.coerce_argument(argument, parameter)
+            })
+            .collect()
+    }
+
+    fn coerce_argument(
+        &self,
+        mut argument: ast::Expression<r#type::Expression>,
+        parameter: &r#type::Expression,
+    ) -> Result<ast::Expression<r#type::Expression>, Error> {
+        let r#type = argument.r#type();
+
+        if self.coerce(&mut argument, parameter) {
+            Ok(argument)
+        } else {
+            // TODO: attach span to parameters
+            expected!(parameter.clone(), argument.span(), r#type)
+        }
+    }
+
     fn check_initialization<S: Into<Scope>>(
         &mut self,
         scope: S,
@@ -836,62 +2094,107 @@ impl Checker {
             span,
         }: ast::Initialization<()>,
     ) -> Result<ast::Initialization<r#type::Expression>, Error> {
-        let (expression, r#types) = match *expression {
-            ast::Expression::Call(call) => {
-                let call = self.check_call(call)?;
-                let returns = match call.function.r#type() {
-                    r#type::Expression::Function(_, returns) => returns,
-                    _ => unreachable!(),
-                };
-                (ast::Expression::Call(call), returns)
+        let scope = scope.into();
+        let checked = match *expression {
+            ast::Expression::Call(call) if !self.is_construct(&call.function) => {
+                self.check_call(call).map(|call| {
+                    let returns = match call.function.r#type() {
+                        r#type::Expression::Function(_, returns) => returns,
+                        r#type::Expression::Poison => vec![r#type::Expression::Poison],
+                        _ => unreachable!(),
+                    };
+                    (ast::Expression::Call(call), returns)
+                })
             }
-            expression => {
-                let expression = self.check_expression(expression)?;
+            expression => self.check_expression(expression).map(|expression| {
                 let r#type = expression.r#type();
                 (expression, vec![r#type])
+            }),
+        };
+
+        let (mut expression, mut r#types) = match checked {
+            Ok(checked) => checked,
+            Err(error) => {
+                self.declare_all(scope, declarations);
+                return Err(error);
             }
         };
 
+        // A poisoned method call could have returned any number of values
+        if r#types == [r#type::Expression::Poison] {
+            r#types = vec![r#type::Expression::Poison; declarations.len()];
+        }
+
         if r#types.is_empty() {
+            self.declare_all(scope, declarations);
             bail!(span, ErrorKind::InitProcedure);
         }
 
         if r#types.len() != declarations.len() {
+            self.declare_all(scope, declarations);
             bail!(span, ErrorKind::InitLength);
         }
 
-        let scope = scope.into();
-
-        let declarations = declarations
-            .into_iter()
-            .zip(r#types)
-            .map(|(declaration, subtype)| {
-                let declaration = match declaration {
-                    Some(declaration) => declaration,
-                    None => return Ok(None),
-                };
+        if r#types.len() > 1 && declarations.iter().all(Option::is_none) {
+            self.warnings.push(Warning::new(
+                expression.span(),
+                WarningKind::UnusedResult(r#types.len()),
+            ));
+        }
 
-                let declaration = match self.check_single_declaration(scope, declaration) {
-                    Ok(declaration) => declaration,
-                    Err(error) => return Err(error),
-                };
+        let mut checked = Vec::with_capacity(declarations.len());
 
-                let supertype = declaration.r#type.r#type();
-                if !self.context.is_subtype(&subtype, &supertype) {
-                    expected!(declaration.span(), supertype, expression.span(), subtype);
+        for (declaration, subtype) in declarations.into_iter().zip(r#types) {
+            let declaration = match declaration {
+                Some(declaration) => declaration,
+                None => {
+                    checked.push(None);
+                    continue;
                 }
+            };
+
+            let declaration = self.check_single_declaration(scope, declaration);
+            let declaration = match self.recover(declaration) {
+                Some(declaration) => declaration,
+                None => continue,
+            };
+
+            let supertype = declaration.r#type.r#type();
+            let coerced = match &expression {
+                // Note: only single expressions can be implicitly converted
+                ast::Expression::Call(_) => self.context.is_subtype(&subtype, &supertype),
+                _ => self.coerce(&mut expression, &supertype),
+            };
+
+            // Note: keep checking the remaining declarations, so they're all bound
+            if !coerced {
+                let kind = ErrorKind::Mismatch {
+                    expected: supertype,
+                    expected_span: Some(declaration.span()),
+                    found: subtype,
+                };
+                self.errors.push(Error::new(expression.span(), kind));
+            }
 
-                Ok(Some(declaration))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+            checked.push(Some(declaration));
+        }
 
         Ok(ast::Initialization {
-            declarations,
+            declarations: checked,
             expression: Box::new(expression),
             span,
         })
     }
 
+    /// Binds each name in `declarations` without an initializer, so that later
+    /// statements can still refer to them after an error.
+    fn declare_all(&mut self, scope: Scope, declarations: Vec<Option<ast::SingleDeclaration<()>>>) {
+        for declaration in declarations.into_iter().flatten() {
+            let declaration = self.check_single_declaration(scope, declaration);
+            self.recover(declaration);
+        }
+    }
+
     fn check_declaration<S: Into<Scope>>(
         &mut self,
         scope: S,
@@ -909,20 +2212,31 @@ impl Checker {
         assert!(!multiple.r#type.has_length());
 
         let scope = scope.into();
+        let r#type = match self.check_type((*multiple.r#type).clone()) {
+            Ok(r#type) => r#type,
+            Err(error) => {
+                for name in &multiple.names {
+                    self.poison(scope, name.clone());
+                }
+                return Err(error);
+            }
+        };
+
         for name in &multiple.names {
-            self.check_single_declaration(
+            let declaration = self.check_single_declaration(
                 scope,
                 ast::SingleDeclaration {
                     name: name.clone(),
                     r#type: multiple.r#type.clone(),
                     span: multiple.span,
                 },
-            )?;
+            );
+            self.recover(declaration);
         }
 
         Ok(ast::Declaration::Multiple(ast::MultipleDeclaration {
             names: multiple.names,
-            r#type: self.check_type(*multiple.r#type).map(Box::new)?,
+            r#type: Box::new(r#type),
             span: multiple.span,
         }))
     }
@@ -932,8 +2246,25 @@ impl Checker {
         scope: S,
         ast::SingleDeclaration { name, r#type, span }: ast::SingleDeclaration<()>,
     ) -> Result<ast::SingleDeclaration<r#type::Expression>, Error> {
-        let r#type = self.check_type(*r#type).map(Box::new)?;
         let scope = scope.into();
+        let r#type = match self.check_type(*r#type) {
+            Ok(r#type) => Box::new(r#type),
+            Err(error) => {
+                self.poison(scope, name);
+                return Err(error);
+            }
+        };
+
+        if let (Scope::Local, Some(class)) = (scope, self.context.get_scoped_class()) {
+            if let Some((field, Entry::Variable(_))) =
+                self.context.get_full(GlobalScope::Class(class), &name)
+            {
+                self.warnings.push(Warning::new(
+                    *name.span,
+                    WarningKind::ShadowedField(name.symbol, *field),
+                ));
+            }
+        }
 
         match (
             scope,
@@ -952,23 +2283,101 @@ impl Checker {
         Ok(ast::SingleDeclaration { name, r#type, span })
     }
 
-    pub(super) fn check_type(
+    /// Binds `name` to a poisoned type after its declaration failed to check,
+    /// so that later uses don't report spurious unbound variables.
+    fn poison(&mut self, scope: Scope, name: ast::Identifier) {
+        // Note: class fields are inserted during loading, so we leave them alone.
+        if let Scope::Global(GlobalScope::Class(_)) = scope {
+            return;
+        }
+
+        self.context
+            .insert(scope, name, Entry::Variable(r#type::Expression::Poison));
+    }
+
+    /// Checks whether `expression` can be used where a value of type `r#type` is expected.
+    ///
+    /// In addition to subtyping, integer literals can be implicitly converted to any
+    /// integer type that can represent them, and array literals are converted element-wise.
+    fn coerce(
+        &self,
+        expression: &mut ast::Expression<r#type::Expression>,
+        r#type: &r#type::Expression,
+    ) -> bool {
+        if self.context.is_subtype(&expression.r#type(), r#type) {
+            return true;
+        }
+
+        if !self.is_coercible(expression, r#type) {
+            return false;
+        }
+
+        Self::retype(expression, r#type);
+        true
+    }
+
+    fn is_coercible(
         &self,
+        expression: &ast::Expression<r#type::Expression>,
+        r#type: &r#type::Expression,
+    ) -> bool {
+        match (expression, r#type) {
+            (ast::Expression::Integer(integer, _), _) => r#type.contains(*integer),
+            (ast::Expression::Character(character, _), _) => r#type.contains(*character as i64),
+            (ast::Expression::Array(expressions, _, _), r#type::Expression::Array(r#type)) => {
+                expressions.iter().all(|expression| {
+                    self.context.is_subtype(&expression.r#type(), r#type)
+                        || self.is_coercible(expression, r#type)
+                })
+            }
+            _ => false,
+        }
+    }
+
+    fn retype(expression: &mut ast::Expression<r#type::Expression>, r#type: &r#type::Expression) {
+        if let (ast::Expression::Array(expressions, array, _), r#type::Expression::Array(element)) =
+            (expression, r#type)
+        {
+            *array = r#type.clone();
+            expressions
+                .iter_mut()
+                .for_each(|expression| Self::retype(expression, element));
+        }
+    }
+
+    pub(super) fn check_type(
+        &mut self,
         r#type: ast::Type<()>,
     ) -> Result<ast::Type<r#type::Expression>, Error> {
         match r#type {
             ast::Type::Bool(span) => Ok(ast::Type::Bool(span)),
             ast::Type::Int(span) => Ok(ast::Type::Int(span)),
+            ast::Type::Sized { signed, size, span } => Ok(ast::Type::Sized { signed, size, span }),
             ast::Type::Array(r#type, None, span) => self
                 .check_type(*r#type)
                 .map(Box::new)
                 .map(|r#type| ast::Type::Array(r#type, None, span)),
             ast::Type::Class(variable) => self.check_variable(variable).map(ast::Type::Class),
+            ast::Type::Function(parameters, returns, span) => {
+                let parameters = parameters
+                    .into_iter()
+                    .map(|parameter| self.check_type(parameter))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let returns = returns
+                    .into_iter()
+                    .map(|r#return| self.check_type(r#return))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(ast::Type::Function(parameters, returns, span))
+            }
             ast::Type::Array(r#type, Some(length), span) => {
                 let r#type = self.check_type(*r#type).map(Box::new)?;
                 let length = self.check_expression(*length).map(Box::new)?;
                 match length.r#type() {
-                    r#type::Expression::Integer => Ok(ast::Type::Array(r#type, Some(length), span)),
+                    r#type_
+                        if r#type_.integer().is_some() || r#type_ == r#type::Expression::Poison =>
+                    {
+                        Ok(ast::Type::Array(r#type, Some(length), span))
+                    }
                     r#type => expected!(r#type::Expression::Integer, length.span(), r#type),
                 }
             }
@@ -997,11 +2406,16 @@ impl Checker {
     // Any unbound classes within the type arguments must be caught
     // during the monomorphization pass.
     fn check_variable(
-        &self,
+        &mut self,
         variable: ast::Variable<()>,
     ) -> Result<ast::Variable<r#type::Expression>, Error> {
         match variable.generics {
             None => match self.context.get_class(&variable.name) {
+                _ if symbol::resolve(variable.name.symbol) == r#type::POISON => Ok(ast::Variable {
+                    name: variable.name,
+                    generics: None,
+                    span: variable.span,
+                }),
                 Some(_) => Ok(ast::Variable {
                     name: variable.name,
                     generics: None,
@@ -1013,18 +2427,28 @@ impl Checker {
                 ),
             },
             Some(generics) => {
-                match self.context.get_class_template(&variable.name) {
+                let template = self
+                    .context
+                    .get_class_template(&variable.name)
+                    .map(|template| (&template.name, template.generics.len()))
+                    .or_else(|| {
+                        self.context
+                            .get_enum_template(&variable.name)
+                            .map(|template| (&template.name, template.generics.len()))
+                    });
+
+                match template {
                     None => {
                         bail!(
                             *variable.name.span,
                             ErrorKind::UnboundClassTemplate(variable.name.symbol)
                         )
                     }
-                    Some(template) if template.generics.len() != generics.len() => bail!(
+                    Some((name, expected)) if expected != generics.len() => bail!(
                         variable.span,
                         ErrorKind::TemplateArgumentMismatch {
-                            span: *template.name.span,
-                            expected: template.generics.len(),
+                            span: *name.span,
+                            expected,
                             found: generics.len()
                         },
                     ),
@@ -1060,6 +2484,10 @@ impl ast::Expression<r#type::Expression> {
                 r#type::Expression::Array(Box::new(r#type::Expression::Integer))
             }
             ast::Expression::Null(_) => r#type::Expression::Null,
+            ast::Expression::Cast(r#type, _, _) | ast::Expression::As(_, r#type, _) => {
+                r#type.r#type()
+            }
+            ast::Expression::InstanceOf(_, _, _) => r#type::Expression::Boolean,
             ast::Expression::This(r#type, _)
             | ast::Expression::Super(r#type, _)
             | ast::Expression::Variable(_, r#type)
@@ -1070,14 +2498,27 @@ impl ast::Expression<r#type::Expression> {
             | ast::Expression::Dot(_, _, r#type, _) => r#type.clone(),
             ast::Expression::New(class, _) => {
                 assert!(class.generics.is_none());
-                r#type::Expression::Class(class.name.symbol)
+                r#type::Expression::class(class.name.symbol)
             }
             ast::Expression::Call(call) => match call.function.r#type() {
                 r#type::Expression::Function(_, returns) if returns.len() == 1 => {
                     returns.first().unwrap().clone()
                 }
+                r#type::Expression::Poison => r#type::Expression::Poison,
                 _ => unreachable!(),
             },
+            ast::Expression::Closure(closure) => r#type::Expression::Function(
+                closure
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.r#type.r#type())
+                    .collect(),
+                closure.returns.iter().map(ast::Type::r#type).collect(),
+            ),
+            ast::Expression::Construct(construct) => {
+                r#type::Expression::Class(construct.r#enum.symbol)
+            }
+            ast::Expression::Match(_, r#type) => r#type.clone(),
         }
     }
 }
@@ -1087,11 +2528,18 @@ impl ast::Type<r#type::Expression> {
         match self {
             ast::Type::Bool(_) => r#type::Expression::Boolean,
             ast::Type::Int(_) => r#type::Expression::Integer,
+            &ast::Type::Sized { signed, size, .. } => {
+                r#type::Expression::from_integer(signed, size)
+            }
             ast::Type::Class(variable) => {
                 assert!(variable.generics.is_none());
-                r#type::Expression::Class(variable.name.symbol)
+                r#type::Expression::class(variable.name.symbol)
             }
             ast::Type::Array(r#type, _, _) => r#type::Expression::Array(Box::new(r#type.r#type())),
+            ast::Type::Function(parameters, returns, _) => r#type::Expression::Function(
+                parameters.iter().map(ast::Type::r#type).collect(),
+                returns.iter().map(ast::Type::r#type).collect(),
+            ),
         }
     }
 }
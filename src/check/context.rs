@@ -38,12 +38,26 @@ pub struct Context {
     /// Set of classes declared as final
     class_final: Set<Identifier>,
 
+    /// Set of interfaces, whose methods are stored alongside classes
+    protocols: Set<Identifier>,
+
+    /// Interfaces implemented by each class, excluding those implemented by its ancestors
+    implements: Map<Symbol, Set<Symbol>>,
+
+    /// Payload types of each enum variant, in declaration order
+    ///
+    /// Enums are also stored alongside classes, without any fields or methods.
+    enums: Environment<Environment<Vec<r#type::Expression>>>,
+
     /// Set of class templates visible to program
     class_templates: Environment<ast::ClassTemplate>,
 
     /// Set of function templates visible to program
     function_templates: Environment<ast::FunctionTemplate>,
 
+    /// Set of enum templates visible to program
+    enum_templates: Environment<ast::EnumTemplate>,
+
     /// Locally scoped variables
     locals: Vec<(LocalScope, Environment<Entry>)>,
 }
@@ -76,10 +90,19 @@ pub enum LocalScope {
     Function {
         returns: Vec<r#type::Expression>,
     },
+    Closure {
+        /// Captured `this`, which is only known during emission
+        this: Option<Temporary>,
+        returns: Vec<r#type::Expression>,
+    },
     Block,
     If,
     Else,
-    While(Option<Label>),
+    While {
+        label: Option<Symbol>,
+        /// `break` and `continue` targets, which are only known during emission
+        targets: Option<(Label, Label)>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -115,8 +138,12 @@ impl Context {
             class_signatures: Set::default(),
             class_implementations: Set::default(),
             class_final: Set::default(),
+            protocols: Set::default(),
+            implements: Map::default(),
+            enums: Environment::default(),
             class_templates: Environment::default(),
             function_templates: Environment::default(),
+            enum_templates: Environment::default(),
             locals: Vec::default(),
             hierarchy: Map::default(),
         }
@@ -226,6 +253,70 @@ impl Context {
             .map(|identifier| &*identifier.span)
     }
 
+    pub fn get_protocol<K: Key>(&self, protocol: &K) -> Option<&Span> {
+        self.protocols.get(protocol).map(|protocol| &*protocol.span)
+    }
+
+    pub fn insert_protocol(&mut self, protocol: Identifier) -> Option<(Span, Environment<Entry>)> {
+        insert_set(&mut self.protocols, protocol.clone());
+        self.classes.insert(protocol, Environment::default())
+    }
+
+    pub fn insert_implements(&mut self, class: Symbol, protocol: Symbol) {
+        self.implements.entry(class).or_default().insert(protocol);
+    }
+
+    /// Interfaces implemented by `class` itself, excluding those implemented by its ancestors.
+    pub fn get_implements(&self, class: &Symbol) -> impl Iterator<Item = Symbol> + '_ {
+        self.implements.get(class).into_iter().flatten().copied()
+    }
+
+    /// Interfaces implemented by `class` or any of its ancestors, starting from the root.
+    pub fn get_implements_inclusive(&self, class: &Symbol) -> Set<Symbol> {
+        self.ancestors_inclusive(class)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .flat_map(|class| self.get_implements(&class))
+            .collect()
+    }
+
+    pub fn get_enum<K: Key>(&self, r#enum: &K) -> Option<&Span> {
+        self.enums.get(r#enum).map(|(span, _)| span)
+    }
+
+    pub fn insert_enum(&mut self, r#enum: Identifier) -> Option<(Span, Environment<Entry>)> {
+        self.enums.insert(r#enum.clone(), Environment::default());
+        self.classes.insert(r#enum, Environment::default())
+    }
+
+    /// Variants of `r#enum` and their payload types, in declaration order.
+    pub fn get_variants(
+        &self,
+        r#enum: &Symbol,
+    ) -> impl Iterator<Item = (&Identifier, &Vec<r#type::Expression>)> + '_ {
+        self.enums[*r#enum].iter()
+    }
+
+    /// Finds the tag and payload types of variant `variant` of `r#enum`.
+    pub fn get_variant<K: Key>(
+        &self,
+        r#enum: &Symbol,
+        variant: &K,
+    ) -> Option<(usize, &[r#type::Expression])> {
+        let (tag, _, payload) = self.enums.get(r#enum)?.1 .0.get_full(variant)?;
+        Some((tag, payload))
+    }
+
+    pub fn insert_variant(
+        &mut self,
+        r#enum: Symbol,
+        variant: Identifier,
+        payload: Vec<r#type::Expression>,
+    ) -> Option<(Span, Vec<r#type::Expression>)> {
+        self.enums[r#enum].insert(variant, payload)
+    }
+
     pub fn insert_supertype(
         &mut self,
         subtype: Identifier,
@@ -268,12 +359,25 @@ impl Context {
             .map(|(_, function)| function)
     }
 
+    pub fn insert_enum_template(
+        &mut self,
+        r#enum: ast::EnumTemplate,
+    ) -> Option<(Span, ast::EnumTemplate)> {
+        self.enum_templates.insert(r#enum.name.clone(), r#enum)
+    }
+
+    pub fn get_enum_template<K: Key>(&self, identifier: &K) -> Option<&ast::EnumTemplate> {
+        self.enum_templates
+            .get(identifier)
+            .map(|(_, r#enum)| r#enum)
+    }
+
     pub fn push(&mut self, scope: LocalScope) {
         self.locals.push((scope, Environment::default()));
     }
 
-    pub fn pop(&mut self) {
-        self.locals.pop();
+    pub fn pop(&mut self) -> Option<(LocalScope, Environment<Entry>)> {
+        self.locals.pop()
     }
 
     pub fn ancestors_inclusive(&self, class: &Symbol) -> impl Iterator<Item = Symbol> + '_ {
@@ -322,7 +426,7 @@ impl Context {
     }
 
     pub fn get_scoped_this(&self) -> Option<Temporary> {
-        match self.locals.first()? {
+        self.locals.iter().rev().find_map(|scope| match scope {
             (
                 LocalScope::Method {
                     class: _,
@@ -330,33 +434,67 @@ impl Context {
                     returns: _,
                 },
                 _,
-            ) => *this,
-            (LocalScope::Function { returns: _ }, _) => None,
-            _ => unreachable!(),
-        }
+            ) => Some(*this),
+            (
+                LocalScope::Closure {
+                    this: Some(this),
+                    returns: _,
+                },
+                _,
+            ) => Some(Some(*this)),
+            (LocalScope::Function { returns: _ }, _) => Some(None),
+            _ => None,
+        })?
     }
 
+    /// Finds the return types of the innermost enclosing function, method, or closure.
     pub fn get_scoped_returns(&self) -> Option<&[r#type::Expression]> {
-        match self.locals.first()? {
+        self.locals.iter().rev().find_map(|scope| match scope {
             (
                 LocalScope::Method {
                     class: _,
                     this: _,
                     returns,
                 }
-                | LocalScope::Function { returns },
+                | LocalScope::Function { returns }
+                | LocalScope::Closure { this: _, returns },
                 _,
             ) => Some(returns.as_slice()),
-            _ => unreachable!(),
+            _ => None,
+        })
+    }
+
+    /// Returns the number of closures between the innermost scope and the scope
+    /// that binds local variable `identifier`, or `None` if it isn't a local.
+    pub fn get_closure_depth<K: Key>(&self, identifier: &K) -> Option<usize> {
+        let mut depth = 0;
+        for (scope, environment) in self.locals.iter().rev() {
+            if environment.get(identifier).is_some() {
+                return Some(depth);
+            }
+            if let LocalScope::Closure { .. } = scope {
+                depth += 1;
+            }
         }
+        None
     }
 
-    pub fn get_scoped_while(&self) -> Option<Option<Label>> {
+    /// Finds the innermost enclosing loop, or the innermost loop named `label` if provided.
+    ///
+    /// Loops outside of the innermost closure aren't visible.
+    pub fn get_scoped_while(&self, label: Option<Symbol>) -> Option<Option<(Label, Label)>> {
         self.locals
             .iter()
             .rev()
+            .take_while(|(scope, _)| !matches!(scope, LocalScope::Closure { .. }))
             .find_map(|scope| match scope {
-                (LocalScope::While(label), _) => Some(label),
+                (
+                    LocalScope::While {
+                        label: name,
+                        targets,
+                    },
+                    _,
+                ) if label.is_none() || label == *name => Some(targets),
                 _ => None,
             })
             .cloned()
@@ -365,13 +503,21 @@ impl Context {
     pub fn is_subtype(&self, subtype: &r#type::Expression, supertype: &r#type::Expression) -> bool {
         use r#type::Expression::*;
         match (subtype, supertype) {
-            (Any, _) | (Null, Class(_)) => true,
+            (Any | Poison, _) | (_, Poison) | (Null, Class(_)) => true,
             (Array(subtype), Array(supertype)) => self.is_subtype_array(subtype, supertype),
             (Class(subtype), Class(supertype)) if subtype == supertype => true,
+            (Class(subtype), Class(supertype)) if self.get_protocol(supertype).is_some() => {
+                self.ancestors_inclusive(subtype).any(|class| {
+                    self.get_implements(&class)
+                        .any(|r#type| r#type == *supertype)
+                })
+            }
             (Class(subtype), Class(supertype)) => self
                 .ancestors_exclusive(subtype)
                 .any(|r#type| r#type == *supertype),
-            (_, _) => subtype == supertype,
+            // Note: implicit widening is only allowed at the top level, since
+            // arrays of different integer types have different element layouts.
+            (_, _) => subtype == supertype || subtype.widens_to(supertype),
         }
     }
 
@@ -382,7 +528,7 @@ impl Context {
     ) -> bool {
         use r#type::Expression::*;
         match (subtype, supertype) {
-            (Any, _) | (Null, Class(_)) => true,
+            (Any | Poison, _) | (_, Poison) | (Null, Class(_)) => true,
             (Array(subtype), Array(supertype)) => self.is_subtype_array(subtype, supertype),
             (_, _) => subtype == supertype,
         }
@@ -412,6 +558,8 @@ impl Context {
     ) -> Option<LeastUpperBound> {
         use r#type::Expression::*;
         match (left, right) {
+            (Poison, _) => Some(LeastUpperBound::Left(Poison)),
+            (_, Poison) => Some(LeastUpperBound::Right(Poison)),
             (r#type, Any) => Some(LeastUpperBound::Left(r#type.clone())),
             (Any, r#type) => Some(LeastUpperBound::Right(r#type.clone())),
             (r#type @ Class(_), Null) => Some(LeastUpperBound::Left(r#type.clone())),
@@ -429,6 +577,8 @@ impl Context {
                 }
             }
             (left, right) if left == right => Some(LeastUpperBound::Left(left.clone())),
+            (left, right) if right.widens_to(left) => Some(LeastUpperBound::Left(left.clone())),
+            (left, right) if left.widens_to(right) => Some(LeastUpperBound::Right(right.clone())),
             (_, _) => None,
         }
     }
@@ -440,12 +590,14 @@ impl Context {
     ) -> Option<LeastUpperBound> {
         use r#type::Expression::*;
         match (left, right) {
+            (Poison, _) => Some(LeastUpperBound::Left(Poison)),
+            (_, Poison) => Some(LeastUpperBound::Right(Poison)),
             (r#type, Any) => Some(LeastUpperBound::Left(r#type.clone())),
             (Any, r#type) => Some(LeastUpperBound::Right(r#type.clone())),
             (r#type @ Class(_), Null) => Some(LeastUpperBound::Left(r#type.clone())),
             (Null, r#type @ Class(_)) => Some(LeastUpperBound::Right(r#type.clone())),
             (Array(left), Array(right)) => self
-                .least_upper_bound(left, right)
+                .least_upper_bound_array(left, right)
                 .map(LeastUpperBound::array),
             (_, _) if left == right => Some(LeastUpperBound::Left(left.clone())),
             (_, _) => None,
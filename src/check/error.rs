@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 
+use crate::abi;
 use crate::data::r#type;
 use crate::data::span::Span;
 use crate::data::symbol;
@@ -28,6 +29,10 @@ impl Error {
         self
     }
 
+    pub(super) fn span(&self) -> Span {
+        self.span
+    }
+
     pub(super) fn kind(&self) -> &ErrorKind {
         &self.kind
     }
@@ -49,10 +54,25 @@ pub enum ErrorKind {
     NotInClass(Option<Symbol>),
     NotInClassModule(Symbol),
     NotInWhile,
+    UnboundLabel(Symbol),
+    AssignCaptured(Symbol),
     NoSuperclass(Symbol),
     FinalSuperclass(Symbol, Span),
     ClassCycle(Symbol),
     ClassIncomplete(Symbol, Span),
+    NotInterface(Symbol),
+    ExtendsInterface(Symbol),
+    NewInterface(Symbol),
+    ImpossibleCast(r#type::Expression, r#type::Expression),
+    EnumNotClass(Symbol),
+    UnboundVariant(Symbol, Symbol),
+    VariantArity(Symbol, usize),
+    PatternMismatch(r#type::Expression),
+    NonExhaustive(String),
+    RedundantPattern,
+    InterfaceEnum(Symbol),
+    BuiltinClass(Symbol),
+    NotException(r#type::Expression),
     IndexEmpty,
     CallLength,
     InitLength,
@@ -68,6 +88,21 @@ pub enum ErrorKind {
         expected: usize,
         found: usize,
     },
+    UnsatisfiedBound {
+        r#type: r#type::Expression,
+        bound: r#type::Expression,
+        span: Span,
+    },
+    UninferredTemplateArgument {
+        name: Symbol,
+        span: Span,
+    },
+    AmbiguousTemplateArgument {
+        name: Symbol,
+        first: r#type::Expression,
+        second: r#type::Expression,
+        span: Span,
+    },
     Mismatch {
         expected: r#type::Expression,
         expected_span: Option<Span>,
@@ -76,6 +111,59 @@ pub enum ErrorKind {
 }
 
 impl ErrorKind {
+    fn code(&self) -> error::Code {
+        match self {
+            ErrorKind::NotFound(..) => error::Code::NotFound,
+            ErrorKind::UnboundVariable(..) => error::Code::UnboundVariable,
+            ErrorKind::UnboundFun(..) => error::Code::UnboundFun,
+            ErrorKind::UnboundFunTemplate(..) => error::Code::UnboundFunTemplate,
+            ErrorKind::UnboundClass(..) => error::Code::UnboundClass,
+            ErrorKind::UnboundClassTemplate(..) => error::Code::UnboundClassTemplate,
+            ErrorKind::NotVariable(..) => error::Code::NotVariable,
+            ErrorKind::NotFun(..) => error::Code::NotFun,
+            ErrorKind::NotExp => error::Code::NotExp,
+            ErrorKind::NotProcedure => error::Code::NotProcedure,
+            ErrorKind::NotClass => error::Code::NotClass,
+            ErrorKind::NotInClass(..) => error::Code::NotInClass,
+            ErrorKind::NotInClassModule(..) => error::Code::NotInClassModule,
+            ErrorKind::NotInWhile => error::Code::NotInWhile,
+            ErrorKind::UnboundLabel(..) => error::Code::UnboundLabel,
+            ErrorKind::AssignCaptured(..) => error::Code::AssignCaptured,
+            ErrorKind::NoSuperclass(..) => error::Code::NoSuperclass,
+            ErrorKind::FinalSuperclass(..) => error::Code::FinalSuperclass,
+            ErrorKind::ClassCycle(..) => error::Code::ClassCycle,
+            ErrorKind::ClassIncomplete(..) => error::Code::ClassIncomplete,
+            ErrorKind::NotInterface(..) => error::Code::NotInterface,
+            ErrorKind::ExtendsInterface(..) => error::Code::ExtendsInterface,
+            ErrorKind::NewInterface(..) => error::Code::NewInterface,
+            ErrorKind::ImpossibleCast(..) => error::Code::ImpossibleCast,
+            ErrorKind::EnumNotClass(..) => error::Code::EnumNotClass,
+            ErrorKind::UnboundVariant(..) => error::Code::UnboundVariant,
+            ErrorKind::VariantArity(..) => error::Code::VariantArity,
+            ErrorKind::PatternMismatch(..) => error::Code::PatternMismatch,
+            ErrorKind::NonExhaustive(..) => error::Code::NonExhaustive,
+            ErrorKind::RedundantPattern => error::Code::RedundantPattern,
+            ErrorKind::InterfaceEnum(..) => error::Code::InterfaceEnum,
+            ErrorKind::BuiltinClass(..) => error::Code::BuiltinClass,
+            ErrorKind::NotException(..) => error::Code::NotException,
+            ErrorKind::IndexEmpty => error::Code::IndexEmpty,
+            ErrorKind::CallLength => error::Code::CallLength,
+            ErrorKind::InitLength => error::Code::InitLength,
+            ErrorKind::InitProcedure => error::Code::InitProcedure,
+            ErrorKind::Unreachable => error::Code::Unreachable,
+            ErrorKind::MissingReturn => error::Code::MissingReturn,
+            ErrorKind::ReturnMismatch => error::Code::ReturnMismatch,
+            ErrorKind::NameClash(..) => error::Code::NameClash,
+            ErrorKind::FinalMismatch(..) => error::Code::FinalMismatch,
+            ErrorKind::SignatureMismatch(..) => error::Code::SignatureMismatch,
+            ErrorKind::TemplateArgumentMismatch { .. } => error::Code::TemplateArgumentMismatch,
+            ErrorKind::UnsatisfiedBound { .. } => error::Code::UnsatisfiedBound,
+            ErrorKind::UninferredTemplateArgument { .. } => error::Code::UninferredTemplateArgument,
+            ErrorKind::AmbiguousTemplateArgument { .. } => error::Code::AmbiguousTemplateArgument,
+            ErrorKind::Mismatch { .. } => error::Code::Mismatch,
+        }
+    }
+
     fn message(&self) -> Cow<'static, str> {
         match self {
             ErrorKind::NotFound(i) => Cow::Owned(format!(
@@ -113,6 +201,11 @@ impl ErrorKind {
                 Cow::Owned(format!("Not inside module that defines class {}", class))
             }
             ErrorKind::NotInWhile => Cow::Borrowed("Not inside while loop"),
+            ErrorKind::UnboundLabel(label) => Cow::Owned(format!("Unbound loop label {}", label)),
+            ErrorKind::AssignCaptured(variable) => Cow::Owned(format!(
+                "Cannot assign to {}, which is captured by value",
+                variable
+            )),
             ErrorKind::NoSuperclass(class) => {
                 Cow::Owned(format!("Class {} has no superclass", class))
             }
@@ -126,6 +219,49 @@ impl ErrorKind {
                 "Class {} does not implement method required in interface",
                 class
             )),
+            ErrorKind::NotInterface(class) => Cow::Owned(format!("{} is not an interface", class)),
+            ErrorKind::ImpossibleCast(from, to) => Cow::Owned(format!(
+                "Expression of type {} can never be an instance of {}",
+                from, to
+            )),
+            ErrorKind::EnumNotClass(r#enum) => {
+                Cow::Owned(format!("{} is an enum, not a class", r#enum))
+            }
+            ErrorKind::UnboundVariant(r#enum, variant) => {
+                Cow::Owned(format!("Enum {} has no variant {}", r#enum, variant))
+            }
+            ErrorKind::VariantArity(variant, count) => Cow::Owned(format!(
+                "Variant {} must be matched with {} patterns",
+                variant, count
+            )),
+            ErrorKind::PatternMismatch(r#type) => {
+                Cow::Owned(format!("Pattern can never match a value of type {}", r#type))
+            }
+            ErrorKind::NonExhaustive(witness) => {
+                Cow::Owned(format!("Match is not exhaustive: {} is not covered", witness))
+            }
+            ErrorKind::RedundantPattern => {
+                Cow::Borrowed("Unreachable pattern, which is covered by earlier arms")
+            }
+            ErrorKind::InterfaceEnum(r#enum) => Cow::Owned(format!(
+                "Enum {} cannot be exported, since interfaces cannot declare enums",
+                r#enum
+            )),
+            ErrorKind::BuiltinClass(class) => {
+                Cow::Owned(format!("Cannot redeclare built-in class {}", class))
+            }
+            ErrorKind::NotException(r#type) => Cow::Owned(format!(
+                "Expected a subclass of {}, but found {}",
+                abi::EXCEPTION,
+                r#type
+            )),
+            ErrorKind::ExtendsInterface(interface) => Cow::Owned(format!(
+                "Cannot extend interface {}, which must be implemented instead",
+                interface
+            )),
+            ErrorKind::NewInterface(interface) => {
+                Cow::Owned(format!("Cannot instantiate interface {}", interface))
+            }
             ErrorKind::IndexEmpty => Cow::Borrowed("Cannot index empty array"),
             ErrorKind::CallLength => {
                 Cow::Borrowed("Incorrect number of arguments for function call")
@@ -147,6 +283,24 @@ impl ErrorKind {
                     found
                 ))
             }
+            ErrorKind::UnsatisfiedBound { r#type, bound, span: _ } => {
+                Cow::Owned(format!("Type {} does not satisfy {}", r#type, bound))
+            }
+            ErrorKind::UninferredTemplateArgument { name, span: _ } => Cow::Owned(format!(
+                "Cannot infer type argument {}, which must be given explicitly",
+                symbol::resolve(*name)
+            )),
+            ErrorKind::AmbiguousTemplateArgument {
+                name,
+                first,
+                second,
+                span: _,
+            } => Cow::Owned(format!(
+                "Cannot infer type argument {}: found both {} and {}",
+                symbol::resolve(*name),
+                first,
+                second
+            )),
             ErrorKind::Mismatch {
                 expected,
                 expected_span: _,
@@ -163,48 +317,54 @@ impl std::fmt::Display for Error {
 }
 
 impl error::Report for Error {
-    fn report(&self) -> ariadne::ReportBuilder<Span> {
-        use ariadne::Span as _;
-        let report = ariadne::Report::build(
-            ariadne::ReportKind::Error,
-            *self.span.source(),
-            self.span.lo.index(),
-        )
-        .with_label(ariadne::Label::new(self.span).with_message(self.kind.message()));
+    fn code(&self) -> error::Code {
+        self.kind.code()
+    }
 
-        let mut report = match &self.kind {
+    fn labels(&self) -> Vec<(Span, Cow<'static, str>)> {
+        let mut labels = vec![(self.span, self.kind.message())];
+
+        let secondary = match &self.kind {
             ErrorKind::Mismatch {
                 expected,
                 expected_span: Some(span),
                 found: _,
-            } => report.with_label(
-                ariadne::Label::new(*span)
-                    .with_message(format!("Expected {} because of this", expected)),
-            ),
+            } => Some((
+                *span,
+                Cow::Owned(format!("Expected {} because of this", expected)),
+            )),
             ErrorKind::FinalSuperclass(_, span) => {
-                report.with_label(ariadne::Label::new(*span).with_message("Class defined here"))
+                Some((*span, Cow::Borrowed("Class defined here")))
             }
             ErrorKind::ClassIncomplete(_, span) => {
-                report.with_label(ariadne::Label::new(*span).with_message("Method required here"))
-            }
-            ErrorKind::NameClash(span) => report
-                .with_label(ariadne::Label::new(*span).with_message("Previous definition here")),
-            ErrorKind::FinalMismatch(span) => report
-                .with_label(ariadne::Label::new(*span).with_message("Previous definition here")),
-            ErrorKind::SignatureMismatch(span) => report
-                .with_label(ariadne::Label::new(*span).with_message("Signature definition here")),
-            ErrorKind::TemplateArgumentMismatch { span, .. } => report
-                .with_label(ariadne::Label::new(*span).with_message("Template definition here")),
-            _ => report,
+                Some((*span, Cow::Borrowed("Method required here")))
+            }
+            ErrorKind::NameClash(span) | ErrorKind::FinalMismatch(span) => {
+                Some((*span, Cow::Borrowed("Previous definition here")))
+            }
+            ErrorKind::SignatureMismatch(span) => {
+                Some((*span, Cow::Borrowed("Signature definition here")))
+            }
+            ErrorKind::TemplateArgumentMismatch { span, .. } => {
+                Some((*span, Cow::Borrowed("Template definition here")))
+            }
+            ErrorKind::UnsatisfiedBound { span, .. } => {
+                Some((*span, Cow::Borrowed("Bound declared here")))
+            }
+            ErrorKind::UninferredTemplateArgument { span, .. }
+            | ErrorKind::AmbiguousTemplateArgument { span, .. } => {
+                Some((*span, Cow::Borrowed("Type parameter declared here")))
+            }
+            _ => None,
         };
 
-        report.add_labels(
+        labels.extend(secondary);
+        labels.extend(
             self.provenance
                 .iter()
                 .rev()
-                .map(|span| ariadne::Label::new(*span).with_message("Template instantiated here")),
+                .map(|span| (*span, Cow::Borrowed("Template instantiated here"))),
         );
-
-        report
+        labels
     }
 }
@@ -0,0 +1,279 @@
+//! Exhaustiveness and redundancy checking for `match`, based on the usefulness
+//! algorithm from Luc Maranget's "Warnings for pattern matching" (2007).
+//!
+//! Patterns are simplified to constructors applied to sub-patterns, where each enum
+//! variant, Boolean literal, and integer literal is a separate constructor. Enums
+//! and Booleans have finitely many constructors, while integers never do.
+
+use std::fmt;
+
+use crate::check::Context;
+use crate::check::Error;
+use crate::check::ErrorKind;
+use crate::data::ast;
+use crate::data::r#type;
+use crate::data::span::Span;
+use crate::data::symbol::Symbol;
+
+/// Checks that each of `patterns` matches some value of type `r#type` that isn't
+/// matched by an earlier pattern, and that some pattern matches every value.
+pub(super) fn check(
+    context: &Context,
+    r#type: &r#type::Expression,
+    patterns: &[&ast::Pattern<r#type::Expression>],
+    span: Span,
+) -> Result<(), Error> {
+    if *r#type == r#type::Expression::Poison {
+        return Ok(());
+    }
+
+    let types = [r#type.clone()];
+    let mut matrix = Vec::with_capacity(patterns.len());
+
+    for pattern in patterns {
+        let row = vec![Pattern::from(*pattern)];
+        if !is_useful(context, &matrix, &row, &types) {
+            bail!(pattern.span(), ErrorKind::RedundantPattern);
+        }
+        matrix.push(row);
+    }
+
+    match find_missing(context, &matrix, &types) {
+        None => Ok(()),
+        Some(witness) => bail!(span, ErrorKind::NonExhaustive(witness[0].to_string())),
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Pattern {
+    Wildcard,
+    Constructor(Constructor, Vec<Pattern>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Constructor {
+    Variant(Symbol),
+    Boolean(bool),
+    Integer(i64),
+}
+
+impl From<&ast::Pattern<r#type::Expression>> for Pattern {
+    fn from(pattern: &ast::Pattern<r#type::Expression>) -> Self {
+        match pattern {
+            ast::Pattern::Wildcard(_) | ast::Pattern::Binding(_, _) => Pattern::Wildcard,
+            ast::Pattern::Variant(name, patterns, _, _) => Pattern::Constructor(
+                Constructor::Variant(name.symbol),
+                patterns.iter().map(Pattern::from).collect(),
+            ),
+            ast::Pattern::Boolean(boolean, _) => {
+                Pattern::Constructor(Constructor::Boolean(*boolean), Vec::new())
+            }
+            ast::Pattern::Integer(integer, _) => {
+                Pattern::Constructor(Constructor::Integer(*integer), Vec::new())
+            }
+            ast::Pattern::Character(character, _) => {
+                Pattern::Constructor(Constructor::Integer(*character as i64), Vec::new())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(fmt, "_"),
+            Pattern::Constructor(Constructor::Boolean(boolean), _) => write!(fmt, "{}", boolean),
+            Pattern::Constructor(Constructor::Integer(integer), _) => write!(fmt, "{}", integer),
+            Pattern::Constructor(Constructor::Variant(variant), fields) if fields.is_empty() => {
+                write!(fmt, "{}", variant)
+            }
+            Pattern::Constructor(Constructor::Variant(variant), fields) => {
+                write!(fmt, "{}(", variant)?;
+                for (index, field) in fields.iter().enumerate() {
+                    if index > 0 {
+                        write!(fmt, ", ")?;
+                    }
+                    write!(fmt, "{}", field)?;
+                }
+                write!(fmt, ")")
+            }
+        }
+    }
+}
+
+/// Whether some value matched by `row` isn't matched by any row of `matrix`,
+/// where the columns have types `types`.
+fn is_useful(
+    context: &Context,
+    matrix: &[Vec<Pattern>],
+    row: &[Pattern],
+    types: &[r#type::Expression],
+) -> bool {
+    let (head, rest) = match types.split_first() {
+        None => return matrix.is_empty(),
+        Some(split) => split,
+    };
+
+    match &row[0] {
+        Pattern::Constructor(constructor, _) => {
+            is_useful_constructor(context, matrix, row, constructor, head, rest)
+        }
+        Pattern::Wildcard => match constructors(context, head) {
+            Some(all) if is_complete(matrix, &all) => all.iter().any(|constructor| {
+                is_useful_constructor(context, matrix, row, constructor, head, rest)
+            }),
+            _ => is_useful(context, &default(matrix), &row[1..], &types[1..]),
+        },
+    }
+}
+
+fn is_useful_constructor(
+    context: &Context,
+    matrix: &[Vec<Pattern>],
+    row: &[Pattern],
+    constructor: &Constructor,
+    head: &r#type::Expression,
+    rest: &[r#type::Expression],
+) -> bool {
+    let mut types = fields(context, constructor, head);
+    let arity = types.len();
+    types.extend_from_slice(rest);
+
+    let row = specialize(row, constructor, arity).expect("[INTERNAL ERROR]: mismatched row");
+    is_useful(
+        context,
+        &specialize_all(matrix, constructor, arity),
+        &row,
+        &types,
+    )
+}
+
+/// Finds a row of patterns whose values aren't matched by any row of `matrix`,
+/// or `None` if `matrix` is exhaustive.
+fn find_missing(
+    context: &Context,
+    matrix: &[Vec<Pattern>],
+    types: &[r#type::Expression],
+) -> Option<Vec<Pattern>> {
+    let (head, rest) = match types.split_first() {
+        None if matrix.is_empty() => return Some(Vec::new()),
+        None => return None,
+        Some(split) => split,
+    };
+
+    match constructors(context, head) {
+        Some(all) if is_complete(matrix, &all) => all.into_iter().find_map(|constructor| {
+            let mut types = fields(context, &constructor, head);
+            let arity = types.len();
+            types.extend_from_slice(rest);
+
+            let mut witness = find_missing(
+                context,
+                &specialize_all(matrix, &constructor, arity),
+                &types,
+            )?;
+            let rest = witness.split_off(arity);
+            witness = std::iter::once(Pattern::Constructor(constructor, witness))
+                .chain(rest)
+                .collect();
+            Some(witness)
+        }),
+        all => {
+            let mut witness = find_missing(context, &default(matrix), rest)?;
+
+            // Prefer naming a missing constructor, unless no constructor is matched
+            // at all, in which case a wildcard is more informative.
+            let missing = match all {
+                Some(all) if heads(matrix).next().is_some() => all
+                    .into_iter()
+                    .find(|constructor| !heads(matrix).any(|head| head == constructor))
+                    .map(|constructor| {
+                        let arity = fields(context, &constructor, head).len();
+                        Pattern::Constructor(constructor, vec![Pattern::Wildcard; arity])
+                    })
+                    .unwrap_or(Pattern::Wildcard),
+                _ => Pattern::Wildcard,
+            };
+
+            witness.insert(0, missing);
+            Some(witness)
+        }
+    }
+}
+
+/// Constructors of values of type `r#type`, or `None` if there are infinitely many.
+fn constructors(context: &Context, r#type: &r#type::Expression) -> Option<Vec<Constructor>> {
+    match r#type {
+        r#type::Expression::Boolean => Some(vec![
+            Constructor::Boolean(false),
+            Constructor::Boolean(true),
+        ]),
+        r#type::Expression::Class(r#enum) if context.get_enum(r#enum).is_some() => Some(
+            context
+                .get_variants(r#enum)
+                .map(|(variant, _)| Constructor::Variant(variant.symbol))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Types of the fields of `constructor`, when matching values of type `r#type`.
+fn fields(
+    context: &Context,
+    constructor: &Constructor,
+    r#type: &r#type::Expression,
+) -> Vec<r#type::Expression> {
+    match (constructor, r#type) {
+        (Constructor::Variant(variant), r#type::Expression::Class(r#enum)) => context
+            .get_variant(r#enum, variant)
+            .map(|(_, payload)| payload.to_vec())
+            .expect("[INTERNAL ERROR]: unbound variant"),
+        (Constructor::Variant(_), _) => unreachable!(),
+        (Constructor::Boolean(_) | Constructor::Integer(_), _) => Vec::new(),
+    }
+}
+
+fn heads(matrix: &[Vec<Pattern>]) -> impl Iterator<Item = &Constructor> {
+    matrix.iter().filter_map(|row| match &row[0] {
+        Pattern::Wildcard => None,
+        Pattern::Constructor(constructor, _) => Some(constructor),
+    })
+}
+
+/// Whether every constructor in `all` heads some row of `matrix`.
+fn is_complete(matrix: &[Vec<Pattern>], all: &[Constructor]) -> bool {
+    all.iter()
+        .all(|constructor| heads(matrix).any(|head| head == constructor))
+}
+
+/// Rows of `matrix` that match `constructor`, with its fields in place of the first column.
+fn specialize_all(
+    matrix: &[Vec<Pattern>],
+    constructor: &Constructor,
+    arity: usize,
+) -> Vec<Vec<Pattern>> {
+    matrix
+        .iter()
+        .filter_map(|row| specialize(row, constructor, arity))
+        .collect()
+}
+
+fn specialize(row: &[Pattern], constructor: &Constructor, arity: usize) -> Option<Vec<Pattern>> {
+    let mut specialized = match &row[0] {
+        Pattern::Wildcard => vec![Pattern::Wildcard; arity],
+        Pattern::Constructor(head, fields) if head == constructor => fields.clone(),
+        Pattern::Constructor(_, _) => return None,
+    };
+    specialized.extend_from_slice(&row[1..]);
+    Some(specialized)
+}
+
+/// Rows of `matrix` whose first column is a wildcard, without that column.
+fn default(matrix: &[Vec<Pattern>]) -> Vec<Vec<Pattern>> {
+    matrix
+        .iter()
+        .filter(|row| matches!(row[0], Pattern::Wildcard))
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
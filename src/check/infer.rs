@@ -0,0 +1,182 @@
+use crate::check::check::Checker;
+use crate::check::context::LeastUpperBound;
+use crate::check::Error;
+use crate::check::ErrorKind;
+use crate::data::ast;
+use crate::data::r#type;
+use crate::data::span::Span;
+use crate::data::symbol::Symbol;
+use crate::Map;
+
+impl Checker {
+    /// Infers the type arguments of the function template called through `variable`
+    /// by unifying its parameter types against the types of `arguments`.
+    ///
+    /// A type parameter matched against several arguments is inferred as their
+    /// least upper bound, e.g. `max(dog, animal)` instantiates `max::<Animal>`.
+    pub(super) fn infer_template_arguments(
+        &mut self,
+        variable: &ast::Variable<()>,
+        arguments: &[ast::Expression<r#type::Expression>],
+        span: Span,
+    ) -> Result<Vec<ast::Type<()>>, Error> {
+        let template = self.context.get_function_template(&variable.name).unwrap();
+
+        if template.parameters.len() != arguments.len() {
+            bail!(span, ErrorKind::CallLength);
+        }
+
+        let generics = template.generics.clone();
+        let parameters = template
+            .parameters
+            .iter()
+            .map(|parameter| (*parameter.r#type).clone())
+            .collect::<Vec<_>>();
+
+        let mut inferred = Map::default();
+
+        for (parameter, argument) in parameters.iter().zip(arguments) {
+            self.unify(
+                &generics,
+                parameter,
+                &argument.r#type(),
+                argument.span(),
+                &mut inferred,
+            )?;
+        }
+
+        generics
+            .iter()
+            .map(|generic| {
+                match inferred
+                    .get(&generic.name.symbol)
+                    .and_then(|r#type| reify(r#type, variable.span))
+                {
+                    Some(r#type) => Ok(r#type),
+                    None => bail!(
+                        span,
+                        ErrorKind::UninferredTemplateArgument {
+                            name: generic.name.symbol,
+                            span: *generic.name.span,
+                        },
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    fn unify(
+        &mut self,
+        generics: &[ast::Generic],
+        parameter: &ast::Type<()>,
+        argument: &r#type::Expression,
+        span: Span,
+        inferred: &mut Map<Symbol, r#type::Expression>,
+    ) -> Result<(), Error> {
+        match (parameter, argument) {
+            (ast::Type::Class(variable), _) if variable.generics.is_none() => {
+                let generic = match generics
+                    .iter()
+                    .find(|generic| generic.name.symbol == variable.name.symbol)
+                {
+                    Some(generic) => generic,
+                    None => return Ok(()),
+                };
+
+                let r#type = match inferred.get(&generic.name.symbol) {
+                    None => argument.clone(),
+                    Some(previous) => match self.context.least_upper_bound(previous, argument) {
+                        Some(LeastUpperBound::Left(r#type) | LeastUpperBound::Right(r#type)) => {
+                            r#type
+                        }
+                        None => bail!(
+                            span,
+                            ErrorKind::AmbiguousTemplateArgument {
+                                name: generic.name.symbol,
+                                first: previous.clone(),
+                                second: argument.clone(),
+                                span: *generic.name.span,
+                            },
+                        ),
+                    },
+                };
+
+                inferred.insert(generic.name.symbol, r#type);
+            }
+            (ast::Type::Class(variable), r#type::Expression::Class(class)) => {
+                // Match type arguments of class and enum template instantiations
+                let arguments = match self.instantiations.get(class) {
+                    Some((template, arguments)) if *template == variable.name.symbol => {
+                        arguments.clone()
+                    }
+                    _ => return Ok(()),
+                };
+
+                for (parameter, argument) in variable.generics.iter().flatten().zip(arguments) {
+                    let argument = self.check_type(argument)?.r#type();
+                    self.unify(generics, parameter, &argument, span, inferred)?;
+                }
+            }
+            (ast::Type::Array(parameter, _, _), r#type::Expression::Array(argument)) => {
+                self.unify(generics, parameter, argument, span, inferred)?;
+            }
+            (
+                ast::Type::Function(parameters, parameter_returns, _),
+                r#type::Expression::Function(arguments, argument_returns),
+            ) if parameters.len() == arguments.len()
+                && parameter_returns.len() == argument_returns.len() =>
+            {
+                for (parameter, argument) in parameters
+                    .iter()
+                    .zip(arguments)
+                    .chain(parameter_returns.iter().zip(argument_returns))
+                {
+                    self.unify(generics, parameter, argument, span, inferred)?;
+                }
+            }
+            // Note: mismatches are reported when checking the instantiated call
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts an inferred type back into a type argument, unless it contains
+/// no information, e.g. the type of `null` or an empty array.
+fn reify(r#type: &r#type::Expression, span: Span) -> Option<ast::Type<()>> {
+    match r#type {
+        r#type::Expression::Any | r#type::Expression::Null => None,
+        // Note: instantiate with the placeholder class, so that the error doesn't cascade
+        r#type::Expression::Poison => Some(ast::Type::poisoned(span)),
+        r#type::Expression::Integer => Some(ast::Type::Int(span)),
+        r#type::Expression::Sized { signed, size } => Some(ast::Type::Sized {
+            signed: *signed,
+            size: *size,
+            span,
+        }),
+        r#type::Expression::Boolean => Some(ast::Type::Bool(span)),
+        r#type::Expression::Class(class) => Some(ast::Type::Class(ast::Variable {
+            name: ast::Identifier {
+                symbol: *class,
+                span: Box::new(span),
+            },
+            generics: None,
+            span,
+        })),
+        r#type::Expression::Array(r#type) => {
+            Some(ast::Type::Array(Box::new(reify(r#type, span)?), None, span))
+        }
+        r#type::Expression::Function(parameters, returns) => Some(ast::Type::Function(
+            parameters
+                .iter()
+                .map(|parameter| reify(parameter, span))
+                .collect::<Option<_>>()?,
+            returns
+                .iter()
+                .map(|r#return| reify(r#return, span))
+                .collect::<Option<_>>()?,
+            Box::new(span),
+        )),
+    }
+}
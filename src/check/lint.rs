@@ -0,0 +1,522 @@
+use crate::check::check::Checker;
+use crate::check::Entry;
+use crate::check::LocalScope;
+use crate::check::Warning;
+use crate::check::WarningKind;
+use crate::data::ast;
+use crate::data::r#type;
+use crate::data::span::Span;
+use crate::data::symbol;
+use crate::data::symbol::Symbol;
+use crate::Set;
+
+impl Checker {
+    /// Pops the innermost local scope, warning about variables that were never read.
+    pub(super) fn pop(&mut self) {
+        let (scope, environment) = self
+            .context
+            .pop()
+            .expect("[INTERNAL ERROR]: missing environment");
+
+        let referenced = self.referenced.borrow();
+
+        for (identifier, entry) in &environment {
+            match entry {
+                // Note: poisoned variables are only bound to suppress further errors
+                Entry::Variable(r#type::Expression::Poison) => continue,
+                Entry::Variable(_) => (),
+                Entry::Function(_, _) | Entry::Signature(_, _) => continue,
+            }
+
+            if referenced.contains(&*identifier.span) {
+                continue;
+            }
+
+            let kind = match scope {
+                LocalScope::Method { .. }
+                | LocalScope::Function { .. }
+                | LocalScope::Closure { .. } => WarningKind::UnusedParameter(identifier.symbol),
+                LocalScope::Block
+                | LocalScope::If
+                | LocalScope::Else
+                | LocalScope::While { .. } => WarningKind::UnusedVariable(identifier.symbol),
+            };
+
+            self.warnings.push(Warning::new(*identifier.span, kind));
+        }
+    }
+
+    /// Warns about assignments to local variables that are overwritten or go out of
+    /// scope before they're read.
+    pub(super) fn lint_stores(&mut self, program: &mut ast::Program<r#type::Expression>) {
+        let mut stores = Stores::default();
+        program.accept_mut(&mut stores);
+        self.warnings.extend(stores.0);
+    }
+
+    /// Collects every name referenced by the program, including inside templates.
+    pub(super) fn collect_references(&self, program: &mut ast::Program<()>) -> Set<Symbol> {
+        let mut collector = References(Set::default());
+        program.accept_mut(&mut collector);
+        collector.0
+    }
+
+    /// Warns about interfaces in the program's use list that no item refers to.
+    pub(super) fn lint_uses(
+        &mut self,
+        program: &ast::Program<r#type::Expression>,
+        references: &Set<Symbol>,
+    ) {
+        for r#use in &program.uses {
+            if let Some(exports) = self.exports.get(&r#use.name.symbol) {
+                if exports.is_disjoint(references) {
+                    self.warnings.push(Warning::new(
+                        r#use.span,
+                        WarningKind::UnusedUse(r#use.name.symbol),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Warns about functions that are neither called nor exported by an interface.
+    pub(super) fn lint_functions(&mut self, program: &ast::Program<r#type::Expression>) {
+        let main = symbol::intern_static("main");
+        let referenced = self.referenced.borrow();
+
+        for item in &program.items {
+            let function = match item {
+                ast::Item::Function(function) => function,
+                _ => continue,
+            };
+
+            // Note: template instantiations only exist because they were called
+            if !function.provenance.is_empty()
+                || function.declared.get()
+                || function.name.symbol == main
+                || referenced.contains(&*function.name.span)
+            {
+                continue;
+            }
+
+            self.warnings.push(Warning::new(
+                *function.name.span,
+                WarningKind::UnusedFunction(function.name.symbol),
+            ));
+        }
+    }
+}
+
+/// Backward liveness analysis over the local variables of a single function or closure
+/// body, which finds assignments whose values can never be read.
+///
+/// Locals can't shadow each other, so they're identified by name, but only within the
+/// scope of their declaration: outside of it, the same name refers to a field or global.
+#[derive(Default)]
+struct Liveness {
+    /// Live variables when control leaves for the enclosing `catch` or `finally` blocks,
+    /// which an exception can do from any statement inside a `try` block.
+    exceptional: Set<Symbol>,
+
+    /// Live variables after each enclosing loop and before its next iteration.
+    loops: Vec<(Option<Symbol>, Set<Symbol>, Set<Symbol>)>,
+
+    /// Every local variable that is read somewhere in the body.
+    reads: Set<Symbol>,
+
+    /// Dead stores found so far, reported only for variables that are read elsewhere,
+    /// since variables that are never read are already reported as unused.
+    stores: Vec<(Symbol, Span)>,
+
+    /// Suppresses dead stores while iterating loops to a fixed point.
+    quiet: usize,
+}
+
+impl Liveness {
+    fn analyze(
+        parameters: &[ast::SingleDeclaration<r#type::Expression>],
+        statements: &mut ast::Statement<r#type::Expression>,
+    ) -> Vec<Warning> {
+        let scope = parameters
+            .iter()
+            .map(|parameter| parameter.name.symbol)
+            .collect();
+
+        let mut liveness = Liveness::default();
+        liveness.statement(&scope, statements, Set::default());
+        liveness
+            .stores
+            .into_iter()
+            .filter(|(variable, _)| liveness.reads.contains(variable))
+            .map(|(variable, span)| Warning::new(span, WarningKind::DeadStore(variable)))
+            .collect()
+    }
+
+    /// Returns the variables live before `statement`, given those live after it.
+    fn statement(
+        &mut self,
+        scope: &Set<Symbol>,
+        statement: &mut ast::Statement<r#type::Expression>,
+        mut live: Set<Symbol>,
+    ) -> Set<Symbol> {
+        match statement {
+            ast::Statement::Assignment(left, right, span) => {
+                match &mut **left {
+                    ast::Expression::Variable(variable, _)
+                        if scope.contains(&variable.name.symbol) =>
+                    {
+                        self.store(&mut live, variable.name.symbol, *span);
+                    }
+                    left => self.read(&mut live, left),
+                }
+                self.read(&mut live, right);
+            }
+            ast::Statement::Call(call) => call.accept_mut(&mut Reads(&mut live, &mut self.reads)),
+            ast::Statement::Initialization(initialization) => {
+                for declaration in initialization.declarations.iter_mut().flatten() {
+                    self.store(&mut live, declaration.name.symbol, initialization.span);
+                    self.declare(&mut live, declaration);
+                }
+                self.read(&mut live, &mut initialization.expression);
+            }
+            ast::Statement::Declaration(declaration, _) => match &mut **declaration {
+                ast::Declaration::Single(declaration) => self.declare(&mut live, declaration),
+                ast::Declaration::Multiple(declaration) => {
+                    for name in &declaration.names {
+                        live.remove(&name.symbol);
+                    }
+                    declaration
+                        .r#type
+                        .accept_mut(&mut Reads(&mut live, &mut self.reads));
+                }
+            },
+            ast::Statement::Return(expressions, _) => {
+                live = self.exceptional.clone();
+                for expression in expressions {
+                    self.read(&mut live, expression);
+                }
+            }
+            ast::Statement::Sequence(statements, _) => {
+                let mut scopes = Vec::with_capacity(statements.len());
+                let mut inner = scope.clone();
+                for statement in statements.iter() {
+                    scopes.push(inner.clone());
+                    inner.extend(declared(statement));
+                }
+                for (statement, scope) in statements.iter_mut().zip(&scopes).rev() {
+                    live = self.statement(scope, statement, live);
+                }
+            }
+            ast::Statement::If(condition, r#if, r#else, _) => {
+                let mut before = self.statement(scope, r#if, live.clone());
+                if let Some(r#else) = r#else {
+                    live = self.statement(scope, r#else, live);
+                }
+                before.extend(live);
+                live = before;
+                self.read(&mut live, condition);
+            }
+            ast::Statement::While(r#do, label, condition, statements, _) => {
+                let label = label.as_ref().map(|label| label.symbol);
+                let exit = live;
+                let mut head = exit.clone();
+                self.read(&mut head, condition);
+
+                // Note: `continue` jumps to the condition, for both `while` and `do`-`while`
+                let (head, body) = self.fixpoint(head, |liveness, head| {
+                    liveness.loops.push((label, exit.clone(), head.clone()));
+                    let before = liveness.statement(scope, statements, head.clone());
+                    liveness.loops.pop();
+                    before
+                });
+
+                live = match r#do {
+                    ast::Do::Yes => body,
+                    ast::Do::No => head,
+                };
+            }
+            ast::Statement::For(r#for) => {
+                let label = r#for.label.as_ref().map(|label| label.symbol);
+                let mut inner = scope.clone();
+                if let Some(initialization) = &r#for.initialization {
+                    inner.extend(declared(initialization));
+                }
+
+                let ast::For {
+                    initialization,
+                    condition,
+                    update,
+                    statements,
+                    ..
+                } = &mut **r#for;
+
+                // Note: without a condition, the loop can only exit via `break`
+                let exit = live;
+                let mut head = Set::default();
+                if let Some(condition) = condition {
+                    head.extend(exit.iter().copied());
+                    self.read(&mut head, condition);
+                }
+
+                let (head, _) = self.fixpoint(head, |liveness, head| {
+                    let next = match update {
+                        Some(update) => liveness.statement(&inner, update, head.clone()),
+                        None => head.clone(),
+                    };
+                    liveness.loops.push((label, exit.clone(), next.clone()));
+                    let before = liveness.statement(&inner, statements, next);
+                    liveness.loops.pop();
+                    before
+                });
+
+                live = match initialization {
+                    Some(initialization) => self.statement(scope, initialization, head),
+                    None => head,
+                };
+            }
+            ast::Statement::Break(label, _) => {
+                live = self.target(label).1.clone();
+                live.extend(self.exceptional.iter().copied());
+            }
+            ast::Statement::Continue(label, _) => {
+                live = self.target(label).2.clone();
+                live.extend(self.exceptional.iter().copied());
+            }
+            ast::Statement::Match(r#match) => {
+                let mut before = Set::default();
+                for arm in &mut r#match.arms {
+                    let mut bindings = Vec::new();
+                    bound(&arm.pattern, &mut bindings);
+                    let mut inner = scope.clone();
+                    inner.extend(bindings.iter().copied());
+                    let mut arm = self.statement(&inner, &mut arm.body, live.clone());
+                    for binding in &bindings {
+                        arm.remove(binding);
+                    }
+                    before.extend(arm);
+                }
+                live = before;
+                self.read(&mut live, &mut r#match.scrutinee);
+            }
+            ast::Statement::Throw(expression, _) => {
+                live = self.exceptional.clone();
+                self.read(&mut live, expression);
+            }
+            ast::Statement::Try(r#try) => {
+                let after = match &mut r#try.finally {
+                    Some(finally) => self.statement(scope, finally, live),
+                    None => live,
+                };
+
+                let mut handlers = after.clone();
+                for catch in &mut r#try.catches {
+                    let mut inner = scope.clone();
+                    inner.insert(catch.declaration.name.symbol);
+                    let mut handler = self.statement(&inner, &mut catch.statements, after.clone());
+                    handler.remove(&catch.declaration.name.symbol);
+                    handlers.extend(handler);
+                }
+
+                let exceptional = std::mem::replace(&mut self.exceptional, handlers);
+                self.exceptional.extend(exceptional.iter().copied());
+                live = self.statement(scope, &mut r#try.statements, after);
+                self.exceptional = exceptional;
+            }
+            ast::Statement::Error(_) => (),
+        }
+
+        live.extend(self.exceptional.iter().copied());
+        live
+    }
+
+    /// Iterates a loop to a fixed point, starting from the variables live at its head
+    /// when exiting. `body` computes the variables live before the loop body from those
+    /// live at its head. Returns the converged variables live at the head and before the body.
+    fn fixpoint<B>(&mut self, mut head: Set<Symbol>, mut body: B) -> (Set<Symbol>, Set<Symbol>)
+    where
+        B: FnMut(&mut Self, &Set<Symbol>) -> Set<Symbol>,
+    {
+        self.quiet += 1;
+        loop {
+            let mut next = body(self, &head);
+            next.extend(head.iter().copied());
+            if next == head {
+                break;
+            }
+            head = next;
+        }
+        self.quiet -= 1;
+
+        // Note: now that liveness has converged, analyze the body once more to report stores
+        let before = body(self, &head);
+        (head, before)
+    }
+
+    fn target(
+        &self,
+        label: &Option<ast::Identifier>,
+    ) -> &(Option<Symbol>, Set<Symbol>, Set<Symbol>) {
+        self.loops
+            .iter()
+            .rev()
+            .find(|(target, _, _)| match label {
+                None => true,
+                Some(label) => *target == Some(label.symbol),
+            })
+            .expect("[INTERNAL ERROR]: unbound loop label")
+    }
+
+    fn store(&mut self, live: &mut Set<Symbol>, variable: Symbol, span: Span) {
+        if !live.remove(&variable) && self.quiet == 0 {
+            self.stores.push((variable, span));
+        }
+    }
+
+    fn declare(
+        &mut self,
+        live: &mut Set<Symbol>,
+        declaration: &mut ast::SingleDeclaration<r#type::Expression>,
+    ) {
+        live.remove(&declaration.name.symbol);
+        declaration
+            .r#type
+            .accept_mut(&mut Reads(live, &mut self.reads));
+    }
+
+    fn read(
+        &mut self,
+        live: &mut Set<Symbol>,
+        expression: &mut ast::Expression<r#type::Expression>,
+    ) {
+        expression.accept_mut(&mut Reads(live, &mut self.reads));
+    }
+}
+
+/// Names declared by `statement` in its enclosing block.
+fn declared<T>(statement: &ast::Statement<T>) -> Vec<Symbol> {
+    match statement {
+        ast::Statement::Initialization(initialization) => initialization
+            .declarations
+            .iter()
+            .flatten()
+            .map(|declaration| declaration.name.symbol)
+            .collect(),
+        ast::Statement::Declaration(declaration, _) => {
+            declaration.iter().map(|(name, _)| name.symbol).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Names bound by `pattern`.
+fn bound<T>(pattern: &ast::Pattern<T>, bindings: &mut Vec<Symbol>) {
+    match pattern {
+        ast::Pattern::Binding(name, _) => bindings.push(name.symbol),
+        ast::Pattern::Variant(_, patterns, _, _) => {
+            patterns.iter().for_each(|pattern| bound(pattern, bindings))
+        }
+        ast::Pattern::Wildcard(_)
+        | ast::Pattern::Boolean(_, _)
+        | ast::Pattern::Integer(_, _)
+        | ast::Pattern::Character(_, _) => (),
+    }
+}
+
+/// Marks every variable read by an expression as live. Closures read the variables
+/// they capture when they're created.
+struct Reads<'a>(&'a mut Set<Symbol>, &'a mut Set<Symbol>);
+
+impl ast::VisitorMut<r#type::Expression> for Reads<'_> {
+    fn visit_variable(&mut self, variable: &mut ast::Variable<r#type::Expression>) {
+        self.0.insert(variable.name.symbol);
+        self.1.insert(variable.name.symbol);
+    }
+
+    fn visit_closure(&mut self, closure: &mut ast::Closure<r#type::Expression>) {
+        self.0.extend(closure.captures.iter().copied());
+        self.1.extend(closure.captures.iter().copied());
+    }
+}
+
+/// Runs liveness analysis on every function, method, and closure body.
+#[derive(Default)]
+struct Stores(Vec<Warning>);
+
+impl ast::VisitorMut<r#type::Expression> for Stores {
+    fn visit_function(&mut self, function: &mut ast::Function<r#type::Expression>) {
+        self.0.extend(Liveness::analyze(
+            &function.parameters,
+            &mut function.statements,
+        ));
+    }
+
+    fn visit_closure(&mut self, closure: &mut ast::Closure<r#type::Expression>) {
+        self.0.extend(Liveness::analyze(
+            &closure.parameters,
+            &mut closure.statements,
+        ));
+    }
+}
+
+struct References(Set<Symbol>);
+
+impl ast::VisitorMut<()> for References {
+    fn visit_variable(&mut self, variable: &mut ast::Variable<()>) {
+        self.0.insert(variable.name.symbol);
+    }
+
+    // Note: templates aren't traversed by default, since they're only checked
+    // after instantiation.
+    fn visit_item(&mut self, item: &mut ast::Item<()>) {
+        match item {
+            ast::Item::ClassTemplate(class) => {
+                self.visit_bounds(&mut class.generics);
+                if let Some(supertype) = &mut class.extends {
+                    supertype.accept_mut(self);
+                }
+                class
+                    .implements
+                    .iter_mut()
+                    .for_each(|protocol| protocol.accept_mut(self));
+                class
+                    .items
+                    .iter_mut()
+                    .for_each(|item| item.accept_mut(self));
+            }
+            ast::Item::FunctionTemplate(function) => {
+                self.visit_bounds(&mut function.generics);
+                function
+                    .parameters
+                    .iter_mut()
+                    .for_each(|parameter| parameter.accept_mut(self));
+                function
+                    .returns
+                    .iter_mut()
+                    .for_each(|r#return| r#return.accept_mut(self));
+                function.statements.accept_mut(self);
+            }
+            ast::Item::EnumTemplate(r#enum) => {
+                self.visit_bounds(&mut r#enum.generics);
+                r#enum
+                    .variants
+                    .iter_mut()
+                    .flat_map(|variant| variant.payload.iter_mut())
+                    .for_each(|r#type| r#type.accept_mut(self))
+            }
+            ast::Item::Global(_)
+            | ast::Item::Class(_)
+            | ast::Item::Protocol(_)
+            | ast::Item::Enum(_)
+            | ast::Item::Function(_) => (),
+        }
+    }
+}
+
+impl References {
+    fn visit_bounds(&mut self, generics: &mut [ast::Generic]) {
+        generics
+            .iter_mut()
+            .filter_map(|generic| generic.bound.as_mut())
+            .for_each(|bound| bound.accept_mut(self));
+    }
+}
@@ -22,28 +22,16 @@ impl Checker {
         directory_library: &Path,
         path: &Path,
         program: &ast::Program<()>,
+        implicit: bool,
     ) -> Result<(), error::Error> {
+        self.load_builtins();
+
         for r#use in &program.uses {
             self.load_use(directory_library, r#use)?;
         }
 
-        let implicit = path
-            .file_stem()
-            .map(Path::new)
-            .map(|path| ast::Use {
-                name: ast::Identifier {
-                    symbol: symbol::intern(path.to_str().unwrap()),
-                    span: Box::new(Span::default()),
-                },
-                span: Span::default(),
-            })
-            .unwrap();
-
-        match self.load_use(directory_library, &implicit) {
-            Ok(()) => (),
-            Err(error::Error::Semantic(error))
-                if *error.kind() == ErrorKind::NotFound(implicit.name.symbol) => {}
-            Err(error) => return Err(error),
+        if implicit {
+            self.load_implicit(directory_library, path)?;
         }
 
         for item in &program.items {
@@ -53,6 +41,9 @@ impl Checker {
                 ast::Item::Global(_) => (),
                 ast::Item::Class(class) => self.load_class(class)?,
                 ast::Item::ClassTemplate(class) => self.load_class_template(class)?,
+                ast::Item::Protocol(protocol) => self.load_protocol(protocol)?,
+                ast::Item::Enum(r#enum) => self.load_enum(r#enum)?,
+                ast::Item::EnumTemplate(r#enum) => self.load_enum_template(r#enum)?,
                 ast::Item::Function(function) => {
                     self.load_function(GlobalScope::Global, function)?
                 }
@@ -65,6 +56,49 @@ impl Checker {
         Ok(())
     }
 
+    /// Loads the interface file with the same name as the program, if it exists.
+    fn load_implicit(&mut self, directory_library: &Path, path: &Path) -> Result<(), error::Error> {
+        let implicit = path
+            .file_stem()
+            .map(Path::new)
+            .map(|path| ast::Use {
+                name: ast::Identifier {
+                    symbol: symbol::intern(path.to_str().unwrap()),
+                    span: Box::new(Span::default()),
+                },
+                span: Span::default(),
+            })
+            .unwrap();
+
+        match self.load_use(directory_library, &implicit) {
+            Ok(()) => (),
+            Err(error::Error::Semantic(errors))
+                if matches!(
+                    errors.as_slice(),
+                    [error] if *error.kind() == ErrorKind::NotFound(implicit.name.symbol),
+                ) => {}
+            Err(error) => return Err(error),
+        }
+
+        Ok(())
+    }
+
+    /// Loads the built-in exception classes, which every program implicitly declares.
+    fn load_builtins(&mut self) {
+        let builtin = |name| ast::Identifier {
+            symbol: symbol::intern_static(name),
+            span: Box::new(Span::default()),
+        };
+
+        let exception = builtin(abi::EXCEPTION);
+        let out_of_bounds = builtin(abi::OUT_OF_BOUNDS);
+
+        self.context.insert_class_implementation(exception.clone());
+        self.context
+            .insert_class_implementation(out_of_bounds.clone());
+        self.context.insert_supertype(out_of_bounds, exception);
+    }
+
     fn load_use(&mut self, directory_library: &Path, r#use: &ast::Use) -> Result<(), error::Error> {
         // Load each interface exactly once
         if !self.used.insert(r#use.name.symbol) {
@@ -80,8 +114,33 @@ impl Checker {
             Err(error) => return Err(error),
         };
 
-        let interface = parse::InterfaceParser::new().parse(tokens)?;
-        self.load_interface(directory_library, &interface)
+        let mut recovered = Vec::new();
+        let interface = parse::InterfaceParser::new().parse(&mut recovered, tokens);
+        let (interface, errors) = parse::finish(recovered, interface)?;
+
+        if !errors.is_empty() {
+            return Err(error::Error::Syntactic(errors));
+        }
+
+        self.load_interface(directory_library, &interface)?;
+
+        let exports = interface
+            .uses
+            .iter()
+            .filter_map(|r#use| self.exports.get(&r#use.name.symbol))
+            .flatten()
+            .copied()
+            .chain(interface.items.iter().map(|item| match item {
+                ast::ItemSignature::Class(class) => class.name.symbol,
+                ast::ItemSignature::ClassTemplate(class) => class.name.symbol,
+                ast::ItemSignature::Protocol(protocol) => protocol.name.symbol,
+                ast::ItemSignature::Function(function) => function.name.symbol,
+                ast::ItemSignature::FunctionTemplate(function) => function.name.symbol,
+            }))
+            .collect();
+
+        self.exports.insert(r#use.name.symbol, exports);
+        Ok(())
     }
 
     fn load_interface(
@@ -97,6 +156,7 @@ impl Checker {
             match item {
                 ast::ItemSignature::Class(class) => self.load_class_signature(class)?,
                 ast::ItemSignature::ClassTemplate(class) => self.load_class_template(class)?,
+                ast::ItemSignature::Protocol(protocol) => self.load_protocol(protocol)?,
                 ast::ItemSignature::Function(function) => {
                     self.load_function_signature(GlobalScope::Global, function)?;
                 }
@@ -109,6 +169,9 @@ impl Checker {
         for item in &interface.items {
             match item {
                 ast::ItemSignature::Class(class) => self.check_class_signature(class)?,
+                ast::ItemSignature::Protocol(protocol) => {
+                    self.check_protocol(protocol.clone())?;
+                }
                 ast::ItemSignature::Function(function) => {
                     self.check_function_like(function)?;
                 }
@@ -123,6 +186,10 @@ impl Checker {
     }
 
     fn load_class_template(&mut self, class: &ast::ClassTemplate) -> Result<(), error::Error> {
+        if let Some(template) = self.context.get_enum_template(&class.name) {
+            bail!(class.span, ErrorKind::NameClash(template.span))
+        }
+
         if let Some((span, _)) = self.context.insert_class_template(class.clone()) {
             bail!(class.span, ErrorKind::NameClash(span))
         }
@@ -130,6 +197,18 @@ impl Checker {
         Ok(())
     }
 
+    fn load_enum_template(&mut self, r#enum: &ast::EnumTemplate) -> Result<(), error::Error> {
+        if let Some(template) = self.context.get_class_template(&r#enum.name) {
+            bail!(r#enum.span, ErrorKind::NameClash(template.span))
+        }
+
+        if let Some((span, _)) = self.context.insert_enum_template(r#enum.clone()) {
+            bail!(r#enum.span, ErrorKind::NameClash(span))
+        }
+
+        Ok(())
+    }
+
     fn load_function_template(
         &mut self,
         function: &ast::FunctionTemplate,
@@ -179,6 +258,73 @@ impl Checker {
         Ok(())
     }
 
+    fn load_protocol(&mut self, protocol: &ast::Protocol<()>) -> Result<(), error::Error> {
+        check_builtin(&protocol.name)?;
+
+        if let Some(span) = self
+            .context
+            .get_class_signature(&protocol.name)
+            .or_else(|| self.context.get_class_implementation(&protocol.name))
+            .or_else(|| self.context.get_enum(&protocol.name))
+        {
+            bail!(*protocol.name.span, ErrorKind::NameClash(*span));
+        }
+
+        let expected = self.context.insert_protocol(protocol.name.clone());
+
+        for method in &protocol.methods {
+            self.load_function_signature(GlobalScope::Class(protocol.name.symbol), method)?;
+        }
+
+        let (expected_span, expected) = match expected {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+
+        let (actual_span, actual) = self.context.get_class_full(&protocol.name).unwrap();
+
+        // Interfaces can be redeclared, but only with exactly the same methods
+        // in the same order, which determines the layout of interface tables.
+        if !expected
+            .iter()
+            .map(|(identifier, entry)| (identifier.symbol, entry))
+            .eq(actual
+                .iter()
+                .map(|(identifier, entry)| (identifier.symbol, entry)))
+        {
+            bail!(*actual_span, ErrorKind::NameClash(expected_span));
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn load_enum(&mut self, r#enum: &ast::Enum<()>) -> Result<(), error::Error> {
+        check_builtin(&r#enum.name)?;
+
+        if let Some((span, _)) = self.context.get_class_full(&r#enum.name) {
+            bail!(*r#enum.name.span, ErrorKind::NameClash(*span));
+        }
+
+        self.context.insert_enum(r#enum.name.clone());
+
+        for variant in &r#enum.variants {
+            let payload = variant
+                .payload
+                .iter()
+                .map(|r#type| self.load_type(r#type))
+                .collect();
+
+            if let Some((span, _)) =
+                self.context
+                    .insert_variant(r#enum.name.symbol, variant.name.clone(), payload)
+            {
+                bail!(*variant.name.span, ErrorKind::NameClash(span));
+            }
+        }
+
+        Ok(())
+    }
+
     fn load_function_signature(
         &mut self,
         scope: GlobalScope,
@@ -223,6 +369,16 @@ impl Checker {
     }
 
     fn load_class_like<C: ast::ClassLike<()>>(&mut self, class: &C) -> Result<(), error::Error> {
+        check_builtin(class.name())?;
+
+        if let Some(span) = self
+            .context
+            .get_protocol(class.name())
+            .or_else(|| self.context.get_enum(class.name()))
+        {
+            bail!(*class.name().span, ErrorKind::NameClash(*span));
+        }
+
         if class.r#final() {
             match self.context.insert_final(class.name().clone()) {
                 Some(_) => (),
@@ -262,6 +418,13 @@ impl Checker {
             }
         }
 
+        // Note: whether each of these is actually an interface is only checked
+        // later, since interfaces can be declared after the classes implementing them.
+        for protocol in class.implements() {
+            let symbol = self.load_variable(protocol);
+            self.context.insert_implements(class.name().symbol, symbol);
+        }
+
         Ok(())
     }
 
@@ -345,11 +508,24 @@ impl Checker {
         match r#type {
             ast::Type::Bool(_) => r#type::Expression::Boolean,
             ast::Type::Int(_) => r#type::Expression::Integer,
-            ast::Type::Class(variable) => r#type::Expression::Class(self.load_variable(variable)),
+            &ast::Type::Sized { signed, size, .. } => {
+                r#type::Expression::from_integer(signed, size)
+            }
+            ast::Type::Class(variable) => r#type::Expression::class(self.load_variable(variable)),
             ast::Type::Array(r#type, length, _) => {
                 assert!(length.is_none());
                 r#type::Expression::Array(Box::new(self.load_type(r#type)))
             }
+            ast::Type::Function(parameters, returns, _) => r#type::Expression::Function(
+                parameters
+                    .iter()
+                    .map(|parameter| self.load_type(parameter))
+                    .collect(),
+                returns
+                    .iter()
+                    .map(|r#return| self.load_type(r#return))
+                    .collect(),
+            ),
         }
     }
 
@@ -371,3 +547,13 @@ impl Checker {
         }
     }
 }
+
+/// Fails if `name` would redeclare one of the built-in exception classes.
+fn check_builtin(name: &ast::Identifier) -> Result<(), error::Error> {
+    match symbol::resolve(name.symbol) {
+        abi::EXCEPTION | abi::OUT_OF_BOUNDS => {
+            bail!(*name.span, ErrorKind::BuiltinClass(name.symbol))
+        }
+        _ => Ok(()),
+    }
+}
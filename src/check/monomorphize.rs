@@ -14,52 +14,64 @@ impl Checker {
         &mut self,
         program: &mut ast::Program<()>,
     ) -> Result<(), Error> {
-        let mut monomorphizer = Monomorphizer {
-            functions: Map::default(),
-            classes: Map::default(),
-            arguments: Vec::new(),
-            checker: self,
-            error: None,
-        };
-
+        let mut monomorphizer = Monomorphizer::new(self, Vec::new());
         program.accept_mut(&mut monomorphizer);
+        let instantiations = monomorphizer.finish()?;
 
         program.items.retain(|item| {
             !matches!(
                 item,
-                ast::Item::ClassTemplate(_) | ast::Item::FunctionTemplate(_)
+                ast::Item::ClassTemplate(_)
+                    | ast::Item::EnumTemplate(_)
+                    | ast::Item::FunctionTemplate(_)
             )
         });
 
-        program.items.extend(
-            monomorphizer
-                .classes
-                .into_values()
-                .flat_map(Map::into_values)
-                .flatten()
-                .map(ast::Item::Class),
-        );
-
-        program.items.extend(
-            monomorphizer
-                .functions
-                .into_values()
-                .flat_map(Map::into_values)
-                .flatten()
-                .map(ast::Item::Function),
-        );
+        program.items.extend(instantiations);
+        Ok(())
+    }
 
-        match monomorphizer.error {
-            None => Ok(()),
-            Some(error) => Err(error),
+    /// Instantiates `template` for checking its body up front, using the bound on
+    /// each parameter as its type argument. Since every type argument must be a
+    /// subtype of its bound, errors are reported in the template itself, rather
+    /// than at each instantiation.
+    ///
+    /// Unbounded parameters are instantiated with the placeholder class `POISON`,
+    /// as are parameters whose bounds have type arguments, since those may refer
+    /// to the template's own parameters. Returns `None` if no parameter has a
+    /// bound to check against, in which case the template is duck-typed.
+    pub(super) fn instantiate_bounds(
+        &mut self,
+        template: &ast::Item<()>,
+    ) -> Option<Result<Vec<ast::Item<()>>, Error>> {
+        let mut monomorphizer = Monomorphizer::new(self, Vec::new());
+        if !monomorphizer.instantiate_bounds(template) {
+            return None;
         }
+        Some(monomorphizer.finish())
+    }
+
+    /// Instantiates the function template called through `variable`, whose type
+    /// arguments were inferred while checking, and rewrites it to refer to the
+    /// instantiation. Returns the new instantiations, which still need to be checked.
+    pub(super) fn monomorphize_call(
+        &mut self,
+        variable: &mut ast::Variable<()>,
+    ) -> Result<Vec<ast::Item<()>>, Error> {
+        let provenance = self.provenance.clone();
+        let mut monomorphizer = Monomorphizer::new(self, provenance);
+        monomorphizer.monomorphize(Monomorphizer::instantiate_function_template, variable);
+        monomorphizer.finish()
     }
 }
 
 struct Monomorphizer<'a> {
     functions: Map<ast::Identifier, Map<Vec<ast::Type<()>>, Option<ast::Function<()>>>>,
     classes: Map<ast::Identifier, Map<Vec<ast::Type<()>>, Option<ast::Class<()>>>>,
+    enums: Map<ast::Identifier, Map<Vec<ast::Type<()>>, Option<ast::Enum<()>>>>,
     arguments: Vec<(Span, Map<ast::Identifier, ast::Type<()>>)>,
+    /// Instantiation chain leading to this monomorphization, if it runs while checking
+    provenance: Vec<Span>,
     checker: &'a mut Checker,
     error: Option<Error>,
 }
@@ -111,15 +123,180 @@ impl<'a> ast::VisitorMut<()> for Monomorphizer<'a> {
             return;
         }
 
-        if let ast::Expression::New(variable, _) = expression {
-            self.monomorphize_class(variable);
+        match expression {
+            ast::Expression::New(variable, _) => self.monomorphize_class(variable),
+            // Enum template variants are constructed through the instantiated enum's name
+            ast::Expression::Dot(receiver, _, _, _) => match &mut **receiver {
+                ast::Expression::Variable(variable, ())
+                    if self
+                        .checker
+                        .context
+                        .get_enum_template(&variable.name)
+                        .is_some() =>
+                {
+                    self.monomorphize_class(variable)
+                }
+                _ => (),
+            },
+            _ => (),
         }
     }
 }
 
 impl<'a> Monomorphizer<'a> {
+    fn new(checker: &'a mut Checker, provenance: Vec<Span>) -> Self {
+        Monomorphizer {
+            functions: Map::default(),
+            classes: Map::default(),
+            enums: Map::default(),
+            arguments: Vec::new(),
+            provenance,
+            checker,
+            error: None,
+        }
+    }
+
+    /// Returns every instantiation, or the first error encountered.
+    fn finish(self) -> Result<Vec<ast::Item<()>>, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let enums = self
+            .enums
+            .into_values()
+            .flat_map(Map::into_values)
+            .flatten()
+            .map(ast::Item::Enum);
+
+        let classes = self
+            .classes
+            .into_values()
+            .flat_map(Map::into_values)
+            .flatten()
+            .map(ast::Item::Class);
+
+        let functions = self
+            .functions
+            .into_values()
+            .flat_map(Map::into_values)
+            .flatten()
+            .map(ast::Item::Function);
+
+        Ok(enums.chain(classes).chain(functions).collect())
+    }
+
+    /// Spans of each template instantiation leading to the current one.
+    fn provenance(&self) -> Vec<Span> {
+        self.provenance
+            .iter()
+            .chain(self.arguments.iter().map(|(span, _)| span))
+            .copied()
+            .collect()
+    }
+
+    /// Instantiates `template` with its bounds, returning false if it has none.
+    fn instantiate_bounds(&mut self, template: &ast::Item<()>) -> bool {
+        #[allow(clippy::type_complexity)]
+        let (instantiate, name, generics): (
+            fn(&mut Self, &ast::Identifier, &[ast::Type<()>], &Span),
+            &ast::Identifier,
+            &[ast::Generic],
+        ) = match template {
+            ast::Item::ClassTemplate(class) => (
+                Self::instantiate_class_template,
+                &class.name,
+                &class.generics,
+            ),
+            ast::Item::EnumTemplate(r#enum) => (
+                Self::instantiate_enum_template,
+                &r#enum.name,
+                &r#enum.generics,
+            ),
+            ast::Item::FunctionTemplate(function) => (
+                Self::instantiate_function_template,
+                &function.name,
+                &function.generics,
+            ),
+            _ => return false,
+        };
+
+        let bounds = generics
+            .iter()
+            .map(|generic| match &generic.bound {
+                Some(bound) if bound.generics.is_none() => Some(ast::Type::Class(bound.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if bounds.iter().all(Option::is_none) {
+            return false;
+        }
+
+        let arguments = generics
+            .iter()
+            .zip(bounds)
+            .map(|(generic, bound)| {
+                bound.unwrap_or_else(|| ast::Type::poisoned(*generic.name.span))
+            })
+            .collect::<Vec<_>>();
+
+        instantiate(self, name, &arguments, &name.span);
+        true
+    }
+
+    /// Checks that each type argument in `generics` is a subtype of the bound
+    /// on its template parameter, if any.
+    fn check_bounds(
+        &mut self,
+        parameters: &[ast::Generic],
+        generics: &[ast::Type<()>],
+        span: &Span,
+    ) -> Result<(), Error> {
+        let arguments = parameters
+            .iter()
+            .map(|parameter| parameter.name.clone())
+            .zip(generics.iter().cloned())
+            .collect::<Map<_, _>>();
+
+        for (parameter, generic) in parameters.iter().zip(generics) {
+            let mut bound = match &parameter.bound {
+                Some(bound) => ast::Type::Class(bound.clone()),
+                None => continue,
+            };
+
+            // Bounds can refer to template parameters, e.g. `T: Ordered::<T>`
+            self.arguments.push((*span, arguments.clone()));
+            bound.accept_mut(self);
+            self.arguments.pop();
+
+            if let Some(error) = self.error.take() {
+                return Err(error);
+            }
+
+            let bound = self.checker.check_type(bound)?.r#type();
+            let r#type = self.checker.check_type(generic.clone())?.r#type();
+
+            if !self.checker.context.is_subtype(&r#type, &bound) {
+                return Err(Error::new(
+                    generic.span(),
+                    ErrorKind::UnsatisfiedBound {
+                        r#type,
+                        bound,
+                        span: parameter.bound.as_ref().unwrap().span,
+                    },
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     fn monomorphize_class(&mut self, variable: &mut ast::Variable<()>) {
-        self.monomorphize(Self::instantiate_class_template, variable);
+        match self.checker.context.get_enum_template(&variable.name) {
+            Some(_) => self.monomorphize(Self::instantiate_enum_template, variable),
+            None => self.monomorphize(Self::instantiate_class_template, variable),
+        }
     }
 
     fn monomorphize(
@@ -151,10 +328,13 @@ impl<'a> Monomorphizer<'a> {
         generics: &[ast::Type<()>],
         span: &Span,
     ) {
-        // Already instantiated, so just rewrite
+        // Already instantiated, so just rewrite. Instantiations from earlier
+        // monomorphizations have already been loaded into the context.
+        let symbol = abi::mangle::template(&name.symbol, generics);
         if self.classes.get(name).map_or(false, |instantiations| {
             instantiations.contains_key(generics)
-        }) {
+        }) || self.checker.context.get_class(&symbol).is_some()
+        {
             return;
         }
 
@@ -180,6 +360,11 @@ impl<'a> Monomorphizer<'a> {
             }
         };
 
+        if let Err(error) = self.check_bounds(&template.generics, generics, span) {
+            self.error = Some(error);
+            return;
+        }
+
         self.classes
             .entry(template.name.clone())
             .or_default()
@@ -189,8 +374,8 @@ impl<'a> Monomorphizer<'a> {
             *span,
             template
                 .generics
-                .clone()
-                .into_iter()
+                .iter()
+                .map(|generic| generic.name.clone())
                 .zip(generics.iter().cloned())
                 .collect(),
         ));
@@ -198,17 +383,13 @@ impl<'a> Monomorphizer<'a> {
         let mut instantiation = ast::Class {
             r#final: template.r#final,
             name: ast::Identifier {
-                symbol: abi::mangle::template(&template.name.symbol, generics),
+                symbol,
                 span: template.name.span.clone(),
             },
             extends: template.extends,
+            implements: template.implements,
             items: template.items,
-            provenance: self
-                .arguments
-                .iter()
-                .map(|(span, _)| span)
-                .copied()
-                .collect(),
+            provenance: self.provenance(),
             declared: Cell::new(false),
             span: template.span,
         };
@@ -217,19 +398,103 @@ impl<'a> Monomorphizer<'a> {
         self.arguments.pop();
 
         self.checker.load_class(&instantiation).unwrap();
+        self.checker.instantiations.insert(
+            instantiation.name.symbol,
+            (template.name.symbol, generics.to_vec()),
+        );
         self.classes[&template.name][&*generics] = Some(instantiation);
     }
 
+    fn instantiate_enum_template(
+        &mut self,
+        name: &ast::Identifier,
+        generics: &[ast::Type<()>],
+        span: &Span,
+    ) {
+        // Already instantiated, so just rewrite. Instantiations from earlier
+        // monomorphizations have already been loaded into the context.
+        let symbol = abi::mangle::template(&name.symbol, generics);
+        if self.enums.get(name).map_or(false, |instantiations| {
+            instantiations.contains_key(generics)
+        }) || self.checker.context.get_enum(&symbol).is_some()
+        {
+            return;
+        }
+
+        let template = self.checker.context.get_enum_template(name).unwrap();
+
+        if template.generics.len() != generics.len() {
+            self.error = Some(Error::new(
+                *span,
+                ErrorKind::TemplateArgumentMismatch {
+                    span: *template.name.span,
+                    expected: template.generics.len(),
+                    found: generics.len(),
+                },
+            ));
+            return;
+        }
+
+        let template = template.clone();
+
+        if let Err(error) = self.check_bounds(&template.generics, generics, span) {
+            self.error = Some(error);
+            return;
+        }
+
+        self.enums
+            .entry(template.name.clone())
+            .or_default()
+            .insert(generics.to_vec(), None);
+
+        self.arguments.push((
+            *span,
+            template
+                .generics
+                .iter()
+                .map(|generic| generic.name.clone())
+                .zip(generics.iter().cloned())
+                .collect(),
+        ));
+
+        let mut instantiation = ast::Enum {
+            name: ast::Identifier {
+                symbol,
+                span: template.name.span.clone(),
+            },
+            variants: template.variants,
+            provenance: self.provenance(),
+            span: template.span,
+        };
+
+        instantiation.accept_mut(self);
+        self.arguments.pop();
+
+        self.checker.load_enum(&instantiation).unwrap();
+        self.checker.instantiations.insert(
+            instantiation.name.symbol,
+            (template.name.symbol, generics.to_vec()),
+        );
+        self.enums[&template.name][&*generics] = Some(instantiation);
+    }
+
     fn instantiate_function_template(
         &mut self,
         name: &ast::Identifier,
         generics: &[ast::Type<()>],
         span: &Span,
     ) {
-        // Already instantiated, so just rewrite
+        // Already instantiated, so just rewrite. Instantiations from earlier
+        // monomorphizations have already been loaded into the context.
+        let symbol = abi::mangle::template(&name.symbol, generics);
         if self.functions.get(name).map_or(false, |instantiations| {
             instantiations.contains_key(generics)
-        }) {
+        }) || self
+            .checker
+            .context
+            .get(GlobalScope::Global, &symbol)
+            .is_some()
+        {
             return;
         }
 
@@ -255,6 +520,11 @@ impl<'a> Monomorphizer<'a> {
             }
         };
 
+        if let Err(error) = self.check_bounds(&template.generics, generics, span) {
+            self.error = Some(error);
+            return;
+        }
+
         self.functions
             .entry(template.name.clone())
             .or_default()
@@ -264,26 +534,21 @@ impl<'a> Monomorphizer<'a> {
             *span,
             template
                 .generics
-                .clone()
-                .into_iter()
+                .iter()
+                .map(|generic| generic.name.clone())
                 .zip(generics.iter().cloned())
                 .collect(),
         ));
 
         let mut instantiation = ast::Function {
             name: ast::Identifier {
-                symbol: abi::mangle::template(&template.name.symbol, generics),
+                symbol,
                 span: template.name.span.clone(),
             },
             parameters: template.parameters,
             returns: template.returns,
             statements: template.statements,
-            provenance: self
-                .arguments
-                .iter()
-                .map(|(span, _)| span)
-                .copied()
-                .collect(),
+            provenance: self.provenance(),
             declared: Cell::new(false),
             span: template.span,
         };
@@ -0,0 +1,224 @@
+use std::borrow::Cow;
+use std::str;
+
+use crate::data::span::Span;
+use crate::data::symbol::Symbol;
+use crate::error;
+use crate::Map;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    span: Span,
+    kind: WarningKind,
+}
+
+impl Warning {
+    pub fn new(span: Span, kind: WarningKind) -> Self {
+        Warning { span, kind }
+    }
+
+    pub(super) fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn lint(&self) -> Lint {
+        self.kind.lint()
+    }
+
+    /// Labeled spans of this warning, starting with the primary span.
+    fn labels(&self) -> Vec<(Span, Cow<'static, str>)> {
+        let mut labels = vec![(self.span, self.kind.message())];
+
+        if let WarningKind::ShadowedField(_, span) = &self.kind {
+            labels.push((*span, Cow::Borrowed("Field defined here")));
+        }
+
+        labels
+    }
+
+    /// Builds a structured diagnostic for this warning, as an error if `level` is `Level::Deny`.
+    pub fn diagnostic(&self, level: Level) -> error::Diagnostic {
+        error::Diagnostic {
+            severity: match level {
+                Level::Deny => error::Severity::Error,
+                Level::Allow | Level::Warn => error::Severity::Warning,
+            },
+            phase: "semantic",
+            code: self.lint().to_static_str(),
+            labels: self.labels(),
+        }
+    }
+
+    /// Builds a report for this warning, rendered as an error if `level` is `Level::Deny`.
+    pub fn report(&self, level: Level) -> ariadne::ReportBuilder<Span> {
+        use ariadne::Span as _;
+
+        let (kind, message) = match level {
+            Level::Deny => (ariadne::ReportKind::Error, "Semantic error"),
+            Level::Allow | Level::Warn => (ariadne::ReportKind::Warning, "Semantic warning"),
+        };
+
+        let mut report = ariadne::Report::build(kind, *self.span.source(), self.span.lo.index())
+            .with_message(format!("{} [{}]", message, self.lint()));
+
+        report.add_labels(
+            self.labels()
+                .into_iter()
+                .map(|(span, message)| ariadne::Label::new(span).with_message(message)),
+        );
+
+        report
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{} warning:{}", self.span, self.kind.message())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WarningKind {
+    UnusedVariable(Symbol),
+    UnusedParameter(Symbol),
+    UnusedUse(Symbol),
+    UnusedFunction(Symbol),
+    UnusedResult(usize),
+    DeadStore(Symbol),
+    ShadowedField(Symbol, Span),
+}
+
+impl WarningKind {
+    fn lint(&self) -> Lint {
+        match self {
+            WarningKind::UnusedVariable(_) => Lint::UnusedVariable,
+            WarningKind::UnusedParameter(_) => Lint::UnusedParameter,
+            WarningKind::UnusedUse(_) => Lint::UnusedUse,
+            WarningKind::UnusedFunction(_) => Lint::UnusedFunction,
+            WarningKind::UnusedResult(_) => Lint::UnusedResult,
+            WarningKind::DeadStore(_) => Lint::DeadStore,
+            WarningKind::ShadowedField(_, _) => Lint::ShadowedField,
+        }
+    }
+
+    fn message(&self) -> Cow<'static, str> {
+        match self {
+            WarningKind::UnusedVariable(variable) => {
+                Cow::Owned(format!("Unused variable {}", variable))
+            }
+            WarningKind::UnusedParameter(parameter) => {
+                Cow::Owned(format!("Unused parameter {}", parameter))
+            }
+            WarningKind::UnusedUse(interface) => {
+                Cow::Owned(format!("Unused interface {}", interface))
+            }
+            WarningKind::UnusedFunction(function) => {
+                Cow::Owned(format!("Function {} is never called", function))
+            }
+            WarningKind::UnusedResult(count) => {
+                Cow::Owned(format!("All {} results of this call are discarded", count))
+            }
+            WarningKind::DeadStore(variable) => {
+                Cow::Owned(format!("Value assigned to {} is never read", variable))
+            }
+            WarningKind::ShadowedField(field, _) => {
+                Cow::Owned(format!("Declaration shadows field {}", field))
+            }
+        }
+    }
+}
+
+/// Represents a category of warning that can be configured independently.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Lint {
+    UnusedVariable,
+    UnusedParameter,
+    UnusedUse,
+    UnusedFunction,
+    UnusedResult,
+    DeadStore,
+    ShadowedField,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 7] = [
+        Lint::UnusedVariable,
+        Lint::UnusedParameter,
+        Lint::UnusedUse,
+        Lint::UnusedFunction,
+        Lint::UnusedResult,
+        Lint::DeadStore,
+        Lint::ShadowedField,
+    ];
+
+    pub const fn to_static_str(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedParameter => "unused-parameter",
+            Lint::UnusedUse => "unused-use",
+            Lint::UnusedFunction => "unused-function",
+            Lint::UnusedResult => "unused-result",
+            Lint::DeadStore => "dead-store",
+            Lint::ShadowedField => "shadowed-field",
+        }
+    }
+
+    /// Level used when a lint isn't configured on the command line.
+    ///
+    /// Parameters are often unused on purpose, e.g. to match an overridden
+    /// method's signature, so that lint must be enabled explicitly.
+    pub const fn default_level(self) -> Level {
+        match self {
+            Lint::UnusedParameter => Level::Allow,
+            Lint::UnusedVariable
+            | Lint::UnusedUse
+            | Lint::UnusedFunction
+            | Lint::UnusedResult
+            | Lint::DeadStore
+            | Lint::ShadowedField => Level::Warn,
+        }
+    }
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}", self.to_static_str())
+    }
+}
+
+impl str::FromStr for Lint {
+    type Err = anyhow::Error;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .into_iter()
+            .find(|lint| lint.to_static_str() == string)
+            .ok_or_else(|| anyhow::anyhow!("Unknown lint {}", string))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Level {
+    /// Silence the lint
+    Allow,
+    /// Report the lint as a warning
+    Warn,
+    /// Report the lint as an error
+    Deny,
+}
+
+/// Configured level for each lint.
+#[derive(Clone, Debug, Default)]
+pub struct Lints(Map<Lint, Level>);
+
+impl Lints {
+    pub fn set(&mut self, lint: Lint, level: Level) {
+        self.0.insert(lint, level);
+    }
+
+    pub fn level(&self, lint: Lint) -> Level {
+        self.0
+            .get(&lint)
+            .copied()
+            .unwrap_or_else(|| lint.default_level())
+    }
+}
@@ -1,11 +1,13 @@
 pub mod asm;
 pub mod ast;
+pub mod debug;
 pub mod hir;
 pub mod ir;
 pub mod lir;
 pub mod operand;
 pub mod sexp;
 pub mod span;
+pub mod ssa;
 pub mod symbol;
 pub mod token;
 pub mod r#type;
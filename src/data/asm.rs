@@ -1,23 +1,38 @@
 use std::fmt;
 
+use crate::data::debug::Debug;
 use crate::data::ir;
 use crate::data::operand;
 use crate::data::operand::Immediate;
 use crate::data::operand::Label;
+use crate::data::operand::Register;
+use crate::data::span::Span;
 use crate::data::symbol::Symbol;
+use crate::data::token::Size;
 
 pub type Unit<T> = ir::Unit<Function<T>>;
 
-impl<T: fmt::Display> fmt::Display for Unit<T> {
+impl<T: operand::Operand> fmt::Display for Unit<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         write!(fmt, "{}", crate::assemble::Intel(self))
     }
 }
 
-impl<T: fmt::Display> Unit<T> {
+impl<T: operand::Operand> Unit<T> {
     pub fn intel(&self) -> impl fmt::Display + '_ {
         crate::assemble::Intel(self)
     }
+
+    pub fn att(&self) -> impl fmt::Display + '_ {
+        crate::assemble::Att(self)
+    }
+}
+
+impl Unit<Register> {
+    /// Encodes this unit as a relocatable ELF64 object file.
+    pub fn elf(&self) -> Vec<u8> {
+        crate::assemble::elf(self)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -73,6 +88,7 @@ pub enum Statement<T> {
     Label(Label),
     Jmp(Label),
     Jcc(Condition, Label),
+    Debug(Debug<operand::Unary<T>>),
 }
 
 impl<T: fmt::Display> Statement<T> {
@@ -90,6 +106,7 @@ impl<T: fmt::Display> fmt::Display for Statement<T> {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Directive {
     Intel,
+    Att,
     Align(usize),
     Linkage(ir::Linkage, Label),
     Quad(Vec<Immediate>),
@@ -98,6 +115,8 @@ pub enum Directive {
     Bss,
     Ctors(u16),
     Text,
+    File(usize, Symbol),
+    Location(usize, Span),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -112,6 +131,15 @@ pub enum Binary {
     Mov,
     Lea,
     Shl,
+
+    /// Sign- or zero-extending load from a narrow memory operand
+    Load {
+        signed: bool,
+        size: Size,
+    },
+
+    /// Truncating store into a narrow memory operand
+    Store(Size),
 }
 
 impl From<ir::Binary> for Binary {
@@ -137,6 +165,9 @@ pub enum Unary {
     Hul,
     Div,
     Mod,
+    UHul,
+    UDiv,
+    UMod,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -155,6 +186,9 @@ pub enum Condition {
     Ne,
     E,
     Ae,
+    B,
+    Be,
+    A,
 }
 
 impl From<ir::Condition> for Condition {
@@ -167,6 +201,9 @@ impl From<ir::Condition> for Condition {
             ir::Condition::Ne => Condition::Ne,
             ir::Condition::Eq => Condition::E,
             ir::Condition::Ae => Condition::Ae,
+            ir::Condition::B => Condition::B,
+            ir::Condition::Be => Condition::Be,
+            ir::Condition::A => Condition::A,
         }
     }
 }
@@ -187,6 +224,9 @@ macro_rules! asm {
     ((jne $label:expr)) => { $crate::data::asm::Statement::Jcc($crate::data::asm::Condition::Ne, $label) };
     ((je $label:expr)) => { $crate::data::asm::Statement::Jcc($crate::data::asm::Condition::E, $label) };
     ((jae $label:expr)) => { $crate::data::asm::Statement::Jcc($crate::data::asm::Condition::Ae, $label) };
+    ((jb $label:expr)) => { $crate::data::asm::Statement::Jcc($crate::data::asm::Condition::B, $label) };
+    ((jbe $label:expr)) => { $crate::data::asm::Statement::Jcc($crate::data::asm::Condition::Be, $label) };
+    ((ja $label:expr)) => { $crate::data::asm::Statement::Jcc($crate::data::asm::Condition::A, $label) };
     ((jcc $condition:expr, $label:expr)) => { $crate::data::asm::Statement::Jcc($condition, $label) };
 
     ((nop)) => {
@@ -234,6 +274,9 @@ macro_rules! asm {
     (@unary ihul) => { $crate::data::asm::Unary::Hul };
     (@unary idiv) => { $crate::data::asm::Unary::Div };
     (@unary imod) => { $crate::data::asm::Unary::Mod };
+    (@unary mul) => { $crate::data::asm::Unary::UHul };
+    (@unary div) => { $crate::data::asm::Unary::UDiv };
+    (@unary mod) => { $crate::data::asm::Unary::UMod };
     (@unary push) => { $crate::data::asm::Unary::Push };
     (@unary pop) => { $crate::data::asm::Unary::Pop };
 
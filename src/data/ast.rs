@@ -8,9 +8,11 @@ use std::hash;
 use std::iter;
 
 use crate::data::r#type;
-use crate::data::sexp::Serialize as _;
+use crate::data::sexp::Serialize;
 use crate::data::span::Span;
+use crate::data::symbol;
 use crate::data::symbol::Symbol;
+use crate::data::token::Size;
 use crate::util::Or;
 
 /// Represents a Xi interface file.
@@ -52,12 +54,13 @@ impl fmt::Display for Use {
     }
 }
 
-const _: [(); 176] = [(); std::mem::size_of::<ItemSignature<()>>()];
+const _: [(); 184] = [(); std::mem::size_of::<ItemSignature<()>>()];
 
 #[derive(Clone, Debug)]
 pub enum ItemSignature<T> {
     Class(ClassSignature<T>),
     ClassTemplate(ClassTemplate),
+    Protocol(Protocol<T>),
     Function(FunctionSignature<T>),
     FunctionTemplate(FunctionTemplate),
 }
@@ -68,13 +71,16 @@ impl<T> fmt::Display for ItemSignature<T> {
     }
 }
 
-const _: [(); 184] = [(); std::mem::size_of::<Item<()>>()];
+const _: [(); 192] = [(); std::mem::size_of::<Item<()>>()];
 
 #[derive(Clone, Debug)]
 pub enum Item<T> {
     Global(Global<T>),
     Class(Class<T>),
     ClassTemplate(ClassTemplate),
+    Protocol(Protocol<T>),
+    Enum(Enum<T>),
+    EnumTemplate(EnumTemplate),
     Function(Function<T>),
     FunctionTemplate(FunctionTemplate),
 }
@@ -114,12 +120,13 @@ impl<T> fmt::Display for Initialization<T> {
     }
 }
 
-const _: [(); 160] = [(); std::mem::size_of::<ClassTemplate>()];
+const _: [(); 184] = [(); std::mem::size_of::<ClassTemplate>()];
 
 pub trait ClassLike<T> {
     fn r#final(&self) -> bool;
     fn name(&self) -> &Identifier;
     fn extends(&self) -> Option<&Variable<T>>;
+    fn implements(&self) -> &[Variable<T>];
 }
 
 macro_rules! impl_class_like {
@@ -136,6 +143,10 @@ macro_rules! impl_class_like {
             fn extends(&self) -> Option<&Variable<T>> {
                 self.extends.as_ref()
             }
+
+            fn implements(&self) -> &[Variable<T>] {
+                &self.implements
+            }
         }
     };
 }
@@ -144,8 +155,9 @@ macro_rules! impl_class_like {
 pub struct ClassTemplate {
     pub r#final: bool,
     pub name: Identifier,
-    pub generics: Vec<Identifier>,
+    pub generics: Vec<Generic>,
     pub extends: Option<Variable<()>>,
+    pub implements: Vec<Variable<()>>,
     pub items: Vec<ClassItem<()>>,
     pub span: Span,
 }
@@ -155,8 +167,9 @@ impl ClassTemplate {
     pub fn new(
         r#final: bool,
         name: Identifier,
-        generics: Vec<Identifier>,
+        generics: Vec<Generic>,
         extends: Option<Variable<()>>,
+        implements: Vec<Variable<()>>,
         items: Vec<ClassItem<()>>,
         span: Span,
     ) -> Self {
@@ -165,6 +178,7 @@ impl ClassTemplate {
             name,
             generics,
             extends,
+            implements,
             items,
             span,
         }
@@ -182,6 +196,7 @@ pub struct ClassSignature<T> {
     pub r#final: bool,
     pub name: Identifier,
     pub extends: Option<Variable<T>>,
+    pub implements: Vec<Variable<T>>,
     pub methods: Vec<FunctionSignature<T>>,
     pub span: Span,
 }
@@ -194,6 +209,7 @@ impl ClassSignature<()> {
         r#final: bool,
         name: Identifier,
         extends: Option<Variable<()>>,
+        implements: Vec<Variable<()>>,
         methods: Vec<FunctionSignature<()>>,
         span: Span,
     ) -> Self {
@@ -201,6 +217,7 @@ impl ClassSignature<()> {
             r#final,
             name,
             extends,
+            implements,
             methods,
             span,
         }
@@ -213,13 +230,14 @@ impl<T> fmt::Display for ClassSignature<T> {
     }
 }
 
-const _: [(); 160] = [(); std::mem::size_of::<Class<()>>()];
+const _: [(); 184] = [(); std::mem::size_of::<Class<()>>()];
 
 #[derive(Clone, Debug)]
 pub struct Class<T> {
     pub r#final: bool,
     pub name: Identifier,
     pub extends: Option<Variable<T>>,
+    pub implements: Vec<Variable<T>>,
     pub items: Vec<ClassItem<T>>,
     // Used for tracking class template instantiation chains in diagnostics
     pub(crate) provenance: Vec<Span>,
@@ -236,6 +254,7 @@ impl Class<()> {
         r#final: bool,
         name: Identifier,
         extends: Option<Variable<()>>,
+        implements: Vec<Variable<()>>,
         items: Vec<ClassItem<()>>,
         provenance: Vec<Span>,
         span: Span,
@@ -244,6 +263,7 @@ impl Class<()> {
             r#final,
             name,
             extends,
+            implements,
             items,
             provenance,
             declared: Cell::new(false),
@@ -258,6 +278,67 @@ impl<T> fmt::Display for Class<T> {
     }
 }
 
+/// Represents an `interface` declaration, which lists methods that implementing
+/// classes must provide.
+///
+/// Named to avoid confusion with [`Interface`], which represents a Xi interface file.
+#[derive(Clone, Debug)]
+pub struct Protocol<T> {
+    pub name: Identifier,
+    pub methods: Vec<FunctionSignature<T>>,
+    pub span: Span,
+}
+
+impl<T> fmt::Display for Protocol<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+/// Represents an `enum` declaration, whose values are tagged with one of its variants.
+#[derive(Clone, Debug)]
+pub struct Enum<T> {
+    pub name: Identifier,
+    pub variants: Vec<Variant<T>>,
+    // Used for tracking enum template instantiation chains in diagnostics
+    pub(crate) provenance: Vec<Span>,
+    pub span: Span,
+}
+
+impl<T> fmt::Display for Enum<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EnumTemplate {
+    pub name: Identifier,
+    pub generics: Vec<Generic>,
+    pub variants: Vec<Variant<()>>,
+    pub span: Span,
+}
+
+impl fmt::Display for EnumTemplate {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+/// Represents a single variant of an `enum`, with the types of its payload.
+#[derive(Clone, Debug)]
+pub struct Variant<T> {
+    pub name: Identifier,
+    pub payload: Vec<Type<T>>,
+    pub span: Span,
+}
+
+impl<T> fmt::Display for Variant<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
 const _: [(); 184] = [(); std::mem::size_of::<ClassItem<()>>()];
 
 #[derive(Clone, Debug)]
@@ -301,7 +382,7 @@ const _: [(); 176] = [(); std::mem::size_of::<FunctionTemplate>()];
 #[derive(Clone, Debug)]
 pub struct FunctionTemplate {
     pub name: Identifier,
-    pub generics: Vec<Identifier>,
+    pub generics: Vec<Generic>,
     pub parameters: Vec<SingleDeclaration<()>>,
     pub returns: Vec<Type<()>>,
     pub statements: Statement<()>,
@@ -357,21 +438,29 @@ impl<T> fmt::Display for Function<T> {
     }
 }
 
-const _: [(); 72] = [(); std::mem::size_of::<Type<()>>()];
+const _: [(); 64] = [(); std::mem::size_of::<Type<()>>()];
 
 /// Represents a primitive type.
 #[derive(Clone, Debug)]
 pub enum Type<T> {
     Bool(Span),
     Int(Span),
+    /// Integer type with an explicit width or signedness, other than `int`
+    Sized {
+        signed: bool,
+        size: Size,
+        span: Span,
+    },
     Class(Variable<T>),
     Array(Box<Type<T>>, Option<Box<Expression<T>>>, Span),
+    /// Function type, with parameter and return types
+    Function(Vec<Type<T>>, Vec<Type<T>>, Box<Span>),
 }
 
 impl<T> Type<T> {
     pub fn has_length(&self) -> bool {
         match self {
-            Type::Bool(_) | Type::Int(_) => false,
+            Type::Bool(_) | Type::Int(_) | Type::Sized { .. } | Type::Function(_, _, _) => false,
             Type::Class(variable) => variable.has_length(),
             Type::Array(r#type, length, _) => length.is_some() || r#type.has_length(),
         }
@@ -379,8 +468,12 @@ impl<T> Type<T> {
 
     pub fn span(&self) -> Span {
         match self {
-            Type::Bool(span) | Type::Int(span) | Type::Array(_, _, span) => *span,
+            Type::Bool(span)
+            | Type::Int(span)
+            | Type::Sized { span, .. }
+            | Type::Array(_, _, span) => *span,
             Type::Class(variable) => variable.span,
+            Type::Function(_, _, span) => **span,
         }
     }
 }
@@ -390,9 +483,19 @@ impl<T> PartialEq for Type<T> {
         match self {
             Type::Bool(_) => matches!(other, Type::Bool(_)),
             Type::Int(_) => matches!(other, Type::Int(_)),
+            Type::Sized { signed, size, .. } => matches!(
+                other,
+                Type::Sized { signed: other_signed, size: other_size, .. }
+                    if signed == other_signed && size == other_size,
+            ),
             Type::Class(lhs) => matches!(other, Type::Class(rhs) if lhs == rhs),
             // Note: ignores array length expression
             Type::Array(lhs, _, _) => matches!(other, Type::Array(rhs, _, _) if lhs == rhs),
+            Type::Function(lhs_parameters, lhs_returns, _) => matches!(
+                other,
+                Type::Function(rhs_parameters, rhs_returns, _)
+                    if lhs_parameters == rhs_parameters && lhs_returns == rhs_returns,
+            ),
         }
     }
 }
@@ -405,13 +508,35 @@ impl<T> hash::Hash for Type<T> {
 
         match self {
             Type::Bool(_) | Type::Int(_) => (),
+            Type::Sized { signed, size, .. } => {
+                signed.hash(state);
+                size.hash(state);
+            }
             Type::Class(variable) => variable.hash(state),
             // Note: ignores array length expression
             Type::Array(r#type, _, _) => r#type.hash(state),
+            Type::Function(parameters, returns, _) => {
+                parameters.hash(state);
+                returns.hash(state);
+            }
         }
     }
 }
 
+impl Type<()> {
+    /// The placeholder class `r#type::POISON`, which is checked as a poisoned type.
+    pub fn poisoned(span: Span) -> Self {
+        Type::Class(Variable {
+            name: Identifier {
+                symbol: symbol::intern_static(r#type::POISON),
+                span: Box::new(span),
+            },
+            generics: None,
+            span,
+        })
+    }
+}
+
 impl<T> fmt::Display for Type<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         write!(fmt, "{}", self.sexp())
@@ -449,11 +574,36 @@ pub enum Statement<T> {
         Span,
     ),
 
-    /// While block
-    While(Do, Box<Expression<T>>, Box<Statement<T>>, Span),
+    /// While block, with an optional loop label
+    While(
+        Do,
+        Option<Identifier>,
+        Box<Expression<T>>,
+        Box<Statement<T>>,
+        Span,
+    ),
 
-    /// Break statement
-    Break(Span),
+    /// For block
+    For(Box<For<T>>),
+
+    /// Break statement, with an optional target loop label
+    Break(Option<Identifier>, Span),
+
+    /// Continue statement, with an optional target loop label
+    Continue(Option<Identifier>, Span),
+
+    /// Match statement, with a block for each arm
+    Match(Box<Match<T, Statement<T>>>),
+
+    /// Throw statement
+    Throw(Box<Expression<T>>, Span),
+
+    /// Try block, with its `catch` and `finally` blocks
+    Try(Box<Try<T>>),
+
+    /// Placeholder for a statement that failed to parse, which is checked as
+    /// diverging so that it can't cause a missing return
+    Error(Span),
 }
 
 impl<T> Statement<T> {
@@ -461,13 +611,19 @@ impl<T> Statement<T> {
         match self {
             Statement::Call(call) => call.span,
             Statement::Initialization(initialization) => initialization.span,
+            Statement::For(r#for) => r#for.span,
+            Statement::Match(r#match) => r#match.span,
+            Statement::Try(r#try) => r#try.span,
             Statement::Assignment(_, _, span)
             | Statement::Declaration(_, span)
             | Statement::Return(_, span)
             | Statement::Sequence(_, span)
             | Statement::If(_, _, _, span)
-            | Statement::While(_, _, _, span)
-            | Statement::Break(span) => *span,
+            | Statement::While(_, _, _, _, span)
+            | Statement::Break(_, span)
+            | Statement::Continue(_, span)
+            | Statement::Throw(_, span)
+            | Statement::Error(span) => *span,
         }
     }
 }
@@ -478,13 +634,128 @@ impl<T> fmt::Display for Statement<T> {
     }
 }
 
+const _: [(); 72] = [(); std::mem::size_of::<For<()>>()];
+
+/// Represents a C-style `for (initialization; condition; update)` loop.
+#[derive(Clone, Debug)]
+pub struct For<T> {
+    pub label: Option<Identifier>,
+    pub initialization: Option<Box<Statement<T>>>,
+    /// Defaults to `true` if missing
+    pub condition: Option<Box<Expression<T>>>,
+    pub update: Option<Box<Statement<T>>>,
+    pub statements: Box<Statement<T>>,
+    pub span: Span,
+}
+
+impl<T> fmt::Display for For<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+/// Represents a `try` block, whose exceptions are handled by the first `catch`
+/// block with a matching class. The `finally` block runs however control leaves.
+#[derive(Clone, Debug)]
+pub struct Try<T> {
+    pub statements: Statement<T>,
+    pub catches: Vec<Catch<T>>,
+    pub finally: Option<Statement<T>>,
+    pub span: Span,
+}
+
+impl<T> fmt::Display for Try<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+/// Represents a `catch` block, which binds the caught exception to `declaration`.
+#[derive(Clone, Debug)]
+pub struct Catch<T> {
+    pub declaration: SingleDeclaration<T>,
+    pub statements: Statement<T>,
+    pub span: Span,
+}
+
+impl<T> fmt::Display for Catch<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+/// Represents a `match` on the value of `scrutinee`, where each arm's body is a `B`.
+#[derive(Clone, Debug)]
+pub struct Match<T, B> {
+    pub scrutinee: Box<Expression<T>>,
+    pub arms: Vec<Arm<T, B>>,
+    pub span: Span,
+}
+
+impl<T, B: Serialize> fmt::Display for Match<T, B> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Arm<T, B> {
+    pub pattern: Pattern<T>,
+    pub body: B,
+    pub span: Span,
+}
+
+/// Represents a (possibly nested) pattern in a `match` arm.
+#[derive(Clone, Debug)]
+pub enum Pattern<T> {
+    /// Matches anything without binding it
+    Wildcard(Span),
+
+    /// Matches anything, binding it to a new variable
+    ///
+    /// The type checker rewrites bindings that name a payload-less variant
+    /// of the matched enum into [`Pattern::Variant`].
+    Binding(Identifier, T),
+
+    /// Matches an enum variant, along with patterns for its payload
+    Variant(Identifier, Vec<Pattern<T>>, T, Span),
+
+    /// Boolean literal
+    Boolean(bool, Span),
+
+    /// Integer literal
+    Integer(i64, Span),
+
+    /// Char literal
+    Character(char, Span),
+}
+
+impl<T> Pattern<T> {
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Binding(name, _) => *name.span,
+            Pattern::Wildcard(span)
+            | Pattern::Variant(_, _, _, span)
+            | Pattern::Boolean(_, span)
+            | Pattern::Integer(_, span)
+            | Pattern::Character(_, span) => *span,
+        }
+    }
+}
+
+impl<T> fmt::Display for Pattern<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Do {
     Yes,
     No,
 }
 
-const _: [(); 96] = [(); std::mem::size_of::<Expression<()>>()];
+const _: [(); 88] = [(); std::mem::size_of::<Expression<()>>()];
 
 /// Represents an expression (i.e. a term that can be evaluated).
 #[derive(Clone, Debug)]
@@ -528,6 +799,15 @@ pub enum Expression<T> {
     /// Array length
     Length(Box<Expression<T>>, Span),
 
+    /// Integer conversion
+    Cast(Box<Type<T>>, Box<Expression<T>>, Span),
+
+    /// Runtime class test
+    InstanceOf(Box<Expression<T>>, Box<Type<T>>, Span),
+
+    /// Checked class conversion
+    As(Box<Expression<T>>, Box<Type<T>>, Span),
+
     /// Function call
     Call(Call<T>),
 
@@ -538,6 +818,18 @@ pub enum Expression<T> {
 
     /// Class constructor
     New(Variable<T>, Span),
+
+    /// Anonymous function
+    Closure(Box<Closure<T>>),
+
+    /// Enum variant constructor
+    ///
+    /// Parsed as a field access or method call on the enum's name, and
+    /// rewritten by the type checker.
+    Construct(Box<Construct<T>>),
+
+    /// Match expression, with an expression for each arm
+    Match(Box<Match<T, Expression<T>>>, T),
 }
 
 impl<T> Expression<T> {
@@ -555,10 +847,16 @@ impl<T> Expression<T> {
             | Expression::Unary(_, _, _, span)
             | Expression::Index(_, _, _, span)
             | Expression::Length(_, span)
+            | Expression::Cast(_, _, span)
+            | Expression::InstanceOf(_, _, span)
+            | Expression::As(_, _, span)
             | Expression::Dot(_, _, _, span)
             | Expression::New(_, span) => *span,
             Expression::Variable(variable, _) => variable.span,
             Expression::Call(call) => call.span,
+            Expression::Closure(closure) => closure.span,
+            Expression::Construct(construct) => construct.span,
+            Expression::Match(r#match, _) => r#match.span,
         }
     }
 
@@ -576,10 +874,16 @@ impl<T> Expression<T> {
             | Expression::Unary(_, _, _, span)
             | Expression::Index(_, _, _, span)
             | Expression::Length(_, span)
+            | Expression::Cast(_, _, span)
+            | Expression::InstanceOf(_, _, span)
+            | Expression::As(_, _, span)
             | Expression::Dot(_, _, _, span)
             | Expression::New(_, span) => span,
             Expression::Variable(variable, _) => &mut variable.span,
             Expression::Call(call) => &mut call.span,
+            Expression::Closure(closure) => &mut closure.span,
+            Expression::Construct(construct) => &mut construct.span,
+            Expression::Match(r#match, _) => &mut r#match.span,
         }
     }
 }
@@ -629,14 +933,16 @@ impl Expression<r#type::Expression> {
                 Expression::Binary(binary, left.clone(), right.clone(), r#type.clone(), *span)
             }
             Expression::Unary(Unary::Not, expression, _, _) => (**expression).clone(),
-            Expression::Index(_, _, _, _) | Expression::Call(_) | Expression::Dot(_, _, _, _) => {
-                Expression::Unary(
-                    Unary::Not,
-                    Box::new(self.clone()),
-                    r#type::Expression::Boolean,
-                    self.span(),
-                )
-            }
+            Expression::Index(_, _, _, _)
+            | Expression::Call(_)
+            | Expression::Dot(_, _, _, _)
+            | Expression::InstanceOf(_, _, _)
+            | Expression::Match(_, _) => Expression::Unary(
+                Unary::Not,
+                Box::new(self.clone()),
+                r#type::Expression::Boolean,
+                self.span(),
+            ),
             Expression::Unary(Unary::Neg, _, _, _)
             | Expression::Character(_, _)
             | Expression::String(_, _)
@@ -646,7 +952,11 @@ impl Expression<r#type::Expression> {
             | Expression::Super(_, _)
             | Expression::Array(_, _, _)
             | Expression::Length(_, _)
-            | Expression::New(_, _) => self.clone(),
+            | Expression::Cast(_, _, _)
+            | Expression::As(_, _, _)
+            | Expression::New(_, _)
+            | Expression::Closure(_)
+            | Expression::Construct(_) => self.clone(),
         }
     }
 }
@@ -657,6 +967,42 @@ impl<T> fmt::Display for Expression<T> {
     }
 }
 
+const _: [(); 160] = [(); std::mem::size_of::<Closure<()>>()];
+
+/// Represents an anonymous function literal.
+#[derive(Clone, Debug)]
+pub struct Closure<T> {
+    pub parameters: Vec<SingleDeclaration<T>>,
+    pub returns: Vec<Type<T>>,
+    pub statements: Statement<T>,
+    /// Local variables captured by value, in order of first use
+    ///
+    /// Filled in by the type checker.
+    pub captures: Vec<Symbol>,
+    pub span: Span,
+}
+
+impl<T> fmt::Display for Closure<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+/// Represents the construction of a value of enum `r#enum` tagged with `variant`.
+#[derive(Clone, Debug)]
+pub struct Construct<T> {
+    pub r#enum: Identifier,
+    pub variant: Identifier,
+    pub arguments: Vec<Expression<T>>,
+    pub span: Span,
+}
+
+impl<T> fmt::Display for Construct<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
 const _: [(); 56] = [(); std::mem::size_of::<Call<()>>()];
 
 /// Represents a function call.
@@ -673,7 +1019,7 @@ impl<T> fmt::Display for Call<T> {
     }
 }
 
-const _: [(); 64] = [(); std::mem::size_of::<Declaration<()>>()];
+const _: [(); 56] = [(); std::mem::size_of::<Declaration<()>>()];
 
 #[derive(Clone, Debug)]
 pub enum Declaration<T> {
@@ -711,6 +1057,18 @@ impl<T> Declaration<T> {
     }
 }
 
+impl Declaration<()> {
+    /// Declares each of `names` with the placeholder type `r#type::POISON`,
+    /// after the rest of their declaration failed to parse.
+    pub fn poisoned(mut names: Vec<Identifier>, span: Span) -> Self {
+        let r#type = Type::poisoned(span);
+        match names.len() {
+            1 => Declaration::Single(SingleDeclaration::new(names.remove(0), r#type, span)),
+            _ => Declaration::Multiple(MultipleDeclaration::new(names, r#type, span)),
+        }
+    }
+}
+
 impl<T> fmt::Display for Declaration<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         write!(fmt, "{}", self.sexp())
@@ -858,6 +1216,20 @@ impl<T> fmt::Display for Variable<T> {
     }
 }
 
+/// A template parameter, optionally bounded by a class or interface
+/// that every type argument must be a subtype of.
+#[derive(Clone, Debug)]
+pub struct Generic {
+    pub name: Identifier,
+    pub bound: Option<Variable<()>>,
+}
+
+impl fmt::Display for Generic {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
 #[derive(Clone, Debug, Eq)]
 pub struct Identifier {
     pub symbol: Symbol,
@@ -21,6 +21,10 @@ pub trait VisitorMut<T> {
 
     fn visit_class_item(&mut self, _item: &mut ast::ClassItem<T>) {}
 
+    fn visit_protocol(&mut self, _protocol: &mut ast::Protocol<T>) {}
+
+    fn visit_enum(&mut self, _enum: &mut ast::Enum<T>) {}
+
     fn visit_function(&mut self, _function: &mut ast::Function<T>) {}
 
     fn visit_statement(&mut self, _statement: &mut ast::Statement<T>) {}
@@ -39,6 +43,8 @@ pub trait VisitorMut<T> {
 
     fn visit_call(&mut self, _call: &mut ast::Call<T>) {}
 
+    fn visit_closure(&mut self, _closure: &mut ast::Closure<T>) {}
+
     fn visit_variable(&mut self, _variable: &mut ast::Variable<T>) {}
 
     fn visit_identifier(&mut self, _identifier: &mut ast::Identifier) {}
@@ -59,6 +65,7 @@ impl<T> ast::ItemSignature<T> {
         match self {
             ast::ItemSignature::Class(class) => class.accept_mut(visitor),
             ast::ItemSignature::ClassTemplate(_) => (),
+            ast::ItemSignature::Protocol(protocol) => protocol.accept_mut(visitor),
             ast::ItemSignature::Function(function) => function.accept_mut(visitor),
             ast::ItemSignature::FunctionTemplate(_) => (),
         }
@@ -73,6 +80,7 @@ impl<T> ast::ClassSignature<T> {
             r#final: _,
             name,
             extends,
+            implements,
             methods,
             span: _,
         } = self;
@@ -83,6 +91,9 @@ impl<T> ast::ClassSignature<T> {
         extends
             .iter_mut()
             .for_each(|supertype| supertype.accept_mut(visitor));
+        implements
+            .iter_mut()
+            .for_each(|supertype| supertype.accept_mut(visitor));
         methods
             .iter_mut()
             .for_each(|method| method.accept_mut(visitor));
@@ -136,6 +147,9 @@ impl<T> ast::Item<T> {
             ast::Item::Global(global) => global.accept_mut(visitor),
             ast::Item::Class(class) => class.accept_mut(visitor),
             ast::Item::ClassTemplate(_) => (),
+            ast::Item::Protocol(protocol) => protocol.accept_mut(visitor),
+            ast::Item::Enum(r#enum) => r#enum.accept_mut(visitor),
+            ast::Item::EnumTemplate(_) => (),
             ast::Item::Function(function) => function.accept_mut(visitor),
             ast::Item::FunctionTemplate(_) => (),
         }
@@ -161,6 +175,7 @@ impl<T> ast::Class<T> {
             r#final: _,
             name,
             extends,
+            implements,
             items,
             provenance: _,
             declared: _,
@@ -170,12 +185,62 @@ impl<T> ast::Class<T> {
         if let Some(supertype) = extends {
             supertype.accept_mut(visitor)
         }
+        implements
+            .iter_mut()
+            .for_each(|supertype| supertype.accept_mut(visitor));
         items.iter_mut().for_each(|item| item.accept_mut(visitor));
 
         visitor.visit_class(self);
     }
 }
 
+impl<T> ast::Protocol<T> {
+    pub fn accept_mut<V: VisitorMut<T>>(&mut self, visitor: &mut V) {
+        let ast::Protocol {
+            name,
+            methods,
+            span: _,
+        } = self;
+        name.accept_mut(visitor);
+        methods
+            .iter_mut()
+            .for_each(|method| method.accept_mut(visitor));
+
+        visitor.visit_protocol(self);
+    }
+}
+
+impl<T> ast::Enum<T> {
+    pub fn accept_mut<V: VisitorMut<T>>(&mut self, visitor: &mut V) {
+        let ast::Enum {
+            name,
+            variants,
+            provenance: _,
+            span: _,
+        } = self;
+        name.accept_mut(visitor);
+        variants
+            .iter_mut()
+            .for_each(|variant| variant.accept_mut(visitor));
+
+        visitor.visit_enum(self);
+    }
+}
+
+impl<T> ast::Variant<T> {
+    pub fn accept_mut<V: VisitorMut<T>>(&mut self, visitor: &mut V) {
+        let ast::Variant {
+            name,
+            payload,
+            span: _,
+        } = self;
+        name.accept_mut(visitor);
+        payload
+            .iter_mut()
+            .for_each(|r#type| r#type.accept_mut(visitor));
+    }
+}
+
 impl<T> ast::ClassItem<T> {
     pub fn accept_mut<V: VisitorMut<T>>(&mut self, visitor: &mut V) {
         match self {
@@ -234,11 +299,43 @@ impl<T> ast::Statement<T> {
                     r#else.accept_mut(visitor);
                 }
             }
-            ast::Statement::While(_, condition, r#while, _) => {
+            ast::Statement::While(_, _, condition, r#while, _) => {
                 condition.accept_mut(visitor);
                 r#while.accept_mut(visitor);
             }
-            ast::Statement::Break(_) => (),
+            ast::Statement::For(r#for) => {
+                if let Some(initialization) = &mut r#for.initialization {
+                    initialization.accept_mut(visitor);
+                }
+                if let Some(condition) = &mut r#for.condition {
+                    condition.accept_mut(visitor);
+                }
+                r#for.statements.accept_mut(visitor);
+                if let Some(update) = &mut r#for.update {
+                    update.accept_mut(visitor);
+                }
+            }
+            ast::Statement::Break(_, _)
+            | ast::Statement::Continue(_, _)
+            | ast::Statement::Error(_) => (),
+            ast::Statement::Match(r#match) => {
+                r#match.scrutinee.accept_mut(visitor);
+                r#match
+                    .arms
+                    .iter_mut()
+                    .for_each(|arm| arm.body.accept_mut(visitor));
+            }
+            ast::Statement::Throw(expression, _) => expression.accept_mut(visitor),
+            ast::Statement::Try(r#try) => {
+                r#try.statements.accept_mut(visitor);
+                for catch in &mut r#try.catches {
+                    catch.declaration.accept_mut(visitor);
+                    catch.statements.accept_mut(visitor);
+                }
+                if let Some(finally) = &mut r#try.finally {
+                    finally.accept_mut(visitor);
+                }
+            }
         }
 
         visitor.visit_statement(self);
@@ -306,6 +403,7 @@ impl<T> ast::Type<T> {
         match self {
             ast::Type::Bool(_) => (),
             ast::Type::Int(_) => (),
+            ast::Type::Sized { .. } => (),
             ast::Type::Class(variable) => variable.accept_mut(visitor),
             ast::Type::Array(r#type, length, _) => {
                 r#type.accept_mut(visitor);
@@ -313,6 +411,14 @@ impl<T> ast::Type<T> {
                     length.accept_mut(visitor);
                 }
             }
+            ast::Type::Function(parameters, returns, _) => {
+                parameters
+                    .iter_mut()
+                    .for_each(|parameter| parameter.accept_mut(visitor));
+                returns
+                    .iter_mut()
+                    .for_each(|r#return| r#return.accept_mut(visitor));
+            }
         }
 
         visitor.visit_type(self);
@@ -345,18 +451,64 @@ impl<T> ast::Expression<T> {
             ast::Expression::Length(array, _) => {
                 array.accept_mut(visitor);
             }
+            ast::Expression::Cast(r#type, expression, _) => {
+                r#type.accept_mut(visitor);
+                expression.accept_mut(visitor);
+            }
+            ast::Expression::InstanceOf(expression, r#type, _)
+            | ast::Expression::As(expression, r#type, _) => {
+                expression.accept_mut(visitor);
+                r#type.accept_mut(visitor);
+            }
             ast::Expression::Call(call) => call.accept_mut(visitor),
             ast::Expression::Dot(receiver, identifier, _, _) => {
                 receiver.accept_mut(visitor);
                 identifier.accept_mut(visitor);
             }
             ast::Expression::New(variable, _) => variable.accept_mut(visitor),
+            ast::Expression::Closure(closure) => closure.accept_mut(visitor),
+            ast::Expression::Construct(construct) => {
+                construct.r#enum.accept_mut(visitor);
+                construct.variant.accept_mut(visitor);
+                construct
+                    .arguments
+                    .iter_mut()
+                    .for_each(|argument| argument.accept_mut(visitor));
+            }
+            ast::Expression::Match(r#match, _) => {
+                r#match.scrutinee.accept_mut(visitor);
+                r#match
+                    .arms
+                    .iter_mut()
+                    .for_each(|arm| arm.body.accept_mut(visitor));
+            }
         }
 
         visitor.visit_expression(self);
     }
 }
 
+impl<T> ast::Closure<T> {
+    pub fn accept_mut<V: VisitorMut<T>>(&mut self, visitor: &mut V) {
+        let ast::Closure {
+            parameters,
+            returns,
+            statements,
+            captures: _,
+            span: _,
+        } = self;
+        parameters
+            .iter_mut()
+            .for_each(|parameter| parameter.accept_mut(visitor));
+        returns
+            .iter_mut()
+            .for_each(|r#return| r#return.accept_mut(visitor));
+        statements.accept_mut(visitor);
+
+        visitor.visit_closure(self);
+    }
+}
+
 impl<T> ast::Call<T> {
     pub fn accept_mut<V: VisitorMut<T>>(&mut self, visitor: &mut V) {
         let ast::Call {
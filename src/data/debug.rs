@@ -0,0 +1,40 @@
+use crate::data::r#type;
+use crate::data::span::Span;
+use crate::data::symbol::Symbol;
+
+/// Debugging information interleaved with the statements of a function, which
+/// has no effect on program behavior. Only emitted when compiling with `--debug-info`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Debug<T> {
+    /// Source location of the statements that follow, up to the next location.
+    Location(Span),
+
+    /// Source-level variable stored in `T`.
+    ///
+    /// Passes may rewrite or eliminate the definitions of `T` without updating this
+    /// marker, so it is not a use of `T`. A variable whose storage no longer exists
+    /// after register allocation is considered optimized out.
+    Variable(Symbol, r#type::Expression, T),
+}
+
+impl<T> Debug<T> {
+    pub fn map<F: FnOnce(&T) -> U, U>(&self, apply: F) -> Debug<U> {
+        match self {
+            Debug::Location(span) => Debug::Location(*span),
+            Debug::Variable(name, r#type, value) => {
+                Debug::Variable(*name, r#type.clone(), apply(value))
+            }
+        }
+    }
+}
+
+/// Whether to keep debugging information when emitting code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DebugInfo {
+    /// Emit source locations and variables so that a debugger can step through
+    /// the program and inspect its variables.
+    Keep,
+
+    /// Emit no debugging information.
+    Omit,
+}
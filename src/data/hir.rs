@@ -1,5 +1,6 @@
 use std::fmt;
 
+use crate::data::debug::Debug;
 use crate::data::ir;
 use crate::data::operand::Immediate;
 use crate::data::operand::Label;
@@ -142,6 +143,7 @@ pub enum Statement {
     },
     Return(Vec<Expression>),
     Sequence(Vec<Statement>),
+    Debug(Debug<Temporary>),
 }
 
 impl fmt::Display for Statement {
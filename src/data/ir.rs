@@ -84,6 +84,16 @@ pub enum Binary {
     Hul,
     Div,
     Mod,
+
+    /// Unsigned high multiplication
+    UHul,
+
+    /// Unsigned division
+    UDiv,
+
+    /// Unsigned modulo
+    UMod,
+
     Xor,
     And,
     Or,
@@ -122,6 +132,28 @@ pub enum Condition {
     /// Used for optimizing (0 <= signed < max) into (unsigned < max),
     /// relying on integer underflow.
     Ae,
+
+    /// Unsigned below
+    B,
+
+    /// Unsigned below or equal
+    Be,
+
+    /// Unsigned above
+    A,
+}
+
+impl Condition {
+    /// Converts a signed comparison into its unsigned counterpart.
+    pub fn unsigned(self) -> Self {
+        match self {
+            Condition::Lt => Condition::B,
+            Condition::Le => Condition::Be,
+            Condition::Ge => Condition::Ae,
+            Condition::Gt => Condition::A,
+            condition => condition,
+        }
+    }
 }
 
 impl From<ast::Binary> for Condition {
@@ -148,6 +180,9 @@ macro_rules! ir {
     (HUL) => { $crate::data::ir::Binary::Hul };
     (DIV) => { $crate::data::ir::Binary::Div };
     (MOD) => { $crate::data::ir::Binary::Mod };
+    (UHUL) => { $crate::data::ir::Binary::UHul };
+    (UDIV) => { $crate::data::ir::Binary::UDiv };
+    (UMOD) => { $crate::data::ir::Binary::UMod };
     (XOR) => { $crate::data::ir::Binary::Xor };
     (AND) => { $crate::data::ir::Binary::And };
     (OR) => { $crate::data::ir::Binary::Or };
@@ -159,6 +194,9 @@ macro_rules! ir {
     (NE) => { $crate::data::ir::Condition::Ne };
     (EQ) => { $crate::data::ir::Condition::Eq };
     (AE) => { $crate::data::ir::Condition::Ae };
+    (B) => { $crate::data::ir::Condition::B };
+    (BE) => { $crate::data::ir::Condition::Be };
+    (A) => { $crate::data::ir::Condition::A };
 
     ($ident:ident) => { $ident };
 }
@@ -1,6 +1,7 @@
 use std::fmt;
 
 use crate::abi;
+use crate::data::debug::Debug;
 use crate::data::ir;
 use crate::data::operand::Immediate;
 use crate::data::operand::Label;
@@ -106,6 +107,7 @@ pub enum Statement<T> {
         source: Expression,
     },
     Return(Vec<Expression>),
+    Debug(Debug<Temporary>),
 }
 
 impl<T: Serialize> fmt::Display for Statement<T> {
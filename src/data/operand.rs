@@ -112,7 +112,7 @@ impl Temporary {
 impl fmt::Display for Temporary {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Temporary::Register(register) => write!(fmt, "{}", register),
+            Temporary::Register(register) => fmt::Display::fmt(register, fmt),
             Temporary::Fresh(temporary, index) => write!(fmt, "_{}{}", temporary, index),
             Temporary::Fixed(temporary) => write!(fmt, "_{}", temporary),
         }
@@ -191,6 +191,28 @@ impl Register {
     pub fn is_callee_saved(&self) -> bool {
         abi::CALLEE_SAVED.contains(self)
     }
+
+    /// Register number in the System V x86-64 DWARF register mapping.
+    pub fn dwarf(&self) -> u8 {
+        match self {
+            Register::Rax => 0,
+            Register::Rdx => 1,
+            Register::Rcx => 2,
+            Register::Rbx => 3,
+            Register::Rsi => 4,
+            Register::Rdi => 5,
+            Register::Rbp | Register::Rsp(false) => 6,
+            Register::Rsp(true) => 7,
+            Register::R8 => 8,
+            Register::R9 => 9,
+            Register::R10 => 10,
+            Register::R11 => 11,
+            Register::R12 => 12,
+            Register::R13 => 13,
+            Register::R14 => 14,
+            Register::R15 => 15,
+        }
+    }
 }
 
 impl PartialEq for Register {
@@ -220,26 +242,37 @@ impl Hash for Register {
     }
 }
 
+// The formatting precision selects the width of the register name, e.g.
+// `format!("{:.8}", Register::Rax)` produces `al`. This lets the printer
+// emit narrow loads and stores without threading widths through operands.
 impl fmt::Display for Register {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let register = match self {
-            Register::Rax => "rax",
-            Register::Rbx => "rbx",
-            Register::Rcx => "rcx",
-            Register::Rdx => "rdx",
-            Register::Rbp => "rbp",
-            Register::Rsp(true) => "rsp",
-            Register::Rsp(false) => "rbp",
-            Register::Rsi => "rsi",
-            Register::Rdi => "rdi",
-            Register::R8 => "r8",
-            Register::R9 => "r9",
-            Register::R10 => "r10",
-            Register::R11 => "r11",
-            Register::R12 => "r12",
-            Register::R13 => "r13",
-            Register::R14 => "r14",
-            Register::R15 => "r15",
+        let [r64, r32, r16, r8] = match self {
+            Register::Rax => ["rax", "eax", "ax", "al"],
+            Register::Rbx => ["rbx", "ebx", "bx", "bl"],
+            Register::Rcx => ["rcx", "ecx", "cx", "cl"],
+            Register::Rdx => ["rdx", "edx", "dx", "dl"],
+            Register::Rbp => ["rbp", "ebp", "bp", "bpl"],
+            Register::Rsp(true) => ["rsp", "esp", "sp", "spl"],
+            Register::Rsp(false) => ["rbp", "ebp", "bp", "bpl"],
+            Register::Rsi => ["rsi", "esi", "si", "sil"],
+            Register::Rdi => ["rdi", "edi", "di", "dil"],
+            Register::R8 => ["r8", "r8d", "r8w", "r8b"],
+            Register::R9 => ["r9", "r9d", "r9w", "r9b"],
+            Register::R10 => ["r10", "r10d", "r10w", "r10b"],
+            Register::R11 => ["r11", "r11d", "r11w", "r11b"],
+            Register::R12 => ["r12", "r12d", "r12w", "r12b"],
+            Register::R13 => ["r13", "r13d", "r13w", "r13b"],
+            Register::R14 => ["r14", "r14d", "r14w", "r14b"],
+            Register::R15 => ["r15", "r15d", "r15w", "r15b"],
+        };
+
+        let register = match fmt.precision() {
+            None | Some(64) => r64,
+            Some(32) => r32,
+            Some(16) => r16,
+            Some(8) => r8,
+            Some(bits) => panic!("[INTERNAL ERROR]: no {}-bit register", bits),
         };
 
         write!(fmt, "{}", register)
@@ -249,9 +282,24 @@ impl fmt::Display for Register {
 pub trait Operand:
     Copy + Eq + Hash + std::fmt::Debug + std::fmt::Display + PartialOrd + Ord
 {
+    /// Physical register this operand refers to, if any.
+    fn register(&self) -> Option<Register>;
+}
+
+impl Operand for Temporary {
+    fn register(&self) -> Option<Register> {
+        match self {
+            Temporary::Register(register) => Some(*register),
+            Temporary::Fixed(_) | Temporary::Fresh(_, _) => None,
+        }
+    }
+}
+
+impl Operand for Register {
+    fn register(&self) -> Option<Register> {
+        Some(*self)
+    }
 }
-impl Operand for Temporary {}
-impl Operand for Register {}
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Memory<T> {
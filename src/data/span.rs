@@ -29,6 +29,14 @@ impl Point {
     pub fn index(&self) -> usize {
         self.index as usize
     }
+
+    pub fn row(&self) -> usize {
+        self.row as usize
+    }
+
+    pub fn column(&self) -> usize {
+        self.column as usize
+    }
 }
 
 impl Default for Point {
@@ -74,6 +82,11 @@ impl Span {
     pub fn new(lo: Point, hi: Point) -> Self {
         Span { lo, hi }
     }
+
+    /// Path of the source file this span points into, if any.
+    pub fn path(&self) -> Option<Symbol> {
+        self.lo.path.or(self.hi.path)
+    }
 }
 
 impl fmt::Display for Span {
@@ -0,0 +1,52 @@
+use std::fmt;
+
+use crate::data::ir;
+use crate::data::lir;
+use crate::data::operand::Label;
+use crate::data::operand::Temporary;
+use crate::data::sexp::Serialize;
+use crate::data::symbol::Symbol;
+
+pub type Unit<T> = ir::Unit<Function<T>>;
+
+impl<T: lir::Target> fmt::Display for Unit<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+/// LIR in static single assignment (SSA) form, where each temporary is defined
+/// by at most one statement.
+#[derive(Clone, Debug)]
+pub struct Function<T: lir::Target> {
+    pub name: Symbol,
+    pub statements: Vec<Statement<T>>,
+    pub arguments: Vec<Temporary>,
+    pub returns: usize,
+    pub linkage: ir::Linkage,
+    pub enter: T::Access,
+    pub exit: T::Access,
+}
+
+impl<T: lir::Target> fmt::Display for Function<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Statement<T> {
+    /// Defines `destination` as the source paired with the predecessor block
+    /// that control arrived from. Only appears at the beginning of a block.
+    Phi {
+        destination: Temporary,
+        sources: Vec<(Label, Temporary)>,
+    },
+    Lir(lir::Statement<T>),
+}
+
+impl<T: Serialize> fmt::Display for Statement<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.sexp())
+    }
+}
@@ -8,11 +8,19 @@ use crate::data::symbol::Symbol;
 pub type Spanned = Result<(span::Point, Token, span::Point), crate::Error>;
 
 #[derive(Debug)]
-pub struct Tokens(Vec<Spanned>);
+pub struct Tokens {
+    tokens: Vec<Spanned>,
+    comments: Vec<Comment>,
+}
 
 impl Tokens {
-    pub fn new(tokens: Vec<Spanned>) -> Self {
-        Tokens(tokens)
+    pub fn new(tokens: Vec<Spanned>, comments: Vec<Comment>) -> Self {
+        Tokens { tokens, comments }
+    }
+
+    /// Line comments skipped while lexing, in source order.
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
     }
 }
 
@@ -20,13 +28,13 @@ impl IntoIterator for Tokens {
     type Item = Spanned;
     type IntoIter = vec::IntoIter<Self::Item>;
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.tokens.into_iter()
     }
 }
 
 impl fmt::Display for Tokens {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        for spanned in &self.0 {
+        for spanned in &self.tokens {
             match spanned {
                 Ok((left, token, _)) => writeln!(fmt, "{} {}", left, token)?,
                 Err(error) => writeln!(fmt, "{}", error)?,
@@ -36,6 +44,14 @@ impl fmt::Display for Tokens {
     }
 }
 
+/// Line comment, which the parser never sees but the formatter preserves.
+#[derive(Clone, Debug)]
+pub struct Comment {
+    /// Text following the leading `//`, without trailing whitespace
+    pub text: String,
+    pub span: span::Span,
+}
+
 /// Represents a possible lexical token in the Xi language.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Token {
@@ -72,15 +88,48 @@ pub enum Token {
     /// `extends` keyword
     Extends,
 
+    /// `interface` keyword
+    Interface,
+
+    /// `implements` keyword
+    Implements,
+
+    /// `instanceof` keyword
+    InstanceOf,
+
+    /// `as` keyword
+    As,
+
     /// `final` keyword
     Final,
 
+    /// `enum` keyword
+    Enum,
+
+    /// `match` keyword
+    Match,
+
+    /// `throw` keyword
+    Throw,
+
+    /// `try` keyword
+    Try,
+
+    /// `catch` keyword
+    Catch,
+
+    /// `finally` keyword
+    Finally,
+
     /// `null` keyword
     Null,
 
     /// `break` keyword
     Break,
 
+    /// `continue` keyword
+    Continue,
+
     /// `if` keyword
     If,
 
@@ -90,12 +139,18 @@ pub enum Token {
     /// `while` keyword
     While,
 
+    /// `for` keyword
+    For,
+
     /// `else` keyword
     Else,
 
     /// `return` keyword
     Return,
 
+    /// `fn` keyword
+    Fn,
+
     /// `length` keyword
     Length,
 
@@ -150,6 +205,9 @@ pub enum Token {
     /// `==` symbol
     Eq,
 
+    /// `=>` symbol
+    Arrow,
+
     /// `!=` symbol
     Ne,
 
@@ -191,6 +249,9 @@ pub enum Token {
 
     /// `.` symbol
     Period,
+
+    /// `@` symbol
+    At,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -201,6 +262,21 @@ pub enum Size {
     _64,
 }
 
+impl Size {
+    pub fn bits(&self) -> u32 {
+        match self {
+            Size::_8 => 8,
+            Size::_16 => 16,
+            Size::_32 => 32,
+            Size::_64 => 64,
+        }
+    }
+
+    pub fn bytes(&self) -> i64 {
+        self.bits() as i64 / 8
+    }
+}
+
 impl fmt::Display for Size {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let size = match self {
@@ -231,14 +307,27 @@ impl fmt::Display for Token {
             Token::Super => write!(fmt, "super"),
             Token::New => write!(fmt, "new"),
             Token::Extends => write!(fmt, "extends"),
+            Token::Interface => write!(fmt, "interface"),
+            Token::Implements => write!(fmt, "implements"),
+            Token::InstanceOf => write!(fmt, "instanceof"),
+            Token::As => write!(fmt, "as"),
             Token::Final => write!(fmt, "final"),
+            Token::Enum => write!(fmt, "enum"),
+            Token::Match => write!(fmt, "match"),
+            Token::Throw => write!(fmt, "throw"),
+            Token::Try => write!(fmt, "try"),
+            Token::Catch => write!(fmt, "catch"),
+            Token::Finally => write!(fmt, "finally"),
             Token::Null => write!(fmt, "null"),
             Token::Break => write!(fmt, "break"),
+            Token::Continue => write!(fmt, "continue"),
             Token::If => write!(fmt, "if"),
             Token::Do => write!(fmt, "do"),
             Token::While => write!(fmt, "while"),
+            Token::For => write!(fmt, "for"),
             Token::Else => write!(fmt, "else"),
             Token::Return => write!(fmt, "return"),
+            Token::Fn => write!(fmt, "fn"),
             Token::Length => write!(fmt, "length"),
             Token::Int { signed, size } => {
                 if !signed {
@@ -269,6 +358,7 @@ impl fmt::Display for Token {
             Token::Ge => write!(fmt, ">="),
             Token::Gt => write!(fmt, ">"),
             Token::Eq => write!(fmt, "=="),
+            Token::Arrow => write!(fmt, "=>"),
             Token::Ne => write!(fmt, "!="),
             Token::And => write!(fmt, "&"),
             Token::Or => write!(fmt, "|"),
@@ -283,6 +373,7 @@ impl fmt::Display for Token {
             Token::Comma => write!(fmt, ","),
             Token::Underscore => write!(fmt, "_"),
             Token::Period => write!(fmt, "."),
+            Token::At => write!(fmt, "@"),
         }
     }
 }
@@ -1,4 +1,10 @@
+use crate::data::symbol;
 use crate::data::symbol::Symbol;
+use crate::data::token::Size;
+
+/// Name of the placeholder class substituted for unbounded template parameters
+/// when checking a template body up front, which checks as `Expression::Poison`.
+pub const POISON: &str = "{error}";
 
 /// ```text
 ///            .  .   .
@@ -12,13 +18,27 @@ use crate::data::symbol::Symbol;
 ///  |     |      |     |     |      |       |      |
 ///  +-----+----< any >-+-----+------+-------+------+---- . . .
 /// ```
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+///
+/// Integer types are additionally ordered by implicit widening: a value of
+/// type `int8` can be used wherever an `int16`, `int32`, or `int` is expected,
+/// and a value of type `uint8` can be used wherever a `uint16`, `uint32`,
+/// `uint`, `int16`, `int32`, or `int` is expected.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Expression {
     /// The inner type of an empty array, which subclasses everything.
     Any,
     /// The type of a `null` expression, which subclasses any class.
     Null,
+    /// The type of an expression that failed to type check, which is compatible
+    /// with every other type so that a single error doesn't cascade.
+    Poison,
     Integer,
+    /// Integer type with an explicit width or signedness. By convention,
+    /// `int64` is always represented by `Integer` instead.
+    Sized {
+        signed: bool,
+        size: Size,
+    },
     Boolean,
     Class(Symbol),
     Array(Box<Expression>),
@@ -30,9 +50,26 @@ impl std::fmt::Display for Expression {
         match self {
             Expression::Any => write!(fmt, "any"),
             Expression::Null => write!(fmt, "null"),
+            Expression::Poison => write!(fmt, "{{error}}"),
             Expression::Class(class) => write!(fmt, "{}", class),
             Expression::Integer => write!(fmt, "int"),
+            Expression::Sized { signed, size } => {
+                if !signed {
+                    write!(fmt, "u")?;
+                }
+
+                write!(fmt, "int")?;
+
+                if *size < Size::_64 {
+                    write!(fmt, "{}", size)?;
+                }
+
+                Ok(())
+            }
             Expression::Boolean => write!(fmt, "bool"),
+            Expression::Array(typ) if matches!(**typ, Expression::Function(_, _)) => {
+                write!(fmt, "({})[]", typ)
+            }
             Expression::Array(typ) => write!(fmt, "{}[]", typ),
             Expression::Function(parameters, returns) => {
                 write!(fmt, "fn(")?;
@@ -49,18 +86,71 @@ impl std::fmt::Display for Expression {
 
                 write!(fmt, ")")?;
 
-                let mut returns = returns.iter();
-
-                if let Some(r#return) = returns.next() {
-                    write!(fmt, ": {}", r#return)?;
+                match returns.as_slice() {
+                    [] => Ok(()),
+                    [r#return] => write!(fmt, ": {}", r#return),
+                    [head, tail @ ..] => {
+                        write!(fmt, ": ({}", head)?;
+                        for r#return in tail {
+                            write!(fmt, ", {}", r#return)?;
+                        }
+                        write!(fmt, ")")
+                    }
                 }
+            }
+        }
+    }
+}
 
-                for r#return in returns {
-                    write!(fmt, ", {}", r#return)?;
-                }
+impl Expression {
+    /// Returns the signedness and width of this type, if it is an integer type.
+    pub fn integer(&self) -> Option<(bool, Size)> {
+        match self {
+            Expression::Integer => Some((true, Size::_64)),
+            Expression::Sized { signed, size } => Some((*signed, *size)),
+            _ => None,
+        }
+    }
 
-                Ok(())
+    /// Constructs the integer type with the given signedness and width.
+    pub fn from_integer(signed: bool, size: Size) -> Self {
+        match (signed, size) {
+            (true, Size::_64) => Expression::Integer,
+            (signed, size) => Expression::Sized { signed, size },
+        }
+    }
+
+    /// Constructs the type of instances of `class`, or `Expression::Poison` for the
+    /// placeholder class `POISON`.
+    pub fn class(class: Symbol) -> Self {
+        match symbol::resolve(class) {
+            POISON => Expression::Poison,
+            _ => Expression::Class(class),
+        }
+    }
+
+    /// Returns true if every value of integer type `self` is representable by integer type `other`.
+    pub fn widens_to(&self, other: &Expression) -> bool {
+        match (self.integer(), other.integer()) {
+            (Some((from_signed, from_size)), Some((to_signed, to_size))) => {
+                (from_signed == to_signed && from_size <= to_size)
+                    || (!from_signed && to_signed && from_size < to_size)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if integer type `self` can represent `integer`.
+    pub fn contains(&self, integer: i64) -> bool {
+        match self.integer() {
+            None => false,
+            Some((true, Size::_64)) => true,
+            Some((false, Size::_64)) => integer >= 0,
+            Some((true, size)) => {
+                let bound = 1 << (size.bits() - 1);
+                (-bound..bound).contains(&integer)
             }
+            Some((false, size)) => (0..1 << size.bits()).contains(&integer),
         }
     }
 }
@@ -1,6 +1,5 @@
 #![allow(unused_parens)]
 
-use std::cell::Cell;
 use std::iter;
 
 use crate::abi;
@@ -11,6 +10,8 @@ use crate::check::GlobalScope;
 use crate::check::LocalScope;
 use crate::check::Scope;
 use crate::data::ast;
+use crate::data::debug::Debug;
+use crate::data::debug::DebugInfo;
 use crate::data::hir;
 use crate::data::ir;
 use crate::data::operand::Immediate;
@@ -20,15 +21,18 @@ use crate::data::r#type;
 use crate::data::span::Span;
 use crate::data::symbol;
 use crate::data::symbol::Symbol;
+use crate::data::token::Size;
 use crate::emit::library;
 use crate::hir;
 use crate::util;
 use crate::Map;
+use crate::Set;
 
 pub fn emit_hir(
     context: &mut check::Context,
     path: &std::path::Path,
     abi: Abi,
+    debug: DebugInfo,
     ast: &ast::Program<r#type::Expression>,
 ) -> ir::Unit<hir::Function> {
     log::info!(
@@ -56,26 +60,59 @@ pub fn emit_hir(
     let mut emitter = Emitter {
         layouts,
         context,
+        debug,
         locals: Map::default(),
+        variables: Vec::new(),
         data: Map::default(),
         bss: Map::default(),
         statics: Map::default(),
-        out_of_bounds: Cell::new(None),
+        concats: Set::default(),
+        out_of_bounds: Map::default(),
+        function: symbol::intern_static(abi::XI_INIT_GLOBALS),
+        tries: Vec::new(),
+        unwind: None,
+        loops: Map::default(),
+        closures: Map::default(),
+        checks: Map::default(),
+        interface_lookup: false,
+        instanceof: false,
+        implements: false,
     };
 
+    // Reserve 1 word in BSS section for the pending exception
+    emitter.bss.insert(
+        symbol::intern_static(abi::XI_EXCEPTION),
+        (ir::Linkage::LinkOnceOdr, 1),
+    );
+
     let mut functions = Map::default();
     let mut globals = Vec::new();
     let mut classes = Vec::new();
 
     // Note: class initialization functions must be emitted before
     // global initialization functions, which can rely on the former.
+    //
+    // Every compilation unit emits its own copy of the built-in classes.
+    for class in [abi::EXCEPTION, abi::OUT_OF_BOUNDS] {
+        let (name, function) = emitter
+            .emit_class_initialization(&symbol::intern_static(class), ir::Linkage::LinkOnceOdr);
+        classes.push(name);
+        functions.insert(name, function);
+    }
+
     for item in &ast.items {
         let class = match item {
             ast::Item::Class(class) => class,
             _ => continue,
         };
 
-        let (name, function) = emitter.emit_class_initialization(class);
+        let linkage = match (class.provenance.is_empty(), class.declared.get()) {
+            (true, false) => ir::Linkage::Local,
+            (true, true) => ir::Linkage::Global,
+            (false, _) => ir::Linkage::LinkOnceOdr,
+        };
+
+        let (name, function) = emitter.emit_class_initialization(&class.name.symbol, linkage);
         classes.push(name);
         functions.insert(name, function);
     }
@@ -115,7 +152,8 @@ pub fn emit_hir(
                     functions.insert(name, function);
                 }
             }
-            ast::Item::ClassTemplate(_) => unreachable!(),
+            ast::Item::ClassTemplate(_) | ast::Item::EnumTemplate(_) => unreachable!(),
+            ast::Item::Protocol(_) | ast::Item::Enum(_) => (),
             ast::Item::Function(function) => {
                 let linkage = match (function.provenance.is_empty(), function.declared.get()) {
                     (true, false) => ir::Linkage::Local,
@@ -156,8 +194,29 @@ pub fn emit_hir(
     let memdup = library::emit_memdup();
     functions.insert(memdup.name, memdup);
 
-    let concat = library::emit_concat();
-    functions.insert(concat.name, concat);
+    functions.extend(emitter.closures);
+
+    prune_exception_checks(&mut functions, &emitter.checks);
+
+    if emitter.interface_lookup {
+        let lookup = library::emit_interface_lookup();
+        functions.insert(lookup.name, lookup);
+    }
+
+    if emitter.instanceof {
+        let instanceof = library::emit_instanceof();
+        functions.insert(instanceof.name, instanceof);
+    }
+
+    if emitter.implements {
+        let implements = library::emit_implements();
+        functions.insert(implements.name, implements);
+    }
+
+    for size in iter::once(Size::_64).chain(emitter.concats) {
+        let concat = library::emit_concat(size);
+        functions.insert(concat.name, concat);
+    }
 
     ir::Unit {
         name: symbol::intern(path.to_string_lossy().trim_start_matches("./")),
@@ -170,11 +229,55 @@ pub fn emit_hir(
 struct Emitter<'env> {
     context: &'env mut check::Context,
     layouts: Map<Symbol, abi::class::Layout>,
+    debug: DebugInfo,
     locals: Map<Symbol, Temporary>,
+
+    /// Debugging markers for the locals declared so far in the current function
+    variables: Vec<hir::Statement>,
+
     data: Map<Label, Vec<Immediate>>,
     bss: Map<Symbol, (ir::Linkage, usize)>,
     statics: Map<Vec<Immediate>, Label>,
-    out_of_bounds: Cell<Option<Label>>,
+    concats: Set<Size>,
+    /// Out of bounds landing pad for each exception handler in the current function
+    out_of_bounds: Map<Label, Label>,
+
+    /// Name of the function currently being emitted, which closures are named after
+    function: Symbol,
+
+    /// `try` statements enclosing the current statement in the current function, innermost last
+    tries: Vec<Handler>,
+
+    /// Landing pad that returns from the current function with an exception pending
+    unwind: Option<Label>,
+
+    /// Number of enclosing `try` statements at each loop, keyed by its `break` target
+    loops: Map<Label, usize>,
+
+    /// Closure bodies and adapters emitted so far
+    closures: Map<Symbol, hir::Function>,
+
+    /// Callee of each direct call followed by an exception check, keyed by
+    /// the label that the check falls through to
+    checks: Map<Label, Symbol>,
+
+    /// Whether any method has been called through an interface
+    interface_lookup: bool,
+
+    /// Whether any instance has been tested against a class at runtime
+    instanceof: bool,
+
+    /// Whether any instance has been tested against an interface at runtime
+    implements: bool,
+}
+
+/// A `try` statement enclosing the code being emitted.
+struct Handler {
+    /// Landing pad for exceptions thrown inside the statement
+    handler: Label,
+
+    /// Block that runs when control leaves the statement
+    finally: Option<ast::Statement<r#type::Expression>>,
 }
 
 impl<'env> Emitter<'env> {
@@ -183,13 +286,17 @@ impl<'env> Emitter<'env> {
         global: &ast::Global<r#type::Expression>,
     ) -> Option<(Symbol, hir::Function)> {
         self.locals.clear();
-        self.out_of_bounds.take();
+        self.out_of_bounds.clear();
+        self.loops.clear();
+        self.unwind = None;
+        self.function = symbol::intern_static(abi::XI_INIT_GLOBALS);
 
-        let (name, statement) = match global {
+        let (name, mut statement) = match global {
             ast::Global::Declaration(declaration) => {
                 // Note: we don't need to push a `LocalScope::Function` as of now because
                 // emitting IR for declarations can't read from or write to the local scope.
                 let statement = self.emit_declaration(GlobalScope::Global, declaration)?;
+                let statement = self.emit_location(declaration.span(), statement);
                 let name =
                     abi::mangle::global_initialization(declaration.iter().map(|(name, _)| {
                         (
@@ -202,10 +309,10 @@ impl<'env> Emitter<'env> {
             ast::Global::Initialization(initialization) => {
                 if let ast::Expression::Integer(integer, _) = &*initialization.expression {
                     assert_eq!(initialization.declarations.len(), 1);
-                    let name = initialization.declarations[0].as_ref().unwrap().name.symbol;
-                    let r#type = r#type::Expression::Integer;
+                    let declaration = initialization.declarations[0].as_ref().unwrap();
+                    let r#type = declaration.r#type.r#type();
                     self.data.insert(
-                        Label::Fixed(abi::mangle::global(&name, &r#type)),
+                        Label::Fixed(abi::mangle::global(&declaration.name.symbol, &r#type)),
                         vec![Immediate::Integer(*integer)],
                     );
                     return None;
@@ -216,6 +323,7 @@ impl<'env> Emitter<'env> {
                 });
                 let statement =
                     self.emit_initialization(Scope::Global(GlobalScope::Global), initialization);
+                let statement = self.emit_location(initialization.span, statement);
                 self.context.pop();
 
                 let name = abi::mangle::global_initialization(
@@ -235,6 +343,10 @@ impl<'env> Emitter<'env> {
             }
         };
 
+        if let Some(unwind) = self.unwind.take() {
+            statement = hir!((SEQ statement (LABEL unwind) (RETURN)));
+        }
+
         Some((
             name,
             hir::Function {
@@ -249,9 +361,10 @@ impl<'env> Emitter<'env> {
 
     fn emit_class_initialization(
         &mut self,
-        class: &ast::Class<r#type::Expression>,
+        class: &Symbol,
+        linkage: ir::Linkage,
     ) -> (Symbol, hir::Function) {
-        let size = abi::mangle::class_size(&class.name.symbol);
+        let size = abi::mangle::class_size(class);
 
         let enter = Label::fresh("enter");
         let exit = Label::fresh("exit");
@@ -262,24 +375,19 @@ impl<'env> Emitter<'env> {
         ];
 
         // Recursively initialize superclass
-        if let Some(superclass) = self.context.get_superclass(&class.name.symbol) {
+        if let Some(superclass) = self.context.get_superclass(class) {
             let initialize = abi::mangle::class_initialization(&superclass);
             statements.push(hir!((EXP (CALL (NAME initialize) (Vec::new())))));
         }
 
-        let linkage = match (class.provenance.is_empty(), class.declared.get()) {
-            (true, false) => ir::Linkage::Local,
-            (true, true) => ir::Linkage::Global,
-            (false, _) => ir::Linkage::LinkOnceOdr,
-        };
-
-        self.emit_class_size(&class.name.symbol, linkage, &mut statements);
-        self.emit_class_virtual_table(&class.name.symbol, linkage, &mut statements);
+        self.emit_class_size(class, linkage, &mut statements);
+        self.emit_class_virtual_table(class, linkage, &mut statements);
+        self.emit_class_type_descriptor(class, linkage, &mut statements);
 
         statements.push(hir!((LABEL exit)));
         statements.push(hir!((RETURN)));
 
-        let name = abi::mangle::class_initialization(&class.name.symbol);
+        let name = abi::mangle::class_initialization(class);
 
         (
             name,
@@ -378,6 +486,99 @@ impl<'env> Emitter<'env> {
         }
     }
 
+    fn emit_class_type_descriptor(
+        &mut self,
+        class: &Symbol,
+        linkage: ir::Linkage,
+        statements: &mut Vec<hir::Statement>,
+    ) {
+        let type_descriptor_class = abi::mangle::class_type_descriptor(class);
+        let type_descriptor_class_size = match self.layouts[class].type_descriptor_len() {
+            Some(size) => size,
+            None => return,
+        };
+
+        // Reserve n words in BSS section for class type descriptor
+        self.bss
+            .insert(type_descriptor_class, (linkage, type_descriptor_class_size));
+
+        let virtual_table_class = abi::mangle::class_virtual_table(class);
+        let protocols = self.layouts[class]
+            .protocols()
+            .map(|(protocol, offset)| (*protocol, *offset))
+            .collect::<Vec<_>>();
+
+        #[rustfmt::skip]
+        let superclass = self
+            .context
+            .get_superclass(class)
+            .map(|superclass| hir!((NAME abi::mangle::class_type_descriptor(&superclass))))
+            .unwrap_or_else(|| hir!((CONST 0)));
+
+        // Point the root class's private virtual table slot at the type descriptor
+        statements.extend([
+            hir!((MOVE (MEM (NAME virtual_table_class)) (NAME type_descriptor_class))),
+            hir!((MOVE (MEM (NAME type_descriptor_class)) superclass)),
+            hir!((MOVE
+                (MEM (ADD (NAME type_descriptor_class) (CONST abi::WORD)))
+                (CONST protocols.len() as i64))),
+        ]);
+
+        for (index, (protocol, offset)) in protocols.into_iter().enumerate() {
+            let identifier = abi::mangle::protocol_identifier(&protocol);
+
+            // Reserve 1 word in BSS section, whose address identifies the interface
+            self.bss.insert(identifier, (ir::Linkage::LinkOnceOdr, 1));
+
+            statements.extend([
+                hir!((MOVE
+                    (MEM (ADD (NAME type_descriptor_class) (CONST (index * 2 + 2) as i64 * abi::WORD)))
+                    (NAME identifier))),
+                hir!((MOVE
+                    (MEM (ADD (NAME type_descriptor_class) (CONST (index * 2 + 3) as i64 * abi::WORD)))
+                    (ADD (NAME type_descriptor_class) (CONST offset as i64 * abi::WORD)))),
+            ]);
+
+            let methods = self
+                .context
+                .get_class(&protocol)
+                .unwrap()
+                .iter()
+                .map(|(identifier, _)| identifier.symbol)
+                .collect::<Vec<_>>();
+
+            for (index, method) in methods.into_iter().enumerate() {
+                let implementation = match self.layouts[class].method_index(&method) {
+                    Some(index) => hir!(
+                        (MEM (ADD (NAME virtual_table_class) (CONST index as i64 * abi::WORD)))
+                    ),
+                    // Methods without a virtual table entry are dispatched statically,
+                    // so they must be implemented by this class (see `crate::abi::class`).
+                    None => {
+                        let (parameters, returns) =
+                            match self.context.get(GlobalScope::Class(*class), &method) {
+                                Some(
+                                    Entry::Function(parameters, returns)
+                                    | Entry::Signature(parameters, returns),
+                                ) => (parameters, returns),
+                                Some(Entry::Variable(_)) | None => {
+                                    panic!("[TYPE ERROR]: unbound method")
+                                }
+                            };
+
+                        hir!((NAME abi::mangle::method(class, &method, parameters, returns)))
+                    }
+                };
+
+                statements.push(hir!(
+                    (MOVE
+                        (MEM (ADD (NAME type_descriptor_class) (CONST (offset + index) as i64 * abi::WORD)))
+                        implementation)
+                ));
+            }
+        }
+    }
+
     fn emit_function(
         &mut self,
         scope: GlobalScope,
@@ -385,7 +586,10 @@ impl<'env> Emitter<'env> {
         linkage: ir::Linkage,
     ) -> (Symbol, hir::Function) {
         self.locals.clear();
-        self.out_of_bounds.take();
+        self.variables.clear();
+        self.out_of_bounds.clear();
+        self.loops.clear();
+        self.unwind = None;
 
         let mut statements = Vec::new();
 
@@ -401,6 +605,7 @@ impl<'env> Emitter<'env> {
         };
 
         let returns = returns.to_vec();
+        self.function = name;
         let argument_offset = match scope {
             GlobalScope::Global => 0,
             GlobalScope::Class(_) => 1,
@@ -426,10 +631,41 @@ impl<'env> Emitter<'env> {
             },
         };
 
+        let main = name == symbol::intern_static(abi::XI_MAIN);
+
+        // Exceptions thrown by global initializers are pending on entry to `main`
+        if main {
+            let handler = self.handler();
+            let ok = Label::fresh("ok");
+            statements.push(hir!(
+                (CJUMP (NE (MEM (NAME abi::XI_EXCEPTION)) (CONST 0)) handler ok)
+            ));
+            statements.push(hir!((LABEL ok)));
+        }
+
         self.context.push(scope);
         statements.push(self.emit_statement(&function.statements));
         self.context.pop();
 
+        if let Some(unwind) = self.unwind.take() {
+            match main {
+                true => {
+                    statements.push(hir!((RETURN)));
+                    statements.push(hir!((LABEL unwind)));
+                    statements.push(self.emit_uncaught());
+                }
+                false => {
+                    statements.push(hir!((LABEL unwind)));
+                    statements.push(hir::Statement::Return(vec![
+                        hir!((CONST 0));
+                        function.returns.len()
+                    ]));
+                }
+            }
+        }
+
+        statements.splice(0..0, self.emit_prologue(function.span));
+
         (
             name,
             hir::Function {
@@ -437,27 +673,138 @@ impl<'env> Emitter<'env> {
                 statement: hir::Statement::Sequence(statements),
                 arguments,
                 returns: function.returns.len(),
-                linkage: if name == symbol::intern_static(abi::XI_MAIN) {
-                    ir::Linkage::Global
-                } else {
-                    linkage
-                },
+                linkage: if main { ir::Linkage::Global } else { linkage },
             },
         )
     }
 
+    /// Emits the landing pad for exceptions that escape `main`, which reports
+    /// them to the runtime instead of returning.
+    fn emit_uncaught(&mut self) -> hir::Statement {
+        let exception = Temporary::fresh("exception");
+        let out_of_bounds = Label::fresh("out_of_bounds");
+        let uncaught = Label::fresh("uncaught");
+
+        let test = self.emit_class_test(
+            hir!((TEMP exception)),
+            &r#type::Expression::Class(symbol::intern_static(abi::OUT_OF_BOUNDS)),
+        );
+
+        // Note: bounds failures keep their original runtime behavior
+        hir!(
+            (SEQ
+                (MOVE (TEMP exception) (MEM (NAME abi::XI_EXCEPTION)))
+                (CJUMP (NE test (CONST 0)) out_of_bounds uncaught)
+                (LABEL out_of_bounds)
+                (EXP (CALL (NAME abi::XI_OUT_OF_BOUNDS) (Vec::new())))
+                (LABEL uncaught)
+                (EXP (CALL (NAME abi::XI_UNCAUGHT) (Vec::new()) (TEMP exception)))
+                (RETURN))
+        )
+    }
+
+    /// Returns the label that exceptions thrown by the current statement jump to.
+    fn handler(&mut self) -> Label {
+        match self.tries.last() {
+            Some(r#try) => r#try.handler,
+            None => *self.unwind.get_or_insert_with(|| Label::fresh("unwind")),
+        }
+    }
+
+    /// Emits a jump to the current handler if `call` left an exception pending.
+    fn emit_exception_check(&mut self, call: &hir::Expression) -> hir::Statement {
+        let handler = self.handler();
+        let ok = Label::fresh("ok");
+
+        if let hir::Expression::Call(function, _, _) = call {
+            if let hir::Expression::Immediate(Immediate::Label(Label::Fixed(name))) = &**function {
+                self.checks.insert(ok, *name);
+            }
+        }
+
+        hir!(
+            (SEQ
+                (CJUMP (NE (MEM (NAME abi::XI_EXCEPTION)) (CONST 0)) handler ok)
+                (LABEL ok))
+        )
+    }
+
+    /// Emits the `finally` blocks of the enclosing `try` statements nested at least
+    /// `depth` deep, from innermost to outermost, as control leaves them.
+    fn emit_finally(&mut self, depth: usize) -> Vec<hir::Statement> {
+        let mut statements = Vec::new();
+
+        for index in (depth..self.tries.len()).rev() {
+            let finally = match &self.tries[index].finally {
+                Some(finally) => finally.clone(),
+                None => continue,
+            };
+
+            // Each block runs outside of its own `try` statement
+            let inner = self.tries.split_off(index);
+            statements.push(self.emit_statement(&finally));
+            self.tries.extend(inner);
+        }
+
+        statements
+    }
+
     fn emit_statement(&mut self, statement: &ast::Statement<r#type::Expression>) -> hir::Statement {
+        let emitted = self.emit_statement_unlocated(statement);
+        match statement {
+            // Each statement in the block is located separately
+            ast::Statement::Sequence(_, _) => emitted,
+            _ => self.emit_location(statement.span(), emitted),
+        }
+    }
+
+    /// Marks `statement` as originating from `span`, if keeping debugging information.
+    fn emit_location(&self, span: Span, statement: hir::Statement) -> hir::Statement {
+        match self.debug {
+            DebugInfo::Omit => statement,
+            DebugInfo::Keep => hir::Statement::Sequence(vec![
+                hir::Statement::Debug(Debug::Location(span)),
+                statement,
+            ]),
+        }
+    }
+
+    /// Emits the location of the current function and its variables, if keeping
+    /// debugging information.
+    fn emit_prologue(&mut self, span: Span) -> Vec<hir::Statement> {
+        match self.debug {
+            DebugInfo::Omit => Vec::new(),
+            DebugInfo::Keep => iter::once(hir::Statement::Debug(Debug::Location(span)))
+                .chain(self.variables.drain(..))
+                .collect(),
+        }
+    }
+
+    fn emit_statement_unlocated(
+        &mut self,
+        statement: &ast::Statement<r#type::Expression>,
+    ) -> hir::Statement {
         use ast::Statement::*;
         match statement {
-            #[rustfmt::skip]
-            Assignment(left, right, _) => {
-                hir!(
+            Assignment(left, right, _) => match &**left {
+                ast::Expression::Index(array, index, r#type, _) if packed(r#type).is_some() => {
+                    let (_, size) = packed(r#type).unwrap();
+                    let (statement, address) = self.emit_index(array, index, size.bytes());
+                    let right = self.emit_expression(right).into();
+                    hir!((SEQ statement (library::emit_store(size, address, right))))
+                }
+                #[rustfmt::skip]
+                _ => hir!(
                     (MOVE
                         (self.emit_expression(left).into())
                         (self.emit_expression(right).into()))
-                )
+                ),
+            },
+            Call(call) => {
+                let call = self.emit_call(call);
+                let check = self.emit_exception_check(&call);
+                hir::Statement::Sequence(vec![hir::Statement::Expression(call), check])
             }
-            Call(call) => hir::Statement::Expression(self.emit_call(call)),
             Initialization(initialization) => {
                 self.emit_initialization(Scope::Local, initialization)
             }
@@ -467,12 +814,33 @@ impl<'env> Emitter<'env> {
                 None => hir!((EXP (CONST 0))),
                 Some(statement) => statement,
             },
-            Return(expressions, _) => hir::Statement::Return(
-                expressions
+            Return(expressions, _) => {
+                let expressions = expressions
                     .iter()
                     .map(|expression| self.emit_expression(expression).into())
-                    .collect(),
-            ),
+                    .collect::<Vec<_>>();
+
+                if self.tries.iter().all(|r#try| r#try.finally.is_none()) {
+                    return hir::Statement::Return(expressions);
+                }
+
+                // Return values are evaluated before any `finally` blocks run
+                let returns = Temporary::fresh_returns(expressions.len());
+                let mut statements = returns
+                    .iter()
+                    .zip(expressions)
+                    .map(|(r#return, expression)| hir!((MOVE (TEMP *r#return) expression)))
+                    .collect::<Vec<_>>();
+
+                statements.extend(self.emit_finally(0));
+                statements.push(hir::Statement::Return(
+                    returns
+                        .iter()
+                        .map(|r#return| hir!((TEMP * r#return)))
+                        .collect(),
+                ));
+                hir::Statement::Sequence(statements)
+            }
             Sequence(statements, _) => hir::Statement::Sequence(
                 statements
                     .iter()
@@ -507,7 +875,7 @@ impl<'env> Emitter<'env> {
                         (LABEL endif))
                 )
             }
-            While(r#do, condition, statement, _) => {
+            While(r#do, label, condition, statement, _) => {
                 let r#while = Label::fresh("while");
                 let r#true = Label::fresh("true");
                 let r#false = Label::fresh("false");
@@ -521,16 +889,18 @@ impl<'env> Emitter<'env> {
                     self.emit_expression(&condition.negate_logical()),
                 )(r#true, r#false);
 
-                self.context.push(LocalScope::While(Some(r#true)));
-                let statement = self.emit_statement(statement);
-                self.context.pop();
-
                 match r#do {
                     ast::Do::Yes => {
+                        // Note: `continue` must re-evaluate the condition before looping
+                        let r#continue = Label::fresh("continue");
+
+                        let statement = self.emit_loop(label, (r#true, r#continue), statement);
+
                         hir!(
                             (SEQ
                                 (LABEL r#while)
                                 statement
+                                (LABEL r#continue)
                                 condition
                                 (LABEL r#false)
                                 (JUMP r#while)
@@ -538,6 +908,8 @@ impl<'env> Emitter<'env> {
                         )
                     }
                     ast::Do::No => {
+                        let statement = self.emit_loop(label, (r#true, r#while), statement);
+
                         hir!(
                             (SEQ
                                 (LABEL r#while)
@@ -550,13 +922,190 @@ impl<'env> Emitter<'env> {
                     }
                 }
             }
-            Break(_) => {
-                let label = self.context.get_scoped_while().flatten().unwrap();
-                hir!((JUMP label))
+            For(r#for) => {
+                let head = Label::fresh("for");
+                let r#true = Label::fresh("true");
+                let r#false = Label::fresh("false");
+                let r#continue = Label::fresh("continue");
+
+                let mut statements = Vec::new();
+
+                if let Some(initialization) = &r#for.initialization {
+                    statements.push(self.emit_statement(initialization));
+                }
+
+                statements.push(hir!((LABEL head)));
+
+                // Note: see `While` above for why the condition is negated
+                if let Some(condition) = &r#for.condition {
+                    statements.push(hir::Condition::from(
+                        self.emit_expression(&condition.negate_logical()),
+                    )(r#true, r#false));
+                    statements.push(hir!((LABEL r#false)));
+                }
+
+                statements.push(self.emit_loop(
+                    &r#for.label,
+                    (r#true, r#continue),
+                    &r#for.statements,
+                ));
+                statements.push(hir!((LABEL r#continue)));
+
+                if let Some(update) = &r#for.update {
+                    statements.push(self.emit_statement(update));
+                }
+
+                statements.push(hir!((JUMP head)));
+                statements.push(hir!((LABEL r#true)));
+                hir::Statement::Sequence(statements)
+            }
+            Match(r#match) => {
+                let (mut statements, exit) = self.emit_match(r#match, |emitter, statement| {
+                    emitter.emit_statement(statement)
+                });
+                statements.push(hir!((LABEL exit)));
+                hir::Statement::Sequence(statements)
             }
+            Break(label, _) => {
+                let (r#break, _) = self.get_scoped_while(label);
+                let mut statements = self.emit_finally(self.loops[&r#break]);
+                statements.push(hir!((JUMP r#break)));
+                hir::Statement::Sequence(statements)
+            }
+            Continue(label, _) => {
+                let (r#break, r#continue) = self.get_scoped_while(label);
+                let mut statements = self.emit_finally(self.loops[&r#break]);
+                statements.push(hir!((JUMP r#continue)));
+                hir::Statement::Sequence(statements)
+            }
+            Throw(expression, _) => {
+                let exception = Temporary::fresh("exception");
+                let null = Label::fresh("null");
+                let thrown = Label::fresh("thrown");
+                let handler = self.handler();
+
+                hir!(
+                    (SEQ
+                        (MOVE (TEMP exception) (self.emit_expression(expression).into()))
+                        (CJUMP (EQ (TEMP exception) (CONST 0)) null thrown)
+                        (LABEL null)
+                        (EXP (CALL (NAME abi::XI_UNCAUGHT) (Vec::new()) (TEMP exception)))
+                        (LABEL thrown)
+                        (MOVE (MEM (NAME abi::XI_EXCEPTION)) (TEMP exception))
+                        (JUMP handler))
+                )
+            }
+            Try(r#try) => self.emit_try(r#try),
+            // Note: programs with syntax errors never reach emission
+            Error(_) => unreachable!(),
         }
     }
 
+    /// Exceptions propagate through a pending exception word, which is set by `throw`
+    /// and checked after every call. Each `try` statement installs a handler label that
+    /// tests the pending exception against each `catch` clause in order. Unmatched
+    /// exceptions run the `finally` block before propagating to the enclosing handler.
+    fn emit_try(&mut self, r#try: &ast::Try<r#type::Expression>) -> hir::Statement {
+        let enclosing = self.handler();
+        let caught = Label::fresh("caught");
+        let done = Label::fresh("done");
+        let rethrow = r#try.finally.as_ref().map(|_| Label::fresh("rethrow"));
+
+        self.tries.push(Handler {
+            handler: caught,
+            finally: r#try.finally.clone(),
+        });
+        let mut statements = vec![self.emit_statement(&r#try.statements)];
+        self.tries.pop();
+
+        let exception = Temporary::fresh("exception");
+
+        statements.extend([
+            hir!((JUMP done)),
+            hir!((LABEL caught)),
+            hir!((MOVE (TEMP exception) (MEM (NAME abi::XI_EXCEPTION)))),
+        ]);
+
+        for catch in &r#try.catches {
+            let r#type = catch.declaration.r#type.r#type();
+            let matched = Label::fresh("matched");
+            let next = Label::fresh("next");
+
+            // Every exception is an instance of the base class
+            if r#type != r#type::Expression::Class(symbol::intern_static(abi::EXCEPTION)) {
+                let test = self.emit_class_test(hir!((TEMP exception)), &r#type);
+                statements.push(hir!((CJUMP (NE test (CONST 0)) matched next)));
+                statements.push(hir!((LABEL matched)));
+            }
+
+            #[rustfmt::skip]
+            statements.extend([
+                hir!((MOVE (MEM (NAME abi::XI_EXCEPTION)) (CONST 0))),
+                hir!((MOVE
+                    (self.emit_single_declaration(Scope::Local, &catch.declaration.name, &catch.declaration.r#type))
+                    (TEMP exception))),
+            ]);
+
+            self.tries.push(Handler {
+                handler: rethrow.unwrap_or(enclosing),
+                finally: r#try.finally.clone(),
+            });
+            statements.push(self.emit_statement(&catch.statements));
+            self.tries.pop();
+
+            statements.push(hir!((JUMP done)));
+            statements.push(hir!((LABEL next)));
+        }
+
+        match (rethrow, &r#try.finally) {
+            (Some(rethrow), Some(finally)) => {
+                let pending = Temporary::fresh("pending");
+
+                statements.extend([
+                    hir!((JUMP rethrow)),
+                    hir!((LABEL rethrow)),
+                    hir!((MOVE (TEMP pending) (MEM (NAME abi::XI_EXCEPTION)))),
+                    hir!((MOVE (MEM (NAME abi::XI_EXCEPTION)) (CONST 0))),
+                    self.emit_statement(finally),
+                    hir!((MOVE (MEM (NAME abi::XI_EXCEPTION)) (TEMP pending))),
+                    hir!((JUMP enclosing)),
+                    hir!((LABEL done)),
+                    self.emit_statement(finally),
+                ]);
+            }
+            _ => {
+                statements.push(hir!((JUMP enclosing)));
+                statements.push(hir!((LABEL done)));
+            }
+        }
+
+        hir::Statement::Sequence(statements)
+    }
+
+    /// Emits the body of a loop, where `break` jumps to `targets.0` and `continue` to `targets.1`.
+    fn emit_loop(
+        &mut self,
+        label: &Option<ast::Identifier>,
+        targets: (Label, Label),
+        statement: &ast::Statement<r#type::Expression>,
+    ) -> hir::Statement {
+        self.loops.insert(targets.0, self.tries.len());
+        self.context.push(LocalScope::While {
+            label: label.as_ref().map(|label| label.symbol),
+            targets: Some(targets),
+        });
+        let statement = self.emit_statement(statement);
+        self.context.pop();
+        statement
+    }
+
+    fn get_scoped_while(&self, label: &Option<ast::Identifier>) -> (Label, Label) {
+        self.context
+            .get_scoped_while(label.as_ref().map(|label| label.symbol))
+            .flatten()
+            .expect("[TYPE ERROR]: `break` or `continue` outside of loop")
+    }
+
     fn emit_expression(&mut self, expression: &ast::Expression<r#type::Expression>) -> hir::Tree {
         use ast::Expression::*;
         match expression {
@@ -593,6 +1142,17 @@ impl<'env> Emitter<'env> {
                         .into();
                 }
 
+                if let Some((parameters, returns)) =
+                    self.get_signature(GlobalScope::Global, &variable.name)
+                {
+                    let function =
+                        abi::mangle::function(&variable.name.symbol, parameters, returns);
+                    let (parameters, returns) = (parameters.len(), returns.len());
+                    return self
+                        .emit_closure_adapter(function, parameters, returns)
+                        .into();
+                }
+
                 let class = self
                     .context
                     .get_scoped_class()
@@ -605,6 +1165,36 @@ impl<'env> Emitter<'env> {
                 )
                 .into()
             }
+            Array(expressions, r#type::Expression::Array(r#type), _)
+                if packed(r#type).is_some() =>
+            {
+                let (_, size) = packed(r#type).unwrap();
+                let array = hir!((TEMP Temporary::fresh("array")));
+
+                let mut statements = vec![
+                    hir!(
+                        (MOVE
+                            (TEMP array.clone())
+                            (CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (library::emit_array_size(size, hir!((CONST expressions.len() as i64))))))
+                    ),
+                    hir!((MOVE (MEM (array.clone())) (CONST expressions.len() as i64))),
+                ];
+
+                for (index, expression) in expressions.iter().enumerate() {
+                    let expression = self.emit_expression(expression).into();
+                    statements.push(library::emit_store(
+                        size,
+                        hir!((ADD (TEMP array.clone()) (CONST abi::WORD + index as i64 * size.bytes()))),
+                        expression,
+                    ));
+                }
+
+                hir!(
+                    (ESEQ
+                        (hir::Statement::Sequence(statements))
+                        (ADD (TEMP array) (CONST abi::WORD))))
+                .into()
+            }
             Array(expressions, _, _) => {
                 if let Some(label) = self.emit_static_array(expressions) {
                     return hir!((ADD (CALL (NAME abi::XI_MEMDUP) (Temporary::fresh_returns(1)) (NAME label)) (CONST abi::WORD))).into();
@@ -636,13 +1226,25 @@ impl<'env> Emitter<'env> {
                         (ADD (TEMP array) (CONST abi::WORD))))
                 .into()
             }
-            Binary(binary, left, right, _, _) => {
+            Binary(binary, left, right, r#type, _) => {
+                let unsigned = is_unsigned(left, right);
                 let left = self.emit_expression(left);
                 let right = self.emit_expression(right);
 
                 match binary {
                     ast::Binary::Cat => {
-                        hir!((CALL (NAME abi::XI_CONCAT) (Temporary::fresh_returns(1)) (left.into()) (right.into()))).into()
+                        let size = match r#type {
+                            r#type::Expression::Array(r#type) => {
+                                packed(r#type).map_or(Size::_64, |(_, size)| size)
+                            }
+                            _ => unreachable!(),
+                        };
+
+                        if size != Size::_64 {
+                            self.concats.insert(size);
+                        }
+
+                        hir!((CALL (NAME abi::concat(size)) (Temporary::fresh_returns(1)) (left.into()) (right.into()))).into()
                     }
                     ast::Binary::Mul
                     | ast::Binary::Hul
@@ -650,8 +1252,31 @@ impl<'env> Emitter<'env> {
                     | ast::Binary::Div
                     | ast::Binary::Add
                     | ast::Binary::Sub => {
-                        let binary = ir::Binary::from(*binary);
-                        hir!((binary(left.into())(right.into()))).into()
+                        let (signed, size) = r#type
+                            .integer()
+                            .expect("[TYPE ERROR]: arithmetic on non-integer type");
+
+                        let expression = match (binary, signed, size) {
+                            // Narrow high multiplication: the full product fits in a word,
+                            // so we shift it right by `size` bits using a 64-bit high multiplication.
+                            (ast::Binary::Hul, signed, size) if size < Size::_64 => {
+                                let hul = if signed {
+                                    ir::Binary::Hul
+                                } else {
+                                    ir::Binary::UHul
+                                };
+                                hir!((hul (MUL (left.into()) (right.into())) (CONST 1 << (64 - size.bits()))))
+                            }
+                            (ast::Binary::Hul, false, _) => hir!((UHUL(left.into())(right.into()))),
+                            (ast::Binary::Div, false, _) => hir!((UDIV(left.into())(right.into()))),
+                            (ast::Binary::Mod, false, _) => hir!((UMOD(left.into())(right.into()))),
+                            (binary, _, _) => {
+                                let binary = ir::Binary::from(*binary);
+                                hir!((binary(left.into())(right.into())))
+                            }
+                        };
+
+                        emit_normalize(r#type, expression).into()
                     }
 
                     ast::Binary::Lt
@@ -660,7 +1285,11 @@ impl<'env> Emitter<'env> {
                     | ast::Binary::Gt
                     | ast::Binary::Ne
                     | ast::Binary::Eq => {
-                        let condition = ir::Condition::from(*binary);
+                        let condition = match unsigned {
+                            true => ir::Condition::from(*binary).unsigned(),
+                            false => ir::Condition::from(*binary),
+                        };
+
                         hir::Tree::Condition(Box::new(move |r#true, r#false| {
                             hir!(
                                 (CJUMP (condition (left.into()) (right.into())) r#true r#false)
@@ -689,92 +1318,341 @@ impl<'env> Emitter<'env> {
                 }
             }
 
-            Unary(ast::Unary::Neg, expression, _, _) => {
+            Unary(ast::Unary::Neg, expression, r#type, _) => {
                 let expression = self.emit_expression(expression).into();
-                hir!((SUB (CONST 0) expression)).into()
+                emit_normalize(r#type, hir!((SUB (CONST 0) expression))).into()
             }
             Unary(ast::Unary::Not, expression, _, _) => {
                 let expression = self.emit_expression(expression).into();
                 hir!((XOR (CONST 1) expression)).into()
             }
 
-            Index(array, array_index, _, _) => {
-                let base = Temporary::fresh("base");
-                let index = Temporary::fresh("index");
+            Index(array, index, r#type, _) => match packed(r#type) {
+                None => {
+                    let (statement, address) = self.emit_index(array, index, abi::WORD);
+                    hir!((ESEQ statement (MEM address))).into()
+                }
+                Some((signed, size)) => {
+                    let (statement, address) = self.emit_index(array, index, size.bytes());
+                    hir!((ESEQ statement (library::emit_load(signed, size, address)))).into()
+                }
+            },
+            Length(array, _) => {
+                let address = self.emit_expression(array).into();
+                hir!((MEM (SUB address (CONST abi::WORD)))).into()
+            }
+            Cast(r#type, expression, _) => {
+                let from = expression.r#type();
+                let to = r#type.r#type();
+                let expression = hir::Expression::from(self.emit_expression(expression));
+                match from.widens_to(&to) {
+                    true => expression.into(),
+                    false => emit_normalize(&to, expression).into(),
+                }
+            }
+            InstanceOf(expression, r#type, _) => {
+                let from = expression.r#type();
+                let to = r#type.r#type();
+                let instance = hir::Expression::from(self.emit_expression(expression));
+
+                // Only null fails a test that's known statically
+                if self.context.is_subtype(&from, &to) {
+                    return hir::Tree::Condition(Box::new(
+                        move |r#true, r#false| hir!((CJUMP (NE instance (CONST 0)) r#true r#false)),
+                    ));
+                }
 
-                let r#in = Label::fresh("in");
+                self.emit_class_test(instance, &to).into()
+            }
+            As(expression, r#type, _) => {
+                let from = expression.r#type();
+                let to = r#type.r#type();
+                let instance = hir::Expression::from(self.emit_expression(expression));
 
-                let mut statements = vec![
-                    hir!((MOVE (TEMP base) (self.emit_expression(&*array).into()))),
-                    hir!((MOVE (TEMP index) (self.emit_expression(&*array_index).into()))),
-                ];
+                if self.context.is_subtype(&from, &to) {
+                    return instance.into();
+                }
 
-                // Ensure we only emit one out of bounds block per function
-                match self.out_of_bounds.get() {
-                    Some(out) => {
-                        statements.extend([
-                            hir!((CJUMP (AE (TEMP index) (MEM (SUB (TEMP base) (CONST abi::WORD)))) out r#in)),
-                        ]);
-                    }
-                    None => {
-                        let out = Label::fresh("out");
-                        self.out_of_bounds.set(Some(out));
-
-                        statements.extend([
-                            hir!((CJUMP (AE (TEMP index) (MEM (SUB (TEMP base) (CONST abi::WORD)))) out r#in)),
-                            hir!((LABEL out)),
-                            hir!((EXP (CALL (NAME abi::XI_OUT_OF_BOUNDS) (Vec::new())))),
-                            // In order to (1) minimize special logic for `XI_OUT_OF_BOUNDS` and (2) still
-                            // treat it correctly in dataflow analyses as an exit site, we put this dummy
-                            // return statement here.
-                            //
-                            // The number of returns must match the rest of the function, so return values
-                            // are defined along all paths to the exit.
-                            hir!((hir::Statement::Return(vec![hir!((CONST 0)); self.context.get_scoped_returns().unwrap().len()]))),
-                        ]);
-                    }
-                };
+                let converted = Temporary::fresh("converted");
+                let test = self.emit_class_test(hir!((TEMP converted)), &to);
 
-                statements.push(hir!((LABEL r#in)));
+                let check = Label::fresh("check");
+                let fail = Label::fresh("fail");
+                let done = Label::fresh("done");
 
                 hir!(
                     (ESEQ
-                        (hir::Statement::Sequence(statements))
-                        (MEM (ADD (TEMP base) (MUL (TEMP index) (CONST abi::WORD)))))
+                        (SEQ
+                            (MOVE (TEMP converted) instance)
+                            // Like a failed test, converting null produces null
+                            (CJUMP (EQ (TEMP converted) (CONST 0)) done check)
+                            (LABEL check)
+                            (CJUMP (NE test (CONST 0)) done fail)
+                            (LABEL fail)
+                            (EXP (CALL (NAME abi::XI_BAD_CAST) (Vec::new())))
+                            (LABEL done))
+                        (TEMP converted))
                 )
                 .into()
             }
-            Length(array, _) => {
-                let address = self.emit_expression(array).into();
-                hir!((MEM (SUB address (CONST abi::WORD)))).into()
-            }
             Dot(receiver, field, _, _) => self.emit_class_field(receiver, &field.symbol).into(),
             New(variable, _) => {
                 assert!(variable.generics.is_none());
+                self.emit_new(&variable.name.symbol).into()
+            }
+            Call(call) => {
+                let call = self.emit_call(call);
+                let check = self.emit_exception_check(&call);
+                let result = Temporary::fresh("result");
+                hir!(
+                    (ESEQ
+                        (SEQ
+                            (MOVE (TEMP result) call)
+                            check)
+                        (TEMP result))
+                )
+                .into()
+            }
+            Closure(closure) => self.emit_closure(closure).into(),
+            Construct(construct) => {
+                let (tag, _) = self
+                    .context
+                    .get_variant(&construct.r#enum.symbol, &construct.variant)
+                    .expect("[TYPE ERROR]: unbound variant");
 
-                let class_size = abi::mangle::class_size(&variable.name.symbol);
+                let variant = Temporary::fresh("variant");
 
-                match self.layouts[&variable.name.symbol].virtual_table_len() {
-                    None => hir!((CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (MEM (NAME class_size)))).into(),
-                    Some(_) => {
-                        let new = Temporary::fresh("new");
-                        let virtual_table = abi::mangle::class_virtual_table(&variable.name.symbol);
-                        hir!(
-                            (ESEQ
-                                (SEQ
-                                    (MOVE (TEMP new) (CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (MEM (NAME class_size))))
-                                    (MOVE (MEM (TEMP new)) (NAME virtual_table)))
-                                (TEMP new))
-                        )
-                        .into()
+                let mut statements = vec![
+                    hir!(
+                        (MOVE
+                            (TEMP variant)
+                            (CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (CONST (construct.arguments.len() + 1) as i64 * abi::WORD)))
+                    ),
+                    hir!((MOVE (MEM (TEMP variant)) (CONST tag as i64))),
+                ];
+
+                for (index, argument) in construct.arguments.iter().enumerate() {
+                    let argument = self.emit_expression(argument).into();
+                    statements.push(hir!(
+                        (MOVE
+                            (MEM (ADD (TEMP variant) (CONST (index + 1) as i64 * abi::WORD)))
+                            argument)
+                    ));
+                }
+
+                hir!((ESEQ (hir::Statement::Sequence(statements)) (TEMP variant))).into()
+            }
+            Match(r#match, _) => {
+                let result = Temporary::fresh("match");
+                let (mut statements, exit) = self.emit_match(r#match, |emitter, expression| {
+                    hir!((MOVE (TEMP result) (emitter.emit_expression(expression).into())))
+                });
+                statements.push(hir!((LABEL exit)));
+                hir!((ESEQ (hir::Statement::Sequence(statements)) (TEMP result))).into()
+            }
+        }
+    }
+
+    fn emit_new(&mut self, class: &Symbol) -> hir::Expression {
+        let class_size = abi::mangle::class_size(class);
+
+        match self.layouts[class].virtual_table_len() {
+            None => {
+                hir!((CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (MEM (NAME class_size))))
+            }
+            Some(_) => {
+                let new = Temporary::fresh("new");
+                let virtual_table = abi::mangle::class_virtual_table(class);
+                hir!(
+                    (ESEQ
+                        (SEQ
+                            (MOVE (TEMP new) (CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (MEM (NAME class_size))))
+                            (MOVE (MEM (TEMP new)) (NAME virtual_table)))
+                        (TEMP new))
+                )
+            }
+        }
+    }
+
+    /// Emits each arm of `r#match` in order, where `emit_body` emits the body of an arm
+    /// whose pattern matched. Returns the emitted arms and the label that each body
+    /// jumps to when done, which the caller must place after them.
+    fn emit_match<B, F>(
+        &mut self,
+        r#match: &ast::Match<r#type::Expression, B>,
+        mut emit_body: F,
+    ) -> (Vec<hir::Statement>, Label)
+    where
+        F: FnMut(&mut Self, &B) -> hir::Statement,
+    {
+        let scrutinee = Temporary::fresh("scrutinee");
+        let exit = Label::fresh("exit");
+
+        let mut statements = vec![hir!(
+            (MOVE (TEMP scrutinee) (self.emit_expression(&r#match.scrutinee).into()))
+        )];
+
+        // Note: the type checker guarantees that some arm matches, so control
+        // never falls through the last arm's `next` label.
+        for arm in &r#match.arms {
+            let next = Label::fresh("next");
+            statements.push(self.emit_pattern(&arm.pattern, scrutinee, next));
+            statements.push(emit_body(self, &arm.body));
+            statements.push(hir!((JUMP exit)));
+            statements.push(hir!((LABEL next)));
+        }
+
+        (statements, exit)
+    }
+
+    /// Emits a test of whether `value` matches `pattern`, binding its variables and
+    /// falling through if so, or jumping to `next` otherwise.
+    ///
+    /// Enum values point to a tag word holding the variant's index, followed by one
+    /// word per payload field.
+    fn emit_pattern(
+        &mut self,
+        pattern: &ast::Pattern<r#type::Expression>,
+        value: Temporary,
+        next: Label,
+    ) -> hir::Statement {
+        let test = |constant: i64| {
+            let matched = Label::fresh("matched");
+            hir!(
+                (SEQ
+                    (CJUMP (EQ (TEMP value) (CONST constant)) matched next)
+                    (LABEL matched))
+            )
+        };
+
+        match pattern {
+            ast::Pattern::Wildcard(_) => hir::Statement::Sequence(Vec::new()),
+            ast::Pattern::Binding(name, _) => {
+                let fresh = Temporary::fresh("binding");
+                self.locals.insert(name.symbol, fresh);
+                hir!((MOVE (TEMP fresh) (TEMP value)))
+            }
+            ast::Pattern::Boolean(boolean, _) => test(*boolean as i64),
+            ast::Pattern::Integer(integer, _) => test(*integer),
+            ast::Pattern::Character(character, _) => test(*character as i64),
+            ast::Pattern::Variant(variant, patterns, r#type, _) => {
+                let (tag, _) = match r#type {
+                    r#type::Expression::Class(r#enum) => self
+                        .context
+                        .get_variant(r#enum, variant)
+                        .expect("[TYPE ERROR]: unbound variant"),
+                    _ => unreachable!("[TYPE ERROR]: variant pattern on non-enum type"),
+                };
+
+                let matched = Label::fresh("matched");
+                let mut statements = vec![
+                    hir!((CJUMP (EQ (MEM (TEMP value)) (CONST tag as i64)) matched next)),
+                    hir!((LABEL matched)),
+                ];
+
+                for (index, pattern) in patterns.iter().enumerate() {
+                    if let ast::Pattern::Wildcard(_) = pattern {
+                        continue;
                     }
+
+                    let field = Temporary::fresh("field");
+                    statements.push(hir!(
+                        (MOVE
+                            (TEMP field)
+                            (MEM (ADD (TEMP value) (CONST (index + 1) as i64 * abi::WORD))))
+                    ));
+                    statements.push(self.emit_pattern(pattern, field, next));
                 }
+
+                hir::Statement::Sequence(statements)
+            }
+        }
+    }
+
+    /// Emits a bounds check, and returns the address of the element at `array_index`
+    /// in `array`, whose elements are each `bytes` wide.
+    fn emit_index(
+        &mut self,
+        array: &ast::Expression<r#type::Expression>,
+        array_index: &ast::Expression<r#type::Expression>,
+        bytes: i64,
+    ) -> (hir::Statement, hir::Expression) {
+        let base = Temporary::fresh("base");
+        let index = Temporary::fresh("index");
+
+        let r#in = Label::fresh("in");
+
+        let mut statements = vec![
+            hir!((MOVE (TEMP base) (self.emit_expression(array).into()))),
+            hir!((MOVE (TEMP index) (self.emit_expression(array_index).into()))),
+        ];
+
+        // Ensure we only emit one out of bounds block per handler
+        let handler = self.handler();
+        match self.out_of_bounds.get(&handler).copied() {
+            Some(out) => {
+                statements.extend([
+                    hir!((CJUMP (AE (TEMP index) (MEM (SUB (TEMP base) (CONST abi::WORD)))) out r#in)),
+                ]);
             }
-            Call(call) => self.emit_call(call).into(),
+            None => {
+                let out = Label::fresh("out");
+                self.out_of_bounds.insert(handler, out);
+
+                let exception = self.emit_new(&symbol::intern_static(abi::OUT_OF_BOUNDS));
+
+                statements.extend([
+                    hir!((CJUMP (AE (TEMP index) (MEM (SUB (TEMP base) (CONST abi::WORD)))) out r#in)),
+                    hir!((LABEL out)),
+                    hir!((MOVE (MEM (NAME abi::XI_EXCEPTION)) exception)),
+                    hir!((JUMP handler)),
+                ]);
+            }
+        };
+
+        statements.push(hir!((LABEL r#in)));
+
+        (
+            hir::Statement::Sequence(statements),
+            hir!((ADD (TEMP base) (MUL (TEMP index) (CONST bytes)))),
+        )
+    }
+
+    /// Emits a runtime test of whether `instance` is an instance of class or interface `r#type`.
+    fn emit_class_test(
+        &mut self,
+        instance: hir::Expression,
+        r#type: &r#type::Expression,
+    ) -> hir::Expression {
+        let class = match r#type {
+            r#type::Expression::Class(class) => class,
+            _ => unreachable!(),
+        };
+
+        if self.context.get_protocol(class).is_some() {
+            let identifier = abi::mangle::protocol_identifier(class);
+
+            self.bss.insert(identifier, (ir::Linkage::LinkOnceOdr, 1));
+            self.implements = true;
+
+            return hir!(
+                (CALL (NAME abi::XI_IMPLEMENTS) (Temporary::fresh_returns(1)) instance (NAME identifier))
+            );
         }
+
+        let type_descriptor = abi::mangle::class_type_descriptor(class);
+        self.instanceof = true;
+
+        hir!(
+            (CALL (NAME abi::XI_INSTANCEOF) (Temporary::fresh_returns(1)) instance (NAME type_descriptor))
+        )
     }
 
     fn emit_call(&mut self, call: &ast::Call<r#type::Expression>) -> hir::Expression {
+        if self.is_closure(&call.function) {
+            return self.emit_closure_call(&call.function, &call.arguments);
+        }
+
         match &*call.function {
             ast::Expression::Variable(variable, _) => {
                 assert!(variable.generics.is_none());
@@ -801,6 +1679,213 @@ impl<'env> Emitter<'env> {
         }
     }
 
+    /// Returns true if calling `function` calls a closure value, rather than
+    /// a function or method by name.
+    fn is_closure(&self, function: &ast::Expression<r#type::Expression>) -> bool {
+        match function {
+            ast::Expression::Variable(variable, _) => {
+                self.locals.contains_key(&variable.name.symbol)
+                    || self
+                        .get_variable(GlobalScope::Global, &variable.name)
+                        .is_some()
+                    || self
+                        .context
+                        .get_scoped_class()
+                        .and_then(|class| {
+                            self.get_variable(GlobalScope::Class(class), &variable.name)
+                        })
+                        .is_some()
+            }
+            ast::Expression::Dot(receiver, field, _, _) => match receiver.r#type() {
+                r#type::Expression::Class(class) => self
+                    .get_variable(GlobalScope::Class(class), field)
+                    .is_some(),
+                _ => unreachable!(),
+            },
+            _ => true,
+        }
+    }
+
+    /// Closures are represented as a pointer to a heap-allocated environment,
+    /// whose first word is the address of the code to run:
+    ///
+    /// ```text
+    /// [code, capture_0, ..., capture_n, this?]
+    /// ```
+    ///
+    /// The code takes a pointer to the environment as an implicit first argument.
+    fn emit_closure_call(
+        &mut self,
+        function: &ast::Expression<r#type::Expression>,
+        arguments: &[ast::Expression<r#type::Expression>],
+    ) -> hir::Expression {
+        let returns = match function.r#type() {
+            r#type::Expression::Function(_, returns) => Temporary::fresh_returns(returns.len()),
+            _ => unreachable!(),
+        };
+
+        let closure = Temporary::fresh("closure");
+
+        let mut arguments = arguments
+            .iter()
+            .map(|argument| self.emit_expression(argument).into())
+            .collect::<Vec<_>>();
+
+        arguments.insert(0, hir!((TEMP closure)));
+
+        hir!(
+            (ESEQ
+                (MOVE (TEMP closure) (self.emit_expression(function).into()))
+                (hir::Expression::Call(Box::new(hir!((MEM (TEMP closure)))), arguments, returns)))
+        )
+    }
+
+    fn emit_closure(&mut self, closure: &ast::Closure<r#type::Expression>) -> hir::Expression {
+        let name = abi::mangle::closure(&self.function, self.closures.len());
+        let this = self.context.get_scoped_this();
+
+        let captures = closure
+            .captures
+            .iter()
+            .map(|capture| hir!((TEMP self.locals[capture])))
+            .chain(this.map(|this| hir!((TEMP this))))
+            .collect::<Vec<_>>();
+
+        let environment = Temporary::fresh("environment");
+        let mut statements = vec![
+            hir!((MOVE (TEMP environment) (CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (CONST (captures.len() + 1) as i64 * abi::WORD)))),
+            hir!((MOVE (MEM (TEMP environment)) (NAME name))),
+        ];
+
+        for (index, capture) in captures.into_iter().enumerate() {
+            statements.push(hir!(
+                (MOVE (MEM (ADD (TEMP environment) (CONST (index + 1) as i64 * abi::WORD))) capture)
+            ));
+        }
+
+        // Note: the closure body is a separate function, with its own locals
+        let locals = std::mem::take(&mut self.locals);
+        let variables = std::mem::take(&mut self.variables);
+        let out_of_bounds = std::mem::take(&mut self.out_of_bounds);
+        let tries = std::mem::take(&mut self.tries);
+        let unwind = self.unwind.take();
+        let loops = std::mem::take(&mut self.loops);
+        let function = std::mem::replace(&mut self.function, name);
+
+        let arguments = Temporary::fresh_arguments(closure.parameters.len() + 1);
+        let mut body = Vec::new();
+
+        for (index, capture) in closure.captures.iter().enumerate() {
+            let fresh = Temporary::fresh(symbol::resolve(*capture));
+            self.locals.insert(*capture, fresh);
+            body.push(hir!(
+                (MOVE (TEMP fresh) (MEM (ADD (TEMP (arguments[0])) (CONST (index + 1) as i64 * abi::WORD))))
+            ));
+        }
+
+        let this = this.map(|_| {
+            let fresh = Temporary::fresh("this");
+            let offset = (closure.captures.len() + 1) as i64 * abi::WORD;
+            body.push(hir!((MOVE (TEMP fresh) (MEM (ADD (TEMP (arguments[0])) (CONST offset))))));
+            fresh
+        });
+
+        for (index, parameter) in closure.parameters.iter().enumerate() {
+            #[rustfmt::skip]
+            body.push(hir!(
+                (MOVE
+                    (self.emit_single_declaration(Scope::Local, &parameter.name, &parameter.r#type))
+                    (TEMP (arguments[index + 1])))
+            ));
+        }
+
+        self.context.push(LocalScope::Closure {
+            this,
+            returns: closure.returns.iter().map(ast::Type::r#type).collect(),
+        });
+        body.push(self.emit_statement(&closure.statements));
+        self.context.pop();
+
+        if let Some(unwind) = std::mem::replace(&mut self.unwind, unwind) {
+            body.push(hir!((LABEL unwind)));
+            body.push(hir::Statement::Return(vec![
+                hir!((CONST 0));
+                closure.returns.len()
+            ]));
+        }
+
+        body.splice(0..0, self.emit_prologue(closure.span));
+        self.variables = variables;
+
+        self.locals = locals;
+        self.out_of_bounds = out_of_bounds;
+        self.tries = tries;
+        self.loops = loops;
+        self.function = function;
+
+        self.closures.insert(
+            name,
+            hir::Function {
+                name,
+                statement: hir::Statement::Sequence(body),
+                arguments,
+                returns: closure.returns.len(),
+                linkage: ir::Linkage::Local,
+            },
+        );
+
+        hir!((ESEQ (hir::Statement::Sequence(statements)) (TEMP environment)))
+    }
+
+    /// Wraps named function `function` in a closure, using an adapter that
+    /// ignores the environment argument.
+    fn emit_closure_adapter(
+        &mut self,
+        function: Symbol,
+        parameters: usize,
+        returns: usize,
+    ) -> hir::Expression {
+        let adapter = abi::mangle::closure_adapter(&function);
+
+        self.closures.entry(adapter).or_insert_with(|| {
+            let arguments = Temporary::fresh_arguments(parameters + 1);
+            let returns = Temporary::fresh_returns(returns);
+            let call = hir::Expression::Call(
+                Box::new(hir!((NAME function))),
+                arguments[1..]
+                    .iter()
+                    .map(|argument| hir!((TEMP * argument)))
+                    .collect(),
+                returns.clone(),
+            );
+
+            hir::Function {
+                name: adapter,
+                statement: hir::Statement::Sequence(vec![
+                    hir!((EXP call)),
+                    hir::Statement::Return(
+                        returns
+                            .iter()
+                            .map(|r#return| hir!((TEMP * r#return)))
+                            .collect(),
+                    ),
+                ]),
+                arguments,
+                returns: returns.len(),
+                linkage: ir::Linkage::LinkOnceOdr,
+            }
+        });
+
+        let environment = Temporary::fresh("environment");
+        hir!(
+            (ESEQ
+                (SEQ
+                    (MOVE (TEMP environment) (CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (CONST abi::WORD)))
+                    (MOVE (MEM (TEMP environment)) (NAME adapter)))
+                (TEMP environment))
+        )
+    }
+
     fn emit_function_call(
         &mut self,
         function: &ast::Variable<r#type::Expression>,
@@ -847,6 +1932,33 @@ impl<'env> Emitter<'env> {
 
         arguments.insert(0, hir!((TEMP instance)));
 
+        if self.context.get_protocol(&class).is_some() {
+            let index = abi::class::protocol_method_index(self.context, &class, &method.symbol)
+                .expect("[TYPE ERROR]: unbound method");
+            let identifier = abi::mangle::protocol_identifier(&class);
+
+            self.bss.insert(identifier, (ir::Linkage::LinkOnceOdr, 1));
+            self.interface_lookup = true;
+
+            let table = hir!(
+                (CALL
+                    (NAME abi::XI_INTERFACE_LOOKUP)
+                    (Temporary::fresh_returns(1))
+                    (TEMP instance)
+                    (NAME identifier))
+            );
+
+            return hir!(
+                (ESEQ
+                    (MOVE (TEMP instance) (self.emit_expression(receiver).into()))
+                    (hir::Expression::Call(
+                        Box::new(hir!((MEM (ADD table (CONST index as i64 * abi::WORD))))),
+                        arguments,
+                        returns,
+                    )))
+            );
+        }
+
         let method = match self.layouts[&class].method_index(&method.symbol) {
             // Special case: if `method_index` returns `None`, then this method
             // does not have a virtual table entry (see `crate::abi::class`
@@ -921,7 +2033,10 @@ impl<'env> Emitter<'env> {
             );
         };
 
-        let expression = self.emit_expression(expression).into();
+        let expression = match &**expression {
+            ast::Expression::Call(call) => self.emit_call(call),
+            _ => unreachable!("[TYPE ERROR]: only calls can return multiple values"),
+        };
 
         // We only emit and recognize the following two kinds of calls,
         // and only calls can return multiple values.
@@ -941,10 +2056,17 @@ impl<'env> Emitter<'env> {
             .zip(returns)
             .filter_map(|(declaration, r#return)| declaration.as_ref().zip(Some(r#return)))
             .map(|(declaration, r#return)| {
-                hir!((MOVE (self.emit_single_declaration(scope, &declaration.name, &declaration.r#type)) (TEMP *r#return)))
+                hir!(
+                    (MOVE(self.emit_single_declaration(
+                        scope,
+                        &declaration.name,
+                        &declaration.r#type
+                    ))(TEMP * r#return))
+                )
             })
             .collect::<Vec<_>>();
 
+        statements.insert(0, self.emit_exception_check(&expression));
         statements.insert(0, hir!((EXP expression)));
         hir::Statement::Sequence(statements)
     }
@@ -987,6 +2109,15 @@ impl<'env> Emitter<'env> {
             Scope::Local => {
                 let fresh = Temporary::fresh(symbol::resolve(name.symbol));
                 self.locals.insert(name.symbol, fresh);
+
+                if self.debug == DebugInfo::Keep {
+                    self.variables.push(hir::Statement::Debug(Debug::Variable(
+                        name.symbol,
+                        r#type.r#type(),
+                        fresh,
+                    )));
+                }
+
                 hir!((TEMP fresh))
             }
         };
@@ -994,8 +2125,10 @@ impl<'env> Emitter<'env> {
         match r#type {
             ast::Type::Bool(_)
             | ast::Type::Int(_)
+            | ast::Type::Sized { .. }
             | ast::Type::Class(_)
-            | ast::Type::Array(_, None, _) => fresh,
+            | ast::Type::Array(_, None, _)
+            | ast::Type::Function(_, _, _) => fresh,
             ast::Type::Array(r#type, Some(length), _) => {
                 let mut lengths = Vec::new();
                 let declaration = self.emit_array_declaration(r#type, length, &mut lengths);
@@ -1022,16 +2155,23 @@ impl<'env> Emitter<'env> {
 
         lengths.push(hir!((MOVE (TEMP length) (self.emit_expression(len).into()))));
 
+        let size = match packed(&r#type.r#type()) {
+            None => hir!((MUL (ADD (TEMP length) (CONST 1)) (CONST abi::WORD))),
+            Some((_, size)) => library::emit_array_size(size, hir!((TEMP length))),
+        };
+
         let mut statements = vec![
-            hir!((MOVE (TEMP array) (CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) (MUL (ADD (TEMP length) (CONST 1)) (CONST abi::WORD))))),
+            hir!((MOVE (TEMP array) (CALL (NAME abi::XI_ALLOC) (Temporary::fresh_returns(1)) size))),
             hir!((MOVE (MEM (TEMP array)) (TEMP length))),
         ];
 
         match r#type {
             ast::Type::Bool(_)
             | ast::Type::Int(_)
+            | ast::Type::Sized { .. }
             | ast::Type::Array(_, None, _)
-            | ast::Type::Class(_) => (),
+            | ast::Type::Class(_)
+            | ast::Type::Function(_, _, _) => (),
             ast::Type::Array(r#type, Some(len), _) => {
                 let r#while = Label::fresh("while");
                 let done = Label::fresh("done");
@@ -1109,3 +2249,186 @@ impl<'env> Emitter<'env> {
         }
     }
 }
+
+/// Returns the signedness and width of integer type `r#type` if it is narrower
+/// than a word, in which case arrays of `r#type` are packed.
+fn packed(r#type: &r#type::Expression) -> Option<(bool, Size)> {
+    r#type.integer().filter(|(_, size)| *size < Size::_64)
+}
+
+/// Values of narrow integer types are kept sign- or zero-extended to a word,
+/// so we truncate `expression` to the width of `r#type` and extend it back.
+fn emit_normalize(r#type: &r#type::Expression, expression: hir::Expression) -> hir::Expression {
+    let (signed, size) = match packed(r#type) {
+        None => return expression,
+        Some(packed) => packed,
+    };
+
+    let mask = (1 << size.bits()) - 1;
+    let sign = 1 << (size.bits() - 1);
+
+    match signed {
+        false => hir!((AND expression (CONST mask))),
+        true => hir!((SUB (XOR (AND expression (CONST mask)) (CONST sign)) (CONST sign))),
+    }
+}
+
+/// Comparisons are unsigned if both operands are unsigned, where nonnegative
+/// literals can be treated as either signed or unsigned.
+fn is_unsigned(
+    left: &ast::Expression<r#type::Expression>,
+    right: &ast::Expression<r#type::Expression>,
+) -> bool {
+    let unsigned = |expression: &ast::Expression<r#type::Expression>| match expression {
+        ast::Expression::Integer(_, _) | ast::Expression::Character(_, _) => None,
+        expression => Some(matches!(expression.r#type().integer(), Some((false, _)))),
+    };
+
+    match (unsigned(left), unsigned(right)) {
+        (Some(left), Some(right)) => left && right,
+        (Some(unsigned), None) | (None, Some(unsigned)) => unsigned,
+        (None, None) => false,
+    }
+}
+
+/// Removes the exception checks after direct calls that can never leave an
+/// exception pending: calls to the runtime, and calls to functions in this unit
+/// that neither raise an exception nor call anything that might.
+fn prune_exception_checks(functions: &mut Map<Symbol, hir::Function>, checks: &Map<Label, Symbol>) {
+    let exception = hir!((MEM (NAME abi::XI_EXCEPTION)));
+    let check = |statement: &hir::Statement| match statement {
+        hir::Statement::CJump {
+            condition: ir::Condition::Ne,
+            left,
+            right,
+            r#false,
+            ..
+        } if *left == exception && *right == hir::ZERO => Some(checks.get(r#false).copied()),
+        _ => None,
+    };
+
+    // Whether each function raises an exception itself, and the
+    // callees whose exceptions it might propagate
+    let mut summaries = Map::default();
+
+    for (name, function) in functions.iter_mut() {
+        let mut raises = false;
+        let mut callees = Vec::new();
+
+        visit_statement(&mut function.statement, &mut |statement| match statement {
+            hir::Statement::Move {
+                destination,
+                source,
+            } if *destination == exception => raises |= *source != hir::ZERO,
+            statement => match check(statement) {
+                None => (),
+                Some(None) => raises = true,
+                Some(Some(callee)) => callees.push(callee),
+            },
+        });
+
+        summaries.insert(*name, (raises, callees));
+    }
+
+    let throws = |callee: &Symbol, throwing: &Set<Symbol>| match summaries.contains_key(callee) {
+        true => throwing.contains(callee),
+        false => !is_runtime(callee),
+    };
+
+    let mut throwing = summaries
+        .iter()
+        .filter(|(_, (raises, _))| *raises)
+        .map(|(name, _)| *name)
+        .collect::<Set<_>>();
+
+    loop {
+        let propagating = summaries
+            .iter()
+            .filter(|(name, _)| !throwing.contains(*name))
+            .filter(|(_, (_, callees))| callees.iter().any(|callee| throws(callee, &throwing)))
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+
+        if propagating.is_empty() {
+            break;
+        }
+
+        throwing.extend(propagating);
+    }
+
+    for function in functions.values_mut() {
+        visit_statement(&mut function.statement, &mut |statement| {
+            if let Some(Some(callee)) = check(statement) {
+                if !throws(&callee, &throwing) {
+                    *statement = hir::Statement::Sequence(Vec::new());
+                }
+            }
+        });
+    }
+}
+
+/// Runtime functions never raise exceptions: failures abort the program instead.
+fn is_runtime(name: &Symbol) -> bool {
+    matches!(
+        symbol::resolve(*name),
+        abi::XI_PRINT
+            | abi::XI_PRINTLN
+            | abi::XI_READLN
+            | abi::XI_GETCHAR
+            | abi::XI_EOF
+            | abi::XI_UNPARSE_INT
+            | abi::XI_PARSE_INT
+            | abi::XI_ASSERT,
+    )
+}
+
+/// Calls `visit` on `statement` and every statement nested within it, outermost first.
+fn visit_statement<F: FnMut(&mut hir::Statement)>(statement: &mut hir::Statement, visit: &mut F) {
+    visit(statement);
+
+    match statement {
+        hir::Statement::Jump(_) | hir::Statement::Label(_) | hir::Statement::Debug(_) => (),
+        hir::Statement::CJump { left, right, .. } => {
+            visit_expression(left, visit);
+            visit_expression(right, visit);
+        }
+        hir::Statement::Expression(expression) => visit_expression(expression, visit),
+        hir::Statement::Move {
+            destination,
+            source,
+        } => {
+            visit_expression(destination, visit);
+            visit_expression(source, visit);
+        }
+        hir::Statement::Return(expressions) => expressions
+            .iter_mut()
+            .for_each(|expression| visit_expression(expression, visit)),
+        hir::Statement::Sequence(statements) => statements
+            .iter_mut()
+            .for_each(|statement| visit_statement(statement, visit)),
+    }
+}
+
+fn visit_expression<F: FnMut(&mut hir::Statement)>(
+    expression: &mut hir::Expression,
+    visit: &mut F,
+) {
+    match expression {
+        hir::Expression::Immediate(_) | hir::Expression::Temporary(_) => (),
+        hir::Expression::Memory(address) => visit_expression(address, visit),
+        hir::Expression::Binary(_, left, right) => {
+            visit_expression(left, visit);
+            visit_expression(right, visit);
+        }
+        hir::Expression::Call(function, arguments, _) => {
+            visit_expression(function, visit);
+            arguments
+                .iter_mut()
+                .for_each(|argument| visit_expression(argument, visit));
+        }
+        hir::Expression::Sequence(statement, expression) => {
+            visit_statement(statement, visit);
+            visit_expression(expression, visit);
+        }
+    }
+}
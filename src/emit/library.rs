@@ -4,6 +4,7 @@ use crate::data::ir;
 use crate::data::operand::Label;
 use crate::data::operand::Temporary;
 use crate::data::symbol;
+use crate::data::token::Size;
 use crate::hir;
 
 pub(super) fn emit_memdup() -> hir::Function {
@@ -40,7 +41,127 @@ pub(super) fn emit_memdup() -> hir::Function {
     }
 }
 
-pub(super) fn emit_concat() -> hir::Function {
+/// Finds the method table for the interface identified by the second argument,
+/// in the type descriptor of the object passed as the first argument.
+///
+/// Assumes that the object implements the interface, which the type checker guarantees.
+pub(super) fn emit_interface_lookup() -> hir::Function {
+    let entry = Temporary::fresh("entry");
+
+    let r#while = Label::fresh("while");
+    let next = Label::fresh("next");
+    let done = Label::fresh("done");
+
+    let arguments = Temporary::fresh_arguments(2);
+
+    hir::Function {
+        name: symbol::intern_static(abi::XI_INTERFACE_LOOKUP),
+        linkage: ir::Linkage::LinkOnceOdr,
+        statement: hir!(
+            (SEQ
+                // Skip past the superclass descriptor and number of interfaces in the type descriptor
+                (MOVE (TEMP entry) (ADD (MEM (MEM (TEMP arguments[0]))) (CONST abi::WORD * 2)))
+                (LABEL r#while)
+                (CJUMP (EQ (MEM (TEMP entry)) (TEMP arguments[1])) done next)
+                (LABEL next)
+                (MOVE (TEMP entry) (ADD (TEMP entry) (CONST abi::WORD * 2)))
+                (JUMP r#while)
+                (LABEL done)
+                (RETURN (MEM (ADD (TEMP entry) (CONST abi::WORD)))))
+        ),
+        arguments,
+        returns: 1,
+    }
+}
+
+/// Tests whether the object passed as the first argument is an instance of the class
+/// whose type descriptor is passed as the second argument, by following the chain of
+/// superclass descriptors. Null is not an instance of any class.
+pub(super) fn emit_instanceof() -> hir::Function {
+    let descriptor = Temporary::fresh("descriptor");
+
+    let load = Label::fresh("load");
+    let r#while = Label::fresh("while");
+    let next = Label::fresh("next");
+    let r#true = Label::fresh("true");
+    let r#false = Label::fresh("false");
+
+    let arguments = Temporary::fresh_arguments(2);
+
+    hir::Function {
+        name: symbol::intern_static(abi::XI_INSTANCEOF),
+        linkage: ir::Linkage::LinkOnceOdr,
+        statement: hir!(
+            (SEQ
+                (CJUMP (EQ (TEMP arguments[0]) (CONST 0)) r#false load)
+                (LABEL load)
+                (MOVE (TEMP descriptor) (MEM (MEM (TEMP arguments[0]))))
+                (LABEL r#while)
+                (CJUMP (EQ (TEMP descriptor) (TEMP arguments[1])) r#true next)
+                (LABEL next)
+                (MOVE (TEMP descriptor) (MEM (TEMP descriptor)))
+                (CJUMP (EQ (TEMP descriptor) (CONST 0)) r#false r#while)
+                (LABEL r#true)
+                (RETURN (CONST 1))
+                (LABEL r#false)
+                (RETURN (CONST 0)))
+        ),
+        arguments,
+        returns: 1,
+    }
+}
+
+/// Tests whether the object passed as the first argument implements the interface
+/// identified by the second argument, by searching its type descriptor. Null does
+/// not implement any interface.
+pub(super) fn emit_implements() -> hir::Function {
+    let descriptor = Temporary::fresh("descriptor");
+    let entry = Temporary::fresh("entry");
+    let bound = Temporary::fresh("bound");
+
+    let load = Label::fresh("load");
+    let r#while = Label::fresh("while");
+    let compare = Label::fresh("compare");
+    let next = Label::fresh("next");
+    let r#true = Label::fresh("true");
+    let r#false = Label::fresh("false");
+
+    let arguments = Temporary::fresh_arguments(2);
+
+    hir::Function {
+        name: symbol::intern_static(abi::XI_IMPLEMENTS),
+        linkage: ir::Linkage::LinkOnceOdr,
+        statement: hir!(
+            (SEQ
+                (CJUMP (EQ (TEMP arguments[0]) (CONST 0)) r#false load)
+                (LABEL load)
+                (MOVE (TEMP descriptor) (MEM (MEM (TEMP arguments[0]))))
+                // Skip past the superclass descriptor and number of interfaces in the type descriptor
+                (MOVE (TEMP entry) (ADD (TEMP descriptor) (CONST abi::WORD * 2)))
+                (MOVE
+                    (TEMP bound)
+                    (ADD
+                        (TEMP entry)
+                        (MUL (MEM (ADD (TEMP descriptor) (CONST abi::WORD))) (CONST abi::WORD * 2))))
+                (LABEL r#while)
+                (CJUMP (EQ (TEMP entry) (TEMP bound)) r#false compare)
+                (LABEL compare)
+                (CJUMP (EQ (MEM (TEMP entry)) (TEMP arguments[1])) r#true next)
+                (LABEL next)
+                (MOVE (TEMP entry) (ADD (TEMP entry) (CONST abi::WORD * 2)))
+                (JUMP r#while)
+                (LABEL r#true)
+                (RETURN (CONST 1))
+                (LABEL r#false)
+                (RETURN (CONST 0)))
+        ),
+        arguments,
+        returns: 1,
+    }
+}
+
+/// Concatenates two arrays whose elements are `size` bits wide.
+pub(super) fn emit_concat(size: Size) -> hir::Function {
     let array_left = Temporary::fresh("array");
     let array_right = Temporary::fresh("array");
     let array = Temporary::fresh("array");
@@ -60,8 +181,18 @@ pub(super) fn emit_concat() -> hir::Function {
     let address = Temporary::fresh("address");
     let arguments = Temporary::fresh_arguments(2);
 
+    let bytes = size.bytes();
+    let copy = |source| match size {
+        Size::_64 => hir!((MOVE (MEM (TEMP address)) (MEM (TEMP source)))),
+        size => emit_store(
+            size,
+            hir!((TEMP address)),
+            emit_load(false, size, hir!((TEMP source))),
+        ),
+    };
+
     hir::Function {
-        name: symbol::intern_static(abi::XI_CONCAT),
+        name: symbol::intern_static(abi::concat(size)),
         linkage: ir::Linkage::LinkOnceOdr,
         statement: hir!(
             (SEQ
@@ -78,29 +209,29 @@ pub(super) fn emit_concat() -> hir::Function {
                     (CALL
                         (NAME abi::XI_ALLOC)
                         (Temporary::fresh_returns(1))
-                        (ADD (MUL (TEMP length) (CONST abi::WORD)) (CONST abi::WORD))))
+                        (emit_array_size(size, hir!((TEMP length))))))
                 (MOVE (MEM (TEMP array)) (TEMP length))
                 (MOVE (TEMP address) (ADD (TEMP array) (CONST abi::WORD)))
 
                 // Copy left array into final destination, starting at
                 // `array + WORD`
-                (MOVE (TEMP bound_left) (ADD (TEMP array_left) (MUL (TEMP length_left) (CONST abi::WORD))))
+                (MOVE (TEMP bound_left) (ADD (TEMP array_left) (MUL (TEMP length_left) (CONST bytes))))
                 (CJUMP (AE (TEMP array_left) (TEMP bound_left)) done_left while_left)
                 (LABEL while_left)
-                (MOVE (MEM (TEMP address)) (MEM (TEMP array_left)))
-                (MOVE (TEMP array_left) (ADD (TEMP array_left) (CONST abi::WORD)))
-                (MOVE (TEMP address) (ADD (TEMP address) (CONST abi::WORD)))
+                (copy(array_left))
+                (MOVE (TEMP array_left) (ADD (TEMP array_left) (CONST bytes)))
+                (MOVE (TEMP address) (ADD (TEMP address) (CONST bytes)))
                 (CJUMP (AE (TEMP array_left) (TEMP bound_left)) done_left while_left)
                 (LABEL done_left)
 
                 // Copy right array into final destination, starting at
-                // `array + WORD + length_left * WORD`
-                (MOVE (TEMP bound_right) (ADD (TEMP array_right) (MUL (TEMP length_right) (CONST abi::WORD))))
+                // `array + WORD + length_left * bytes`
+                (MOVE (TEMP bound_right) (ADD (TEMP array_right) (MUL (TEMP length_right) (CONST bytes))))
                 (CJUMP (AE (TEMP array_right) (TEMP bound_right)) done_right while_right)
                 (LABEL while_right)
-                (MOVE (MEM (TEMP address)) (MEM (TEMP array_right)))
-                (MOVE (TEMP array_right) (ADD (TEMP array_right) (CONST abi::WORD)))
-                (MOVE (TEMP address) (ADD (TEMP address) (CONST abi::WORD)))
+                (copy(array_right))
+                (MOVE (TEMP array_right) (ADD (TEMP array_right) (CONST bytes)))
+                (MOVE (TEMP address) (ADD (TEMP address) (CONST bytes)))
                 (CJUMP (AE (TEMP array_right) (TEMP bound_right)) done_right while_right)
                 (LABEL done_right)
 
@@ -110,3 +241,34 @@ pub(super) fn emit_concat() -> hir::Function {
         returns: 1,
     }
 }
+
+/// Number of bytes to allocate for an array of `length` elements that are
+/// `size` bits wide, including the length metadata.
+///
+/// Packed arrays are padded to a multiple of the word size, so that the
+/// allocation remains word-aligned.
+pub(super) fn emit_array_size(size: Size, length: hir::Expression) -> hir::Expression {
+    match size {
+        Size::_64 => hir!((ADD (MUL length (CONST abi::WORD)) (CONST abi::WORD))),
+        size => hir!(
+            (AND
+                (ADD (MUL length (CONST size.bytes())) (CONST abi::WORD * 2 - 1))
+                (CONST -abi::WORD))
+        ),
+    }
+}
+
+/// Loads a packed array element, sign- or zero-extending it to a word.
+pub(super) fn emit_load(signed: bool, size: Size, address: hir::Expression) -> hir::Expression {
+    let returns = Temporary::fresh_returns(1);
+    hir!((CALL (NAME abi::load(signed, size)) returns address))
+}
+
+/// Stores the low `size` bits of `value` into a packed array element.
+pub(super) fn emit_store(
+    size: Size,
+    address: hir::Expression,
+    value: hir::Expression,
+) -> hir::Statement {
+    hir!((EXP (CALL (NAME abi::store(size)) (Vec::new()) address value)))
+}
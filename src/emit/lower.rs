@@ -30,7 +30,10 @@ pub fn emit_lir(function: &hir::Function) -> lir::Function<Label> {
         ) => (),
         None
         | Some(
-            lir::Statement::Call(_, _, _) | lir::Statement::Move { .. } | lir::Statement::Label(_),
+            lir::Statement::Call(_, _, _)
+            | lir::Statement::Move { .. }
+            | lir::Statement::Label(_)
+            | lir::Statement::Debug(_),
         ) => {
             // Guaranteed valid by type-checker
             lowered.push(lir::Statement::Return(Vec::new()));
@@ -104,6 +107,7 @@ impl Lowerer {
                 self.lower_expression(expression);
             }
             hir::Statement::Label(label) => self.lowered.push(lir::Statement::Label(*label)),
+            hir::Statement::Debug(debug) => self.lowered.push(lir::Statement::Debug(debug.clone())),
             hir::Statement::Sequence(statements) => {
                 for statement in statements {
                     self.lower_statement(statement);
@@ -261,7 +265,7 @@ fn pure_statement(statement: &hir::Statement) -> bool {
         | hir::Statement::CJump { .. }
         | hir::Statement::Move { .. }
         | hir::Statement::Return(_) => false,
-        hir::Statement::Label(_) => true,
+        hir::Statement::Label(_) | hir::Statement::Debug(_) => true,
         hir::Statement::Expression(expression) => pure_expression(expression),
         hir::Statement::Sequence(statements) => statements.iter().all(pure_statement),
     }
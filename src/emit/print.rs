@@ -1,6 +1,7 @@
 use std::borrow;
 use std::iter;
 
+use crate::data::debug::Debug;
 use crate::data::hir;
 use crate::data::ir;
 use crate::data::lir;
@@ -9,6 +10,7 @@ use crate::data::operand::Label;
 use crate::data::operand::Temporary;
 use crate::data::sexp::Serialize;
 use crate::data::sexp::Sexp;
+use crate::data::ssa;
 
 impl<T: Serialize> Serialize for ir::Unit<T> {
     fn sexp(&self) -> Sexp {
@@ -74,6 +76,7 @@ impl Serialize for hir::Statement {
                 .chain(returns.iter().map(|r#return| r#return.sexp()))
                 .collect::<Vec<_>>()
                 .sexp_move(),
+            Debug(debug) => debug.sexp(),
             Sequence(statements) => iter::once("SEQ".sexp())
                 .chain(statements.iter().map(|statement| statement.sexp()))
                 .collect::<Vec<_>>()
@@ -82,6 +85,21 @@ impl Serialize for hir::Statement {
     }
 }
 
+impl Serialize for Debug<Temporary> {
+    fn sexp(&self) -> Sexp {
+        match self {
+            Debug::Location(span) => ["LOC".sexp(), span.to_string().sexp_move()].sexp_move(),
+            Debug::Variable(name, r#type, temporary) => [
+                "VAR".sexp(),
+                name.sexp(),
+                r#type.to_string().sexp_move(),
+                ["TEMP".sexp(), temporary.sexp()].sexp_move(),
+            ]
+            .sexp_move(),
+        }
+    }
+}
+
 impl<T: Serialize + lir::Target> Serialize for lir::Function<T> {
     fn sexp(&self) -> Sexp {
         [
@@ -151,6 +169,47 @@ impl<T: Serialize> Serialize for lir::Statement<T> {
                 .chain(returns.iter().map(|r#return| r#return.sexp()))
                 .collect::<Vec<_>>()
                 .sexp_move(),
+            Debug(debug) => debug.sexp(),
+        }
+    }
+}
+
+impl<T: Serialize + lir::Target> Serialize for ssa::Function<T> {
+    fn sexp(&self) -> Sexp {
+        [
+            "FUNC".sexp(),
+            self.name.sexp(),
+            iter::once("SEQ".sexp())
+                .chain(self.statements.iter().map(|statement| statement.sexp()))
+                .collect::<Vec<_>>()
+                .sexp_move(),
+        ]
+        .sexp_move()
+    }
+}
+
+impl<T: Serialize> Serialize for ssa::Statement<T> {
+    fn sexp(&self) -> Sexp {
+        match self {
+            ssa::Statement::Phi {
+                destination,
+                sources,
+            } => {
+                let phi = iter::once("PHI".sexp())
+                    .chain(sources.iter().map(|(label, source)| {
+                        [label.sexp(), ["TEMP".sexp(), source.sexp()].sexp_move()].sexp_move()
+                    }))
+                    .collect::<Vec<_>>()
+                    .sexp_move();
+
+                [
+                    "MOVE".sexp(),
+                    ["TEMP".sexp(), destination.sexp()].sexp_move(),
+                    phi,
+                ]
+                .sexp_move()
+            }
+            ssa::Statement::Lir(statement) => statement.sexp(),
         }
     }
 }
@@ -171,6 +230,9 @@ impl Serialize for ir::Binary {
             Hul => "HMUL",
             Div => "DIV",
             Mod => "MOD",
+            UHul => "UHMUL",
+            UDiv => "UDIV",
+            UMod => "UMOD",
             Xor => "XOR",
             And => "AND",
             Or => "OR",
@@ -190,6 +252,9 @@ impl Serialize for ir::Condition {
             Ne => "NEQ",
             Eq => "EQ",
             Ae => "AEQ",
+            B => "BLT",
+            Be => "BLEQ",
+            A => "AGT",
         }
         .sexp()
     }
@@ -1,26 +1,80 @@
+mod code;
+
+use std::borrow::Cow;
+
 use crate::check;
 use crate::data::span::Span;
+use crate::data::symbol;
 use crate::lex;
 use crate::parse;
 
+pub use code::Code;
+
 #[derive(Debug)]
 pub enum Error {
     Lexical(lex::Error),
-    Syntactic(parse::Error),
-    Semantic(check::Error),
+    Syntactic(Vec<parse::Error>),
+    Semantic(Vec<check::Error>),
+    /// Syntax errors that the parser recovered from, followed by any semantic
+    /// errors found in the recovered program.
+    Recovered(Vec<parse::Error>, Vec<check::Error>),
     Io(std::io::Error),
 }
 
 impl Error {
-    pub fn report(&self) -> Option<ariadne::ReportBuilder<Span>> {
-        let report = match self {
-            Error::Lexical(error) => error.report().with_message("Lexical error"),
-            Error::Syntactic(error) => error.report().with_message("Syntactic error"),
-            Error::Semantic(error) => error.report().with_message("Semantic error"),
-            Error::Io(_) => return None,
+    /// Builds one report per diagnostic, keeping at most the first `limit` if provided.
+    ///
+    /// Returns an empty vector if this error has no associated source location.
+    pub fn report(&self, limit: Option<usize>) -> Vec<ariadne::ReportBuilder<Span>> {
+        let mut reports = match self {
+            Error::Lexical(error) => vec![error.report().with_message("Lexical error")],
+            Error::Syntactic(errors) => report_all(errors, "Syntactic error"),
+            Error::Semantic(errors) => report_all(errors, "Semantic error"),
+            Error::Recovered(syntactic, semantic) => {
+                let mut reports = report_all(syntactic, "Syntactic error");
+                reports.extend(report_all(semantic, "Semantic error"));
+                reports
+            }
+            Error::Io(_) => Vec::new(),
+        };
+
+        if let Some(limit) = limit {
+            reports.truncate(limit);
+        }
+
+        reports
+    }
+
+    /// Builds one structured diagnostic per report, in the same order and subject to the
+    /// same `limit` as [`Error::report`], for tools that render diagnostics themselves.
+    pub fn diagnostics(&self, limit: Option<usize>) -> Vec<Diagnostic> {
+        let mut diagnostics = match self {
+            Error::Lexical(error) => vec![Diagnostic::new(error, "lexical")],
+            Error::Syntactic(errors) => diagnose_all(errors, "syntactic"),
+            Error::Semantic(errors) => diagnose_all(errors, "semantic"),
+            Error::Recovered(syntactic, semantic) => {
+                let mut diagnostics = diagnose_all(syntactic, "syntactic");
+                diagnostics.extend(diagnose_all(semantic, "semantic"));
+                diagnostics
+            }
+            Error::Io(_) => Vec::new(),
         };
 
-        Some(report)
+        if let Some(limit) = limit {
+            diagnostics.truncate(limit);
+        }
+
+        diagnostics
+    }
+
+    /// Number of diagnostics contained in this error.
+    pub fn count(&self) -> usize {
+        match self {
+            Error::Lexical(_) | Error::Io(_) => 1,
+            Error::Syntactic(errors) => errors.len(),
+            Error::Semantic(errors) => errors.len(),
+            Error::Recovered(syntactic, semantic) => syntactic.len() + semantic.len(),
+        }
     }
 }
 
@@ -28,13 +82,51 @@ impl std::fmt::Display for Error {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Error::Lexical(error) => write!(fmt, "{}", error),
-            Error::Syntactic(error) => write!(fmt, "{}", error),
-            Error::Semantic(error) => write!(fmt, "{}", error),
+            Error::Syntactic(errors) => write_lines(fmt, errors),
+            Error::Semantic(errors) => write_lines(fmt, errors),
+            Error::Recovered(syntactic, semantic) => {
+                write_lines(fmt, syntactic)?;
+                if !syntactic.is_empty() && !semantic.is_empty() {
+                    writeln!(fmt)?;
+                }
+                write_lines(fmt, semantic)
+            }
             Error::Io(error) => write!(fmt, "{}", error),
         }
     }
 }
 
+fn report_all<T: Report>(errors: &[T], message: &str) -> Vec<ariadne::ReportBuilder<Span>> {
+    errors
+        .iter()
+        .map(|error| error.report().with_message(message))
+        .collect()
+}
+
+fn diagnose_all<T: Report>(errors: &[T], phase: &'static str) -> Vec<Diagnostic> {
+    errors
+        .iter()
+        .map(|error| Diagnostic::new(error, phase))
+        .collect()
+}
+
+fn write_lines<T: std::fmt::Display>(
+    fmt: &mut std::fmt::Formatter,
+    errors: &[T],
+) -> std::fmt::Result {
+    let mut errors = errors.iter();
+
+    if let Some(error) = errors.next() {
+        write!(fmt, "{}", error)?;
+    }
+
+    for error in errors {
+        write!(fmt, "\n{}", error)?;
+    }
+
+    Ok(())
+}
+
 impl std::error::Error for Error {}
 
 impl From<std::io::Error> for Error {
@@ -51,16 +143,119 @@ impl From<lex::Error> for Error {
 
 impl From<parse::Error> for Error {
     fn from(error: parse::Error) -> Self {
-        Error::Syntactic(error)
+        Error::Syntactic(vec![error])
     }
 }
 
 impl From<check::Error> for Error {
     fn from(error: check::Error) -> Self {
-        Error::Semantic(error)
+        Error::Semantic(vec![error])
+    }
+}
+
+impl From<Vec<check::Error>> for Error {
+    fn from(errors: Vec<check::Error>) -> Self {
+        Error::Semantic(errors)
     }
 }
 
 pub(crate) trait Report {
-    fn report(&self) -> ariadne::ReportBuilder<Span>;
+    fn code(&self) -> Code;
+
+    /// Labeled spans of this error, starting with the primary span.
+    fn labels(&self) -> Vec<(Span, Cow<'static, str>)>;
+
+    fn report(&self) -> ariadne::ReportBuilder<Span> {
+        use ariadne::Span as _;
+
+        let labels = self.labels();
+        let (primary, _) = labels[0];
+
+        let mut report = ariadne::Report::build(
+            ariadne::ReportKind::Error,
+            *primary.source(),
+            primary.lo.index(),
+        )
+        .with_code(self.code());
+
+        report.add_labels(
+            labels
+                .into_iter()
+                .map(|(span, message)| ariadne::Label::new(span).with_message(message)),
+        );
+
+        report
+    }
+}
+
+/// Structured form of a single report.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+
+    /// Compiler phase that produced this diagnostic: `lexical`, `syntactic`, or `semantic`
+    pub phase: &'static str,
+    pub code: &'static str,
+
+    /// Labeled spans, starting with the primary span, whose label is the main message
+    pub labels: Vec<(Span, Cow<'static, str>)>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Diagnostic {
+    fn new<T: Report>(error: &T, phase: &'static str) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            phase,
+            code: error.code().to_static_str(),
+            labels: error.labels(),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.labels[0].0
+    }
+
+    pub fn message(&self) -> &str {
+        &self.labels[0].1
+    }
+
+    /// Serializes this diagnostic as a single JSON object, with one entry in `spans`
+    /// per label. Lines and columns start from 1, and byte offsets from 0.
+    pub fn json(&self) -> serde_json::Value {
+        let spans = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(index, (span, label))| {
+                serde_json::json!({
+                    "file": span.path().map(symbol::resolve),
+                    "line_start": span.lo.row(),
+                    "column_start": span.lo.column(),
+                    "line_end": span.hi.row(),
+                    "column_end": span.hi.column(),
+                    "byte_start": span.lo.index(),
+                    "byte_end": span.hi.index(),
+                    "primary": index == 0,
+                    "label": label,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::json!({
+            "severity": match self.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            },
+            "phase": self.phase,
+            "code": self.code,
+            "message": self.message(),
+            "spans": spans,
+        })
+    }
 }
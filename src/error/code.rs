@@ -0,0 +1,1794 @@
+use std::fmt;
+use std::str;
+
+use anyhow::anyhow;
+
+/// Stable identifier for a kind of error, which `xic --explain` describes in detail.
+///
+/// Codes are grouped by phase and topic: lexical errors start at `E0001`, syntactic
+/// errors at `E0101`, and semantic errors at `E0201`. Codes are never reused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Code {
+    InvalidCharacter,
+    InvalidEscape,
+    InvalidString,
+    UnknownCharacter,
+    UnclosedCharacter,
+    UnclosedString,
+    UnexpectedEof,
+    UnexpectedToken,
+    InvalidInteger,
+    ArrayLength,
+    MissingLength,
+    NoSuperclass,
+    FinalSuperclass,
+    ClassIncomplete,
+    ClassCycle,
+    NotInterface,
+    ExtendsInterface,
+    NewInterface,
+    FinalMismatch,
+    BuiltinClass,
+    NotInClass,
+    NotInClassModule,
+    NotClass,
+    ImpossibleCast,
+    NotFound,
+    UnboundVariable,
+    UnboundFun,
+    UnboundFunTemplate,
+    UnboundClass,
+    UnboundClassTemplate,
+    NotVariable,
+    NotFun,
+    NameClash,
+    SignatureMismatch,
+    UnboundLabel,
+    NotInWhile,
+    AssignCaptured,
+    Mismatch,
+    NotExp,
+    NotProcedure,
+    CallLength,
+    InitLength,
+    InitProcedure,
+    IndexEmpty,
+    NotException,
+    Unreachable,
+    MissingReturn,
+    ReturnMismatch,
+    TemplateArgumentMismatch,
+    UnsatisfiedBound,
+    UninferredTemplateArgument,
+    AmbiguousTemplateArgument,
+    EnumNotClass,
+    UnboundVariant,
+    VariantArity,
+    PatternMismatch,
+    NonExhaustive,
+    RedundantPattern,
+    InterfaceEnum,
+}
+
+impl Code {
+    pub const ALL: [Code; 59] = [
+        Code::InvalidCharacter,
+        Code::InvalidEscape,
+        Code::InvalidString,
+        Code::UnknownCharacter,
+        Code::UnclosedCharacter,
+        Code::UnclosedString,
+        Code::UnexpectedEof,
+        Code::UnexpectedToken,
+        Code::InvalidInteger,
+        Code::ArrayLength,
+        Code::MissingLength,
+        Code::NoSuperclass,
+        Code::FinalSuperclass,
+        Code::ClassIncomplete,
+        Code::ClassCycle,
+        Code::NotInterface,
+        Code::ExtendsInterface,
+        Code::NewInterface,
+        Code::FinalMismatch,
+        Code::BuiltinClass,
+        Code::NotInClass,
+        Code::NotInClassModule,
+        Code::NotClass,
+        Code::ImpossibleCast,
+        Code::NotFound,
+        Code::UnboundVariable,
+        Code::UnboundFun,
+        Code::UnboundFunTemplate,
+        Code::UnboundClass,
+        Code::UnboundClassTemplate,
+        Code::NotVariable,
+        Code::NotFun,
+        Code::NameClash,
+        Code::SignatureMismatch,
+        Code::UnboundLabel,
+        Code::NotInWhile,
+        Code::AssignCaptured,
+        Code::Mismatch,
+        Code::NotExp,
+        Code::NotProcedure,
+        Code::CallLength,
+        Code::InitLength,
+        Code::InitProcedure,
+        Code::IndexEmpty,
+        Code::NotException,
+        Code::Unreachable,
+        Code::MissingReturn,
+        Code::ReturnMismatch,
+        Code::TemplateArgumentMismatch,
+        Code::UnsatisfiedBound,
+        Code::UninferredTemplateArgument,
+        Code::AmbiguousTemplateArgument,
+        Code::EnumNotClass,
+        Code::UnboundVariant,
+        Code::VariantArity,
+        Code::PatternMismatch,
+        Code::NonExhaustive,
+        Code::RedundantPattern,
+        Code::InterfaceEnum,
+    ];
+
+    pub const fn to_static_str(self) -> &'static str {
+        match self {
+            Code::InvalidCharacter => "E0001",
+            Code::InvalidEscape => "E0002",
+            Code::InvalidString => "E0003",
+            Code::UnknownCharacter => "E0004",
+            Code::UnclosedCharacter => "E0005",
+            Code::UnclosedString => "E0006",
+            Code::UnexpectedEof => "E0101",
+            Code::UnexpectedToken => "E0102",
+            Code::InvalidInteger => "E0103",
+            Code::ArrayLength => "E0104",
+            Code::MissingLength => "E0105",
+            Code::NoSuperclass => "E0201",
+            Code::FinalSuperclass => "E0202",
+            Code::ClassIncomplete => "E0203",
+            Code::ClassCycle => "E0204",
+            Code::NotInterface => "E0205",
+            Code::ExtendsInterface => "E0206",
+            Code::NewInterface => "E0207",
+            Code::FinalMismatch => "E0208",
+            Code::BuiltinClass => "E0209",
+            Code::NotInClass => "E0210",
+            Code::NotInClassModule => "E0211",
+            Code::NotClass => "E0212",
+            Code::ImpossibleCast => "E0213",
+            Code::NotFound => "E0301",
+            Code::UnboundVariable => "E0302",
+            Code::UnboundFun => "E0303",
+            Code::UnboundFunTemplate => "E0304",
+            Code::UnboundClass => "E0305",
+            Code::UnboundClassTemplate => "E0306",
+            Code::NotVariable => "E0307",
+            Code::NotFun => "E0308",
+            Code::NameClash => "E0309",
+            Code::SignatureMismatch => "E0310",
+            Code::UnboundLabel => "E0311",
+            Code::NotInWhile => "E0312",
+            Code::AssignCaptured => "E0313",
+            Code::Mismatch => "E0401",
+            Code::NotExp => "E0402",
+            Code::NotProcedure => "E0403",
+            Code::CallLength => "E0404",
+            Code::InitLength => "E0405",
+            Code::InitProcedure => "E0406",
+            Code::IndexEmpty => "E0407",
+            Code::NotException => "E0408",
+            Code::Unreachable => "E0501",
+            Code::MissingReturn => "E0502",
+            Code::ReturnMismatch => "E0503",
+            Code::TemplateArgumentMismatch => "E0601",
+            Code::UnsatisfiedBound => "E0602",
+            Code::UninferredTemplateArgument => "E0603",
+            Code::AmbiguousTemplateArgument => "E0604",
+            Code::EnumNotClass => "E0701",
+            Code::UnboundVariant => "E0702",
+            Code::VariantArity => "E0703",
+            Code::PatternMismatch => "E0704",
+            Code::NonExhaustive => "E0705",
+            Code::RedundantPattern => "E0706",
+            Code::InterfaceEnum => "E0707",
+        }
+    }
+
+    /// Name of the kind of error, e.g. `ClassIncomplete`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Code::InvalidCharacter => "InvalidCharacter",
+            Code::InvalidEscape => "InvalidEscape",
+            Code::InvalidString => "InvalidString",
+            Code::UnknownCharacter => "UnknownCharacter",
+            Code::UnclosedCharacter => "UnclosedCharacter",
+            Code::UnclosedString => "UnclosedString",
+            Code::UnexpectedEof => "UnexpectedEof",
+            Code::UnexpectedToken => "UnexpectedToken",
+            Code::InvalidInteger => "InvalidInteger",
+            Code::ArrayLength => "ArrayLength",
+            Code::MissingLength => "MissingLength",
+            Code::NoSuperclass => "NoSuperclass",
+            Code::FinalSuperclass => "FinalSuperclass",
+            Code::ClassIncomplete => "ClassIncomplete",
+            Code::ClassCycle => "ClassCycle",
+            Code::NotInterface => "NotInterface",
+            Code::ExtendsInterface => "ExtendsInterface",
+            Code::NewInterface => "NewInterface",
+            Code::FinalMismatch => "FinalMismatch",
+            Code::BuiltinClass => "BuiltinClass",
+            Code::NotInClass => "NotInClass",
+            Code::NotInClassModule => "NotInClassModule",
+            Code::NotClass => "NotClass",
+            Code::ImpossibleCast => "ImpossibleCast",
+            Code::NotFound => "NotFound",
+            Code::UnboundVariable => "UnboundVariable",
+            Code::UnboundFun => "UnboundFun",
+            Code::UnboundFunTemplate => "UnboundFunTemplate",
+            Code::UnboundClass => "UnboundClass",
+            Code::UnboundClassTemplate => "UnboundClassTemplate",
+            Code::NotVariable => "NotVariable",
+            Code::NotFun => "NotFun",
+            Code::NameClash => "NameClash",
+            Code::SignatureMismatch => "SignatureMismatch",
+            Code::UnboundLabel => "UnboundLabel",
+            Code::NotInWhile => "NotInWhile",
+            Code::AssignCaptured => "AssignCaptured",
+            Code::Mismatch => "Mismatch",
+            Code::NotExp => "NotExp",
+            Code::NotProcedure => "NotProcedure",
+            Code::CallLength => "CallLength",
+            Code::InitLength => "InitLength",
+            Code::InitProcedure => "InitProcedure",
+            Code::IndexEmpty => "IndexEmpty",
+            Code::NotException => "NotException",
+            Code::Unreachable => "Unreachable",
+            Code::MissingReturn => "MissingReturn",
+            Code::ReturnMismatch => "ReturnMismatch",
+            Code::TemplateArgumentMismatch => "TemplateArgumentMismatch",
+            Code::UnsatisfiedBound => "UnsatisfiedBound",
+            Code::UninferredTemplateArgument => "UninferredTemplateArgument",
+            Code::AmbiguousTemplateArgument => "AmbiguousTemplateArgument",
+            Code::EnumNotClass => "EnumNotClass",
+            Code::UnboundVariant => "UnboundVariant",
+            Code::VariantArity => "VariantArity",
+            Code::PatternMismatch => "PatternMismatch",
+            Code::NonExhaustive => "NonExhaustive",
+            Code::RedundantPattern => "RedundantPattern",
+            Code::InterfaceEnum => "InterfaceEnum",
+        }
+    }
+
+    /// Long-form description of this kind of error in Markdown, with an example
+    /// of code that triggers it and how to fix it.
+    pub const fn explanation(self) -> &'static str {
+        match self {
+            Code::InvalidCharacter => {
+                r#"A character literal does not contain exactly one valid character.
+
+Character literals must contain a single character or escape sequence, and
+cannot contain a raw newline. Unicode escapes must name a valid code point.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    c: int = ''
+}
+```
+
+To fix this, write exactly one character between the quotes:
+
+```xi
+main(args: int[][]) {
+    c: int = ' '
+}
+```"#
+            }
+            Code::InvalidEscape => {
+                r#"A character or string literal contains an unknown escape sequence.
+
+The supported escape sequences are `\n`, `\r`, `\t`, `\b`, `\f`, `\\`,
+`\'`, `\"`, and Unicode escapes of up to four hexadecimal digits written as
+`\x` or `\u` followed by the digits.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    s: int[] = "\q"
+}
+```
+
+To fix this, escape the backslash itself if it should appear literally:
+
+```xi
+main(args: int[][]) {
+    s: int[] = "\\q"
+}
+```"#
+            }
+            Code::InvalidString => {
+                r#"A string literal contains a raw newline or other control character.
+
+String literals must fit on a single line. Use the `\n` escape sequence to
+include a newline in the string instead.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    s: int[] = "hello
+world"
+}
+```
+
+To fix this, replace the line break with an escape sequence:
+
+```xi
+main(args: int[][]) {
+    s: int[] = "hello\nworld"
+}
+```"#
+            }
+            Code::UnknownCharacter => {
+                r#"The source file contains a character that cannot begin any token.
+
+Outside of comments and literals, Xi only uses ASCII letters, digits,
+whitespace, and a fixed set of operators and punctuation.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = 2 # 3
+}
+```
+
+To fix this, remove the character or replace it with a supported operator:
+
+```xi
+main(args: int[][]) {
+    x: int = 2 * 3
+}
+```"#
+            }
+            Code::UnclosedCharacter => {
+                r#"A character literal is missing its closing quote.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    c: int = 'a
+}
+```
+
+To fix this, close the literal with a single quote:
+
+```xi
+main(args: int[][]) {
+    c: int = 'a'
+}
+```"#
+            }
+            Code::UnclosedString => {
+                r#"A string literal is missing its closing quote before the end of the file.
+
+A string literal that is missing its closing quote before the end of a line
+is reported as E0003 instead.
+
+Erroneous code example, where the file ends right after `hello`:
+
+```text
+main(args: int[][]) {
+    s: int[] = "hello
+```
+
+To fix this, close the literal with a double quote:
+
+```xi
+main(args: int[][]) {
+    s: int[] = "hello"
+}
+```"#
+            }
+            Code::UnexpectedEof => {
+                r#"The file ended in the middle of an item, usually because of a missing
+closing brace.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = 1
+```
+
+To fix this, close every block that is still open:
+
+```xi
+main(args: int[][]) {
+    x: int = 1
+}
+```"#
+            }
+            Code::UnexpectedToken => {
+                r#"The parser found a token that cannot appear at this point in the program.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = * 2
+}
+```
+
+To fix this, complete or remove the malformed expression or statement:
+
+```xi
+main(args: int[][]) {
+    x: int = 3 * 2
+}
+```"#
+            }
+            Code::InvalidInteger => {
+                r#"An integer literal does not fit in a signed 64-bit integer.
+
+Integer literals must lie between `-9223372036854775808` and
+`9223372036854775807`. The minimum value is only accepted when the negation
+applies directly to the literal, without parentheses.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = -(9223372036854775808)
+}
+```
+
+To fix this, negate the literal directly:
+
+```xi
+main(args: int[][]) {
+    x: int = -9223372036854775808
+}
+```"#
+            }
+            Code::ArrayLength => {
+                r#"An array type specifies a length outside of a variable declaration.
+
+Array lengths allocate storage, so they are only allowed when declaring a
+local or global variable. Parameter, return, and field types cannot have them.
+
+Erroneous code example:
+
+```xi
+make(): int[3] {
+    return {1, 2, 3}
+}
+```
+
+To fix this, remove the length from the type:
+
+```xi
+make(): int[] {
+    return {1, 2, 3}
+}
+```"#
+            }
+            Code::MissingLength => {
+                r#"An array type specifies the length of an inner dimension, but not of an
+outer dimension.
+
+Lengths in an array declaration must form a prefix: once a dimension has no
+length, none of the dimensions nested inside it can have one.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    grid: int[][3]
+}
+```
+
+To fix this, also give the outer dimension a length, or remove the inner one:
+
+```xi
+main(args: int[][]) {
+    grid: int[3][3]
+}
+```"#
+            }
+            Code::NoSuperclass => {
+                r#"A method uses `super` in a class that does not extend another class.
+
+Erroneous code example:
+
+```xi
+class A {
+    x: int
+
+    reset() {
+        super.x = 0
+    }
+}
+```
+
+To fix this, access the field or method through `this` instead, or make the
+class extend the superclass that defines it:
+
+```xi
+class A {
+    x: int
+
+    reset() {
+        this.x = 0
+    }
+}
+```"#
+            }
+            Code::FinalSuperclass => {
+                r#"A class extends another class that is declared `final`.
+
+Final classes cannot have subclasses, which lets the compiler call their
+methods directly instead of through a dispatch vector.
+
+Erroneous code example:
+
+```xi
+final class A {}
+
+class B extends A {}
+```
+
+To fix this, remove the `final` annotation from the superclass, or stop
+extending it:
+
+```xi
+class A {}
+
+class B extends A {}
+```"#
+            }
+            Code::ClassIncomplete => {
+                r#"A class does not implement a method required by an interface.
+
+A class that implements an interface, or that is declared in a module's
+interface file, must define every method declared there.
+
+Erroneous code example:
+
+```xi
+interface Shape {
+    area(): int
+}
+
+class Square implements Shape {
+    side: int
+}
+```
+
+To fix this, implement each missing method with the same signature:
+
+```xi
+interface Shape {
+    area(): int
+}
+
+class Square implements Shape {
+    side: int
+
+    area(): int {
+        return side * side
+    }
+}
+```"#
+            }
+            Code::ClassCycle => {
+                r#"A class directly or indirectly extends itself.
+
+Erroneous code example:
+
+```xi
+class A extends B {}
+
+class B extends A {}
+```
+
+To fix this, break the cycle so that the hierarchy has a root:
+
+```xi
+class A {}
+
+class B extends A {}
+```"#
+            }
+            Code::NotInterface => {
+                r#"A class lists something other than an interface after `implements`.
+
+Classes are inherited with `extends`. Only interfaces can be implemented.
+
+Erroneous code example:
+
+```xi
+class Shape {
+    area(): int {
+        return 0
+    }
+}
+
+class Square implements Shape {
+    area(): int {
+        return 1
+    }
+}
+```
+
+To fix this, extend the class instead, or turn it into an interface:
+
+```xi
+class Shape {
+    area(): int {
+        return 0
+    }
+}
+
+class Square extends Shape {
+    area(): int {
+        return 1
+    }
+}
+```"#
+            }
+            Code::ExtendsInterface => {
+                r#"A class lists an interface after `extends`.
+
+Interfaces have no fields or method bodies to inherit, so classes must
+implement them instead.
+
+Erroneous code example:
+
+```xi
+interface Shape {
+    area(): int
+}
+
+class Square extends Shape {
+    area(): int {
+        return 1
+    }
+}
+```
+
+To fix this, use `implements`:
+
+```xi
+interface Shape {
+    area(): int
+}
+
+class Square implements Shape {
+    area(): int {
+        return 1
+    }
+}
+```"#
+            }
+            Code::NewInterface => {
+                r#"A `new` expression names an interface.
+
+Interfaces have no implementation, so only classes can be instantiated.
+
+Erroneous code example:
+
+```xi
+interface Shape {
+    area(): int
+}
+
+main(args: int[][]) {
+    shape: Shape = new Shape
+}
+```
+
+To fix this, instantiate a class that implements the interface:
+
+```xi
+interface Shape {
+    area(): int
+}
+
+class Square implements Shape {
+    area(): int {
+        return 1
+    }
+}
+
+main(args: int[][]) {
+    shape: Shape = new Square
+}
+```"#
+            }
+            Code::FinalMismatch => {
+                r#"A class is declared `final` in a module's interface file but not in its
+implementation, or the other way around.
+
+Other modules rely on the interface to decide how methods are dispatched, so
+both declarations must agree.
+
+Erroneous code example, where `shape.ixi` contains `final class Shape {}`:
+
+```text
+class Shape {}
+```
+
+To fix this, annotate the class the same way in both files:
+
+```text
+final class Shape {}
+```"#
+            }
+            Code::BuiltinClass => {
+                r#"A program declares a class with the same name as a built-in class.
+
+The `Exception` class, along with its subclasses thrown by the runtime,
+is declared implicitly in every program and cannot be redeclared.
+
+Erroneous code example:
+
+```xi
+class Exception {
+    message: int[]
+}
+```
+
+To fix this, rename the class, or extend the built-in class instead:
+
+```xi
+class Failure extends Exception {
+    message: int[]
+}
+```"#
+            }
+            Code::NotInClass => {
+                r#"An expression uses `this` or `super` outside of a method.
+
+Erroneous code example:
+
+```xi
+class Counter {
+    count: int
+}
+
+increment() {
+    this.count = this.count + 1
+}
+```
+
+To fix this, move the code into a method, or take the object as a parameter:
+
+```xi
+class Counter {
+    count: int
+}
+
+increment(counter: Counter) {
+    counter.count = counter.count + 1
+}
+```"#
+            }
+            Code::NotInClassModule => {
+                r#"A `new` expression names a class that is defined in a different module.
+
+Only the module that implements a class knows its fields and how to
+initialize them, so other modules must call a function from that module to
+create instances.
+
+Erroneous code example, where `shape.ixi` declares the class `Shape` and the
+function `createShape(): Shape`:
+
+```text
+use shape
+
+main(args: int[][]) {
+    s: Shape = new Shape
+}
+```
+
+To fix this, use a function exported by the defining module:
+
+```text
+use shape
+
+main(args: int[][]) {
+    s: Shape = createShape()
+}
+```"#
+            }
+            Code::NotClass => {
+                r#"The receiver of a `.` operator is not an object.
+
+Only objects have fields and methods. Arrays, integers, and booleans do not.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    n: int = 5
+    m: int = n.value
+}
+```
+
+To fix this, use the value directly, or wrap it in a class with a field:
+
+```xi
+class Box {
+    value: int
+}
+
+main(args: int[][]) {
+    n: Box = new Box
+    m: int = n.value
+}
+```"#
+            }
+            Code::ImpossibleCast => {
+                r#"A cast or `instanceof` test can never succeed, because the expression's type
+is unrelated to the target class.
+
+Erroneous code example:
+
+```xi
+class Animal {}
+
+test(x: int): Animal {
+    return x as Animal
+}
+```
+
+To fix this, only cast between a class and its superclasses or subclasses:
+
+```xi
+class Animal {}
+
+class Dog extends Animal {}
+
+test(x: Animal): Dog {
+    return x as Dog
+}
+```"#
+            }
+            Code::NotFound => {
+                r#"A `use` statement names an interface that is not in the library directory.
+
+Interfaces are loaded from the directory given by `--libpath`, or from the
+directory containing the source file if it is not provided.
+
+Erroneous code example, when there is no `shapes.ixi` in the library directory:
+
+```text
+use shapes
+
+main(args: int[][]) {}
+```
+
+To fix this, correct the name of the interface, or pass the directory
+containing it with `--libpath`."#
+            }
+            Code::UnboundVariable => {
+                r#"An expression refers to a variable or field that is not in scope.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = y + 1
+}
+```
+
+To fix this, declare the variable before using it:
+
+```xi
+main(args: int[][]) {
+    y: int = 1
+    x: int = y + 1
+}
+```"#
+            }
+            Code::UnboundFun => {
+                r#"A call refers to a function or method that is not in scope.
+
+Functions from other modules must be imported with a `use` statement.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    greet()
+}
+```
+
+To fix this, define the function, or import the interface that declares it
+with `use`:
+
+```xi
+greet() {}
+
+main(args: int[][]) {
+    greet()
+}
+```"#
+            }
+            Code::UnboundFunTemplate => {
+                r#"An explicit template instantiation refers to a function template that is not
+in scope.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = identity::<int>(1)
+}
+```
+
+To fix this, define the function template before instantiating it:
+
+```xi
+template identity<T>(x: T): T {
+    return x
+}
+
+main(args: int[][]) {
+    x: int = identity::<int>(1)
+}
+```"#
+            }
+            Code::UnboundClass => {
+                r#"A type or `new` expression refers to a class that is not in scope.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    p: Point = new Point
+}
+```
+
+To fix this, define the class, or import the interface that declares it:
+
+```xi
+class Point {
+    x: int
+    y: int
+}
+
+main(args: int[][]) {
+    p: Point = new Point
+}
+```"#
+            }
+            Code::UnboundClassTemplate => {
+                r#"A type refers to a class template that is not in scope.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    b: Box::<int> = new Box::<int>
+}
+```
+
+To fix this, define the class template before instantiating it:
+
+```xi
+template class Box<T> {
+    value: T
+}
+
+main(args: int[][]) {
+    b: Box::<int> = new Box::<int>
+}
+```"#
+            }
+            Code::NotVariable => {
+                r#"An expression reads a method as if it were a field, or assigns to the name of
+a function.
+
+Erroneous code example:
+
+```xi
+class Point {
+    x: int
+
+    getX(): int {
+        return x
+    }
+}
+
+main(args: int[][]) {
+    p: Point = new Point
+    x: int = p.getX
+}
+```
+
+To fix this, call the method:
+
+```xi
+class Point {
+    x: int
+
+    getX(): int {
+        return x
+    }
+}
+
+main(args: int[][]) {
+    p: Point = new Point
+    x: int = p.getX()
+}
+```"#
+            }
+            Code::NotFun => {
+                r#"A call refers to something that is not a function.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    answer: int = 42
+    x: int = answer()
+}
+```
+
+To fix this, remove the call, or rename the variable that shadows the function:
+
+```xi
+main(args: int[][]) {
+    answer: int = 42
+    x: int = answer
+}
+```"#
+            }
+            Code::NameClash => {
+                r#"A declaration reuses a name that is already bound in the same scope.
+
+Xi does not allow shadowing: local variables cannot reuse the names of other
+variables in scope, and top-level items must have unique names.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = 1
+    if x > 0 {
+        x: int = 2
+    }
+}
+```
+
+To fix this, give the inner declaration a different name:
+
+```xi
+main(args: int[][]) {
+    x: int = 1
+    if x > 0 {
+        y: int = 2
+    }
+}
+```"#
+            }
+            Code::SignatureMismatch => {
+                r#"A function or method is defined with a different signature than the one
+declared in an interface file.
+
+Erroneous code example, where `shape.ixi` declares `area(side: int): int`:
+
+```text
+area(side: int, height: int): int {
+    return side * height
+}
+```
+
+To fix this, make the parameter and return types match the declaration:
+
+```text
+area(side: int): int {
+    return side * side
+}
+```"#
+            }
+            Code::UnboundLabel => {
+                r#"A `break` or `continue` names a loop label that is not in scope.
+
+Labels are only visible inside the body of the loop they annotate.
+
+Erroneous code example:
+
+```xi
+f(b: bool) {
+    outer@ while b {
+    }
+    continue@outer
+}
+```
+
+To fix this, move the statement inside the labeled loop:
+
+```xi
+f(b: bool) {
+    outer@ while b {
+        continue@outer
+    }
+}
+```"#
+            }
+            Code::NotInWhile => {
+                r#"A `break` or `continue` statement appears outside of a loop.
+
+Erroneous code example:
+
+```xi
+f(b: bool) {
+    if b {
+        break
+    }
+}
+```
+
+To fix this, use `return` to leave the function instead:
+
+```xi
+f(b: bool) {
+    if b {
+        return
+    }
+}
+```"#
+            }
+            Code::AssignCaptured => {
+                r#"A closure assigns to a variable that it captures from an enclosing function.
+
+Closures capture variables by value, so the assignment would only update the
+closure's own copy.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = 1
+    f: fn() = fn() {
+        x = 2
+    }
+}
+```
+
+To fix this, store the value in an array or object, which are captured by
+reference:
+
+```xi
+main(args: int[][]) {
+    x: int[] = {1}
+    f: fn() = fn() {
+        x[0] = 2
+    }
+}
+```"#
+            }
+            Code::Mismatch => {
+                r#"An expression has a different type than its context requires.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: bool = 1
+}
+```
+
+To fix this, change either the expression or the expected type:
+
+```xi
+main(args: int[][]) {
+    x: int = 1
+}
+```"#
+            }
+            Code::NotExp => {
+                r#"A call used as an expression does not return exactly one value.
+
+Procedures return no values, and calls to functions with multiple return
+values can only appear on the right-hand side of a multiple assignment.
+
+Erroneous code example:
+
+```xi
+pair(): int, int {
+    return 1, 2
+}
+
+main(args: int[][]) {
+    x: int = pair() + 1
+}
+```
+
+To fix this, bind each return value to a variable first:
+
+```xi
+pair(): int, int {
+    return 1, 2
+}
+
+main(args: int[][]) {
+    a: int, b: int = pair()
+    x: int = a + 1
+}
+```"#
+            }
+            Code::NotProcedure => {
+                r#"A call statement invokes a function that returns values.
+
+Call statements can only invoke procedures. Results of functions must be
+used, or explicitly discarded with `_`.
+
+Erroneous code example:
+
+```xi
+answer(): int {
+    return 42
+}
+
+main(args: int[][]) {
+    answer()
+}
+```
+
+To fix this, discard the result explicitly:
+
+```xi
+answer(): int {
+    return 42
+}
+
+main(args: int[][]) {
+    _ = answer()
+}
+```"#
+            }
+            Code::CallLength => {
+                r#"A function is called with the wrong number of arguments.
+
+Erroneous code example:
+
+```xi
+add(x: int, y: int): int {
+    return x + y
+}
+
+main(args: int[][]) {
+    z: int = add(1)
+}
+```
+
+To fix this, pass one argument for each parameter:
+
+```xi
+add(x: int, y: int): int {
+    return x + y
+}
+
+main(args: int[][]) {
+    z: int = add(1, 2)
+}
+```"#
+            }
+            Code::InitLength => {
+                r#"A multiple assignment declares a different number of variables than the
+function on the right-hand side returns.
+
+Erroneous code example:
+
+```xi
+pair(): int, int {
+    return 1, 2
+}
+
+main(args: int[][]) {
+    _ = pair()
+}
+```
+
+To fix this, declare or discard each of the returned values:
+
+```xi
+pair(): int, int {
+    return 1, 2
+}
+
+main(args: int[][]) {
+    x: int, _ = pair()
+}
+```"#
+            }
+            Code::InitProcedure => {
+                r#"A declaration is initialized with a call to a procedure, which returns no
+values.
+
+Erroneous code example:
+
+```xi
+reset() {}
+
+main(args: int[][]) {
+    _ = reset()
+}
+```
+
+To fix this, call the procedure as a statement:
+
+```xi
+reset() {}
+
+main(args: int[][]) {
+    reset()
+}
+```"#
+            }
+            Code::IndexEmpty => {
+                r#"An expression indexes into an empty array literal.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    x: int = {}[0]
+}
+```
+
+To fix this, index into an array that has elements:
+
+```xi
+main(args: int[][]) {
+    x: int = {1}[0]
+}
+```"#
+            }
+            Code::NotException => {
+                r#"A `throw` statement or `catch` clause uses a type that does not extend the
+built-in `Exception` class.
+
+Erroneous code example:
+
+```xi
+main(args: int[][]) {
+    throw null
+}
+```
+
+To fix this, throw an instance of a subclass of `Exception`:
+
+```xi
+class Failure extends Exception {}
+
+main(args: int[][]) {
+    throw new Failure
+}
+```"#
+            }
+            Code::Unreachable => {
+                r#"A statement can never be executed, because every path before it returns,
+throws, breaks, or continues.
+
+Erroneous code example:
+
+```xi
+f(args: int[][]) {
+    throw new OutOfBounds
+    args[0][0] = 1
+}
+```
+
+To fix this, remove the unreachable statement, or make the earlier statement
+conditional:
+
+```xi
+f(args: int[][]) {
+    if length(args) == 0 {
+        throw new OutOfBounds
+    }
+    args[0][0] = 1
+}
+```"#
+            }
+            Code::MissingReturn => {
+                r#"A function that returns values can reach the end of its body without a
+`return` statement.
+
+Erroneous code example:
+
+```xi
+sign(x: int): int {
+    if x < 0 {
+        return -1
+    }
+}
+```
+
+To fix this, return a value on every path through the function:
+
+```xi
+sign(x: int): int {
+    if x < 0 {
+        return -1
+    }
+    return 1
+}
+```"#
+            }
+            Code::ReturnMismatch => {
+                r#"A `return` statement returns a different number of values than the function
+declares.
+
+Erroneous code example:
+
+```xi
+f() {
+    return 1
+}
+```
+
+To fix this, declare the return type, or return nothing:
+
+```xi
+f(): int {
+    return 1
+}
+```"#
+            }
+            Code::TemplateArgumentMismatch => {
+                r#"A template is instantiated with the wrong number of type arguments.
+
+Erroneous code example:
+
+```xi
+template identity<T>(x: T): T {
+    return x
+}
+
+main(args: int[][]) {
+    x: int = identity::<int, bool>(1)
+}
+```
+
+To fix this, pass one type argument for each type parameter:
+
+```xi
+template identity<T>(x: T): T {
+    return x
+}
+
+main(args: int[][]) {
+    x: int = identity::<int>(1)
+}
+```"#
+            }
+            Code::UnsatisfiedBound => {
+                r#"A template is instantiated with a type argument that does not satisfy the
+bound on its type parameter.
+
+Erroneous code example:
+
+```xi
+class Shape {}
+
+template class Canvas<T: Shape> {
+    shapes: T[]
+}
+
+main(args: int[][]) {
+    c: Canvas::<int> = new Canvas::<int>
+}
+```
+
+To fix this, pass a type that is a subtype of the bound:
+
+```xi
+class Shape {}
+
+class Square extends Shape {}
+
+template class Canvas<T: Shape> {
+    shapes: T[]
+}
+
+main(args: int[][]) {
+    c: Canvas::<Square> = new Canvas::<Square>
+}
+```"#
+            }
+            Code::UninferredTemplateArgument => {
+                r#"A call to a function template omits its type arguments, but they cannot be
+inferred from the types of the call's arguments.
+
+Erroneous code example:
+
+```xi
+template make<T>(): T[] {
+    return {}
+}
+
+main(args: int[][]) {
+    x: int[] = make()
+}
+```
+
+To fix this, pass the type arguments explicitly:
+
+```xi
+template make<T>(): T[] {
+    return {}
+}
+
+main(args: int[][]) {
+    x: int[] = make::<int>()
+}
+```"#
+            }
+            Code::AmbiguousTemplateArgument => {
+                r#"A call to a function template omits its type arguments, but the call's
+arguments imply conflicting types for the same type parameter.
+
+Erroneous code example:
+
+```xi
+template first<T>(x: T, y: T): T {
+    return x
+}
+
+main(args: int[][]) {
+    x: int = first(1, true)
+}
+```
+
+To fix this, pass arguments of compatible types, or pass the type arguments
+explicitly so that mismatches are reported at each argument:
+
+```xi
+template first<T>(x: T, y: T): T {
+    return x
+}
+
+main(args: int[][]) {
+    x: int = first(1, 2)
+}
+```"#
+            }
+            Code::EnumNotClass => {
+                r#"An enum is used where a class is required, such as after `extends` or in a
+`new` expression.
+
+Erroneous code example:
+
+```xi
+enum Shape {
+    Circle(int)
+    Empty
+}
+
+main(args: int[][]) {
+    shape: Shape = new Shape
+}
+```
+
+To fix this, construct one of the enum's variants:
+
+```xi
+enum Shape {
+    Circle(int)
+    Empty
+}
+
+main(args: int[][]) {
+    shape: Shape = Shape.Circle(1)
+}
+```"#
+            }
+            Code::UnboundVariant => {
+                r#"An expression or pattern refers to a variant that the enum does not define.
+
+Erroneous code example:
+
+```xi
+enum Shape {
+    Circle(int)
+    Empty
+}
+
+main(args: int[][]) {
+    shape: Shape = Shape.Square(1)
+}
+```
+
+To fix this, use one of the enum's variants, or add the missing variant:
+
+```xi
+enum Shape {
+    Circle(int)
+    Square(int)
+    Empty
+}
+
+main(args: int[][]) {
+    shape: Shape = Shape.Square(1)
+}
+```"#
+            }
+            Code::VariantArity => {
+                r#"A pattern matches a variant with the wrong number of subpatterns.
+
+Erroneous code example:
+
+```xi
+enum Shape {
+    Circle(int)
+    Empty
+}
+
+area(shape: Shape): int {
+    return match shape {
+        Circle(r, s) => r,
+        Empty => 0,
+    }
+}
+```
+
+To fix this, match one subpattern for each of the variant's fields:
+
+```xi
+enum Shape {
+    Circle(int)
+    Empty
+}
+
+area(shape: Shape): int {
+    return match shape {
+        Circle(r) => r,
+        Empty => 0,
+    }
+}
+```"#
+            }
+            Code::PatternMismatch => {
+                r#"A pattern can never match values of the type being matched.
+
+Erroneous code example:
+
+```xi
+test(n: int): int {
+    return match n {
+        true => 1,
+        _ => 0,
+    }
+}
+```
+
+To fix this, use patterns of the same type as the matched value:
+
+```xi
+test(n: int): int {
+    return match n {
+        0 => 1,
+        _ => 0,
+    }
+}
+```"#
+            }
+            Code::NonExhaustive => {
+                r#"A `match` does not cover every possible value of the matched type. The
+error message includes an example of a value that is not covered.
+
+Erroneous code example:
+
+```xi
+test(n: int): int {
+    return match n {
+        0 => 1,
+        1 => 2,
+    }
+}
+```
+
+To fix this, add arms for the missing values, or a wildcard arm:
+
+```xi
+test(n: int): int {
+    return match n {
+        0 => 1,
+        1 => 2,
+        _ => 3,
+    }
+}
+```"#
+            }
+            Code::RedundantPattern => {
+                r#"A `match` arm can never be taken, because earlier arms cover every value it
+matches.
+
+Erroneous code example:
+
+```xi
+enum Shape {
+    Circle(int)
+    Empty
+}
+
+area(shape: Shape): int {
+    return match shape {
+        Circle(_) => 1,
+        Empty => 0,
+        Circle(1) => 2,
+    }
+}
+```
+
+To fix this, remove the arm, or move it before the arms that cover it:
+
+```xi
+enum Shape {
+    Circle(int)
+    Empty
+}
+
+area(shape: Shape): int {
+    return match shape {
+        Circle(1) => 2,
+        Circle(_) => 1,
+        Empty => 0,
+    }
+}
+```"#
+            }
+            Code::InterfaceEnum => {
+                r#"An interface derived with `--emit-interface` or `--check-interface` would
+mention an enum. Interface files cannot declare enums, so modules that use the
+interface could not resolve it.
+
+This applies to the signatures of exported functions and methods, and to the
+bodies of exported templates. Deriving an interface for the following reports
+this error, since `pick` is exported:
+
+```xi
+enum Color {
+    Red
+    Green
+}
+
+pick(): Color {
+    return Color.Red
+}
+```
+
+To fix this, keep the enum out of exported signatures and templates, for example
+by wrapping it in a class or converting it to an `int` at the module boundary."#
+            }
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.to_static_str())
+    }
+}
+
+impl str::FromStr for Code {
+    type Err = anyhow::Error;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Code::ALL
+            .into_iter()
+            .find(|code| code.to_static_str().eq_ignore_ascii_case(string) || code.name() == string)
+            .ok_or_else(|| anyhow!("Unknown error code {}", string))
+    }
+}
@@ -0,0 +1,1152 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+use pretty::RcDoc;
+
+use crate::data::ast;
+use crate::data::span::Point;
+use crate::data::span::Span;
+use crate::data::symbol;
+use crate::data::token;
+use crate::data::token::Size;
+use crate::parse;
+use crate::util;
+
+/// Maximum line width before breaking argument lists, array literals, and match expressions
+const WIDTH: usize = 100;
+
+/// Spaces per indentation level
+const INDENT: isize = 4;
+
+type Doc = RcDoc<'static, ()>;
+
+/// Delimiters that `pretty` only provides on `DocBuilder`.
+trait Enclose {
+    fn parens(self) -> Self;
+    fn angles(self) -> Self;
+    fn brackets(self) -> Self;
+}
+
+impl Enclose for Doc {
+    fn parens(self) -> Self {
+        Doc::text("(").append(self).append(Doc::text(")"))
+    }
+
+    fn angles(self) -> Self {
+        Doc::text("<").append(self).append(Doc::text(">"))
+    }
+
+    fn brackets(self) -> Self {
+        Doc::text("[").append(self).append(Doc::text("]"))
+    }
+}
+
+/// Pretty-prints a Xi program or interface as canonical source, preserving its comments.
+///
+/// Unlike the compiler, refuses to recover from syntax errors, since the recovered
+/// program would be missing the items and statements that failed to parse.
+pub fn format(path: &Path, tokens: token::Tokens) -> Result<String, crate::Error> {
+    log::info!(
+        "[{}] Formatting {}...",
+        std::any::type_name::<token::Tokens>(),
+        path.display()
+    );
+    util::time!(
+        "[{}] Done formatting {}",
+        std::any::type_name::<token::Tokens>(),
+        path.display()
+    );
+
+    let comments = tokens.comments().to_vec();
+    let mut formatter = Formatter {
+        comments: &comments,
+        next: 0,
+    };
+
+    let doc = if path.extension() == Some(OsStr::new("ixi")) {
+        let mut recovered = Vec::new();
+        let interface = parse::InterfaceParser::new().parse(&mut recovered, tokens);
+        let (interface, errors) = parse::finish(recovered, interface)?;
+
+        if !errors.is_empty() {
+            return Err(crate::Error::Syntactic(errors));
+        }
+
+        formatter.interface(&interface)
+    } else {
+        formatter.program(&parse::parse(path, tokens)?)
+    };
+
+    Ok(render(doc))
+}
+
+/// Pretty-prints an interface without comments, e.g. one derived from an implementation.
+pub(crate) fn interface_string(interface: &ast::Interface<()>) -> String {
+    render(Formatter::default().interface(interface))
+}
+
+/// Pretty-prints a single item of an interface without comments.
+pub(crate) fn item_signature_string(item: &ast::ItemSignature<()>) -> String {
+    render(Formatter::default().item_signature(item))
+}
+
+fn render(doc: Doc) -> String {
+    let mut rendered = String::new();
+    doc.render_fmt(WIDTH, &mut rendered)
+        .expect("[INTERNAL ERROR]: writing to string cannot fail");
+
+    // Blank lines inherit the indentation of their enclosing block
+    let mut formatted = String::with_capacity(rendered.len());
+    for line in rendered.lines() {
+        formatted.push_str(line.trim_end());
+        formatted.push('\n');
+    }
+
+    formatted
+}
+
+/// Renders a type as canonical source on a single line.
+pub(crate) fn type_string(r#type: &ast::Type<()>) -> String {
+    type_doc(r#type).pretty(usize::MAX).to_string()
+}
+
+/// Renders parameters and return types as canonical source on a single line,
+/// e.g. `(x: int, y: int): bool`.
+pub(crate) fn parameters_string(
+    parameters: &[ast::SingleDeclaration<()>],
+    types: &[ast::Type<()>],
+) -> String {
+    tuple(parameters.iter().map(single_declaration))
+        .append(returns(types))
+        .pretty(usize::MAX)
+        .to_string()
+}
+
+#[derive(Default)]
+struct Formatter<'a> {
+    /// Every comment in the source file, in order
+    comments: &'a [token::Comment],
+
+    /// Index of the first comment not yet emitted
+    next: usize,
+}
+
+/// Accumulates nodes and comments one per line, preserving at most one blank
+/// line between them from the original source.
+#[derive(Default)]
+struct Lines {
+    doc: Option<Doc>,
+
+    /// Last source row of the previous line
+    row: usize,
+
+    /// Whether to force a blank line before the next line
+    separate: bool,
+}
+
+impl Lines {
+    fn push(&mut self, span: Span, line: Doc) {
+        self.doc = Some(match self.doc.take() {
+            None => line,
+            Some(doc) if self.separate || span.lo.row() > self.row + 1 => doc
+                .append(Doc::hardline())
+                .append(Doc::hardline())
+                .append(line),
+            Some(doc) => doc.append(Doc::hardline()).append(line),
+        });
+        self.row = span.hi.row();
+        self.separate = false;
+    }
+}
+
+impl<'a> Formatter<'a> {
+    fn interface(&mut self, interface: &ast::Interface<()>) -> Doc {
+        self.file(
+            &interface.uses,
+            &interface.items,
+            |item| match item {
+                ast::ItemSignature::Class(class) => class.span,
+                ast::ItemSignature::ClassTemplate(class) => class.span,
+                ast::ItemSignature::Protocol(protocol) => protocol.span,
+                ast::ItemSignature::Function(function) => function.span,
+                ast::ItemSignature::FunctionTemplate(function) => function.span,
+            },
+            |previous, next| {
+                // Consecutive function signatures keep their original spacing
+                !matches!(
+                    (previous, next),
+                    (
+                        ast::ItemSignature::Function(_),
+                        ast::ItemSignature::Function(_)
+                    ),
+                )
+            },
+            Self::item_signature,
+        )
+    }
+
+    fn program(&mut self, program: &ast::Program<()>) -> Doc {
+        self.file(
+            &program.uses,
+            &program.items,
+            |item| match item {
+                ast::Item::Global(ast::Global::Declaration(declaration)) => declaration.span(),
+                ast::Item::Global(ast::Global::Initialization(initialization)) => {
+                    initialization.span
+                }
+                ast::Item::Class(class) => class.span,
+                ast::Item::ClassTemplate(class) => class.span,
+                ast::Item::Protocol(protocol) => protocol.span,
+                ast::Item::Enum(r#enum) => r#enum.span,
+                ast::Item::EnumTemplate(r#enum) => r#enum.span,
+                ast::Item::Function(function) => function.span,
+                ast::Item::FunctionTemplate(function) => function.span,
+            },
+            |previous, next| {
+                // Consecutive globals keep their original spacing
+                !matches!(
+                    (previous, next),
+                    (ast::Item::Global(_), ast::Item::Global(_))
+                )
+            },
+            Self::item,
+        )
+    }
+
+    fn file<I>(
+        &mut self,
+        uses: &[ast::Use],
+        items: &[I],
+        span: impl Fn(&I) -> Span,
+        separate: impl Fn(&I, &I) -> bool,
+        item: impl FnMut(&mut Self, &I) -> Doc,
+    ) -> Doc {
+        let uses = uses.last().and_then(|last| {
+            self.lines(
+                uses,
+                Some(last.span.hi),
+                |r#use| r#use.span,
+                |_, _| false,
+                |_, r#use| Doc::text("use ").append(identifier(&r#use.name)),
+            )
+        });
+
+        let items = self.lines(items, None, span, separate, item);
+
+        match (uses, items) {
+            (None, None) => Doc::nil(),
+            (Some(doc), None) | (None, Some(doc)) => doc,
+            (Some(uses), Some(items)) => uses
+                .append(Doc::hardline())
+                .append(Doc::hardline())
+                .append(items),
+        }
+    }
+
+    /// Lays out `nodes` one per line, each preceded by the comments before it and
+    /// followed by any comment on its last line. Flushes the remaining comments
+    /// before `end`, or every remaining comment if `end` is `None`.
+    ///
+    /// Returns `None` if there are neither nodes nor comments to lay out.
+    fn lines<N>(
+        &mut self,
+        nodes: &[N],
+        end: Option<Point>,
+        span: impl Fn(&N) -> Span,
+        separate: impl Fn(&N, &N) -> bool,
+        mut format: impl FnMut(&mut Self, &N) -> Doc,
+    ) -> Option<Doc> {
+        let mut lines = Lines::default();
+
+        for (index, node) in nodes.iter().enumerate() {
+            let node_span = span(node);
+
+            lines.separate = index > 0 && separate(&nodes[index - 1], node);
+
+            while let Some(comment) = self.comment(Some(node_span.lo)) {
+                lines.push(comment.span, comment_doc(comment));
+            }
+
+            let mut doc = format(self, node);
+
+            let next = nodes.get(index + 1).map(|next| span(next).lo).or(end);
+            if let Some(comment) = self.trailing(node_span.hi.row(), next) {
+                doc = doc.append(Doc::space()).append(comment_doc(comment));
+            }
+
+            lines.push(node_span, doc);
+        }
+
+        while let Some(comment) = self.comment(end) {
+            lines.push(comment.span, comment_doc(comment));
+        }
+
+        lines.doc
+    }
+
+    /// Takes the next comment if it starts before `before`.
+    fn comment(&mut self, before: Option<Point>) -> Option<&'a token::Comment> {
+        let comment = self
+            .comments
+            .get(self.next)
+            .filter(|comment| before.is_none_or(|before| comment.span.lo < before))?;
+        self.next += 1;
+        Some(comment)
+    }
+
+    /// Takes the next comment if it starts on `row` and before `before`.
+    fn trailing(&mut self, row: usize, before: Option<Point>) -> Option<&'a token::Comment> {
+        match self.comments.get(self.next) {
+            Some(comment) if comment.span.lo.row() == row => self.comment(before),
+            _ => None,
+        }
+    }
+
+    fn item_signature(&mut self, item: &ast::ItemSignature<()>) -> Doc {
+        match item {
+            ast::ItemSignature::Class(class) => {
+                let header = class_header(class.r#final, &class.name, None, &class.extends)
+                    .append(implements(&class.implements));
+                let methods = self.lines(
+                    &class.methods,
+                    Some(class.span.hi),
+                    |method| method.span,
+                    |_, _| false,
+                    |_, method| signature(&method.name, &method.parameters, &method.returns),
+                );
+                header.append(Doc::space()).append(braces(methods))
+            }
+            ast::ItemSignature::ClassTemplate(class) => self.class_template(class),
+            ast::ItemSignature::Protocol(protocol) => self.protocol(protocol),
+            ast::ItemSignature::Function(function) => {
+                signature(&function.name, &function.parameters, &function.returns)
+            }
+            ast::ItemSignature::FunctionTemplate(function) => self.function_template(function),
+        }
+    }
+
+    fn item(&mut self, item: &ast::Item<()>) -> Doc {
+        match item {
+            ast::Item::Global(ast::Global::Declaration(declaration)) => {
+                declaration_doc(declaration)
+            }
+            ast::Item::Global(ast::Global::Initialization(initialization)) => {
+                self.initialization(initialization)
+            }
+            ast::Item::Class(class) => {
+                let header = class_header(class.r#final, &class.name, None, &class.extends)
+                    .append(implements(&class.implements));
+                let items = self.class_items(&class.items, class.span);
+                header.append(Doc::space()).append(items)
+            }
+            ast::Item::ClassTemplate(class) => self.class_template(class),
+            ast::Item::Protocol(protocol) => self.protocol(protocol),
+            ast::Item::Enum(r#enum) => {
+                let variants = self.variants(&r#enum.variants, r#enum.span);
+                Doc::text("enum ")
+                    .append(identifier(&r#enum.name))
+                    .append(Doc::space())
+                    .append(variants)
+            }
+            ast::Item::EnumTemplate(r#enum) => {
+                let variants = self.variants(&r#enum.variants, r#enum.span);
+                Doc::text("template enum ")
+                    .append(identifier(&r#enum.name))
+                    .append(generics(&r#enum.generics))
+                    .append(Doc::space())
+                    .append(variants)
+            }
+            ast::Item::Function(function) => self.function(function),
+            ast::Item::FunctionTemplate(function) => self.function_template(function),
+        }
+    }
+
+    fn class_template(&mut self, class: &ast::ClassTemplate) -> Doc {
+        let header = Doc::text("template ")
+            .append(class_header(
+                class.r#final,
+                &class.name,
+                Some(&class.generics[..]),
+                &class.extends,
+            ))
+            .append(implements(&class.implements));
+        let items = self.class_items(&class.items, class.span);
+        header.append(Doc::space()).append(items)
+    }
+
+    fn class_items(&mut self, items: &[ast::ClassItem<()>], span: Span) -> Doc {
+        let items = self.lines(
+            items,
+            Some(span.hi),
+            |item| match item {
+                ast::ClassItem::Field(declaration) => declaration.span(),
+                ast::ClassItem::Method(method) => method.span,
+            },
+            // Consecutive fields keep their original spacing
+            |previous, next| {
+                !matches!(
+                    (previous, next),
+                    (ast::ClassItem::Field(_), ast::ClassItem::Field(_)),
+                )
+            },
+            |formatter, item| match item {
+                ast::ClassItem::Field(declaration) => declaration_doc(declaration),
+                ast::ClassItem::Method(method) => formatter.function(method),
+            },
+        );
+        braces(items)
+    }
+
+    fn protocol(&mut self, protocol: &ast::Protocol<()>) -> Doc {
+        let methods = self.lines(
+            &protocol.methods,
+            Some(protocol.span.hi),
+            |method| method.span,
+            |_, _| false,
+            |_, method| signature(&method.name, &method.parameters, &method.returns),
+        );
+        Doc::text("interface ")
+            .append(identifier(&protocol.name))
+            .append(Doc::space())
+            .append(braces(methods))
+    }
+
+    fn variants(&mut self, variants: &[ast::Variant<()>], span: Span) -> Doc {
+        let variants = self.lines(
+            variants,
+            Some(span.hi),
+            |variant| variant.span,
+            |_, _| false,
+            |_, variant| {
+                let name = identifier(&variant.name);
+                match variant.payload.as_slice() {
+                    [] => name,
+                    payload => name.append(tuple(payload.iter().map(type_doc))),
+                }
+            },
+        );
+        braces(variants)
+    }
+
+    fn function(&mut self, function: &ast::Function<()>) -> Doc {
+        signature(&function.name, &function.parameters, &function.returns)
+            .append(Doc::space())
+            .append(self.statement(&function.statements))
+    }
+
+    fn function_template(&mut self, function: &ast::FunctionTemplate) -> Doc {
+        let name = Doc::text("template ")
+            .append(identifier(&function.name))
+            .append(generics(&function.generics));
+        let parameters = tuple(function.parameters.iter().map(single_declaration));
+        name.append(parameters)
+            .append(returns(&function.returns))
+            .append(Doc::space())
+            .append(self.statement(&function.statements))
+    }
+
+    fn block(&mut self, statements: &[ast::Statement<()>], span: Span) -> Doc {
+        let statements = self.lines(
+            statements,
+            Some(span.hi),
+            ast::Statement::span,
+            |_, _| false,
+            Self::statement,
+        );
+        braces(statements)
+    }
+
+    fn statement(&mut self, statement: &ast::Statement<()>) -> Doc {
+        match statement {
+            ast::Statement::Assignment(left, right, _) => self
+                .expression(left)
+                .append(Doc::text(" = "))
+                .append(self.expression(right)),
+            ast::Statement::Call(call) => self.call(call),
+            ast::Statement::Initialization(initialization) => self.initialization(initialization),
+            ast::Statement::Declaration(declaration, _) => declaration_doc(declaration),
+            ast::Statement::Return(expressions, _) if expressions.is_empty() => Doc::text("return"),
+            ast::Statement::Return(expressions, _) => {
+                let expressions = expressions
+                    .iter()
+                    .map(|expression| self.expression(expression))
+                    .collect::<Vec<_>>();
+                Doc::text("return ").append(Doc::intersperse(expressions, Doc::text(", ")))
+            }
+            ast::Statement::Sequence(statements, span) => self.block(statements, *span),
+            ast::Statement::If(condition, r#if, r#else, _) => {
+                let doc = Doc::text("if ")
+                    .append(self.expression(condition))
+                    .append(Doc::space())
+                    .append(self.statement(r#if));
+                match r#else {
+                    None => doc,
+                    Some(r#else) => doc
+                        .append(Doc::text(" else "))
+                        .append(self.statement(r#else)),
+                }
+            }
+            ast::Statement::While(ast::Do::No, label, condition, statements, _) => label_doc(label)
+                .append(Doc::text("while "))
+                .append(self.expression(condition))
+                .append(Doc::space())
+                .append(self.statement(statements)),
+            ast::Statement::While(ast::Do::Yes, label, condition, statements, _) => {
+                label_doc(label)
+                    .append(Doc::text("do "))
+                    .append(self.statement(statements))
+                    .append(Doc::text(" while "))
+                    .append(self.expression(condition))
+            }
+            ast::Statement::For(r#for) => {
+                let mut header = label_doc(&r#for.label).append(Doc::text("for ("));
+
+                if let Some(initialization) = &r#for.initialization {
+                    header = header.append(self.statement(initialization));
+                }
+
+                header = header.append(Doc::text(";"));
+
+                if let Some(condition) = &r#for.condition {
+                    header = header
+                        .append(Doc::space())
+                        .append(self.expression(condition));
+                }
+
+                header = header.append(Doc::text(";"));
+
+                if let Some(update) = &r#for.update {
+                    header = header.append(Doc::space()).append(self.statement(update));
+                }
+
+                header
+                    .append(Doc::text(") "))
+                    .append(self.statement(&r#for.statements))
+            }
+            ast::Statement::Break(label, _) => Doc::text("break").append(target_doc(label)),
+            ast::Statement::Continue(label, _) => Doc::text("continue").append(target_doc(label)),
+            ast::Statement::Match(r#match) => {
+                let scrutinee = self.expression(&r#match.scrutinee);
+                let arms = self.lines(
+                    &r#match.arms,
+                    Some(r#match.span.hi),
+                    |arm| arm.span,
+                    |_, _| false,
+                    |formatter, arm| {
+                        pattern_doc(&arm.pattern)
+                            .append(Doc::text(" => "))
+                            .append(formatter.statement(&arm.body))
+                    },
+                );
+                Doc::text("match ")
+                    .append(scrutinee)
+                    .append(Doc::space())
+                    .append(braces(arms))
+            }
+            ast::Statement::Throw(expression, _) => {
+                Doc::text("throw ").append(self.expression(expression))
+            }
+            ast::Statement::Try(r#try) => {
+                let mut doc = Doc::text("try ").append(self.statement(&r#try.statements));
+
+                for catch in &r#try.catches {
+                    doc = doc
+                        .append(Doc::text(" catch "))
+                        .append(single_declaration(&catch.declaration))
+                        .append(Doc::space())
+                        .append(self.statement(&catch.statements));
+                }
+
+                if let Some(finally) = &r#try.finally {
+                    doc = doc
+                        .append(Doc::text(" finally "))
+                        .append(self.statement(finally));
+                }
+
+                doc
+            }
+            // Note: programs with syntax errors are rejected before formatting
+            ast::Statement::Error(_) => unreachable!(),
+        }
+    }
+
+    fn initialization(&mut self, initialization: &ast::Initialization<()>) -> Doc {
+        let declarations =
+            initialization
+                .declarations
+                .iter()
+                .map(|declaration| match declaration {
+                    None => Doc::text("_"),
+                    Some(declaration) => single_declaration(declaration),
+                });
+
+        Doc::intersperse(declarations, Doc::text(", "))
+            .append(Doc::text(" = "))
+            .append(self.expression(&initialization.expression))
+    }
+
+    fn call(&mut self, call: &ast::Call<()>) -> Doc {
+        let function = self.postfix(&call.function);
+        let arguments = call
+            .arguments
+            .iter()
+            .map(|argument| self.expression(argument))
+            .collect::<Vec<_>>();
+        function.append(tuple(arguments))
+    }
+
+    fn expression(&mut self, expression: &ast::Expression<()>) -> Doc {
+        match expression {
+            ast::Expression::Boolean(true, _) => Doc::text("true"),
+            ast::Expression::Boolean(false, _) => Doc::text("false"),
+            ast::Expression::Character(character, _) => {
+                let mut escaped = String::from('\'');
+                escape(*character, '\'', &mut escaped);
+                escaped.push('\'');
+                Doc::text(escaped)
+            }
+            ast::Expression::String(string, _) => string_doc(string),
+            ast::Expression::Integer(integer, _) => Doc::as_string(integer),
+            ast::Expression::Null(_) => Doc::text("null"),
+            ast::Expression::This(_, _) => Doc::text("this"),
+            ast::Expression::Super(_, _) => Doc::text("super"),
+            ast::Expression::Variable(variable, _) => variable_doc(variable),
+            ast::Expression::Array(expressions, _, _) if expressions.is_empty() => Doc::text("{}"),
+            ast::Expression::Array(expressions, _, _) => {
+                let expressions = expressions
+                    .iter()
+                    .map(|expression| self.expression(expression))
+                    .collect::<Vec<_>>();
+                Doc::text("{")
+                    .append(
+                        Doc::line_()
+                            .append(Doc::intersperse(
+                                expressions,
+                                Doc::text(",").append(Doc::line()),
+                            ))
+                            .append(Doc::text(",").flat_alt(Doc::nil()))
+                            .nest(INDENT),
+                    )
+                    .append(Doc::line_())
+                    .append(Doc::text("}"))
+                    .group()
+            }
+            ast::Expression::Binary(binary, left, right, _, _) => {
+                let precedence = Precedence::from(*binary);
+                self.operand(left, precedence)
+                    .append(Doc::space())
+                    .append(Doc::text(binary_str(*binary)))
+                    .append(Doc::space())
+                    .append(self.operand(right, precedence.next()))
+            }
+            ast::Expression::Unary(ast::Unary::Neg, expression, _, _) => {
+                // Negative integer literals are parsed directly, so `-(1)` must keep
+                // its parentheses to round-trip as a negation.
+                let expression = match &**expression {
+                    ast::Expression::Integer(integer, _) if *integer >= 0 => {
+                        Doc::as_string(integer).parens()
+                    }
+                    expression => self.operand(expression, Precedence::Prefix),
+                };
+                Doc::text("-").append(expression)
+            }
+            ast::Expression::Unary(ast::Unary::Not, expression, _, _) => {
+                Doc::text("!").append(self.operand(expression, Precedence::Prefix))
+            }
+            ast::Expression::Index(array, index, _, _) => self
+                .postfix(array)
+                .append(Doc::text("["))
+                .append(self.expression(index))
+                .append(Doc::text("]")),
+            ast::Expression::Length(expression, _) => {
+                Doc::text("length").append(self.expression(expression).parens())
+            }
+            ast::Expression::Cast(r#type, expression, _) => {
+                type_doc(r#type).append(self.expression(expression).parens())
+            }
+            ast::Expression::InstanceOf(expression, r#type, _) => self
+                .operand(expression, Precedence::Add)
+                .append(Doc::text(" instanceof "))
+                .append(type_doc(r#type)),
+            ast::Expression::As(expression, r#type, _) => self
+                .operand(expression, Precedence::As)
+                .append(Doc::text(" as "))
+                .append(type_doc(r#type)),
+            ast::Expression::Call(call) => self.call(call),
+            ast::Expression::Dot(expression, field, _, _) => self
+                .postfix(expression)
+                .append(Doc::text("."))
+                .append(identifier(field)),
+            ast::Expression::New(variable, _) => Doc::text("new ").append(variable_doc(variable)),
+            ast::Expression::Closure(closure) => {
+                let header = Doc::text("fn")
+                    .append(tuple(closure.parameters.iter().map(single_declaration)))
+                    .append(returns(&closure.returns))
+                    .append(Doc::space());
+
+                // Short closures without comments may stay on one line
+                match &closure.statements {
+                    ast::Statement::Sequence(statements, span)
+                        if statements.len() == 1
+                            && self
+                                .comments
+                                .get(self.next)
+                                .is_none_or(|comment| comment.span.lo > span.hi) =>
+                    {
+                        header.append(
+                            Doc::text("{")
+                                .append(
+                                    Doc::line()
+                                        .append(self.statement(&statements[0]))
+                                        .nest(INDENT),
+                                )
+                                .append(Doc::line())
+                                .append(Doc::text("}"))
+                                .group(),
+                        )
+                    }
+                    statements => header.append(self.statement(statements)),
+                }
+            }
+            ast::Expression::Construct(construct) => {
+                let doc = identifier(&construct.r#enum)
+                    .append(Doc::text("."))
+                    .append(identifier(&construct.variant));
+                match construct.arguments.as_slice() {
+                    [] => doc,
+                    arguments => {
+                        let arguments = arguments
+                            .iter()
+                            .map(|argument| self.expression(argument))
+                            .collect::<Vec<_>>();
+                        doc.append(tuple(arguments))
+                    }
+                }
+            }
+            ast::Expression::Match(r#match, _) => {
+                let scrutinee = Doc::text("match ")
+                    .append(self.expression(&r#match.scrutinee))
+                    .append(Doc::text(" {"));
+
+                if r#match.arms.is_empty() {
+                    return scrutinee.append(Doc::text("}"));
+                }
+
+                let arms = r#match
+                    .arms
+                    .iter()
+                    .map(|arm| {
+                        pattern_doc(&arm.pattern)
+                            .append(Doc::text(" => "))
+                            .append(self.expression(&arm.body))
+                    })
+                    .collect::<Vec<_>>();
+
+                scrutinee
+                    .append(
+                        Doc::line()
+                            .append(Doc::intersperse(arms, Doc::text(",").append(Doc::line())))
+                            .append(Doc::text(",").flat_alt(Doc::nil()))
+                            .nest(INDENT),
+                    )
+                    .append(Doc::line())
+                    .append(Doc::text("}"))
+                    .group()
+            }
+        }
+    }
+
+    /// Formats `expression`, parenthesizing it if it binds more loosely than `precedence`.
+    fn operand(&mut self, expression: &ast::Expression<()>, precedence: Precedence) -> Doc {
+        let doc = self.expression(expression);
+        match Precedence::of(expression) < precedence {
+            true => doc.parens(),
+            false => doc,
+        }
+    }
+
+    /// Formats `expression` as the target of a call, index, or field access.
+    fn postfix(&mut self, expression: &ast::Expression<()>) -> Doc {
+        let doc = self.expression(expression);
+        match expression {
+            ast::Expression::Boolean(_, _)
+            | ast::Expression::Character(_, _)
+            | ast::Expression::String(_, _)
+            | ast::Expression::Null(_)
+            | ast::Expression::This(_, _)
+            | ast::Expression::Super(_, _)
+            | ast::Expression::Variable(_, _)
+            | ast::Expression::Array(_, _, _)
+            | ast::Expression::Index(_, _, _, _)
+            | ast::Expression::Length(_, _)
+            | ast::Expression::Cast(_, _, _)
+            | ast::Expression::Call(_)
+            | ast::Expression::Dot(_, _, _, _)
+            | ast::Expression::New(_, _)
+            | ast::Expression::Closure(_)
+            | ast::Expression::Construct(_)
+            | ast::Expression::Match(_, _) => doc,
+            ast::Expression::Integer(_, _)
+            | ast::Expression::Binary(_, _, _, _, _)
+            | ast::Expression::Unary(_, _, _, _)
+            | ast::Expression::InstanceOf(_, _, _)
+            | ast::Expression::As(_, _, _) => doc.parens(),
+        }
+    }
+}
+
+/// Binding strength of expressions, from loosest to tightest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Or,
+    And,
+    Equal,
+    Compare,
+    InstanceOf,
+    Add,
+    Mul,
+    As,
+    Prefix,
+    Value,
+}
+
+impl Precedence {
+    fn of(expression: &ast::Expression<()>) -> Self {
+        match expression {
+            ast::Expression::Binary(binary, _, _, _, _) => Precedence::from(*binary),
+            ast::Expression::InstanceOf(_, _, _) => Precedence::InstanceOf,
+            ast::Expression::As(_, _, _) => Precedence::As,
+            ast::Expression::Unary(_, _, _, _) => Precedence::Prefix,
+            ast::Expression::Integer(integer, _) if *integer < 0 => Precedence::Prefix,
+            _ => Precedence::Value,
+        }
+    }
+
+    /// Binary operators are left-associative, so right operands must bind more tightly.
+    fn next(self) -> Self {
+        match self {
+            Precedence::Or => Precedence::And,
+            Precedence::And => Precedence::Equal,
+            Precedence::Equal => Precedence::Compare,
+            Precedence::Compare => Precedence::InstanceOf,
+            Precedence::InstanceOf => Precedence::Add,
+            Precedence::Add => Precedence::Mul,
+            Precedence::Mul => Precedence::As,
+            Precedence::As => Precedence::Prefix,
+            Precedence::Prefix | Precedence::Value => Precedence::Value,
+        }
+    }
+}
+
+impl From<ast::Binary> for Precedence {
+    fn from(binary: ast::Binary) -> Self {
+        match binary {
+            ast::Binary::Mul | ast::Binary::Hul | ast::Binary::Div | ast::Binary::Mod => {
+                Precedence::Mul
+            }
+            ast::Binary::Add | ast::Binary::Cat | ast::Binary::Sub => Precedence::Add,
+            ast::Binary::Lt | ast::Binary::Le | ast::Binary::Ge | ast::Binary::Gt => {
+                Precedence::Compare
+            }
+            ast::Binary::Eq | ast::Binary::Ne => Precedence::Equal,
+            ast::Binary::And => Precedence::And,
+            ast::Binary::Or => Precedence::Or,
+        }
+    }
+}
+
+fn binary_str(binary: ast::Binary) -> &'static str {
+    match binary {
+        ast::Binary::Mul => "*",
+        ast::Binary::Hul => "*>>",
+        ast::Binary::Div => "/",
+        ast::Binary::Mod => "%",
+        ast::Binary::Add | ast::Binary::Cat => "+",
+        ast::Binary::Sub => "-",
+        ast::Binary::Lt => "<",
+        ast::Binary::Le => "<=",
+        ast::Binary::Ge => ">=",
+        ast::Binary::Gt => ">",
+        ast::Binary::Eq => "==",
+        ast::Binary::Ne => "!=",
+        ast::Binary::And => "&",
+        ast::Binary::Or => "|",
+    }
+}
+
+/// Escapes `character` for a literal delimited by `quote`.
+fn escape(character: char, quote: char, buffer: &mut String) {
+    match character {
+        '\n' => buffer.push_str("\\n"),
+        '\r' => buffer.push_str("\\r"),
+        '\t' => buffer.push_str("\\t"),
+        '\x08' => buffer.push_str("\\b"),
+        '\x0C' => buffer.push_str("\\f"),
+        '\\' => buffer.push_str("\\\\"),
+        _ if character == quote => {
+            buffer.push('\\');
+            buffer.push(character);
+        }
+        // Note: the lexer reads at most four hexadecimal digits
+        _ if character.is_control() && (character as u32) <= 0xFFFF => {
+            buffer.push_str(&format!("\\x{:04X}", character as u32));
+        }
+        _ => buffer.push(character),
+    }
+}
+
+/// Formats a string literal, continuing it across lines after each newline
+/// if it would otherwise be too long.
+fn string_doc(string: &str) -> Doc {
+    let mut escaped = String::new();
+    for character in string.chars() {
+        escape(character, '"', &mut escaped);
+    }
+
+    if escaped.len() + 2 <= WIDTH || !string.trim_end_matches('\n').contains('\n') {
+        return Doc::text(format!("\"{}\"", escaped));
+    }
+
+    let mut lines = vec![String::from('"')];
+    let mut characters = string.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        let line = lines.last_mut().unwrap();
+
+        match character {
+            // Note: the lexer skips whitespace after a line continuation
+            ' ' if line.is_empty() => line.push_str("\\x0020"),
+            _ => escape(character, '"', line),
+        }
+
+        if character == '\n' && characters.peek().is_some() {
+            line.push('\\');
+            lines.push(String::new());
+        }
+    }
+
+    lines.last_mut().unwrap().push('"');
+    Doc::intersperse(lines.into_iter().map(Doc::text), Doc::hardline())
+}
+
+fn comment_doc(comment: &token::Comment) -> Doc {
+    Doc::text(format!("//{}", comment.text))
+}
+
+fn identifier(identifier: &ast::Identifier) -> Doc {
+    Doc::text(symbol::resolve(identifier.symbol))
+}
+
+fn label_doc(label: &Option<ast::Identifier>) -> Doc {
+    match label {
+        None => Doc::nil(),
+        Some(label) => identifier(label).append(Doc::text("@ ")),
+    }
+}
+
+fn target_doc(label: &Option<ast::Identifier>) -> Doc {
+    match label {
+        None => Doc::nil(),
+        Some(label) => Doc::text("@").append(identifier(label)),
+    }
+}
+
+/// Wraps `body` in an indented block, or `{}` if empty.
+fn braces(body: Option<Doc>) -> Doc {
+    match body {
+        None => Doc::text("{}"),
+        Some(body) => Doc::text("{")
+            .append(Doc::hardline().append(body).nest(INDENT))
+            .append(Doc::hardline())
+            .append(Doc::text("}")),
+    }
+}
+
+/// Parenthesized, comma-separated list that breaks one element per line if too long.
+fn tuple<I: IntoIterator<Item = Doc>>(docs: I) -> Doc {
+    let mut docs = docs.into_iter().peekable();
+
+    if docs.peek().is_none() {
+        return Doc::text("()");
+    }
+
+    Doc::text("(")
+        .append(
+            Doc::line_()
+                .append(Doc::intersperse(docs, Doc::text(",").append(Doc::line())))
+                .nest(INDENT),
+        )
+        .append(Doc::line_())
+        .append(Doc::text(")"))
+        .group()
+}
+
+fn class_header(
+    r#final: bool,
+    name: &ast::Identifier,
+    template: Option<&[ast::Generic]>,
+    extends: &Option<ast::Variable<()>>,
+) -> Doc {
+    let doc = match r#final {
+        true => Doc::text("final class "),
+        false => Doc::text("class "),
+    }
+    .append(identifier(name))
+    .append(template.map_or_else(Doc::nil, generics));
+
+    match extends {
+        None => doc,
+        Some(extends) => doc
+            .append(Doc::text(" extends "))
+            .append(variable_doc(extends)),
+    }
+}
+
+fn implements(implements: &[ast::Variable<()>]) -> Doc {
+    match implements {
+        [] => Doc::nil(),
+        implements => Doc::text(" implements ").append(Doc::intersperse(
+            implements.iter().map(variable_doc),
+            Doc::text(", "),
+        )),
+    }
+}
+
+fn generics(generics: &[ast::Generic]) -> Doc {
+    match generics {
+        [] => Doc::nil(),
+        generics => Doc::intersperse(
+            generics.iter().map(|generic| match &generic.bound {
+                None => identifier(&generic.name),
+                Some(bound) => identifier(&generic.name)
+                    .append(Doc::text(": "))
+                    .append(variable_doc(bound)),
+            }),
+            Doc::text(", "),
+        )
+        .angles(),
+    }
+}
+
+fn signature(
+    name: &ast::Identifier,
+    parameters: &[ast::SingleDeclaration<()>],
+    types: &[ast::Type<()>],
+) -> Doc {
+    identifier(name)
+        .append(tuple(parameters.iter().map(single_declaration)))
+        .append(returns(types))
+}
+
+fn returns(returns: &[ast::Type<()>]) -> Doc {
+    match returns {
+        [] => Doc::nil(),
+        returns => Doc::text(": ").append(Doc::intersperse(
+            returns.iter().map(type_doc),
+            Doc::text(", "),
+        )),
+    }
+}
+
+fn declaration_doc(declaration: &ast::Declaration<()>) -> Doc {
+    match declaration {
+        ast::Declaration::Single(declaration) => single_declaration(declaration),
+        ast::Declaration::Multiple(declaration) => {
+            Doc::intersperse(declaration.names.iter().map(identifier), Doc::text(", "))
+                .append(Doc::text(": "))
+                .append(type_doc(&declaration.r#type))
+        }
+    }
+}
+
+fn single_declaration(declaration: &ast::SingleDeclaration<()>) -> Doc {
+    identifier(&declaration.name)
+        .append(Doc::text(": "))
+        .append(type_doc(&declaration.r#type))
+}
+
+fn variable_doc(variable: &ast::Variable<()>) -> Doc {
+    let name = identifier(&variable.name);
+    match &variable.generics {
+        None => name,
+        Some(generics) => name
+            .append(Doc::text("::"))
+            .append(Doc::intersperse(generics.iter().map(type_doc), Doc::text(", ")).angles()),
+    }
+}
+
+fn type_doc(r#type: &ast::Type<()>) -> Doc {
+    match r#type {
+        ast::Type::Bool(_) => Doc::text("bool"),
+        ast::Type::Int(_) => Doc::text("int"),
+        ast::Type::Sized { signed, size, .. } => {
+            let signed = match signed {
+                true => "",
+                false => "u",
+            };
+            let size = match size {
+                Size::_8 => "8",
+                Size::_16 => "16",
+                Size::_32 => "32",
+                Size::_64 => "",
+            };
+            Doc::text(format!("{}int{}", signed, size))
+        }
+        ast::Type::Class(variable) => variable_doc(variable),
+        ast::Type::Array(_, _, _) => {
+            // Lengths are written outermost first, e.g. `int[3][]`
+            let mut lengths = Vec::new();
+            let mut element = r#type;
+
+            while let ast::Type::Array(inner, length, _) = element {
+                lengths.push(length);
+                element = inner;
+            }
+
+            let mut doc = match element {
+                ast::Type::Function(_, _, _) => type_doc(element).parens(),
+                _ => type_doc(element),
+            };
+
+            for length in lengths {
+                let length = match length {
+                    None => Doc::nil(),
+                    // Note: any comments inside are emitted after the enclosing statement
+                    Some(length) => Formatter::default().expression(length),
+                };
+                doc = doc.append(length.brackets());
+            }
+
+            doc
+        }
+        ast::Type::Function(parameters, returns, _) => {
+            let doc = Doc::text("fn").append(tuple(parameters.iter().map(type_doc)));
+            match returns.as_slice() {
+                [] => doc,
+                [r#return] => doc.append(Doc::text(": ")).append(type_doc(r#return)),
+                returns => doc.append(Doc::text(": ")).append(
+                    Doc::intersperse(returns.iter().map(type_doc), Doc::text(", ")).parens(),
+                ),
+            }
+        }
+    }
+}
+
+fn pattern_doc(pattern: &ast::Pattern<()>) -> Doc {
+    match pattern {
+        ast::Pattern::Wildcard(_) => Doc::text("_"),
+        ast::Pattern::Binding(name, _) => identifier(name),
+        ast::Pattern::Variant(name, patterns, _, _) => {
+            identifier(name).append(tuple(patterns.iter().map(pattern_doc)))
+        }
+        ast::Pattern::Boolean(true, _) => Doc::text("true"),
+        ast::Pattern::Boolean(false, _) => Doc::text("false"),
+        ast::Pattern::Integer(integer, _) => Doc::as_string(integer),
+        ast::Pattern::Character(character, _) => {
+            let mut escaped = String::from('\'');
+            escape(*character, '\'', &mut escaped);
+            escaped.push('\'');
+            Doc::text(escaped)
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::path::Path;
+
+use crate::check;
+use crate::check::ErrorKind;
+use crate::data::ast;
+use crate::data::ast::VisitorMut;
+use crate::data::span::Span;
+use crate::data::symbol;
+use crate::data::symbol::Symbol;
+use crate::format;
+use crate::lex;
+use crate::parse;
+use crate::util;
+
+/// Derives the interface that other modules see from a type-checked program,
+/// and pretty-prints it as canonical source.
+///
+/// Exports every function except `main`, every class with the signatures of its
+/// methods, and every interface and template along with its body. Interface files
+/// cannot declare globals or enums, so neither is exported, and it is an error for
+/// anything exported to mention an enum.
+pub fn emit_interface(path: &Path, program: &ast::Program<()>) -> Result<String, crate::Error> {
+    log::info!(
+        "[{}] Deriving interface for {}...",
+        std::any::type_name::<ast::Program<()>>(),
+        path.display()
+    );
+    util::time!(
+        "[{}] Done deriving interface for {}",
+        std::any::type_name::<ast::Program<()>>(),
+        path.display()
+    );
+
+    Ok(format::interface_string(&derive(program)?))
+}
+
+/// Compares the interface derived from `program` against the existing interface
+/// file at `path`, ignoring comments, formatting, and the order of items.
+///
+/// The derived interface keeps every `use` of the implementation, while a handwritten
+/// one only needs those its own items refer to, so `use` statements aren't compared.
+///
+/// Returns the lines of each item that differs, rendered as canonical source and
+/// prefixed by `-` if it only appears in the existing interface, or by `+` if it
+/// only appears in the derived one. An up-to-date interface has no differences.
+pub fn diff_interface(
+    path: &Path,
+    program: &ast::Program<()>,
+) -> Result<Vec<String>, crate::Error> {
+    let tokens = lex::lex(path)?;
+    let mut recovered = Vec::new();
+    let existing = parse::InterfaceParser::new().parse(&mut recovered, tokens);
+    let (existing, errors) = parse::finish(recovered, existing)?;
+
+    if !errors.is_empty() {
+        return Err(crate::Error::Syntactic(errors));
+    }
+
+    let mut derived = items(&derive(program)?);
+    let mut diff = Vec::new();
+
+    for item in items(&existing) {
+        match derived.iter().position(|derived| *derived == item) {
+            Some(index) => {
+                derived.remove(index);
+            }
+            None => diff.extend(item.lines().map(|line| format!("-{}", line))),
+        }
+    }
+
+    for item in derived {
+        diff.extend(item.lines().map(|line| format!("+{}", line)));
+    }
+
+    Ok(diff)
+}
+
+/// Renders each item of `interface` separately.
+fn items(interface: &ast::Interface<()>) -> Vec<String> {
+    interface
+        .items
+        .iter()
+        .map(format::item_signature_string)
+        .collect()
+}
+
+fn derive(program: &ast::Program<()>) -> Result<ast::Interface<()>, crate::Error> {
+    let main = symbol::intern_static("main");
+
+    // Template bodies are checked against the interface's own `use` statements,
+    // so keep every one that the implementation needs.
+    let uses = program
+        .uses
+        .iter()
+        .map(|r#use| ast::Use {
+            name: r#use.name.clone(),
+            span: Span::default(),
+        })
+        .collect();
+
+    let items = program
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ast::Item::Global(_) | ast::Item::Enum(_) | ast::Item::EnumTemplate(_) => None,
+            ast::Item::Class(class) => Some(ast::ItemSignature::Class(ast::ClassSignature {
+                r#final: class.r#final,
+                name: class.name.clone(),
+                extends: class.extends.clone(),
+                implements: class.implements.clone(),
+                methods: class
+                    .items
+                    .iter()
+                    .filter_map(|item| match item {
+                        ast::ClassItem::Field(_) => None,
+                        ast::ClassItem::Method(method) => Some(signature(method)),
+                    })
+                    .collect(),
+                span: Span::default(),
+            })),
+            ast::Item::ClassTemplate(class) => {
+                Some(ast::ItemSignature::ClassTemplate(class.clone()))
+            }
+            ast::Item::Protocol(protocol) => Some(ast::ItemSignature::Protocol(protocol.clone())),
+            ast::Item::Function(function) if function.name.symbol == main => None,
+            ast::Item::Function(function) => {
+                Some(ast::ItemSignature::Function(signature(function)))
+            }
+            ast::Item::FunctionTemplate(function) => {
+                Some(ast::ItemSignature::FunctionTemplate(function.clone()))
+            }
+        })
+        .collect();
+
+    let mut interface = ast::Interface { uses, items };
+    let mut exported = Exported {
+        enums: program
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ast::Item::Enum(r#enum) => Some(r#enum.name.symbol),
+                ast::Item::EnumTemplate(r#enum) => Some(r#enum.name.symbol),
+                _ => None,
+            })
+            .collect(),
+        errors: Vec::new(),
+    };
+
+    // Template bodies are exported too, but aren't traversed by `accept_mut`.
+    for item in &mut interface.items {
+        match item {
+            ast::ItemSignature::ClassTemplate(class) => {
+                class
+                    .extends
+                    .iter_mut()
+                    .chain(&mut class.implements)
+                    .for_each(|supertype| supertype.accept_mut(&mut exported));
+                class
+                    .items
+                    .iter_mut()
+                    .for_each(|item| item.accept_mut(&mut exported));
+            }
+            ast::ItemSignature::FunctionTemplate(function) => {
+                function
+                    .parameters
+                    .iter_mut()
+                    .for_each(|parameter| parameter.accept_mut(&mut exported));
+                function
+                    .returns
+                    .iter_mut()
+                    .for_each(|r#return| r#return.accept_mut(&mut exported));
+                function.statements.accept_mut(&mut exported);
+            }
+            ast::ItemSignature::Class(_)
+            | ast::ItemSignature::Protocol(_)
+            | ast::ItemSignature::Function(_) => item.accept_mut(&mut exported),
+        }
+    }
+
+    match exported.errors.is_empty() {
+        true => Ok(interface),
+        false => Err(crate::Error::Semantic(exported.errors)),
+    }
+}
+
+/// Collects references to enums, which modules using the interface couldn't resolve.
+struct Exported {
+    enums: Vec<Symbol>,
+    errors: Vec<check::Error>,
+}
+
+impl Exported {
+    fn check(&mut self, identifier: &ast::Identifier) {
+        if self.enums.contains(&identifier.symbol) {
+            self.errors.push(check::Error::new(
+                *identifier.span,
+                ErrorKind::InterfaceEnum(identifier.symbol),
+            ));
+        }
+    }
+}
+
+impl VisitorMut<()> for Exported {
+    fn visit_type(&mut self, r#type: &mut ast::Type<()>) {
+        if let ast::Type::Class(variable) = r#type {
+            self.check(&variable.name);
+        }
+    }
+
+    // Variants are constructed through the enum's name, e.g. `Color.Red`
+    fn visit_expression(&mut self, expression: &mut ast::Expression<()>) {
+        if let ast::Expression::Dot(receiver, _, _, _) = expression {
+            if let ast::Expression::Variable(variable, _) = &**receiver {
+                self.check(&variable.name);
+            }
+        }
+    }
+}
+
+/// Strips the body from `function`, along with its span so that consecutive
+/// signatures aren't separated by the blank lines between their definitions.
+fn signature(function: &ast::Function<()>) -> ast::FunctionSignature<()> {
+    ast::FunctionSignature {
+        name: function.name.clone(),
+        parameters: function.parameters.clone(),
+        returns: function.returns.clone(),
+        span: Span::default(),
+    }
+}
@@ -12,6 +12,7 @@ use crate::data::operand::Immediate;
 use crate::data::operand::Label;
 use crate::data::symbol;
 use crate::data::symbol::Symbol;
+use crate::data::token::Size;
 use crate::interpret::Value;
 use crate::Map;
 
@@ -52,7 +53,8 @@ impl<'io> Global<'io> {
 
         Global {
             data: r#static,
-            heap: Vec::new(),
+            // Reserve address 0, so that no allocation compares equal to `null`
+            heap: vec![Value::Integer(0)],
             rng: rand::thread_rng(),
             stdin: Box::new(stdin),
             stdout: Box::new(stdout),
@@ -153,6 +155,29 @@ impl<'io> Global<'io> {
                 vec![self.calloc(arguments[0])]
             }
             abi::XI_OUT_OF_BOUNDS => panic!("out of bounds"),
+            abi::XI_BAD_CAST => panic!("bad cast"),
+            abi::XI_UNCAUGHT => {
+                debug_assert_eq!(arguments.len(), 1);
+                return Some(Err(anyhow!("Uncaught exception: {:?}", arguments[0])));
+            }
+            abi::XI_LOAD_I8 => vec![self.read_narrow(arguments[0], true, Size::_8)],
+            abi::XI_LOAD_U8 => vec![self.read_narrow(arguments[0], false, Size::_8)],
+            abi::XI_LOAD_I16 => vec![self.read_narrow(arguments[0], true, Size::_16)],
+            abi::XI_LOAD_U16 => vec![self.read_narrow(arguments[0], false, Size::_16)],
+            abi::XI_LOAD_I32 => vec![self.read_narrow(arguments[0], true, Size::_32)],
+            abi::XI_LOAD_U32 => vec![self.read_narrow(arguments[0], false, Size::_32)],
+            abi::XI_STORE_8 => {
+                self.write_narrow(arguments[0], arguments[1], Size::_8);
+                Vec::new()
+            }
+            abi::XI_STORE_16 => {
+                self.write_narrow(arguments[0], arguments[1], Size::_16);
+                Vec::new()
+            }
+            abi::XI_STORE_32 => {
+                self.write_narrow(arguments[0], arguments[1], Size::_32);
+                Vec::new()
+            }
             abi::XI_ASSERT => {
                 debug_assert_eq!(arguments.len(), 1);
                 if arguments[0].into_integer() != 1 {
@@ -198,6 +223,49 @@ impl<'io> Global<'io> {
         }
     }
 
+    /// Memory is word-granular, so narrow accesses read (and modify) the
+    /// enclosing word. Assumes a little-endian layout.
+    pub fn read_narrow(&self, address: Value, signed: bool, size: Size) -> Value {
+        let (address, shift) = Self::split(address, size);
+        let unused = 64 - size.bits();
+        let word = (self.read(address).into_integer() as u64 >> shift) << unused;
+        let value = match signed {
+            true => (word as i64) >> unused,
+            false => (word >> unused) as i64,
+        };
+        Value::Integer(value)
+    }
+
+    pub fn write_narrow(&mut self, address: Value, value: Value, size: Size) {
+        let (address, shift) = Self::split(address, size);
+        let mask = (u64::MAX >> (64 - size.bits())) << shift;
+        let word = self.read(address).into_integer() as u64;
+        let value = (value.into_integer() as u64) << shift;
+        self.write(
+            address,
+            Value::Integer(((word & !mask) | (value & mask)) as i64),
+        );
+    }
+
+    fn split(address: Value, size: Size) -> (Value, u32) {
+        let (address, offset) = match address {
+            Value::Integer(address) => {
+                let offset = address.rem_euclid(abi::WORD);
+                (Value::Integer(address - offset), offset)
+            }
+            Value::Label(label, address) => {
+                let offset = address.rem_euclid(abi::WORD);
+                (Value::Label(label, address - offset), offset)
+            }
+        };
+
+        if offset % size.bytes() > 0 {
+            panic!("Unaligned {}-bit memory access: {:?}", size.bits(), address);
+        }
+
+        (address, offset as u32 * 8)
+    }
+
     pub fn read_array(&self, address: Value) -> &[Value] {
         log::debug!("Reading array from memory at address {:?}", address);
         match address {
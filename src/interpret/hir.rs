@@ -122,6 +122,7 @@ impl<'a> Local<'a, postorder::Hir<'a>> {
             hir::Statement::Expression(_) => unreachable!(),
             hir::Statement::Label(_) => unreachable!(),
             hir::Statement::Sequence(_) => unreachable!(),
+            hir::Statement::Debug(_) => unreachable!(),
             hir::Statement::Jump(label) => {
                 self.interpret_jump(label);
             }
@@ -99,6 +99,7 @@ impl<'a, T: lir::Target> Local<'a, postorder::Lir<'a, T>> {
         log::debug!("S> {}", statement);
         match statement {
             lir::Statement::Label(_) => unreachable!(),
+            lir::Statement::Debug(_) => unreachable!(),
             lir::Statement::Jump(label) => {
                 self.interpret_jump(label);
             }
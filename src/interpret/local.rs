@@ -87,6 +87,16 @@ impl<'a, T: 'a> Local<'a, T> {
             {
                 (left_offset, right_offset)
             }
+            // Distinct labels have distinct, non-null addresses, but their order is unknown
+            (Value::Label(_, _), Value::Label(_, _))
+            | (Value::Label(_, _), Value::Integer(0))
+            | (Value::Integer(0), Value::Label(_, _)) => {
+                return match condition {
+                    ir::Condition::Eq => false,
+                    ir::Condition::Ne => true,
+                    _ => unreachable!(),
+                };
+            }
             (_, _) => unreachable!(),
         };
 
@@ -98,6 +108,9 @@ impl<'a, T: 'a> Local<'a, T> {
             ir::Condition::Ne => left != right,
             ir::Condition::Eq => left == right,
             ir::Condition::Ae => left as u64 >= right as u64,
+            ir::Condition::B => (left as u64) < right as u64,
+            ir::Condition::Be => left as u64 <= right as u64,
+            ir::Condition::A => left as u64 > right as u64,
         }
     }
 
@@ -132,17 +145,12 @@ impl<'a, T: 'a> Local<'a, T> {
             // TODO: handle divide by 0
             ir::Binary::Div => left / right,
             ir::Binary::Mod => left % right,
+            ir::Binary::UHul => (((left as u64 as u128) * (right as u64 as u128)) >> 64) as i64,
+            ir::Binary::UDiv => (left as u64 / right as u64) as i64,
+            ir::Binary::UMod => (left as u64 % right as u64) as i64,
             ir::Binary::Xor => left ^ right,
-            ir::Binary::And => {
-                debug_assert!(left == 0 || left == 1);
-                debug_assert!(right == 0 || right == 1);
-                left & right
-            }
-            ir::Binary::Or => {
-                debug_assert!(left == 0 || left == 1);
-                debug_assert!(right == 0 || right == 1);
-                left | right
-            }
+            ir::Binary::And => left & right,
+            ir::Binary::Or => left | right,
         };
 
         self.push(Operand::Integer(value));
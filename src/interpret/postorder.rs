@@ -125,6 +125,7 @@ impl<'a> Postorder<Hir<'a>> {
                 self.labels.insert(*label, self.statements.len());
                 return;
             }
+            hir::Statement::Debug(_) => return,
             hir::Statement::Expression(expression) => {
                 self.traverse_hir_expression(expression);
                 return;
@@ -199,6 +200,7 @@ impl<'a, T: lir::Target> Postorder<Lir<'a, T>> {
                 self.labels.insert(*label, self.statements.len());
                 return;
             }
+            lir::Statement::Debug(_) => return,
             lir::Statement::Call(name, arguments, _) => {
                 self.traverse_lir_expression(name);
                 arguments
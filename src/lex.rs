@@ -5,3 +5,4 @@ mod lex;
 pub(crate) use error::Error;
 pub(crate) use error::ErrorKind;
 pub use lex::lex;
+pub(crate) use lex::lex_source;
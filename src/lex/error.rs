@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::data::span;
 use crate::error;
 
@@ -43,13 +45,18 @@ impl std::fmt::Display for Error {
 }
 
 impl error::Report for Error {
-    fn report(&self) -> ariadne::ReportBuilder<span::Span> {
-        use ariadne::Span as _;
-        ariadne::Report::build(
-            ariadne::ReportKind::Error,
-            *self.span.source(),
-            self.span.lo.index(),
-        )
-        .with_label(ariadne::Label::new(self.span).with_message(self.kind.message()))
+    fn code(&self) -> error::Code {
+        match self.kind {
+            ErrorKind::InvalidCharacter => error::Code::InvalidCharacter,
+            ErrorKind::InvalidEscape => error::Code::InvalidEscape,
+            ErrorKind::InvalidString => error::Code::InvalidString,
+            ErrorKind::UnknownCharacter => error::Code::UnknownCharacter,
+            ErrorKind::UnclosedCharacter => error::Code::UnclosedCharacter,
+            ErrorKind::UnclosedString => error::Code::UnclosedString,
+        }
+    }
+
+    fn labels(&self) -> Vec<(span::Span, Cow<'static, str>)> {
+        vec![(self.span, Cow::Borrowed(self.kind.message()))]
     }
 }
@@ -24,9 +24,13 @@ pub fn lex(path: &Path) -> Result<token::Tokens, crate::Error> {
     );
 
     let source = std::fs::read_to_string(path)?;
+    Ok(lex_source(path, &source))
+}
+
+/// Lexes `source` as if it were the contents of `path`, e.g. for an unsaved editor buffer.
+pub(crate) fn lex_source(path: &Path, source: &str) -> token::Tokens {
     let path = symbol::intern(path.to_str().unwrap());
-    let tokens = Lexer::new(&path, &source).lex();
-    Ok(tokens)
+    Lexer::new(&path, source).lex()
 }
 
 /// Stateful Xi lexer.
@@ -52,6 +56,9 @@ struct Lexer<'source> {
 
     /// Current column position
     column: usize,
+
+    /// Comments skipped so far
+    comments: Vec<token::Comment>,
 }
 
 fn is_digit(c: char) -> bool {
@@ -83,13 +90,13 @@ impl<'source> Lexer<'source> {
             index: 0,
             row: 1,
             column: 1,
+            comments: Vec::new(),
         }
     }
 
     pub fn lex(&mut self) -> token::Tokens {
-        self.take_until(Result::is_err)
-            .collect::<Vec<_>>()
-            .tap(token::Tokens::new)
+        let tokens = self.take_until(Result::is_err).collect::<Vec<_>>();
+        token::Tokens::new(tokens, std::mem::take(&mut self.comments))
     }
 
     /// Look at the next character without consuming
@@ -135,7 +142,18 @@ impl<'source> Lexer<'source> {
                     self.skip();
                 }
                 Some('/') if self.peeeek() == Some('/') => {
-                    self.take_while(|c| c != '\n');
+                    let start = self.point();
+                    let end = self.take_while(|c| c != '\n');
+                    let text = self.source[start.index() + 2..]
+                        .split('\n')
+                        .next()
+                        .unwrap()
+                        .trim_end()
+                        .to_string();
+                    self.comments.push(token::Comment {
+                        text,
+                        span: span::Span::new(start, end),
+                    });
                     self.skip();
                 }
                 None | Some(_) => return,
@@ -169,14 +187,27 @@ impl<'source> Lexer<'source> {
             "super" => Super,
             "new" => New,
             "extends" => Extends,
+            "interface" => Interface,
+            "implements" => Implements,
+            "instanceof" => InstanceOf,
+            "as" => As,
             "final" => Final,
+            "enum" => Enum,
+            "match" => Match,
+            "throw" => Throw,
+            "try" => Try,
+            "catch" => Catch,
+            "finally" => Finally,
             "null" => Null,
             "break" => Break,
+            "continue" => Continue,
             "if" => If,
             "do" => Do,
             "while" => While,
+            "for" => For,
             "else" => Else,
             "return" => Return,
+            "fn" => Fn,
             "length" => Length,
             r#type @ ("int" | "int64" | "uint" | "uint64") => Int {
                 signed: r#type.starts_with('i'),
@@ -335,6 +366,7 @@ impl<'source> Iterator for Lexer<'source> {
             '0'..='9' => return Some(self.lex_integer(start)),
             '_' => Underscore,
             '.' => Period,
+            '@' => At,
             ',' => Comma,
             ';' => Semicolon,
             ':' => Colon,
@@ -357,6 +389,7 @@ impl<'source> Iterator for Lexer<'source> {
             '>' if self.peek() == Some('=') => eat!(Ge),
             '>' => Gt,
             '=' if self.peek() == Some('=') => eat!(Eq),
+            '=' if self.peek() == Some('>') => eat!(Arrow),
             '=' => Assign,
             '*' if self.peek() == Some('>') && self.peeeek() == Some('>') => {
                 self.skip();
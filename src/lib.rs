@@ -9,8 +9,11 @@ mod cfg;
 mod check;
 mod emit;
 mod error;
+mod format;
+mod interface;
 mod interpret;
 mod lex;
+mod lsp;
 mod parse;
 mod util;
 
@@ -19,20 +22,43 @@ type Set<T> = indexmap::IndexSet<T, std::hash::BuildHasherDefault<rustc_hash::Fx
 
 pub use abi::Abi;
 pub use abi::FramePointer;
+pub use data::debug::DebugInfo;
+pub use error::Code;
+pub use error::Diagnostic;
 pub use error::Error;
+pub use error::Severity;
 
 pub mod api {
+    pub use crate::abi::demangle::demangle;
+    pub use crate::abi::demangle::demangle_text;
+    pub use crate::allocate::allocate_coloring;
     pub use crate::allocate::allocate_linear;
     pub use crate::allocate::allocate_trivial;
     pub use crate::assemble::tile;
     pub use crate::cfg::clean_cfg;
     pub use crate::cfg::construct_cfg;
+    pub use crate::cfg::construct_ssa;
     pub use crate::cfg::destruct_cfg;
+    pub use crate::cfg::destruct_ssa;
     pub use crate::check::check;
+    pub use crate::check::check_warnings;
+    pub use crate::check::check_without_interface;
     pub use crate::emit::emit_hir;
     pub use crate::emit::emit_lir;
+    pub use crate::format::format;
+    pub use crate::interface::diff_interface;
+    pub use crate::interface::emit_interface;
     pub use crate::interpret::interpret_hir;
     pub use crate::interpret::interpret_lir;
     pub use crate::lex::lex;
+    pub use crate::lsp::serve;
     pub use crate::parse::parse;
+    pub use crate::parse::parse_recover;
+}
+
+pub mod lint {
+    pub use crate::check::Level;
+    pub use crate::check::Lint;
+    pub use crate::check::Lints;
+    pub use crate::check::Warning;
 }
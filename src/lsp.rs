@@ -0,0 +1,6 @@
+mod analysis;
+mod index;
+mod protocol;
+mod server;
+
+pub use server::serve;
@@ -0,0 +1,312 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+use crate::check;
+use crate::check::Level;
+use crate::check::Lints;
+use crate::data::ast;
+use crate::data::span::Span;
+use crate::data::symbol;
+use crate::format;
+use crate::lex;
+use crate::lsp::index::Index;
+use crate::parse;
+
+/// Results of lexing, parsing, and type checking a single document.
+pub(super) struct Analysis {
+    pub(super) diagnostics: Vec<Diagnostic>,
+
+    /// Outline of the document's items, available as long as parsing recovers
+    pub(super) outline: Vec<Outline>,
+
+    /// Available only if the document type checks
+    pub(super) index: Option<Index>,
+}
+
+pub(super) struct Diagnostic {
+    pub(super) span: Option<Span>,
+    pub(super) severity: Severity,
+    pub(super) code: Option<&'static str>,
+    pub(super) message: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Severity {
+    Error = 1,
+    Warning = 2,
+}
+
+/// Named item in a document, along with any items nested inside it.
+pub(super) struct Outline {
+    pub(super) name: String,
+    pub(super) detail: Option<String>,
+    pub(super) kind: Kind,
+    pub(super) span: Span,
+    pub(super) selection: Span,
+    pub(super) children: Vec<Outline>,
+}
+
+/// Subset of symbol kinds defined by the protocol.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Kind {
+    Class = 5,
+    Method = 6,
+    Field = 8,
+    Enum = 10,
+    Interface = 11,
+    Function = 12,
+    Variable = 13,
+    EnumMember = 22,
+}
+
+impl Analysis {
+    pub(super) fn new(directory_library: Option<&Path>, path: &Path, source: &str) -> Self {
+        let tokens = lex::lex_source(path, source);
+
+        if path.extension() == Some(OsStr::new("ixi")) {
+            let mut recovered = Vec::new();
+            let interface = parse::InterfaceParser::new().parse(&mut recovered, tokens);
+
+            return match parse::finish(recovered, interface) {
+                Ok((interface, errors)) => Analysis {
+                    diagnostics: errors_diagnostics(&crate::Error::Syntactic(errors)),
+                    outline: interface.items.iter().map(item_signature).collect(),
+                    index: None,
+                },
+                Err(error) => Analysis::from(error),
+            };
+        }
+
+        let (program, syntax) = match parse::parse_recover(path, tokens) {
+            Ok(parsed) => parsed,
+            Err(error) => return Analysis::from(error),
+        };
+
+        let outline = program.items.iter().flat_map(item).collect();
+
+        let (error, index, warnings) = match check::check_warnings(directory_library, path, program)
+        {
+            Ok((program, context, warnings)) if syntax.is_empty() => {
+                let path = symbol::intern(path.to_str().unwrap());
+                let index = Index::new(path, &program, context);
+                (None, Some(index), warnings)
+            }
+            Ok(_) => (Some(crate::Error::Syntactic(syntax)), None, Vec::new()),
+            Err(crate::Error::Semantic(semantic)) => (
+                Some(crate::Error::Recovered(syntax, semantic)),
+                None,
+                Vec::new(),
+            ),
+            Err(error) => (Some(error), None, Vec::new()),
+        };
+
+        let lints = Lints::default();
+        let mut diagnostics = error.as_ref().map(errors_diagnostics).unwrap_or_default();
+
+        diagnostics.extend(warnings.iter().filter_map(|warning| {
+            let diagnostic = match lints.level(warning.lint()) {
+                Level::Allow => return None,
+                level => warning.diagnostic(level),
+            };
+
+            Some(Diagnostic {
+                span: Some(diagnostic.span()),
+                severity: match diagnostic.severity {
+                    crate::Severity::Error => Severity::Error,
+                    crate::Severity::Warning => Severity::Warning,
+                },
+                code: Some(diagnostic.code),
+                message: diagnostic.message().to_owned(),
+            })
+        }));
+
+        Analysis {
+            diagnostics,
+            outline,
+            index,
+        }
+    }
+}
+
+impl From<crate::Error> for Analysis {
+    fn from(error: crate::Error) -> Self {
+        Analysis {
+            diagnostics: errors_diagnostics(&error),
+            outline: Vec::new(),
+            index: None,
+        }
+    }
+}
+
+/// Converts each report in `error` into a diagnostic, falling back to a single
+/// diagnostic without a location for errors that have no reports.
+fn errors_diagnostics(error: &crate::Error) -> Vec<Diagnostic> {
+    let diagnostics = error.diagnostics(None);
+
+    if diagnostics.is_empty() && error.count() > 0 {
+        return vec![Diagnostic {
+            span: None,
+            severity: Severity::Error,
+            code: None,
+            message: error.to_string(),
+        }];
+    }
+
+    diagnostics
+        .iter()
+        .map(|diagnostic| Diagnostic {
+            span: Some(diagnostic.span()),
+            severity: Severity::Error,
+            code: Some(diagnostic.code),
+            message: diagnostic.message().to_owned(),
+        })
+        .collect()
+}
+
+fn item(item: &ast::Item<()>) -> Vec<Outline> {
+    match item {
+        ast::Item::Global(ast::Global::Declaration(declaration)) => declaration
+            .iter()
+            .map(|(name, r#type)| {
+                leaf(
+                    name,
+                    Some(format::type_string(r#type)),
+                    Kind::Variable,
+                    declaration.span(),
+                )
+            })
+            .collect(),
+        ast::Item::Global(ast::Global::Initialization(initialization)) => initialization
+            .declarations
+            .iter()
+            .flatten()
+            .map(|declaration| {
+                leaf(
+                    &declaration.name,
+                    Some(format::type_string(&declaration.r#type)),
+                    Kind::Variable,
+                    initialization.span,
+                )
+            })
+            .collect(),
+        ast::Item::Class(class) => vec![Outline {
+            children: class_items(&class.items),
+            ..leaf(&class.name, None, Kind::Class, class.span)
+        }],
+        ast::Item::ClassTemplate(class) => vec![Outline {
+            children: class_items(&class.items),
+            ..leaf(&class.name, None, Kind::Class, class.span)
+        }],
+        ast::Item::Protocol(protocol) => vec![protocol_outline(protocol)],
+        ast::Item::Enum(r#enum) => vec![Outline {
+            children: r#enum.variants.iter().map(variant).collect(),
+            ..leaf(&r#enum.name, None, Kind::Enum, r#enum.span)
+        }],
+        ast::Item::EnumTemplate(r#enum) => vec![Outline {
+            children: r#enum.variants.iter().map(variant).collect(),
+            ..leaf(&r#enum.name, None, Kind::Enum, r#enum.span)
+        }],
+        ast::Item::Function(function) => {
+            vec![function_outline(function, function.span, Kind::Function)]
+        }
+        ast::Item::FunctionTemplate(function) => vec![leaf(
+            &function.name,
+            Some(format::parameters_string(
+                &function.parameters,
+                &function.returns,
+            )),
+            Kind::Function,
+            function.span,
+        )],
+    }
+}
+
+fn item_signature(item: &ast::ItemSignature<()>) -> Outline {
+    match item {
+        ast::ItemSignature::Class(class) => Outline {
+            children: class
+                .methods
+                .iter()
+                .map(|method| function_outline(method, method.span, Kind::Method))
+                .collect(),
+            ..leaf(&class.name, None, Kind::Class, class.span)
+        },
+        ast::ItemSignature::ClassTemplate(class) => Outline {
+            children: class_items(&class.items),
+            ..leaf(&class.name, None, Kind::Class, class.span)
+        },
+        ast::ItemSignature::Protocol(protocol) => protocol_outline(protocol),
+        ast::ItemSignature::Function(function) => {
+            function_outline(function, function.span, Kind::Function)
+        }
+        ast::ItemSignature::FunctionTemplate(function) => leaf(
+            &function.name,
+            Some(format::parameters_string(
+                &function.parameters,
+                &function.returns,
+            )),
+            Kind::Function,
+            function.span,
+        ),
+    }
+}
+
+fn class_items(items: &[ast::ClassItem<()>]) -> Vec<Outline> {
+    items
+        .iter()
+        .flat_map(|item| match item {
+            ast::ClassItem::Field(declaration) => declaration
+                .iter()
+                .map(|(name, r#type)| {
+                    leaf(
+                        name,
+                        Some(format::type_string(r#type)),
+                        Kind::Field,
+                        declaration.span(),
+                    )
+                })
+                .collect(),
+            ast::ClassItem::Method(method) => {
+                vec![function_outline(method, method.span, Kind::Method)]
+            }
+        })
+        .collect()
+}
+
+fn protocol_outline(protocol: &ast::Protocol<()>) -> Outline {
+    Outline {
+        children: protocol
+            .methods
+            .iter()
+            .map(|method| function_outline(method, method.span, Kind::Method))
+            .collect(),
+        ..leaf(&protocol.name, None, Kind::Interface, protocol.span)
+    }
+}
+
+fn variant(variant: &ast::Variant<()>) -> Outline {
+    leaf(&variant.name, None, Kind::EnumMember, variant.span)
+}
+
+fn function_outline<F: ast::FunctionLike<()>>(function: &F, span: Span, kind: Kind) -> Outline {
+    leaf(
+        function.name(),
+        Some(format::parameters_string(
+            function.parameters(),
+            function.returns(),
+        )),
+        kind,
+        span,
+    )
+}
+
+fn leaf(name: &ast::Identifier, detail: Option<String>, kind: Kind, span: Span) -> Outline {
+    Outline {
+        name: name.symbol.to_string(),
+        detail,
+        kind,
+        span,
+        selection: *name.span,
+        children: Vec::new(),
+    }
+}
@@ -0,0 +1,631 @@
+use crate::check::Context;
+use crate::check::Entry;
+use crate::check::GlobalScope;
+use crate::data::ast;
+use crate::data::r#type;
+use crate::data::span::Span;
+use crate::data::symbol;
+use crate::data::symbol::Symbol;
+use crate::Set;
+
+/// Answers queries about names and types in a successfully type checked program.
+pub(super) struct Index {
+    /// Path of the indexed source file
+    path: Symbol,
+
+    /// Text shown when hovering over each expression or declared name
+    hovers: Vec<(Span, String)>,
+
+    /// Definition of each resolved name
+    references: Vec<(Span, Span)>,
+
+    /// Every local variable and parameter, with its type
+    locals: Vec<(Symbol, Span, r#type::Expression)>,
+
+    /// Span of each method, along with its enclosing class
+    methods: Vec<(Span, Symbol)>,
+
+    context: Context,
+}
+
+/// Field or method that can complete a member access.
+pub(super) struct Member {
+    pub(super) name: Symbol,
+    pub(super) method: bool,
+    pub(super) detail: String,
+}
+
+impl Index {
+    pub(super) fn new(
+        path: Symbol,
+        program: &ast::Program<r#type::Expression>,
+        context: Context,
+    ) -> Self {
+        let mut indexer = Indexer {
+            context: &context,
+            class: None,
+            scopes: Vec::new(),
+            hovers: Vec::new(),
+            references: Vec::new(),
+            locals: Vec::new(),
+            methods: Vec::new(),
+        };
+
+        indexer.program(program);
+
+        let Indexer {
+            hovers,
+            references,
+            locals,
+            methods,
+            ..
+        } = indexer;
+
+        Index {
+            path,
+            hovers,
+            references,
+            locals,
+            methods,
+            context,
+        }
+    }
+
+    pub(super) fn hover(&self, offset: usize) -> Option<&str> {
+        self.innermost(&self.hovers, offset)
+            .map(|(_, hover)| hover.as_str())
+    }
+
+    pub(super) fn definition(&self, offset: usize) -> Option<Span> {
+        self.innermost(&self.references, offset)
+            .map(|(_, definition)| *definition)
+    }
+
+    /// Finds the fields and methods of `receiver`, which is a chain of names separated
+    /// by `.` that ends at `offset`, e.g. `this.left` or `node`.
+    ///
+    /// Completion is usually requested while the program doesn't type check, so this
+    /// index may be out of date: names are resolved by closest definition instead.
+    pub(super) fn members(&self, receiver: &str, offset: usize) -> Vec<Member> {
+        let mut names = receiver.split('.');
+
+        let head = match names.next() {
+            Some(head) => head,
+            None => return Vec::new(),
+        };
+
+        let class = self
+            .methods
+            .iter()
+            .filter(|(span, _)| span.lo.index() <= offset && offset <= span.hi.index())
+            .min_by_key(|(span, _)| span.hi.index() - span.lo.index())
+            .map(|(_, class)| *class);
+
+        let mut r#type = match head {
+            "this" => class.map(r#type::Expression::Class),
+            name => self
+                .locals
+                .iter()
+                .filter(|(local, _, _)| symbol::resolve(*local) == name)
+                .min_by_key(|(_, span, _)| {
+                    (span.lo.index() > offset, offset.abs_diff(span.lo.index()))
+                })
+                .map(|(_, _, r#type)| r#type.clone())
+                .or_else(|| {
+                    let name = symbol::intern(name);
+                    class.and_then(|class| self.field(class, name)).or_else(|| {
+                        match self.context.get(GlobalScope::Global, &name)? {
+                            Entry::Variable(r#type) => Some(r#type.clone()),
+                            Entry::Function(_, _) | Entry::Signature(_, _) => None,
+                        }
+                    })
+                }),
+        };
+
+        for name in names {
+            r#type = match r#type {
+                Some(r#type::Expression::Class(class)) => self.field(class, symbol::intern(name)),
+                _ => None,
+            };
+        }
+
+        let class = match r#type {
+            Some(r#type::Expression::Class(class)) if self.context.get_class(&class).is_some() => {
+                class
+            }
+            _ => return Vec::new(),
+        };
+
+        let mut seen = Set::default();
+        let mut members = Vec::new();
+
+        for class in self.context.ancestors_inclusive(&class) {
+            for (name, entry) in self.context.get_class(&class).into_iter().flatten() {
+                if !seen.insert(name.symbol) {
+                    continue;
+                }
+
+                let (method, detail) = match entry {
+                    Entry::Variable(r#type) => (false, r#type.to_string()),
+                    Entry::Function(parameters, returns)
+                    | Entry::Signature(parameters, returns) => {
+                        let r#type =
+                            r#type::Expression::Function(parameters.clone(), returns.clone());
+                        (true, r#type.to_string())
+                    }
+                };
+
+                members.push(Member {
+                    name: name.symbol,
+                    method,
+                    detail,
+                });
+            }
+        }
+
+        members
+    }
+
+    fn field(&self, class: Symbol, field: Symbol) -> Option<r#type::Expression> {
+        self.context.get_class(&class)?;
+        match self.context.get(GlobalScope::Class(class), &field)? {
+            Entry::Variable(r#type) => Some(r#type.clone()),
+            Entry::Function(_, _) | Entry::Signature(_, _) => None,
+        }
+    }
+
+    /// Finds the smallest span in this file that contains `offset`, including its end
+    /// so that the cursor can sit just past an identifier.
+    fn innermost<'a, T>(&self, spans: &'a [(Span, T)], offset: usize) -> Option<&'a (Span, T)> {
+        spans
+            .iter()
+            .filter(|(span, _)| span.path() == Some(self.path))
+            .filter(|(span, _)| span.lo.index() <= offset && offset <= span.hi.index())
+            .min_by_key(|(span, _)| span.hi.index() - span.lo.index())
+    }
+}
+
+struct Indexer<'a> {
+    context: &'a Context,
+
+    /// Class whose methods are being indexed, whose members are in scope
+    class: Option<Symbol>,
+
+    /// Local variables in scope, innermost last
+    scopes: Vec<Vec<(Symbol, Span)>>,
+
+    hovers: Vec<(Span, String)>,
+    references: Vec<(Span, Span)>,
+    locals: Vec<(Symbol, Span, r#type::Expression)>,
+    methods: Vec<(Span, Symbol)>,
+}
+
+impl<'a> Indexer<'a> {
+    fn program(&mut self, program: &ast::Program<r#type::Expression>) {
+        for item in &program.items {
+            self.item(item);
+        }
+    }
+
+    fn item(&mut self, item: &ast::Item<r#type::Expression>) {
+        match item {
+            ast::Item::Global(ast::Global::Declaration(declaration)) => {
+                for (name, r#type) in declaration.iter() {
+                    self.hover_name(name, &r#type.r#type());
+                    self.r#type(r#type);
+                }
+            }
+            ast::Item::Global(ast::Global::Initialization(initialization)) => {
+                self.expression(&initialization.expression);
+                for declaration in initialization.declarations.iter().flatten() {
+                    self.hover_name(&declaration.name, &declaration.r#type.r#type());
+                    self.r#type(&declaration.r#type);
+                }
+            }
+            ast::Item::Class(class) => {
+                self.class = Some(class.name.symbol);
+                self.supertypes(class.extends.iter().chain(&class.implements));
+
+                for item in &class.items {
+                    match item {
+                        ast::ClassItem::Field(declaration) => {
+                            for (name, r#type) in declaration.iter() {
+                                self.hover_name(name, &r#type.r#type());
+                                self.r#type(r#type);
+                            }
+                        }
+                        ast::ClassItem::Method(method) => {
+                            self.methods.push((method.span, class.name.symbol));
+                            self.function(method);
+                        }
+                    }
+                }
+
+                self.class = None;
+            }
+            ast::Item::Protocol(protocol) => {
+                for method in &protocol.methods {
+                    self.hover_signature(method);
+                    for parameter in &method.parameters {
+                        self.hover_name(&parameter.name, &parameter.r#type.r#type());
+                        self.r#type(&parameter.r#type);
+                    }
+                    method.returns.iter().for_each(|r#type| self.r#type(r#type));
+                }
+            }
+            ast::Item::Enum(r#enum) => {
+                for variant in &r#enum.variants {
+                    variant
+                        .payload
+                        .iter()
+                        .for_each(|r#type| self.r#type(r#type));
+                }
+            }
+            ast::Item::Function(function) => self.function(function),
+            // Templates are only type checked once instantiated
+            ast::Item::ClassTemplate(_)
+            | ast::Item::EnumTemplate(_)
+            | ast::Item::FunctionTemplate(_) => (),
+        }
+    }
+
+    fn function(&mut self, function: &ast::Function<r#type::Expression>) {
+        self.hover_signature(function);
+        self.scopes.push(Vec::new());
+
+        for parameter in &function.parameters {
+            self.declare(parameter);
+        }
+
+        function
+            .returns
+            .iter()
+            .for_each(|r#type| self.r#type(r#type));
+
+        self.statement(&function.statements);
+        self.scopes.pop();
+    }
+
+    fn statement(&mut self, statement: &ast::Statement<r#type::Expression>) {
+        match statement {
+            ast::Statement::Assignment(left, right, _) => {
+                self.expression(left);
+                self.expression(right);
+            }
+            ast::Statement::Call(call) => self.call(call),
+            ast::Statement::Initialization(initialization) => {
+                self.expression(&initialization.expression);
+                for declaration in initialization.declarations.iter().flatten() {
+                    self.declare(declaration);
+                }
+            }
+            ast::Statement::Declaration(declaration, _) => match &**declaration {
+                ast::Declaration::Single(declaration) => self.declare(declaration),
+                ast::Declaration::Multiple(declaration) => {
+                    for name in &declaration.names {
+                        self.declare(&ast::SingleDeclaration {
+                            name: name.clone(),
+                            r#type: declaration.r#type.clone(),
+                            span: declaration.span,
+                        });
+                    }
+                }
+            },
+            ast::Statement::Return(expressions, _) => {
+                expressions
+                    .iter()
+                    .for_each(|expression| self.expression(expression));
+            }
+            ast::Statement::Sequence(statements, _) => {
+                self.scopes.push(Vec::new());
+                statements
+                    .iter()
+                    .for_each(|statement| self.statement(statement));
+                self.scopes.pop();
+            }
+            ast::Statement::If(condition, r#if, r#else, _) => {
+                self.expression(condition);
+                self.scoped(r#if);
+                if let Some(r#else) = r#else {
+                    self.scoped(r#else);
+                }
+            }
+            ast::Statement::While(_, _, condition, statements, _) => {
+                self.expression(condition);
+                self.scoped(statements);
+            }
+            ast::Statement::For(r#for) => {
+                self.scopes.push(Vec::new());
+                if let Some(initialization) = &r#for.initialization {
+                    self.statement(initialization);
+                }
+                if let Some(condition) = &r#for.condition {
+                    self.expression(condition);
+                }
+                if let Some(update) = &r#for.update {
+                    self.statement(update);
+                }
+                self.statement(&r#for.statements);
+                self.scopes.pop();
+            }
+            ast::Statement::Break(_, _)
+            | ast::Statement::Continue(_, _)
+            | ast::Statement::Error(_) => (),
+            ast::Statement::Match(r#match) => {
+                self.expression(&r#match.scrutinee);
+                for arm in &r#match.arms {
+                    self.scopes.push(Vec::new());
+                    self.pattern(&arm.pattern);
+                    self.statement(&arm.body);
+                    self.scopes.pop();
+                }
+            }
+            ast::Statement::Throw(expression, _) => self.expression(expression),
+            ast::Statement::Try(r#try) => {
+                self.scoped(&r#try.statements);
+                for catch in &r#try.catches {
+                    self.scopes.push(Vec::new());
+                    self.declare(&catch.declaration);
+                    self.statement(&catch.statements);
+                    self.scopes.pop();
+                }
+                if let Some(finally) = &r#try.finally {
+                    self.scoped(finally);
+                }
+            }
+        }
+    }
+
+    fn scoped(&mut self, statement: &ast::Statement<r#type::Expression>) {
+        self.scopes.push(Vec::new());
+        self.statement(statement);
+        self.scopes.pop();
+    }
+
+    fn pattern(&mut self, pattern: &ast::Pattern<r#type::Expression>) {
+        match pattern {
+            ast::Pattern::Binding(name, r#type) => self.local(name, r#type.clone()),
+            ast::Pattern::Variant(name, patterns, r#type, _) => {
+                if let r#type::Expression::Class(r#enum) = r#type {
+                    self.reference_variant(*r#enum, name);
+                }
+                patterns.iter().for_each(|pattern| self.pattern(pattern));
+            }
+            ast::Pattern::Wildcard(_)
+            | ast::Pattern::Boolean(_, _)
+            | ast::Pattern::Integer(_, _)
+            | ast::Pattern::Character(_, _) => (),
+        }
+    }
+
+    fn expression(&mut self, expression: &ast::Expression<r#type::Expression>) {
+        match expression {
+            ast::Expression::Boolean(_, _)
+            | ast::Expression::Character(_, _)
+            | ast::Expression::String(_, _)
+            | ast::Expression::Integer(_, _)
+            | ast::Expression::Null(_)
+            | ast::Expression::This(_, _)
+            | ast::Expression::Super(_, _) => (),
+            ast::Expression::Variable(variable, r#type) => {
+                self.hover_name(&variable.name, r#type);
+                self.reference_variable(&variable.name);
+                self.generics(variable);
+                return;
+            }
+            ast::Expression::Array(expressions, _, _) => {
+                expressions
+                    .iter()
+                    .for_each(|expression| self.expression(expression));
+            }
+            ast::Expression::Binary(_, left, right, _, _)
+            | ast::Expression::Index(left, right, _, _) => {
+                self.expression(left);
+                self.expression(right);
+            }
+            ast::Expression::Unary(_, expression, _, _)
+            | ast::Expression::Length(expression, _) => self.expression(expression),
+            ast::Expression::Cast(r#type, expression, _)
+            | ast::Expression::InstanceOf(expression, r#type, _)
+            | ast::Expression::As(expression, r#type, _) => {
+                self.expression(expression);
+                self.r#type(r#type);
+            }
+            ast::Expression::Call(call) => self.call(call),
+            ast::Expression::Dot(receiver, field, r#type, _) => {
+                self.expression(receiver);
+                self.hover_name(field, r#type);
+
+                if let r#type::Expression::Class(class) = receiver.r#type() {
+                    if self.context.get_class(&class).is_some() {
+                        if let Some((span, _)) =
+                            self.context.get_full(GlobalScope::Class(class), field)
+                        {
+                            self.references.push((*field.span, *span));
+                        }
+                    }
+                }
+            }
+            ast::Expression::New(variable, _) => {
+                self.reference_class(&variable.name);
+                self.generics(variable);
+            }
+            ast::Expression::Closure(closure) => {
+                self.scopes.push(Vec::new());
+                for parameter in &closure.parameters {
+                    self.declare(parameter);
+                }
+                closure
+                    .returns
+                    .iter()
+                    .for_each(|r#type| self.r#type(r#type));
+                self.statement(&closure.statements);
+                self.scopes.pop();
+            }
+            ast::Expression::Construct(construct) => {
+                self.reference_class(&construct.r#enum);
+                self.reference_variant(construct.r#enum.symbol, &construct.variant);
+                construct
+                    .arguments
+                    .iter()
+                    .for_each(|argument| self.expression(argument));
+            }
+            ast::Expression::Match(r#match, _) => {
+                self.expression(&r#match.scrutinee);
+                for arm in &r#match.arms {
+                    self.scopes.push(Vec::new());
+                    self.pattern(&arm.pattern);
+                    self.expression(&arm.body);
+                    self.scopes.pop();
+                }
+            }
+        }
+
+        // Calls to procedures and functions with multiple returns have no single type
+        let r#type = match expression {
+            ast::Expression::Call(call) => match call.function.r#type() {
+                r#type::Expression::Function(_, mut returns) if returns.len() == 1 => {
+                    returns.remove(0)
+                }
+                _ => return,
+            },
+            expression => expression.r#type(),
+        };
+
+        self.hover(expression.span(), &r#type);
+    }
+
+    fn call(&mut self, call: &ast::Call<r#type::Expression>) {
+        self.expression(&call.function);
+        call.arguments
+            .iter()
+            .for_each(|argument| self.expression(argument));
+    }
+
+    fn r#type(&mut self, r#type: &ast::Type<r#type::Expression>) {
+        match r#type {
+            ast::Type::Bool(_) | ast::Type::Int(_) | ast::Type::Sized { .. } => (),
+            ast::Type::Class(variable) => {
+                self.reference_class(&variable.name);
+                self.generics(variable);
+            }
+            ast::Type::Array(r#type, length, _) => {
+                self.r#type(r#type);
+                if let Some(length) = length {
+                    self.expression(length);
+                }
+            }
+            ast::Type::Function(parameters, returns, _) => {
+                parameters
+                    .iter()
+                    .chain(returns)
+                    .for_each(|r#type| self.r#type(r#type));
+            }
+        }
+    }
+
+    fn generics(&mut self, variable: &ast::Variable<r#type::Expression>) {
+        for r#type in variable.generics.iter().flatten() {
+            self.r#type(r#type);
+        }
+    }
+
+    fn supertypes<'v, I>(&mut self, supertypes: I)
+    where
+        I: IntoIterator<Item = &'v ast::Variable<r#type::Expression>>,
+    {
+        for supertype in supertypes {
+            self.reference_class(&supertype.name);
+        }
+    }
+
+    /// Brings a local variable or parameter into scope.
+    fn declare(&mut self, declaration: &ast::SingleDeclaration<r#type::Expression>) {
+        self.local(&declaration.name, declaration.r#type.r#type());
+        self.r#type(&declaration.r#type);
+    }
+
+    fn local(&mut self, name: &ast::Identifier, r#type: r#type::Expression) {
+        self.hover_name(name, &r#type);
+        self.locals.push((name.symbol, *name.span, r#type));
+        self.scopes
+            .last_mut()
+            .expect("[INTERNAL ERROR]: missing local scope")
+            .push((name.symbol, *name.span));
+    }
+
+    fn reference_variable(&mut self, name: &ast::Identifier) {
+        let local = self
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(local, _)| *local == name.symbol)
+            .map(|(_, span)| *span);
+
+        let definition = local
+            .or_else(|| {
+                self.context
+                    .get_full(GlobalScope::Global, name)
+                    .map(|(span, _)| *span)
+            })
+            .or_else(|| {
+                let class = self.class?;
+                self.context
+                    .get_full(GlobalScope::Class(class), name)
+                    .map(|(span, _)| *span)
+            });
+
+        if let Some(definition) = definition {
+            self.references.push((*name.span, definition));
+        }
+    }
+
+    fn reference_class(&mut self, name: &ast::Identifier) {
+        let definition = self
+            .context
+            .get_class_full(name)
+            .map(|(span, _)| span)
+            .or_else(|| self.context.get_protocol(name))
+            .or_else(|| self.context.get_enum(name));
+
+        if let Some(definition) = definition {
+            self.references.push((*name.span, *definition));
+        }
+    }
+
+    fn reference_variant(&mut self, r#enum: Symbol, variant: &ast::Identifier) {
+        if self.context.get_enum(&r#enum).is_none() {
+            return;
+        }
+
+        if let Some((definition, _)) = self
+            .context
+            .get_variants(&r#enum)
+            .find(|(name, _)| name.symbol == variant.symbol)
+        {
+            self.references.push((*variant.span, *definition.span));
+        }
+    }
+
+    fn hover(&mut self, span: Span, r#type: &r#type::Expression) {
+        self.hovers.push((span, format!("{}", r#type)));
+    }
+
+    fn hover_name(&mut self, name: &ast::Identifier, r#type: &r#type::Expression) {
+        self.hovers
+            .push((*name.span, format!("{}: {}", name.symbol, r#type)));
+    }
+
+    fn hover_signature<F: ast::FunctionLike<r#type::Expression>>(&mut self, function: &F) {
+        let r#type = r#type::Expression::Function(
+            function
+                .parameters()
+                .iter()
+                .map(|parameter| parameter.r#type.r#type())
+                .collect(),
+            function.returns().iter().map(ast::Type::r#type).collect(),
+        );
+        self.hover_name(function.name(), &r#type);
+    }
+}
@@ -0,0 +1,181 @@
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_json::json;
+use serde_json::Value;
+
+use crate::data::span::Span;
+
+/// Standard JSON-RPC error codes.
+pub(super) const PARSE_ERROR: i64 = -32700;
+pub(super) const INVALID_PARAMS: i64 = -32602;
+pub(super) const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Reads the body of the next message, or returns `None` at the end of input.
+///
+/// Messages are framed by a `Content-Length` header, followed by a blank line.
+pub(super) fn read<R: BufRead>(input: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length header")
+    })?;
+
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+pub(super) fn write<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+pub(super) fn response(id: Value, result: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result,
+    })
+}
+
+pub(super) fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": code,
+            "message": message,
+        },
+    })
+}
+
+pub(super) fn notification(method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+    })
+}
+
+/// Converts between byte offsets into a source file and editor positions,
+/// which count lines from zero and characters in UTF-16 code units.
+pub(super) struct Lines<'source> {
+    source: &'source str,
+    starts: Vec<usize>,
+}
+
+impl<'source> Lines<'source> {
+    pub(super) fn new(source: &'source str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Lines { source, starts }
+    }
+
+    pub(super) fn position(&self, index: usize) -> Value {
+        let mut index = index.min(self.source.len());
+
+        while !self.source.is_char_boundary(index) {
+            index -= 1;
+        }
+
+        let line = self.starts.partition_point(|start| *start <= index) - 1;
+        let character = self.source[self.starts[line]..index].encode_utf16().count();
+
+        json!({
+            "line": line,
+            "character": character,
+        })
+    }
+
+    pub(super) fn range(&self, span: Span) -> Value {
+        json!({
+            "start": self.position(span.lo.index()),
+            "end": self.position(span.hi.index()),
+        })
+    }
+
+    /// Returns the byte offset of `position`, clamped to the end of its line.
+    pub(super) fn offset(&self, position: &Value) -> Option<usize> {
+        let line = position["line"].as_u64()? as usize;
+        let column = position["character"].as_u64()? as usize;
+        let start = *self.starts.get(line)?;
+        let text = self.source[start..].split('\n').next().unwrap_or_default();
+
+        let mut units = 0;
+
+        for (index, character) in text.char_indices() {
+            if units >= column {
+                return Some(start + index);
+            }
+            units += character.len_utf16();
+        }
+
+        Some(start + text.len())
+    }
+}
+
+/// Converts a `file://` URI into a path, decoding any percent-encoded bytes.
+pub(super) fn path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut index = 0;
+
+    while index < encoded.len() {
+        match encoded[index] {
+            b'%' => {
+                let hex = std::str::from_utf8(encoded.get(index + 1..index + 3)?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                index += 3;
+            }
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+/// Converts an absolute path into a `file://` URI, percent-encoding reserved bytes.
+pub(super) fn uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            byte => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
@@ -0,0 +1,383 @@
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::panic;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_json::json;
+use serde_json::Value;
+
+use crate::data::span::Span;
+use crate::data::symbol;
+use crate::lsp::analysis::Analysis;
+use crate::lsp::analysis::Outline;
+use crate::lsp::index::Index;
+use crate::lsp::protocol;
+use crate::lsp::protocol::Lines;
+use crate::Map;
+
+/// Runs a language server over `input` and `output` until the client exits,
+/// resolving interfaces against `directory_library` like the compiler does.
+pub fn serve<R: BufRead, W: Write>(
+    directory_library: Option<&Path>,
+    mut input: R,
+    mut output: W,
+) -> Result<(), crate::Error> {
+    let mut server = Server {
+        directory_library,
+        documents: Map::default(),
+        output: &mut output,
+    };
+
+    while let Some(body) = protocol::read(&mut input)? {
+        let message = match serde_json::from_slice::<Value>(&body) {
+            Ok(message) => message,
+            Err(error) => {
+                let error = protocol::error(Value::Null, protocol::PARSE_ERROR, &error.to_string());
+                protocol::write(server.output, &error)?;
+                continue;
+            }
+        };
+
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        match (message.get("id").cloned(), method) {
+            // Responses to requests, which this server never sends
+            (_, "") => (),
+            (None, "exit") => return Ok(()),
+            (None, method) => server.notification(method, params)?,
+            (Some(id), method) => server.request(id, method, params)?,
+        }
+    }
+
+    Ok(())
+}
+
+struct Server<'a, W> {
+    directory_library: Option<&'a Path>,
+    documents: Map<String, Document>,
+    output: &'a mut W,
+}
+
+struct Document {
+    path: PathBuf,
+    text: String,
+    analysis: Analysis,
+
+    /// Index from the last version of this document that type checked,
+    /// which may be stale, but is better than nothing for completion
+    index: Option<Index>,
+}
+
+impl<'a, W: Write> Server<'a, W> {
+    fn request(&mut self, id: Value, method: &str, params: &Value) -> io::Result<()> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        let result = match method {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    // Full document synchronization
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "documentSymbolProvider": true,
+                    "completionProvider": {
+                        "triggerCharacters": ["."],
+                    },
+                },
+                "serverInfo": {
+                    "name": "xic",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => Some(Value::Null),
+            "textDocument/hover" => self.hover(uri, &params["position"]),
+            "textDocument/definition" => self.definition(uri, &params["position"]),
+            "textDocument/documentSymbol" => self.symbols(uri),
+            "textDocument/completion" => self.completion(uri, &params["position"]),
+            _ => {
+                let message = format!("Unsupported method: {}", method);
+                let error = protocol::error(id, protocol::METHOD_NOT_FOUND, &message);
+                return protocol::write(self.output, &error);
+            }
+        };
+
+        match result {
+            Some(result) => protocol::write(self.output, &protocol::response(id, result)),
+            None => {
+                let message = format!("Unknown document: {}", uri);
+                let error = protocol::error(id, protocol::INVALID_PARAMS, &message);
+                protocol::write(self.output, &error)
+            }
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> io::Result<()> {
+        let document = &params["textDocument"];
+        let uri = document["uri"].as_str().unwrap_or_default();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = document["text"].as_str().unwrap_or_default();
+                self.update(uri, String::from(text))
+            }
+            "textDocument/didChange" => {
+                // With full synchronization, the last change contains the whole document
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                match text {
+                    Some(text) => self.update(uri, String::from(text)),
+                    None => Ok(()),
+                }
+            }
+            // Interfaces used by other documents may have changed on disk
+            "textDocument/didSave" => {
+                let open = self
+                    .documents
+                    .iter()
+                    .map(|(uri, document)| (uri.clone(), document.text.clone()))
+                    .collect::<Vec<_>>();
+                for (uri, text) in open {
+                    self.update(&uri, text)?;
+                }
+                Ok(())
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                self.publish(uri, Vec::new())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn update(&mut self, uri: &str, text: String) -> io::Result<()> {
+        let path = match protocol::path(uri) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let directory_library = self.directory_library;
+
+        // The compiler treats invalid states as internal errors, which shouldn't
+        // take down the server along with every other open document.
+        let analysis = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            Analysis::new(directory_library, &path, &text)
+        }))
+        .unwrap_or_else(|_| Analysis {
+            diagnostics: Vec::new(),
+            outline: Vec::new(),
+            index: None,
+        });
+
+        let lines = Lines::new(&text);
+        let diagnostics = analysis
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let (range, message) = match diagnostic.span {
+                    Some(span) if span.path() == Some(intern(&path)) => {
+                        (lines.range(span), diagnostic.message.clone())
+                    }
+                    // Errors in other files, e.g. used interfaces, are shown at the top
+                    Some(span) => (
+                        lines.range(Span::default()),
+                        format!(
+                            "{}:{}: {}",
+                            span.path().map_or("", symbol::resolve),
+                            span,
+                            diagnostic.message,
+                        ),
+                    ),
+                    None => (lines.range(Span::default()), diagnostic.message.clone()),
+                };
+
+                let mut value = json!({
+                    "range": range,
+                    "severity": diagnostic.severity as u8,
+                    "source": "xic",
+                    "message": message,
+                });
+
+                if let Some(code) = diagnostic.code {
+                    value["code"] = Value::from(code);
+                }
+
+                value
+            })
+            .collect();
+
+        let previous = self
+            .documents
+            .remove(uri)
+            .and_then(|document| document.analysis.index.or(document.index));
+
+        self.documents.insert(
+            String::from(uri),
+            Document {
+                path,
+                text,
+                analysis,
+                index: previous,
+            },
+        );
+
+        self.publish(uri, diagnostics)
+    }
+
+    fn publish(&mut self, uri: &str, diagnostics: Vec<Value>) -> io::Result<()> {
+        let notification = protocol::notification(
+            "textDocument/publishDiagnostics",
+            json!({
+                "uri": uri,
+                "diagnostics": diagnostics,
+            }),
+        );
+        protocol::write(self.output, &notification)
+    }
+
+    fn hover(&self, uri: &str, position: &Value) -> Option<Value> {
+        let document = self.documents.get(uri)?;
+        let offset = Lines::new(&document.text).offset(position)?;
+
+        let hover = match document
+            .analysis
+            .index
+            .as_ref()
+            .and_then(|index| index.hover(offset))
+        {
+            Some(hover) => hover,
+            None => return Some(Value::Null),
+        };
+
+        Some(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!("```xi\n{}\n```", hover),
+            },
+        }))
+    }
+
+    fn definition(&self, uri: &str, position: &Value) -> Option<Value> {
+        let document = self.documents.get(uri)?;
+        let offset = Lines::new(&document.text).offset(position)?;
+
+        let span = match document
+            .analysis
+            .index
+            .as_ref()
+            .and_then(|index| index.definition(offset))
+        {
+            Some(span) => span,
+            None => return Some(Value::Null),
+        };
+
+        let path = match span.path() {
+            Some(path) => PathBuf::from(symbol::resolve(path)),
+            None => return Some(Value::Null),
+        };
+
+        // Definitions can be in interfaces, which may or may not be open
+        let text = match self
+            .documents
+            .values()
+            .find(|document| document.path == path)
+        {
+            Some(document) => document.text.clone(),
+            None => match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(_) => return Some(Value::Null),
+            },
+        };
+
+        Some(json!({
+            "uri": protocol::uri(&path),
+            "range": Lines::new(&text).range(span),
+        }))
+    }
+
+    fn symbols(&self, uri: &str) -> Option<Value> {
+        let document = self.documents.get(uri)?;
+        let lines = Lines::new(&document.text);
+        let symbols = document
+            .analysis
+            .outline
+            .iter()
+            .map(|outline| symbol(&lines, outline))
+            .collect::<Vec<_>>();
+        Some(Value::from(symbols))
+    }
+
+    fn completion(&self, uri: &str, position: &Value) -> Option<Value> {
+        let document = self.documents.get(uri)?;
+        let offset = Lines::new(&document.text).offset(position)?;
+
+        // Skip over the partially typed member name, then expect a `.`
+        let before = &document.text[..offset];
+        let prefix = before.trim_end_matches(is_identifier);
+        let receiver = match prefix.strip_suffix('.') {
+            Some(receiver) => receiver,
+            None => return Some(json!([])),
+        };
+
+        let start = receiver
+            .trim_end_matches(|character| is_identifier(character) || character == '.')
+            .len();
+        let receiver = receiver[start..].trim_start_matches('.');
+
+        let index = match document.analysis.index.as_ref().or(document.index.as_ref()) {
+            Some(index) if !receiver.is_empty() => index,
+            _ => return Some(json!([])),
+        };
+
+        let items = index
+            .members(receiver, offset)
+            .into_iter()
+            .map(|member| {
+                // Completion item kinds for methods and fields
+                let kind = match member.method {
+                    true => 2,
+                    false => 5,
+                };
+                json!({
+                    "label": member.name.to_string(),
+                    "kind": kind,
+                    "detail": member.detail,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Some(Value::from(items))
+    }
+}
+
+fn symbol(lines: &Lines, outline: &Outline) -> Value {
+    let mut symbol = json!({
+        "name": outline.name,
+        "kind": outline.kind as u8,
+        "range": lines.range(outline.span),
+        "selectionRange": lines.range(outline.selection),
+        "children": outline
+            .children
+            .iter()
+            .map(|child| self::symbol(lines, child))
+            .collect::<Vec<_>>(),
+    });
+
+    if let Some(detail) = &outline.detail {
+        symbol["detail"] = Value::from(detail.as_str());
+    }
+
+    symbol
+}
+
+fn intern(path: &Path) -> symbol::Symbol {
+    symbol::intern(path.to_str().unwrap())
+}
+
+fn is_identifier(character: char) -> bool {
+    matches!(character, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '\'')
+}
@@ -1,7 +1,10 @@
+use std::env;
 use std::ffi::OsStr;
+use std::ffi::OsString;
 use std::fmt;
 use std::fs;
 use std::io;
+use std::io::BufRead as _;
 use std::io::Write as _;
 use std::path::Path;
 use std::path::PathBuf;
@@ -14,6 +17,13 @@ use clap::Parser;
 use clap::ValueHint;
 use xic::analyze;
 use xic::api;
+use xic::data::asm;
+use xic::data::operand::Register;
+use xic::data::span::Span;
+use xic::lint::Level;
+use xic::lint::Lint;
+use xic::lint::Lints;
+use xic::lint::Warning;
 use xic::optimize;
 
 #[derive(Debug, Parser)]
@@ -128,6 +138,8 @@ struct Command {
             Opt::ConditionalConstantPropagation.to_static_str(),
             Opt::DeadCodeElimination.to_static_str(),
             Opt::PartialRedundancyElimination.to_static_str(),
+            Opt::LoopInvariantCodeMotion.to_static_str(),
+            Opt::StrengthReduction.to_static_str(),
             DebugOpt::Final.to_static_str(),
         ],
         display_order = 10,
@@ -167,15 +179,163 @@ struct Command {
     )]
     debug_optimize_assembly: Vec<DebugOpt>,
 
+    /// Generate an interface file from each source file instead of compiling it
+    ///
+    /// Writes `<name>.ixi` to the output directory after semantic analysis.
+    #[clap(
+        long = "emit-interface",
+        conflicts_with = "check-interface",
+        display_order = 15
+    )]
+    emit_interface: bool,
+
+    /// Check that each source file's existing interface file matches the generated one
+    ///
+    /// Looks for `<name>.ixi` in the library directory, and prints any differences.
+    #[clap(long = "check-interface", id = "check-interface", display_order = 16)]
+    check_interface: bool,
+
+    /// Generate relocatable object files instead of assembly files
+    ///
+    /// Writes `<name>.o` to the output directory without invoking an assembler.
+    #[clap(long = "emit-object", display_order = 17)]
+    emit_object: bool,
+
+    /// Syntax of generated assembly files
+    #[clap(
+        long = "syntax",
+        default_value = "intel",
+        value_name = "SYNTAX",
+        possible_values = ["intel", "att"],
+        display_order = 18,
+    )]
+    syntax: Syntax,
+
+    /// Register allocation algorithm used when optimizing with `reg`
+    ///
+    /// `linear` is a fast linear scan allocator that reserves shuttle registers for spills.
+    /// `coloring` is an iterated register coalescing allocator that removes more moves.
+    #[clap(
+        long = "allocator",
+        default_value = "linear",
+        value_name = "ALLOCATOR",
+        possible_values = ["linear", "coloring"],
+        display_order = 19,
+    )]
+    allocator: Allocator,
+
+    /// Generate DWARF debugging information
+    ///
+    /// Maps instructions back to Xi source lines and describes local variables, so that
+    /// debuggers like `gdb` can step through programs. Implies keeping the frame pointer.
+    /// Variables whose temporaries are rewritten by optimizations are reported as optimized
+    /// out, so combine with `-O reg` for the most complete information.
+    #[clap(long = "debug-info", display_order = 20)]
+    debug_info: bool,
+
     /// Print a newline-separated list of supported optimizations
-    #[clap(long = "report-opts", display_order = 15)]
+    #[clap(long = "report-opts", display_order = 21)]
     report_optimizations: bool,
 
+    /// Print a detailed explanation of an error code, such as `E0203`
+    #[clap(long = "explain", value_name = "CODE", display_order = 22)]
+    explain: Option<xic::Code>,
+
+    /// Maximum number of errors to report
+    ///
+    /// Defaults to reporting every error.
+    #[clap(long = "error-limit", value_name = "LIMIT", display_order = 23)]
+    error_limit: Option<usize>,
+
+    /// Format of reported errors and warnings
+    ///
+    /// With `json`, prints one JSON object per diagnostic to `stderr`, and
+    /// generates the same objects as output from semantic analysis.
+    #[clap(
+        long = "error-format",
+        default_value = "human",
+        value_name = "FORMAT",
+        possible_values = ["human", "json"],
+        display_order = 24,
+    )]
+    error_format: ErrorFormat,
+
+    /// Report lints as warnings
+    ///
+    /// Takes a comma-separated list of lints, or `all`. Use `error=LINT` to report
+    /// a lint as an error instead, or `error` to do so for every enabled lint.
+    #[clap(
+        short = 'W',
+        long = "warn",
+        use_value_delimiter = true,
+        require_value_delimiter = true,
+        value_name = "LINT",
+        display_order = 25
+    )]
+    lint_warn: Vec<LintWarn>,
+
+    /// Silence lints
+    ///
+    /// Takes a comma-separated list of lints, or `all`. Applied before `-W`.
+    #[clap(
+        short = 'A',
+        long = "allow",
+        use_value_delimiter = true,
+        require_value_delimiter = true,
+        value_name = "LINT",
+        display_order = 26
+    )]
+    lint_allow: Vec<LintName>,
+
+    #[clap(subcommand)]
+    mode: Option<Mode>,
+
     /// Source files to compile, relative to `source_dir`
     #[clap(value_hint = ValueHint::FilePath)]
     input: Vec<PathBuf>,
 }
 
+#[derive(Debug, clap::Subcommand)]
+enum Mode {
+    /// Rewrite source and interface files in canonical format
+    Fmt {
+        /// Report files that are not canonically formatted instead of rewriting them
+        #[clap(long = "check")]
+        check: bool,
+
+        /// Source and interface files to format, relative to `source_dir`
+        #[clap(value_hint = ValueHint::FilePath)]
+        input: Vec<PathBuf>,
+    },
+
+    /// Compile source files and link them into an executable
+    Build {
+        /// Path to write the linked executable to
+        #[clap(short = 'o', default_value = "a.out", value_hint = ValueHint::FilePath)]
+        output: PathBuf,
+
+        /// Directory containing the runtime library `libxi.a`
+        ///
+        /// Defaults to the `XI_RUNTIME` environment variable if set, or else the
+        /// `runtime` directory next to the `xic` executable if it contains `libxi.a`.
+        #[clap(long = "runtime", value_hint = ValueHint::DirPath)]
+        runtime: Option<PathBuf>,
+
+        /// Source files to compile, relative to `source_dir`
+        #[clap(value_hint = ValueHint::FilePath)]
+        input: Vec<PathBuf>,
+    },
+
+    /// Demangle symbols produced by the compiler
+    Demangle {
+        /// Symbols to demangle
+        ///
+        /// If none are provided, demangle every symbol in `stdin` instead, e.g.
+        /// when piping in the output of `nm`, `objdump`, or a linker.
+        symbols: Vec<String>,
+    },
+}
+
 impl Command {
     fn optimize(&self, optimization: Opt) -> bool {
         match (
@@ -189,6 +349,79 @@ impl Command {
         }
     }
 
+    fn lints(&self) -> Lints {
+        let mut lints = Lints::default();
+
+        for name in &self.lint_allow {
+            for lint in name.lints() {
+                lints.set(lint, Level::Allow);
+            }
+        }
+
+        // Note: `-W error` only promotes lints that are otherwise enabled
+        let mut deny_all = false;
+
+        for flag in &self.lint_warn {
+            match flag {
+                LintWarn::Warn(name) => {
+                    for lint in name.lints() {
+                        lints.set(lint, Level::Warn);
+                    }
+                }
+                LintWarn::Deny(LintName(None)) => deny_all = true,
+                LintWarn::Deny(name) => {
+                    for lint in name.lints() {
+                        lints.set(lint, Level::Deny);
+                    }
+                }
+            }
+        }
+
+        if deny_all {
+            for lint in Lint::ALL {
+                if lints.level(lint) == Level::Warn {
+                    lints.set(lint, Level::Deny);
+                }
+            }
+        }
+
+        lints
+    }
+
+    fn warn(&self, warnings: &[Warning]) -> anyhow::Result<()> {
+        let lints = self.lints();
+        let mut denied = 0;
+
+        let warnings = warnings
+            .iter()
+            .filter_map(|warning| match lints.level(warning.lint()) {
+                Level::Allow => None,
+                Level::Warn => Some((warning, Level::Warn)),
+                Level::Deny => {
+                    denied += 1;
+                    Some((warning, Level::Deny))
+                }
+            });
+
+        match self.error_format {
+            ErrorFormat::Human => eprint(
+                warnings
+                    .map(|(warning, level)| warning.report(level))
+                    .collect(),
+            )?,
+            ErrorFormat::Json => {
+                for (warning, level) in warnings {
+                    eprintln!("{}", warning.diagnostic(level).json());
+                }
+            }
+        }
+
+        match denied {
+            0 => Ok(()),
+            _ => Err(anyhow!("Aborting due to {} denied warnings", denied)),
+        }
+    }
+
     fn debug_optimize_lir<T: fmt::Display, O: Into<DebugOpt>>(
         &self,
         path: &Path,
@@ -257,6 +490,50 @@ impl Command {
         self.write(&self.directory_output.join(path), extension, data)
     }
 
+    fn output_bytes(&self, path: &Path, extension: &str, data: &[u8]) -> anyhow::Result<()> {
+        if self.directory_output == Path::new("-") {
+            return io::stdout()
+                .write_all(data)
+                .context("Failed to write to stdout");
+        }
+
+        let path = self.directory_output.join(path).with_extension(extension);
+        fs::write(&path, data)
+            .with_context(|| anyhow!("Failed to write to file: {}", path.display()))
+    }
+
+    /// Encodes `assembly` as an object file. The built-in encoder doesn't emit
+    /// debugging information, so it's delegated to the system assembler instead.
+    fn object(&self, assembly: &asm::Unit<Register>) -> anyhow::Result<Vec<u8>> {
+        if !self.debug_info {
+            return Ok(assembly.elf());
+        }
+
+        let directory = tempfile::tempdir().context("Failed to create temporary directory")?;
+        let source = directory.path().join("unit.S");
+        let object = directory.path().join("unit.o");
+
+        self.write(&source, "S", assembly.intel())?;
+
+        let assembled = process::Command::new("cc")
+            .arg("-c")
+            .arg(&source)
+            .arg("-o")
+            .arg(&object)
+            .output()
+            .context("Failed to run `cc`")?;
+
+        if !assembled.status.success() {
+            return Err(anyhow!(
+                "Failed to assemble {}: {}",
+                source.display(),
+                String::from_utf8_lossy(&assembled.stderr),
+            ));
+        }
+
+        fs::read(&object).with_context(|| anyhow!("Failed to read file: {}", object.display()))
+    }
+
     fn write<T: fmt::Display>(&self, path: &Path, extension: &str, data: T) -> anyhow::Result<()> {
         let path = path.with_extension(extension);
         let mut file = fs::File::create(&path)
@@ -268,6 +545,99 @@ impl Command {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Syntax {
+    Intel,
+    Att,
+}
+
+impl str::FromStr for Syntax {
+    type Err = anyhow::Error;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "intel" => Ok(Syntax::Intel),
+            "att" => Ok(Syntax::Att),
+            _ => Err(anyhow!("Unknown assembly syntax {}", string)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Allocator {
+    Linear,
+    Coloring,
+}
+
+impl str::FromStr for Allocator {
+    type Err = anyhow::Error;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "linear" => Ok(Allocator::Linear),
+            "coloring" => Ok(Allocator::Coloring),
+            _ => Err(anyhow!("Unknown register allocator {}", string)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ErrorFormat {
+    Human,
+    Json,
+}
+
+impl str::FromStr for ErrorFormat {
+    type Err = anyhow::Error;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(anyhow!("Unknown error format {}", string)),
+        }
+    }
+}
+
+/// Lint name passed on the command line, where `all` selects every lint.
+#[derive(Copy, Clone, Debug)]
+struct LintName(Option<Lint>);
+
+impl LintName {
+    fn lints(self) -> impl Iterator<Item = Lint> {
+        Lint::ALL.into_iter().filter(move |lint| match self.0 {
+            None => true,
+            Some(name) => name == *lint,
+        })
+    }
+}
+
+impl str::FromStr for LintName {
+    type Err = anyhow::Error;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "all" => Ok(LintName(None)),
+            _ => Lint::from_str(string).map(Some).map(LintName),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum LintWarn {
+    Warn(LintName),
+    Deny(LintName),
+}
+
+impl str::FromStr for LintWarn {
+    type Err = anyhow::Error;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "error" => Ok(LintWarn::Deny(LintName(None))),
+            _ => match string.strip_prefix("error=") {
+                Some(name) => LintName::from_str(name).map(LintWarn::Deny),
+                None => LintName::from_str(string).map(LintWarn::Warn),
+            },
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum DebugOpt {
     Initial,
@@ -311,6 +681,8 @@ enum Opt {
     Inline,
     ConditionalConstantPropagation,
     PartialRedundancyElimination,
+    LoopInvariantCodeMotion,
+    StrengthReduction,
     FramePointer,
     ConstantPropagation,
     CopyPropagation,
@@ -321,7 +693,7 @@ enum Opt {
 // Need something like https://doc.rust-lang.org/std/mem/fn.variant_count.html
 // to make sure array matches up with enum definition. Procedural macro options
 // seem too heavyweight for something like this.
-const OPTIMIZATIONS: [&str; 12] = [
+const OPTIMIZATIONS: [&str; 14] = [
     Opt::LoopInversion.to_static_str(),
     Opt::ConstantFold.to_static_str(),
     Opt::FinalClass.to_static_str(),
@@ -329,6 +701,8 @@ const OPTIMIZATIONS: [&str; 12] = [
     Opt::Inline.to_static_str(),
     Opt::ConditionalConstantPropagation.to_static_str(),
     Opt::PartialRedundancyElimination.to_static_str(),
+    Opt::LoopInvariantCodeMotion.to_static_str(),
+    Opt::StrengthReduction.to_static_str(),
     Opt::FramePointer.to_static_str(),
     Opt::ConstantPropagation.to_static_str(),
     Opt::CopyPropagation.to_static_str(),
@@ -346,6 +720,8 @@ impl Opt {
             Opt::Inline => "inl",
             Opt::ConditionalConstantPropagation => "ccp",
             Opt::PartialRedundancyElimination => "pre",
+            Opt::LoopInvariantCodeMotion => "licm",
+            Opt::StrengthReduction => "sr",
             Opt::FramePointer => "fp",
             Opt::ConstantPropagation => "cp",
             Opt::CopyPropagation => "copy",
@@ -366,6 +742,8 @@ impl str::FromStr for Opt {
             "inl" => Ok(Opt::Inline),
             "ccp" => Ok(Opt::ConditionalConstantPropagation),
             "pre" => Ok(Opt::PartialRedundancyElimination),
+            "licm" => Ok(Opt::LoopInvariantCodeMotion),
+            "sr" => Ok(Opt::StrengthReduction),
             "fp" => Ok(Opt::FramePointer),
             "cp" => Ok(Opt::ConstantPropagation),
             "copy" => Ok(Opt::CopyPropagation),
@@ -376,11 +754,9 @@ impl str::FromStr for Opt {
     }
 }
 
-fn run() -> anyhow::Result<()> {
+fn run(command: &Command) -> anyhow::Result<()> {
     pretty_env_logger::init_timed();
 
-    let command = Command::parse();
-
     if command.report_optimizations {
         for optimization in OPTIMIZATIONS {
             println!("{}", optimization);
@@ -388,7 +764,28 @@ fn run() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    for path in &command.input {
+    if let Some(code) = command.explain {
+        println!("{} {}\n\n{}", code, code.name(), code.explanation());
+        return Ok(());
+    }
+
+    if let Some(Mode::Fmt { check, input }) = &command.mode {
+        return format(command, *check, input);
+    }
+
+    if let Some(Mode::Demangle { symbols }) = &command.mode {
+        return demangle(symbols);
+    }
+
+    let input = match &command.mode {
+        Some(Mode::Build { input, .. }) => input,
+        _ => &command.input,
+    };
+
+    let mut outdated = 0;
+    let mut objects = Vec::new();
+
+    for path in input {
         let path = command.directory_source.join(path);
 
         let tokens = api::lex(&path)?;
@@ -397,26 +794,82 @@ fn run() -> anyhow::Result<()> {
             command.debug(&path, "lexed", &tokens)?;
         }
 
-        let program = api::parse(&path, tokens)?;
+        let (program, syntax) = api::parse_recover(&path, tokens)?;
 
-        if command.debug_parse {
+        if command.debug_parse && syntax.is_empty() {
             command.debug(&path, "parsed", &program)?;
         }
 
-        let checked = api::check(command.directory_library.as_deref(), &path, program);
+        let interface = match command.emit_interface || command.check_interface {
+            true => Some(program.clone()),
+            false => None,
+        };
+
+        let checked = match interface {
+            Some(_) => {
+                api::check_without_interface(command.directory_library.as_deref(), &path, program)
+            }
+            None => api::check_warnings(command.directory_library.as_deref(), &path, program),
+        };
+
+        // Keep checking the recovered program so that semantic errors are
+        // reported alongside syntax errors, but never proceed past this phase.
+        if !syntax.is_empty() {
+            let semantic = match checked {
+                Ok(_) => Vec::new(),
+                Err(xic::Error::Semantic(errors)) => errors,
+                Err(error) => return Err(error.into()),
+            };
+            return Err(xic::Error::Recovered(syntax, semantic).into());
+        }
 
         if command.debug_check {
             command.debug(
                 &path,
                 "typed",
-                match &checked {
-                    Ok(_) => String::from("Valid Xi Program"),
-                    Err(error) => error.to_string(),
+                match (&checked, command.error_format) {
+                    (Ok(_), _) => String::from("Valid Xi Program"),
+                    (Err(error), ErrorFormat::Human) => error.to_string(),
+                    (Err(error), ErrorFormat::Json) => error
+                        .diagnostics(None)
+                        .iter()
+                        .map(|diagnostic| diagnostic.json().to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
                 },
             )?;
         }
 
-        let (mut program, mut context) = checked?;
+        let (mut program, mut context, warnings) = checked?;
+
+        command.warn(&warnings)?;
+
+        if let Some(interface) = interface {
+            if command.emit_interface {
+                command.output(&path, "ixi", api::emit_interface(&path, &interface)?)?;
+                continue;
+            }
+
+            let directory = match &command.directory_library {
+                Some(directory) => directory.as_path(),
+                None => path.parent().unwrap_or_else(|| Path::new(".")),
+            };
+
+            let existing = directory
+                .join(path.file_name().unwrap())
+                .with_extension("ixi");
+            let diff = api::diff_interface(&existing, &interface)?;
+
+            if !diff.is_empty() {
+                println!("{}", existing.display());
+                for line in diff {
+                    println!("{}", line);
+                }
+                outdated += 1;
+            }
+
+            continue;
+        }
 
         if command.optimize(Opt::LoopInversion) {
             optimize::invert_loops_ast(&path, &mut program);
@@ -427,7 +880,12 @@ fn run() -> anyhow::Result<()> {
             true => xic::Abi::XiFinal,
         };
 
-        let mut hir = api::emit_hir(&mut context, &path, abi, &program);
+        let debug = match command.debug_info {
+            false => xic::DebugInfo::Omit,
+            true => xic::DebugInfo::Keep,
+        };
+
+        let mut hir = api::emit_hir(&mut context, &path, abi, debug, &program);
 
         if command.optimize(Opt::ConstantFold) {
             hir = hir.map(optimize::fold_constants);
@@ -492,6 +950,16 @@ fn run() -> anyhow::Result<()> {
             )?;
         }
 
+        if command.optimize(Opt::LoopInvariantCodeMotion) {
+            cfg = cfg.map_mut(optimize::hoist_invariants_lir);
+            command.debug_optimize_lir(&path, DebugOpt::Opt(Opt::LoopInvariantCodeMotion), &cfg)?;
+        }
+
+        if command.optimize(Opt::StrengthReduction) {
+            cfg = cfg.map_mut(optimize::reduce_strength_lir);
+            command.debug_optimize_lir(&path, DebugOpt::Opt(Opt::StrengthReduction), &cfg)?;
+        }
+
         if command.optimize(Opt::CleanCfg) {
             cfg = cfg.map_mut(api::clean_cfg);
         }
@@ -508,7 +976,8 @@ fn run() -> anyhow::Result<()> {
             api::interpret_lir(&lir, io::BufReader::new(io::stdin()), io::stdout())?;
         }
 
-        let frame_pointer = match command.optimize(Opt::FramePointer) {
+        // Debuggers rely on the frame pointer to unwind the stack
+        let frame_pointer = match command.optimize(Opt::FramePointer) && !command.debug_info {
             true => xic::FramePointer::Omit,
             false => xic::FramePointer::Keep,
         };
@@ -557,7 +1026,10 @@ fn run() -> anyhow::Result<()> {
         command.debug_optimize_assembly(&path, DebugOpt::Final, &cfg)?;
 
         let mut assembly = if command.optimize(Opt::RegisterAllocation) {
-            cfg.map(api::allocate_linear)
+            match command.allocator {
+                Allocator::Linear => cfg.map(api::allocate_linear),
+                Allocator::Coloring => cfg.map(api::allocate_coloring),
+            }
         } else {
             cfg.map(api::destruct_cfg).map_ref(api::allocate_trivial)
         };
@@ -569,14 +1041,168 @@ fn run() -> anyhow::Result<()> {
                 .map(api::destruct_cfg)
         };
 
-        command.output(&path, "S", assembly.intel())?;
+        match &command.mode {
+            Some(Mode::Build { .. }) => {
+                let object = command.object(&assembly)?;
+                objects.push((path, object));
+            }
+            _ if command.emit_object => {
+                command.output_bytes(&path, "o", &command.object(&assembly)?)?
+            }
+            _ => match command.syntax {
+                Syntax::Intel => command.output(&path, "S", assembly.intel())?,
+                Syntax::Att => command.output(&path, "S", assembly.att())?,
+            },
+        }
+    }
+
+    if let Some(Mode::Build {
+        output, runtime, ..
+    }) = &command.mode
+    {
+        return link(output, &locate_runtime(runtime.as_deref())?, &objects);
+    }
+
+    match outdated {
+        0 => Ok(()),
+        _ => Err(anyhow!("Aborting due to {} outdated interfaces", outdated)),
+    }
+}
+
+/// Finds the directory containing `libxi.a`, preferring an explicit `--runtime`.
+fn locate_runtime(runtime: Option<&Path>) -> anyhow::Result<PathBuf> {
+    if let Some(runtime) = runtime {
+        return Ok(runtime.to_path_buf());
+    }
+
+    if let Some(runtime) = env::var_os("XI_RUNTIME") {
+        return Ok(PathBuf::from(runtime));
+    }
+
+    env::current_exe()
+        .ok()
+        .and_then(|executable| Some(executable.parent()?.join("runtime")))
+        .filter(|runtime| runtime.join("libxi.a").is_file())
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not find runtime library `libxi.a`: pass `--runtime` or set `XI_RUNTIME`"
+            )
+        })
+}
+
+/// Links `objects` against the runtime library in `runtime`, the same way as
+/// `runtime/linkxi.sh`.
+fn link(output: &Path, runtime: &Path, objects: &[(PathBuf, Vec<u8>)]) -> anyhow::Result<()> {
+    let directory = tempfile::tempdir().context("Failed to create temporary directory")?;
+    let mut paths = Vec::new();
+
+    // Note: prefix with index in case inputs from different directories share a name
+    for (index, (path, object)) in objects.iter().enumerate() {
+        let stem = path
+            .file_stem()
+            .ok_or_else(|| anyhow!("Expected .xi file, but got {}", path.display()))?;
+        let mut name = OsString::from(format!("{}_", index));
+        name.push(stem);
+
+        let path = directory.path().join(name).with_extension("o");
+        fs::write(&path, object)
+            .with_context(|| anyhow!("Failed to write to file: {}", path.display()))?;
+        paths.push(path);
+    }
+
+    let linked = process::Command::new("cc")
+        .args(&paths)
+        .arg("-L")
+        .arg(runtime)
+        // Units can reference each other's symbols and the runtime's in any order
+        .arg("-Wl,--start-group")
+        .arg("-lxi")
+        .arg("-Wl,--end-group")
+        .arg("-lpthread")
+        .arg("-no-pie")
+        .arg("-o")
+        .arg(output)
+        .output()
+        .context("Failed to run `cc`")?;
+
+    // Report symbols like `_IparseInt_t2ibai` as `parseInt(int[]): int, bool`
+    eprint!(
+        "{}",
+        api::demangle_text(&String::from_utf8_lossy(&linked.stderr))
+    );
+
+    match linked.status.success() {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "Failed to link {} with exit code {:?}",
+            output.display(),
+            linked.status.code(),
+        )),
+    }
+}
+
+fn format(command: &Command, check: bool, input: &[PathBuf]) -> anyhow::Result<()> {
+    let mut unformatted = 0;
+
+    for path in input {
+        let path = command.directory_source.join(path);
+        let tokens = api::lex(&path)?;
+        let formatted = api::format(&path, tokens)?;
+        let source = fs::read_to_string(&path)
+            .with_context(|| anyhow!("Failed to read file: {}", path.display()))?;
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            println!("{}", path.display());
+            unformatted += 1;
+        } else {
+            fs::write(&path, formatted)
+                .with_context(|| anyhow!("Failed to write to file: {}", path.display()))?;
+        }
+    }
+
+    match unformatted {
+        0 => Ok(()),
+        _ => Err(anyhow!("Aborting due to {} unformatted files", unformatted)),
+    }
+}
+
+fn demangle(symbols: &[String]) -> anyhow::Result<()> {
+    // Symbols that aren't mangled are echoed back unchanged, like `c++filt`
+    for symbol in symbols {
+        println!("{}", api::demangle(symbol).as_deref().unwrap_or(symbol));
+    }
+
+    if !symbols.is_empty() {
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let mut line = String::new();
+
+    while stdin
+        .lock()
+        .read_line(&mut line)
+        .context("Failed to read from stdin")?
+        > 0
+    {
+        write!(stdout, "{}", api::demangle_text(&line)).context("Failed to write to stdout")?;
+        stdout.flush().context("Failed to flush stdout")?;
+        line.clear();
     }
 
     Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
-    let error = match run() {
+    let command = Command::parse();
+
+    let error = match run(&command) {
         Ok(()) => return Ok(()),
         Err(error) => error,
     };
@@ -586,24 +1212,55 @@ fn main() -> anyhow::Result<()> {
         Err(error) => return Err(error),
     };
 
-    if let Some(report) = error.report() {
-        let (character_set, color) = match atty::is(atty::Stream::Stderr) {
-            true => (ariadne::CharSet::Unicode, true),
-            false => (ariadne::CharSet::Ascii, false),
-        };
+    let reported = match command.error_format {
+        ErrorFormat::Human => {
+            let reports = error.report(command.error_limit);
+            let reported = reports.len();
+            eprint(reports)?;
+            reported
+        }
+        ErrorFormat::Json => {
+            let diagnostics = error.diagnostics(command.error_limit);
+            for diagnostic in &diagnostics {
+                eprintln!("{}", diagnostic.json());
+            }
+            diagnostics.len()
+        }
+    };
 
-        let report = report
+    if reported == 0 {
+        return Err(anyhow::Error::from(error));
+    }
+
+    if reported < error.count() && command.error_format == ErrorFormat::Human {
+        eprintln!(
+            "Stopped after {} errors ({} more not shown)",
+            reported,
+            error.count() - reported,
+        );
+    }
+
+    process::exit(1)
+}
+
+fn eprint(reports: Vec<ariadne::ReportBuilder<Span>>) -> anyhow::Result<()> {
+    let (character_set, color) = match atty::is(atty::Stream::Stderr) {
+        true => (ariadne::CharSet::Unicode, true),
+        false => (ariadne::CharSet::Ascii, false),
+    };
+
+    let mut cache = xic::data::span::FileCache::default();
+
+    for report in reports {
+        report
             .with_config(
                 ariadne::Config::default()
                     .with_char_set(character_set)
                     .with_color(color),
             )
-            .finish();
-
-        let mut cache = xic::data::span::FileCache::default();
-        report.eprint(&mut cache)?;
-        process::exit(1)
-    } else {
-        Err(anyhow::Error::from(error))
+            .finish()
+            .eprint(&mut cache)?;
     }
+
+    Ok(())
 }
@@ -2,6 +2,8 @@ mod constant;
 mod copy;
 mod dead_code;
 mod function;
+mod induction;
+mod invariant;
 mod r#loop;
 mod partial_redundancy;
 
@@ -16,5 +18,7 @@ pub use dead_code::eliminate_assembly as eliminate_dead_code_assembly;
 pub use dead_code::eliminate_functions as eliminate_dead_code_functions;
 pub use dead_code::eliminate_lir as eliminate_dead_code_lir;
 pub use function::inline_lir as inline_functions_lir;
+pub use induction::reduce_lir as reduce_strength_lir;
+pub use invariant::hoist_lir as hoist_invariants_lir;
 pub use partial_redundancy::eliminate_lir as eliminate_partial_redundancy_lir;
 pub use r#loop::invert_ast as invert_loops_ast;
@@ -45,7 +45,9 @@ pub fn conditional_propagate_lir<T: lir::Target>(cfg: &mut Cfg<lir::Function<T>>
             let transfer = statement.clone();
 
             let rewrite = match statement {
-                lir::Statement::Jump(_) | lir::Statement::Label(_) => None,
+                lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => {
+                    None
+                }
                 lir::Statement::CJump {
                     condition,
                     left,
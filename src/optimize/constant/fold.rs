@@ -121,6 +121,7 @@ impl Foldable for hir::Statement {
             Expression(expression) => Expression(expression.fold()),
             Jump(label) => Jump(label),
             Label(label) => Label(label),
+            Debug(debug) => Debug(debug),
             Move {
                 destination,
                 source,
@@ -233,6 +234,7 @@ impl<T: lir::Target> Foldable for lir::Statement<T> {
             },
             Return(returns) => Return(returns.fold()),
             Label(label) => Label(label),
+            Debug(debug) => Debug(debug),
             CJump {
                 condition,
                 left,
@@ -285,12 +287,13 @@ pub fn fold_binary(binary: ir::Binary, left: i64, right: i64) -> i64 {
         ir::Binary::Mul => left.wrapping_mul(right),
         ir::Binary::Hul => ((left as i128 * right as i128) >> 64) as i64,
         ir::Binary::Xor => left ^ right,
-        #[rustfmt::skip]
-        ir::Binary::And => if (left & right) & 1 > 0 { 1 } else { 0 },
-        #[rustfmt::skip]
-        ir::Binary::Or => if (left | right) & 1 > 0 { 1 } else { 0 },
+        ir::Binary::And => left & right,
+        ir::Binary::Or => left | right,
         ir::Binary::Div => left / right,
         ir::Binary::Mod => left % right,
+        ir::Binary::UHul => ((left as u64 as u128 * right as u64 as u128) >> 64) as i64,
+        ir::Binary::UDiv => (left as u64 / right as u64) as i64,
+        ir::Binary::UMod => (left as u64 % right as u64) as i64,
     }
 }
 
@@ -303,5 +306,8 @@ pub fn fold_condition(condition: ir::Condition, left: i64, right: i64) -> bool {
         ir::Condition::Ne => left != right,
         ir::Condition::Eq => left == right,
         ir::Condition::Ae => left as u64 >= right as u64,
+        ir::Condition::B => (left as u64) < right as u64,
+        ir::Condition::Be => left as u64 <= right as u64,
+        ir::Condition::A => left as u64 > right as u64,
     }
 }
@@ -35,7 +35,18 @@ pub fn propagate_assembly(cfg: &mut Cfg<asm::Function<Temporary>>) {
 
             let statement = match statement {
                 Statement::Binary(
-                    binary @ (Cmp | Mov | Lea | Add | Sub | Shl | Mul | And | Or | Xor),
+                    binary @ (Cmp
+                    | Mov
+                    | Lea
+                    | Add
+                    | Sub
+                    | Shl
+                    | Mul
+                    | And
+                    | Or
+                    | Xor
+                    | Load { .. }
+                    | Store(_)),
                     operands,
                 ) => {
                     // TODO: propagate constants to temporaries inside memory operands
@@ -82,11 +93,15 @@ pub fn propagate_assembly(cfg: &mut Cfg<asm::Function<Temporary>>) {
                         None | Some(_) => Cow::Borrowed(statement),
                     }
                 }
-                Statement::Unary(Neg | Hul | Div | Mod | Call { .. } | Push | Pop, _)
+                Statement::Unary(
+                    Neg | Hul | Div | Mod | UHul | UDiv | UMod | Call { .. } | Push | Pop,
+                    _,
+                )
                 | Statement::Nullary(Nop | Cqo | Ret(_))
                 | Statement::Label(_)
                 | Statement::Jmp(_)
-                | Statement::Jcc(_, _) => Cow::Borrowed(statement),
+                | Statement::Jcc(_, _)
+                | Statement::Debug(_) => Cow::Borrowed(statement),
             };
 
             solution.analysis.transfer(&statement, &mut output);
@@ -36,7 +36,7 @@ pub fn propagate_lir<T: lir::Target>(cfg: &mut Cfg<lir::Function<T>>) {
             let mut traverse = |temporary| traverse(&output, &save, &mut propagated, temporary);
 
             match statement {
-                lir::Statement::Jump(_) | lir::Statement::Label(_) => (),
+                lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => (),
                 lir::Statement::CJump {
                     condition: _,
                     left,
@@ -161,7 +161,18 @@ pub fn propagate_assembly(cfg: &mut Cfg<asm::Function<Temporary>>) {
                 }
 
                 Statement::Binary(
-                    Cmp | Mov | Lea | Add | Sub | Shl | Mul | And | Or | Xor,
+                    Cmp
+                    | Mov
+                    | Lea
+                    | Add
+                    | Sub
+                    | Shl
+                    | Mul
+                    | And
+                    | Or
+                    | Xor
+                    | Load { .. }
+                    | Store(_),
                     operands,
                 ) => {
                     let memory = match operands {
@@ -185,15 +196,22 @@ pub fn propagate_assembly(cfg: &mut Cfg<asm::Function<Temporary>>) {
                         *memory = memory.map(|temporary| traverse(*temporary))
                     }
                 }
-                Statement::Unary(Hul | Div | Mod | Call { .. }, operand::Unary::R(source)) => {
+                Statement::Unary(
+                    Hul | Div | Mod | UHul | UDiv | UMod | Call { .. },
+                    operand::Unary::R(source),
+                ) => {
                     *source = traverse(*source);
                 }
 
-                Statement::Unary(Neg | Hul | Div | Mod | Call { .. } | Push | Pop, _)
+                Statement::Unary(
+                    Neg | Hul | Div | Mod | UHul | UDiv | UMod | Call { .. } | Push | Pop,
+                    _,
+                )
                 | Statement::Nullary(Nop | Cqo | Ret(_))
                 | Statement::Label(_)
                 | Statement::Jmp(_)
-                | Statement::Jcc(_, _) => (),
+                | Statement::Jcc(_, _)
+                | Statement::Debug(_) => (),
             };
 
             <CopyPropagation as Analysis<asm::Function<Temporary>>>::transfer(
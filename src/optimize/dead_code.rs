@@ -83,6 +83,7 @@ pub fn eliminate_lir<T: lir::Target>(cfg: &mut Cfg<lir::Function<T>>) {
                 | lir::Statement::CJump { .. }
                 | lir::Statement::Call(_, _, _)
                 | lir::Statement::Label(_)
+                | lir::Statement::Debug(_)
                 | lir::Statement::Return(_) => None,
                 lir::Statement::Move {
                     destination: lir::Expression::Memory(_),
@@ -145,20 +146,24 @@ pub fn eliminate_assembly(
                     | asm::Binary::Or
                     | asm::Binary::Xor
                     | asm::Binary::Mov
-                    | asm::Binary::Lea,
+                    | asm::Binary::Lea
+                    | asm::Binary::Load { .. }
+                    | asm::Binary::Store(_),
                     operands,
                 ) => match operands.destination() {
                     Or::L(Temporary::Register(register)) if register.is_callee_saved() => None,
                     Or::L(temporary) => Some(temporary),
                     Or::R(_) => None,
                 },
-                asm::Statement::Unary(asm::Unary::Hul, _) => {
+                asm::Statement::Unary(asm::Unary::Hul | asm::Unary::UHul, _) => {
                     Some(Temporary::Register(Register::Rdx))
                 }
                 // Preserve statements that may have side effects
                 asm::Statement::Unary(
                     asm::Unary::Div
                     | asm::Unary::Mod
+                    | asm::Unary::UDiv
+                    | asm::Unary::UMod
                     | asm::Unary::Call { .. }
                     | asm::Unary::Push
                     | asm::Unary::Pop,
@@ -174,7 +179,8 @@ pub fn eliminate_assembly(
                 asm::Statement::Nullary(asm::Nullary::Nop | asm::Nullary::Ret(_))
                 | asm::Statement::Label(_)
                 | asm::Statement::Jmp(_)
-                | asm::Statement::Jcc(_, _) => None,
+                | asm::Statement::Jcc(_, _)
+                | asm::Statement::Debug(_) => None,
             };
 
             let live = destination.map_or(true, |destination| output.contains(&destination));
@@ -7,6 +7,7 @@ use crate::abi;
 use crate::analyze::CallGraph;
 use crate::cfg;
 use crate::cfg::Cfg;
+use crate::data::debug::Debug;
 use crate::data::ir;
 use crate::data::lir;
 use crate::data::operand::Immediate;
@@ -190,6 +191,11 @@ impl Rewriter {
                 lir::Statement::Call(function, arguments, returns)
             }
             lir::Statement::Label(label) => lir::Statement::Label(self.rewrite_label(label)),
+            lir::Statement::Debug(debug @ Debug::Location(_)) => {
+                lir::Statement::Debug(debug.clone())
+            }
+            // Note: the callee's variables would be indistinguishable from the caller's
+            lir::Statement::Debug(Debug::Variable(_, _, _)) => return,
             lir::Statement::Move {
                 destination,
                 source,
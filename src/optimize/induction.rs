@@ -0,0 +1,260 @@
+use crate::cfg::Cfg;
+use crate::data::ir;
+use crate::data::lir;
+use crate::data::operand::Immediate;
+use crate::data::operand::Label;
+use crate::data::operand::Temporary;
+use crate::util;
+use crate::Map;
+
+/// Strength-reduces multiplications of basic induction variables by constants.
+///
+/// A basic induction variable is a temporary whose only definition inside a loop
+/// has the form `i = i + c` or `i = i - c` for some constant `c`. Every occurrence
+/// of `i * k` in the loop (most commonly from array indexing) is replaced by a new
+/// temporary `j`, which is initialized to `i * k` in the loop preheader and
+/// incremented by `c * k` immediately after each update to `i`.
+///
+/// Loops are visited from the inside out. Dead code elimination can clean up
+/// induction variables that are no longer used after reduction.
+pub fn reduce_lir<T: lir::Target>(cfg: &mut Cfg<lir::Function<T>>) {
+    log::info!(
+        "[{}] Reducing induction variable strength in {}",
+        std::any::type_name::<Cfg<lir::Function<T>>>(),
+        cfg.name()
+    );
+    util::time!(
+        "[{}] Done reducing induction variable strength in {}",
+        std::any::type_name::<Cfg<lir::Function<T>>>(),
+        cfg.name()
+    );
+
+    let mut loops = cfg.loops();
+    let mut reduced = 0;
+
+    for index in loops.postorder() {
+        let mut definitions = Map::<Temporary, Vec<(Label, usize)>>::default();
+
+        for label in loops[index].body() {
+            for (index, statement) in cfg.blocks[label].iter().enumerate() {
+                for temporary in defined(statement) {
+                    definitions
+                        .entry(temporary)
+                        .or_default()
+                        .push((*label, index));
+                }
+            }
+        }
+
+        let induction = definitions
+            .into_iter()
+            .filter_map(|(temporary, definitions)| match definitions.as_slice() {
+                [(label, index)] => step(&temporary, &cfg.blocks[label][*index])
+                    .map(|step| (temporary, (*label, *index, step))),
+                _ => None,
+            })
+            .collect::<Map<_, _>>();
+
+        if induction.is_empty() {
+            continue;
+        }
+
+        let mut reducer = Reducer {
+            induction: &induction,
+            reduced: Map::default(),
+        };
+
+        for label in loops[index].body() {
+            for statement in cfg.blocks.get_mut(label).unwrap() {
+                reducer.reduce_statement(statement);
+            }
+        }
+
+        if reducer.reduced.is_empty() {
+            continue;
+        }
+
+        reduced += reducer.reduced.len();
+
+        // Insert updates in reverse order, so earlier indices stay valid.
+        let mut updates = reducer
+            .reduced
+            .iter()
+            .map(|((temporary, factor), reduced)| {
+                let (label, index, step) = induction[temporary];
+                let update = lir::Statement::Move {
+                    destination: lir::Expression::Temporary(*reduced),
+                    source: lir::Expression::Binary(
+                        ir::Binary::Add,
+                        Box::new(lir::Expression::Temporary(*reduced)),
+                        Box::new(lir::Expression::Immediate(Immediate::Integer(
+                            step.wrapping_mul(*factor),
+                        ))),
+                    ),
+                };
+                (label, index, update)
+            })
+            .collect::<Vec<_>>();
+
+        updates.sort_by_key(|(label, index, _)| (*label, *index));
+
+        for (label, index, update) in updates.into_iter().rev() {
+            cfg.blocks
+                .get_mut(&label)
+                .unwrap()
+                .insert(index + 1, update);
+        }
+
+        let preheader = loops.insert_preheader(cfg, index);
+        let block = cfg.blocks.get_mut(&preheader).unwrap();
+        let jump = block.pop().unwrap();
+
+        block.extend(
+            reducer
+                .reduced
+                .into_iter()
+                .map(|((temporary, factor), reduced)| lir::Statement::Move {
+                    destination: lir::Expression::Temporary(reduced),
+                    source: multiply(temporary, factor),
+                }),
+        );
+
+        block.push(jump);
+    }
+
+    log::debug!("Reduced {} induction variables!", reduced);
+}
+
+struct Reducer<'a> {
+    induction: &'a Map<Temporary, (Label, usize, i64)>,
+    reduced: Map<(Temporary, i64), Temporary>,
+}
+
+impl Reducer<'_> {
+    fn reduce_statement<T>(&mut self, statement: &mut lir::Statement<T>) {
+        match statement {
+            lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => (),
+            lir::Statement::CJump {
+                condition: _,
+                left,
+                right,
+                r#true: _,
+                r#false: _,
+            } => {
+                self.reduce_expression(left);
+                self.reduce_expression(right);
+            }
+            lir::Statement::Call(function, arguments, _) => {
+                self.reduce_expression(function);
+                arguments
+                    .iter_mut()
+                    .for_each(|argument| self.reduce_expression(argument));
+            }
+            lir::Statement::Move {
+                destination: lir::Expression::Temporary(_),
+                source,
+            } => self.reduce_expression(source),
+            lir::Statement::Move {
+                destination: lir::Expression::Memory(address),
+                source,
+            } => {
+                self.reduce_expression(address);
+                self.reduce_expression(source);
+            }
+            lir::Statement::Move { .. } => unreachable!(),
+            lir::Statement::Return(returns) => returns
+                .iter_mut()
+                .for_each(|r#return| self.reduce_expression(r#return)),
+        }
+    }
+
+    fn reduce_expression(&mut self, expression: &mut lir::Expression) {
+        match expression {
+            lir::Expression::Immediate(_) | lir::Expression::Temporary(_) => (),
+            lir::Expression::Memory(address) => self.reduce_expression(address),
+            lir::Expression::Binary(ir::Binary::Mul, left, right) => {
+                let (temporary, factor) = match (&**left, &**right) {
+                    (
+                        lir::Expression::Temporary(temporary),
+                        lir::Expression::Immediate(Immediate::Integer(factor)),
+                    )
+                    | (
+                        lir::Expression::Immediate(Immediate::Integer(factor)),
+                        lir::Expression::Temporary(temporary),
+                    ) if self.induction.contains_key(temporary) => (*temporary, *factor),
+                    _ => {
+                        self.reduce_expression(left);
+                        self.reduce_expression(right);
+                        return;
+                    }
+                };
+
+                let reduced = *self
+                    .reduced
+                    .entry((temporary, factor))
+                    .or_insert_with(|| Temporary::fresh("induction"));
+
+                *expression = lir::Expression::Temporary(reduced);
+            }
+            lir::Expression::Binary(_, left, right) => {
+                self.reduce_expression(left);
+                self.reduce_expression(right);
+            }
+        }
+    }
+}
+
+fn defined<T>(statement: &lir::Statement<T>) -> Vec<Temporary> {
+    match statement {
+        lir::Statement::Move {
+            destination: lir::Expression::Temporary(temporary),
+            source: _,
+        } => vec![*temporary],
+        lir::Statement::Call(_, _, returns) => returns.clone(),
+        lir::Statement::Move { .. }
+        | lir::Statement::Jump(_)
+        | lir::Statement::CJump { .. }
+        | lir::Statement::Label(_)
+        | lir::Statement::Return(_)
+        | lir::Statement::Debug(_) => Vec::new(),
+    }
+}
+
+/// Returns the constant step of `statement` if it updates `temporary` by `i = i + c`,
+/// `i = c + i`, or `i = i - c`.
+fn step<T>(temporary: &Temporary, statement: &lir::Statement<T>) -> Option<i64> {
+    let (binary, left, right) = match statement {
+        lir::Statement::Move {
+            destination: lir::Expression::Temporary(destination),
+            source: lir::Expression::Binary(binary, left, right),
+        } if destination == temporary => (binary, &**left, &**right),
+        _ => return None,
+    };
+
+    match (binary, left, right) {
+        (
+            ir::Binary::Add,
+            lir::Expression::Temporary(source),
+            lir::Expression::Immediate(Immediate::Integer(step)),
+        )
+        | (
+            ir::Binary::Add,
+            lir::Expression::Immediate(Immediate::Integer(step)),
+            lir::Expression::Temporary(source),
+        ) if source == temporary => Some(*step),
+        (
+            ir::Binary::Sub,
+            lir::Expression::Temporary(source),
+            lir::Expression::Immediate(Immediate::Integer(step)),
+        ) if source == temporary => Some(step.wrapping_neg()),
+        _ => None,
+    }
+}
+
+fn multiply(temporary: Temporary, factor: i64) -> lir::Expression {
+    lir::Expression::Binary(
+        ir::Binary::Mul,
+        Box::new(lir::Expression::Temporary(temporary)),
+        Box::new(lir::Expression::Immediate(Immediate::Integer(factor))),
+    )
+}
@@ -0,0 +1,171 @@
+use crate::cfg::Cfg;
+use crate::data::ir;
+use crate::data::lir;
+use crate::data::operand::Temporary;
+use crate::util;
+use crate::Map;
+use crate::Set;
+
+/// Hoists loop-invariant expressions into loop preheaders.
+///
+/// An expression is loop-invariant if none of its temporaries are defined inside the loop.
+/// Like `emit::lower`, we consider immediates, temporaries, and arithmetic to be pure, but
+/// we don't hoist memory reads or division: the preheader runs even when the loop body
+/// doesn't, so moving them could introduce an out-of-bounds access or a division by zero.
+///
+/// Loops are visited from the inside out, so that expressions hoisted into an inner
+/// preheader can be hoisted again out of the enclosing loop.
+pub fn hoist_lir<T: lir::Target>(cfg: &mut Cfg<lir::Function<T>>) {
+    log::info!(
+        "[{}] Hoisting loop invariants in {}",
+        std::any::type_name::<Cfg<lir::Function<T>>>(),
+        cfg.name()
+    );
+    util::time!(
+        "[{}] Done hoisting loop invariants in {}",
+        std::any::type_name::<Cfg<lir::Function<T>>>(),
+        cfg.name()
+    );
+
+    let mut loops = cfg.loops();
+    let mut hoisted = 0;
+
+    for index in loops.postorder() {
+        let preheader = loops.insert_preheader(cfg, index);
+
+        let mut hoister = Hoister {
+            defined: Set::default(),
+            invariants: Map::default(),
+        };
+
+        for label in loops[index].body() {
+            for statement in &cfg.blocks[label] {
+                hoister.define(statement);
+            }
+        }
+
+        for label in loops[index].body() {
+            for statement in cfg.blocks.get_mut(label).unwrap() {
+                hoister.hoist_statement(statement);
+            }
+        }
+
+        hoisted += hoister.invariants.len();
+
+        let block = cfg.blocks.get_mut(&preheader).unwrap();
+        let jump = block.pop().unwrap();
+
+        block.extend(
+            hoister
+                .invariants
+                .into_iter()
+                .map(|(expression, temporary)| lir::Statement::Move {
+                    destination: lir::Expression::Temporary(temporary),
+                    source: expression,
+                }),
+        );
+
+        block.push(jump);
+    }
+
+    log::debug!("Hoisted {} loop invariants!", hoisted);
+}
+
+struct Hoister {
+    defined: Set<Temporary>,
+    invariants: Map<lir::Expression, Temporary>,
+}
+
+impl Hoister {
+    fn define<T>(&mut self, statement: &lir::Statement<T>) {
+        match statement {
+            lir::Statement::Move {
+                destination: lir::Expression::Temporary(temporary),
+                source: _,
+            } => {
+                self.defined.insert(*temporary);
+            }
+            lir::Statement::Call(_, _, returns) => {
+                self.defined.extend(returns.iter().copied());
+            }
+            lir::Statement::Move { .. }
+            | lir::Statement::Jump(_)
+            | lir::Statement::CJump { .. }
+            | lir::Statement::Label(_)
+            | lir::Statement::Return(_)
+            | lir::Statement::Debug(_) => (),
+        }
+    }
+
+    fn hoist_statement<T>(&mut self, statement: &mut lir::Statement<T>) {
+        match statement {
+            lir::Statement::Jump(_) | lir::Statement::Label(_) | lir::Statement::Debug(_) => (),
+            lir::Statement::CJump {
+                condition: _,
+                left,
+                right,
+                r#true: _,
+                r#false: _,
+            } => {
+                self.hoist_expression(left);
+                self.hoist_expression(right);
+            }
+            lir::Statement::Call(function, arguments, _) => {
+                self.hoist_expression(function);
+                arguments
+                    .iter_mut()
+                    .for_each(|argument| self.hoist_expression(argument));
+            }
+            lir::Statement::Move {
+                destination: lir::Expression::Temporary(_),
+                source,
+            } => self.hoist_expression(source),
+            lir::Statement::Move {
+                destination: lir::Expression::Memory(address),
+                source,
+            } => {
+                self.hoist_expression(address);
+                self.hoist_expression(source);
+            }
+            lir::Statement::Move { .. } => unreachable!(),
+            lir::Statement::Return(returns) => returns
+                .iter_mut()
+                .for_each(|r#return| self.hoist_expression(r#return)),
+        }
+    }
+
+    /// Replaces maximal loop-invariant subexpressions of `expression` with temporaries.
+    fn hoist_expression(&mut self, expression: &mut lir::Expression) {
+        match expression {
+            lir::Expression::Immediate(_) | lir::Expression::Temporary(_) => (),
+            lir::Expression::Binary(_, _, _) if self.is_invariant(expression) => {
+                let temporary = *self
+                    .invariants
+                    .entry(expression.clone())
+                    .or_insert_with(|| Temporary::fresh("invariant"));
+                *expression = lir::Expression::Temporary(temporary);
+            }
+            lir::Expression::Memory(address) => self.hoist_expression(address),
+            lir::Expression::Binary(_, left, right) => {
+                self.hoist_expression(left);
+                self.hoist_expression(right);
+            }
+        }
+    }
+
+    fn is_invariant(&self, expression: &lir::Expression) -> bool {
+        match expression {
+            lir::Expression::Immediate(_) => true,
+            lir::Expression::Temporary(temporary) => !self.defined.contains(temporary),
+            lir::Expression::Memory(_) => false,
+            lir::Expression::Binary(
+                ir::Binary::Div | ir::Binary::Mod | ir::Binary::UDiv | ir::Binary::UMod,
+                _,
+                _,
+            ) => false,
+            lir::Expression::Binary(_, left, right) => {
+                self.is_invariant(left) && self.is_invariant(right)
+            }
+        }
+    }
+}
@@ -25,7 +25,7 @@ struct Inverter(usize);
 
 impl ast::VisitorMut<r#type::Expression> for Inverter {
     fn visit_statement(&mut self, statement: &mut ast::Statement<r#type::Expression>) {
-        if let ast::Statement::While(ast::Do::No, condition, r#while, span) = statement {
+        if let ast::Statement::While(ast::Do::No, label, condition, r#while, span) = statement {
             if !effectful(condition) {
                 log::trace!("Inverted loop at {}", span);
                 self.0 += 1;
@@ -35,6 +35,7 @@ impl ast::VisitorMut<r#type::Expression> for Inverter {
                     Box::new(ast::Statement::Sequence(Vec::new(), *span)),
                     Some(Box::new(ast::Statement::While(
                         ast::Do::Yes,
+                        label.clone(),
                         condition.clone(),
                         r#while.clone(),
                         *span,
@@ -59,17 +60,22 @@ fn effectful(expression: &ast::Expression<r#type::Expression>) -> bool {
         // Recomputing these shouldn't be observable, but it _is_ inefficient.
         ast::Expression::String(_, _)
         | ast::Expression::Array(_, _, _)
-        | ast::Expression::New(_, _) => true,
+        | ast::Expression::New(_, _)
+        | ast::Expression::Construct(_)
+        | ast::Expression::Closure(_) => true,
 
-        // Note: it's safe to hoist an index even if it may
-        // crash, since it's evaluated at least once whether
+        // Note: it's safe to hoist an index or class conversion even
+        // if it may crash, since it's evaluated at least once whether
         // or not we invert the loop. There can be no other
         // effects other than crashing.
         ast::Expression::Index(array, index, _, _) => effectful(array) || effectful(index),
         ast::Expression::Length(array, _) => effectful(array),
+        ast::Expression::Cast(_, expression, _) => effectful(expression),
+        ast::Expression::InstanceOf(expression, _, _) => effectful(expression),
+        ast::Expression::As(expression, _, _) => effectful(expression),
         ast::Expression::Dot(expression, _, _, _) => effectful(expression),
 
-        ast::Expression::Call(_) => true,
+        ast::Expression::Call(_) | ast::Expression::Match(_, _) => true,
         ast::Expression::Binary(binary, left, right, _, _) => match binary {
             // Avoid recomputing array concatenation, which is expensive.
             ast::Binary::Cat => true,
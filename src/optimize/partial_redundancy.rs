@@ -171,6 +171,9 @@ impl<T: lir::Target> Transformer<T> {
             lir::Statement::Jump(target) => {
                 statements.push(lir::Statement::Jump(target));
             }
+            lir::Statement::Debug(debug) => {
+                statements.push(lir::Statement::Debug(debug));
+            }
             lir::Statement::CJump {
                 condition,
                 left,
@@ -2,6 +2,8 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::try_err)]
 #![allow(clippy::just_underscores_and_digits)]
+#![allow(clippy::needless_lifetimes)]
+#![allow(clippy::ptr_arg)]
 #![allow(unused_imports)]
 
 mod error;
@@ -15,10 +17,31 @@ pub(crate) use grammar::ProgramParser;
 use std::path::Path;
 
 use crate::data::ast;
+use crate::data::span;
 use crate::data::token;
 use crate::util;
 
 pub fn parse<I>(path: &Path, tokens: I) -> Result<ast::Program<()>, crate::Error>
+where
+    I: IntoIterator<Item = token::Spanned>,
+{
+    let (program, errors) = parse_recover(path, tokens)?;
+
+    if errors.is_empty() {
+        Ok(program)
+    } else {
+        Err(crate::Error::Syntactic(errors))
+    }
+}
+
+/// Parses a program, recovering from syntax errors at the item and statement level.
+///
+/// Returns the recovered program along with every syntax error encountered, or fails
+/// if the parser could not recover at all.
+pub fn parse_recover<I>(
+    path: &Path,
+    tokens: I,
+) -> Result<(ast::Program<()>, Vec<Error>), crate::Error>
 where
     I: IntoIterator<Item = token::Spanned>,
 {
@@ -33,7 +56,32 @@ where
         path.display()
     );
 
-    ProgramParser::new()
-        .parse(tokens)
-        .map_err(crate::Error::from)
+    let mut recovered = Vec::new();
+    let program = ProgramParser::new().parse(&mut recovered, tokens);
+    finish(recovered, program)
+}
+
+/// Collects the syntax errors recovered from by a generated parser, along with
+/// its final result.
+pub(crate) fn finish<T>(
+    recovered: Vec<lalrpop_util::ErrorRecovery<span::Point, token::Token, crate::Error>>,
+    result: Result<T, lalrpop_util::ParseError<span::Point, token::Token, crate::Error>>,
+) -> Result<(T, Vec<Error>), crate::Error> {
+    let mut errors = Vec::new();
+
+    for recovery in recovered {
+        match crate::Error::from(recovery.error) {
+            crate::Error::Syntactic(recovered) => errors.extend(recovered),
+            error => return Err(error),
+        }
+    }
+
+    match result.map_err(crate::Error::from) {
+        Ok(node) => Ok((node, errors)),
+        Err(crate::Error::Syntactic(error)) => {
+            errors.extend(error);
+            Err(crate::Error::Syntactic(errors))
+        }
+        Err(error) => Err(error),
+    }
 }
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::data::span;
 use crate::data::token;
 use crate::error;
@@ -52,36 +54,34 @@ impl From<ParseError> for error::Error {
         match error {
             InvalidToken { .. } | ExtraToken { .. } => unreachable!(),
             User { error } => error,
-            UnrecognizedEOF { location, .. } => error::Error::Syntactic(Error::Eof(location)),
+            UnrecognizedEOF { location, .. } => error::Error::Syntactic(vec![Error::Eof(location)]),
             UnrecognizedToken {
                 token: (start, token, end),
                 ..
-            } => error::Error::Syntactic(Error::Token(span::Span::new(start, end), token)),
+            } => error::Error::Syntactic(vec![Error::Token(span::Span::new(start, end), token)]),
         }
     }
 }
 
 impl error::Report for Error {
-    fn report(&self) -> ariadne::ReportBuilder<span::Span> {
-        use ariadne::Span as _;
-
-        const ERROR: ariadne::ReportKind = ariadne::ReportKind::Error;
-
-        let message = self.message();
-
+    fn code(&self) -> error::Code {
         match self {
-            Error::Eof(point) => {
-                let span = span::Span::new(*point, point.bump());
-                ariadne::Report::build(ERROR, *span.source(), point.index())
-                    .with_label(ariadne::Label::new(span).with_message(message))
-            }
+            Error::Eof(_) => error::Code::UnexpectedEof,
+            Error::Integer(_) => error::Code::InvalidInteger,
+            Error::Array(_) => error::Code::ArrayLength,
+            Error::Length(_) => error::Code::MissingLength,
+            Error::Token(_, _) => error::Code::UnexpectedToken,
+        }
+    }
+
+    fn labels(&self) -> Vec<(span::Span, Cow<'static, str>)> {
+        let span = match self {
+            Error::Eof(point) => span::Span::new(*point, point.bump()),
             Error::Integer(span)
             | Error::Array(span)
             | Error::Length(span)
-            | Error::Token(span, _) => {
-                ariadne::Report::build(ERROR, *span.source(), span.lo.index())
-                    .with_label(ariadne::Label::new(*span).with_message(message))
-            }
-        }
+            | Error::Token(span, _) => *span,
+        };
+        vec![(span, Cow::Borrowed(self.message()))]
     }
 }
@@ -27,6 +27,7 @@ impl<T> Serialize for ast::ItemSignature<T> {
         match self {
             ast::ItemSignature::Class(class) => class.sexp(),
             ast::ItemSignature::ClassTemplate(class) => class.sexp(),
+            ast::ItemSignature::Protocol(protocol) => protocol.sexp(),
             ast::ItemSignature::Function(function) => function.sexp(),
             ast::ItemSignature::FunctionTemplate(function) => function.sexp(),
         }
@@ -39,6 +40,9 @@ impl<T> Serialize for ast::Item<T> {
             ast::Item::Global(global) => global.sexp(),
             ast::Item::Class(class) => class.sexp(),
             ast::Item::ClassTemplate(class) => class.sexp(),
+            ast::Item::Protocol(protocol) => protocol.sexp(),
+            ast::Item::Enum(r#enum) => r#enum.sexp(),
+            ast::Item::EnumTemplate(r#enum) => r#enum.sexp(),
             ast::Item::Function(function) => function.sexp(),
             ast::Item::FunctionTemplate(function) => function.sexp(),
         }
@@ -79,53 +83,104 @@ impl<T> Serialize for ast::Initialization<T> {
 
 impl Serialize for ast::ClassTemplate {
     fn sexp(&self) -> Sexp {
-        [
-            match self.r#final {
-                true => ["final".sexp(), self.name.sexp()].sexp_move(),
-                false => self.name.sexp(),
-            },
-            self.generics.sexp(),
-            self.items.sexp(),
-        ]
-        .sexp_move()
+        with_implements(
+            vec![
+                match self.r#final {
+                    true => ["final".sexp(), self.name.sexp()].sexp_move(),
+                    false => self.name.sexp(),
+                },
+                self.generics.sexp(),
+                self.items.sexp(),
+            ],
+            &self.implements,
+        )
     }
 }
 
 impl<T> Serialize for ast::ClassSignature<T> {
     fn sexp(&self) -> Sexp {
-        [
-            match self.r#final {
-                true => ["final".sexp(), self.name.sexp()].sexp_move(),
-                false => self.name.sexp(),
-            },
-            match &self.extends {
-                None => ["extends".sexp()].sexp_move(),
-                Some(extends) => ["extends".sexp(), extends.sexp()].sexp_move(),
-            },
-            self.methods.sexp(),
-        ]
-        .sexp_move()
+        with_implements(
+            vec![
+                match self.r#final {
+                    true => ["final".sexp(), self.name.sexp()].sexp_move(),
+                    false => self.name.sexp(),
+                },
+                match &self.extends {
+                    None => ["extends".sexp()].sexp_move(),
+                    Some(extends) => ["extends".sexp(), extends.sexp()].sexp_move(),
+                },
+                self.methods.sexp(),
+            ],
+            &self.implements,
+        )
     }
 }
 
 impl<T> Serialize for ast::Class<T> {
+    fn sexp(&self) -> Sexp {
+        with_implements(
+            vec![
+                match self.r#final {
+                    true => ["final".sexp(), self.name.sexp()].sexp_move(),
+                    false => self.name.sexp(),
+                },
+                if let Some(extends) = &self.extends {
+                    ["extends".sexp(), extends.sexp()].sexp_move()
+                } else {
+                    Vec::<&'static str>::new().sexp_move()
+                },
+                self.items.sexp(),
+            ],
+            &self.implements,
+        )
+    }
+}
+
+/// Inserts the list of implemented interfaces, if any, before the class body.
+fn with_implements<T>(mut class: Vec<Sexp>, implements: &[ast::Variable<T>]) -> Sexp {
+    if !implements.is_empty() {
+        let implements = std::iter::once("implements".sexp())
+            .chain(implements.iter().map(Serialize::sexp))
+            .collect::<Vec<_>>()
+            .tap(Sexp::List);
+        class.insert(class.len() - 1, implements);
+    }
+    class.sexp_move()
+}
+
+impl<T> Serialize for ast::Protocol<T> {
+    fn sexp(&self) -> Sexp {
+        ["interface".sexp(), self.name.sexp(), self.methods.sexp()].sexp_move()
+    }
+}
+
+impl<T> Serialize for ast::Enum<T> {
+    fn sexp(&self) -> Sexp {
+        ["enum".sexp(), self.name.sexp(), self.variants.sexp()].sexp_move()
+    }
+}
+
+impl Serialize for ast::EnumTemplate {
     fn sexp(&self) -> Sexp {
         [
-            match self.r#final {
-                true => ["final".sexp(), self.name.sexp()].sexp_move(),
-                false => self.name.sexp(),
-            },
-            if let Some(extends) = &self.extends {
-                ["extends".sexp(), extends.sexp()].sexp_move()
-            } else {
-                Vec::<&'static str>::new().sexp_move()
-            },
-            self.items.sexp(),
+            "enum".sexp(),
+            self.name.sexp(),
+            self.generics.sexp(),
+            self.variants.sexp(),
         ]
         .sexp_move()
     }
 }
 
+impl<T> Serialize for ast::Variant<T> {
+    fn sexp(&self) -> Sexp {
+        std::iter::once(self.name.sexp())
+            .chain(self.payload.iter().map(Serialize::sexp))
+            .collect::<Vec<_>>()
+            .tap(Sexp::List)
+    }
+}
+
 impl<T> Serialize for ast::ClassItem<T> {
     fn sexp(&self) -> Sexp {
         match self {
@@ -177,11 +232,20 @@ impl<T> Serialize for ast::Type<T> {
         match self {
             Bool(_) => "bool".sexp(),
             Int(_) => "int".sexp(),
+            Sized { signed, size, .. } => token::Token::Int {
+                signed: *signed,
+                size: *size,
+            }
+            .to_string()
+            .sexp_move(),
             Class(class) => class.sexp(),
             Array(r#type, None, _) => ["[]".sexp(), r#type.sexp()].sexp_move(),
             Array(r#type, Some(length), _) => {
                 ["[]".sexp(), r#type.sexp(), length.sexp()].sexp_move()
             }
+            Function(parameters, returns, _) => {
+                ["fn".sexp(), parameters.sexp(), returns.sexp()].sexp_move()
+            }
         }
     }
 }
@@ -246,13 +310,83 @@ impl<T> Serialize for ast::Expression<T> {
             Unary(unary, expression, _, _) => [unary.sexp(), expression.sexp()].sexp_move(),
             Index(array, index, _, _) => ["[]".sexp(), array.sexp(), index.sexp()].sexp_move(),
             Length(array, _) => ["length".sexp(), array.sexp()].sexp_move(),
+            Cast(r#type, expression, _) => [r#type.sexp(), expression.sexp()].sexp_move(),
+            InstanceOf(expression, r#type, _) => {
+                ["instanceof".sexp(), expression.sexp(), r#type.sexp()].sexp_move()
+            }
+            As(expression, r#type, _) => {
+                ["as".sexp(), expression.sexp(), r#type.sexp()].sexp_move()
+            }
             Dot(receiver, symbol, _, _) => [".".sexp(), receiver.sexp(), symbol.sexp()].sexp_move(),
             New(variable, _) => ["new".sexp(), variable.sexp()].sexp_move(),
             Call(call) => call.sexp(),
+            Closure(closure) => closure.sexp(),
+            Construct(construct) => construct.sexp(),
+            Match(r#match, _) => r#match.sexp(),
         }
     }
 }
 
+impl<T> Serialize for ast::Construct<T> {
+    fn sexp(&self) -> Sexp {
+        let variant = [".".sexp(), self.r#enum.sexp(), self.variant.sexp()].sexp_move();
+        std::iter::once(variant)
+            .chain(self.arguments.iter().map(Serialize::sexp))
+            .collect::<Vec<_>>()
+            .tap(Sexp::List)
+    }
+}
+
+impl<T, B: Serialize> Serialize for ast::Match<T, B> {
+    fn sexp(&self) -> Sexp {
+        std::iter::once("match".sexp())
+            .chain(std::iter::once(self.scrutinee.sexp()))
+            .chain(
+                self.arms
+                    .iter()
+                    .map(|arm| ["=>".sexp(), arm.pattern.sexp(), arm.body.sexp()].sexp_move()),
+            )
+            .collect::<Vec<_>>()
+            .tap(Sexp::List)
+    }
+}
+
+impl<T> Serialize for ast::Pattern<T> {
+    fn sexp(&self) -> Sexp {
+        use ast::Pattern::*;
+        match self {
+            Wildcard(_) => "_".sexp(),
+            Binding(name, _) => name.sexp(),
+            Variant(name, patterns, _, _) => std::iter::once(name.sexp())
+                .chain(patterns.iter().map(Serialize::sexp))
+                .collect::<Vec<_>>()
+                .tap(Sexp::List),
+            Boolean(false, _) => "false".sexp(),
+            Boolean(true, _) => "true".sexp(),
+            Integer(integer, _) if *integer < 0 => {
+                ["-".sexp(), (-(*integer as i128)).to_string().sexp_move()].sexp_move()
+            }
+            Integer(integer, _) => integer.to_string().sexp_move(),
+            Character(char, _) => match token::unescape_char(*char) {
+                Some(string) => format!("\'{}\'", string).sexp_move(),
+                None => format!("\'{}\'", char).sexp_move(),
+            },
+        }
+    }
+}
+
+impl<T> Serialize for ast::Closure<T> {
+    fn sexp(&self) -> Sexp {
+        [
+            "fn".sexp(),
+            self.parameters.sexp(),
+            self.returns.sexp(),
+            self.statements.sexp(),
+        ]
+        .sexp_move()
+    }
+}
+
 impl<T> Serialize for ast::Declaration<T> {
     fn sexp(&self) -> Sexp {
         match self {
@@ -305,17 +439,83 @@ impl<T> Serialize for ast::Statement<T> {
             If(condition, r#if, None, _) => {
                 ["if".sexp(), condition.sexp(), r#if.sexp()].sexp_move()
             }
-            While(ast::Do::Yes, condition, body, _) => {
-                ["do".sexp(), body.sexp(), "while".sexp(), condition.sexp()].sexp_move()
-            }
-            While(ast::Do::No, condition, body, _) => {
-                ["while".sexp(), condition.sexp(), body.sexp()].sexp_move()
-            }
-            Break(_) => "break".sexp(),
+            While(ast::Do::Yes, label, condition, body, _) => labeled(
+                label,
+                ["do".sexp(), body.sexp(), "while".sexp(), condition.sexp()].sexp_move(),
+            ),
+            While(ast::Do::No, label, condition, body, _) => labeled(
+                label,
+                ["while".sexp(), condition.sexp(), body.sexp()].sexp_move(),
+            ),
+            For(r#for) => r#for.sexp(),
+            Break(None, _) => "break".sexp(),
+            Break(Some(label), _) => format!("break@{}", label).sexp_move(),
+            Continue(None, _) => "continue".sexp(),
+            Continue(Some(label), _) => format!("continue@{}", label).sexp_move(),
+            Match(r#match) => r#match.sexp(),
+            Throw(expression, _) => ["throw".sexp(), expression.sexp()].sexp_move(),
+            Try(r#try) => r#try.sexp(),
+            Error(_) => "error".sexp(),
         }
     }
 }
 
+impl<T> Serialize for ast::Try<T> {
+    fn sexp(&self) -> Sexp {
+        std::iter::once("try".sexp())
+            .chain(std::iter::once(self.statements.sexp()))
+            .chain(self.catches.iter().map(Serialize::sexp))
+            .chain(
+                self.finally
+                    .iter()
+                    .map(|finally| ["finally".sexp(), finally.sexp()].sexp_move()),
+            )
+            .collect::<Vec<_>>()
+            .tap(Sexp::List)
+    }
+}
+
+impl<T> Serialize for ast::Catch<T> {
+    fn sexp(&self) -> Sexp {
+        [
+            "catch".sexp(),
+            self.declaration.sexp(),
+            self.statements.sexp(),
+        ]
+        .sexp_move()
+    }
+}
+
+impl<T> Serialize for ast::For<T> {
+    fn sexp(&self) -> Sexp {
+        let empty = || Sexp::List(Vec::new());
+        labeled(
+            &self.label,
+            [
+                "for".sexp(),
+                self.initialization
+                    .as_ref()
+                    .map_or_else(empty, |initialization| initialization.sexp()),
+                self.condition
+                    .as_ref()
+                    .map_or_else(empty, |condition| condition.sexp()),
+                self.update
+                    .as_ref()
+                    .map_or_else(empty, |update| update.sexp()),
+                self.statements.sexp(),
+            ]
+            .sexp_move(),
+        )
+    }
+}
+
+fn labeled(label: &Option<ast::Identifier>, r#loop: Sexp) -> Sexp {
+    match label {
+        None => r#loop,
+        Some(label) => [format!("{}@", label).sexp_move(), r#loop].sexp_move(),
+    }
+}
+
 impl<T> Serialize for ast::Variable<T> {
     fn sexp(&self) -> Sexp {
         match self.generics.as_ref() {
@@ -325,6 +525,15 @@ impl<T> Serialize for ast::Variable<T> {
     }
 }
 
+impl Serialize for ast::Generic {
+    fn sexp(&self) -> Sexp {
+        match &self.bound {
+            None => self.name.sexp(),
+            Some(bound) => [self.name.sexp(), bound.sexp()].sexp_move(),
+        }
+    }
+}
+
 impl Serialize for ast::Identifier {
     fn sexp(&self) -> Sexp {
         self.symbol.sexp()
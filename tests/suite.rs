@@ -4,9 +4,30 @@ mod lex;
 #[path = "suite/parse.rs"]
 mod parse;
 
+#[path = "suite/format.rs"]
+mod format;
+
 #[path = "suite/check.rs"]
 mod check;
 
+#[path = "suite/lint.rs"]
+mod lint;
+
+#[path = "suite/diagnostic.rs"]
+mod diagnostic;
+
+#[path = "suite/interface.rs"]
+mod interface;
+
+#[path = "suite/explain.rs"]
+mod explain;
+
+#[path = "suite/lsp.rs"]
+mod lsp;
+
+#[path = "suite/demangle.rs"]
+mod demangle;
+
 #[path = "suite/emit.rs"]
 mod emit;
 
@@ -58,6 +79,7 @@ pub fn emit_hir(path: &str) -> anyhow::Result<hir::Unit> {
         &mut context,
         Path::new(path),
         xic::Abi::Xi,
+        xic::DebugInfo::Omit,
         &program,
     ))
 }
@@ -134,6 +156,33 @@ pub fn execute<T: Display>(object: T) -> anyhow::Result<String> {
     stdout(Command::new(&path), None::<String>).context("Running assembled binary")
 }
 
+pub fn execute_object(object: &[u8]) -> anyhow::Result<String> {
+    let mut file = tempfile::Builder::new()
+        .suffix(".o")
+        .tempfile()
+        .context("Creating temporary file")?;
+    file.write_all(object).context("Writing object file")?;
+    file.flush().context("Flushing object file")?;
+
+    let object = file.into_temp_path();
+    let path = NamedTempFile::new()
+        .context("Creating temporary file")?
+        .into_temp_path();
+
+    let mut cc = Command::new("cc");
+    cc.arg(&object)
+        .arg("-L")
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/runtime"))
+        .arg("-lxi")
+        .arg("-lpthread")
+        .arg("-no-pie")
+        .arg("-o")
+        .arg(&path);
+
+    stdout(cc, None::<String>).context("Linking with `cc`")?;
+    stdout(Command::new(&path), None::<String>).context("Running linked binary")
+}
+
 pub fn execute_all<I, T>(objects: I) -> anyhow::Result<String>
 where
     I: IntoIterator<Item = T>,
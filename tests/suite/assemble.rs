@@ -1,3 +1,5 @@
+use std::path::Path;
+
 #[test_generator::test_resources("tests/execute/*.xi")]
 pub fn tile(path: &str) -> anyhow::Result<()> {
     let expected_stdout = super::execute_expected(path)?;
@@ -46,3 +48,80 @@ pub fn allocate(path: &str) -> anyhow::Result<()> {
     pretty_assertions::assert_eq!(expected_stdout, linear_stdout);
     Ok(())
 }
+
+#[test_generator::test_resources("tests/execute/*.xi")]
+pub fn coloring(path: &str) -> anyhow::Result<()> {
+    let expected_stdout = super::execute_expected(path)?;
+
+    let coloring = super::tile(path)?
+        .map(xic::api::construct_cfg)
+        .map(xic::api::allocate_coloring);
+
+    let coloring_stdout = super::execute(&coloring)?;
+
+    pretty_assertions::assert_eq!(expected_stdout, coloring_stdout);
+    Ok(())
+}
+
+#[test_generator::test_resources("tests/execute/*.xi")]
+pub fn att(path: &str) -> anyhow::Result<()> {
+    let expected_stdout = super::execute_expected(path)?;
+
+    let linear = super::tile(path)?
+        .map(xic::api::construct_cfg)
+        .map(xic::api::allocate_linear);
+
+    let att_stdout = super::execute(linear.att())?;
+
+    pretty_assertions::assert_eq!(expected_stdout, att_stdout);
+    Ok(())
+}
+
+#[test_generator::test_resources("tests/execute/*.xi")]
+pub fn encode(path: &str) -> anyhow::Result<()> {
+    let expected_stdout = super::execute_expected(path)?;
+
+    let object = super::tile(path)?
+        .map(xic::api::construct_cfg)
+        .map(xic::api::allocate_linear)
+        .elf();
+
+    let object_stdout = super::execute_object(&object)?;
+
+    pretty_assertions::assert_eq!(expected_stdout, object_stdout);
+    Ok(())
+}
+
+#[test_generator::test_resources("tests/execute/*.xi")]
+pub fn debug(path: &str) -> anyhow::Result<()> {
+    let expected_stdout = super::execute_expected(path)?;
+
+    let program = super::parse(path)?;
+    let (program, mut context) = xic::api::check(None, Path::new(path), program).unwrap();
+    let hir = xic::api::emit_hir(
+        &mut context,
+        Path::new(path),
+        xic::Abi::Xi,
+        xic::DebugInfo::Keep,
+        &program,
+    );
+
+    let hir_stdout = super::interpret_hir(&hir)?;
+    pretty_assertions::assert_eq!(expected_stdout, hir_stdout);
+
+    let lir = hir.map_ref(xic::api::emit_lir);
+    let lir_stdout = super::interpret_lir(&lir)?;
+    pretty_assertions::assert_eq!(expected_stdout, lir_stdout);
+
+    let assembly = lir
+        .map(xic::api::construct_cfg)
+        .map(xic::api::destruct_cfg)
+        .map_ref(|function| xic::api::tile(xic::FramePointer::Keep, function))
+        .map(xic::api::construct_cfg)
+        .map(xic::api::allocate_linear);
+
+    let assembly_stdout = super::execute(&assembly)?;
+
+    pretty_assertions::assert_eq!(expected_stdout, assembly_stdout);
+    Ok(())
+}
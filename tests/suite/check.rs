@@ -1,27 +1,32 @@
 use std::fmt;
 use std::path::Path;
 
-struct Snapshot<T>(Result<T, xic::Error>);
+pub struct Snapshot<T>(pub Result<T, xic::Error>);
 
 impl<T> fmt::Display for Snapshot<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match &self.0 {
             Ok(_) => write!(fmt, "Valid Xi Program"),
-            Err(error) => match error.report() {
-                None => write!(fmt, "{}", error),
-                Some(report) => {
-                    let cache = xic::data::span::FileCache::default();
-                    let mut buffer = Vec::new();
+            Err(error) => {
+                let reports = error.report(None);
 
+                if reports.is_empty() {
+                    return write!(fmt, "{}", error);
+                }
+
+                let mut cache = xic::data::span::FileCache::default();
+                let mut buffer = Vec::new();
+
+                for report in reports {
                     report
                         .with_config(ariadne::Config::default().with_color(false))
                         .finish()
-                        .write(cache, &mut buffer)
+                        .write(&mut cache, &mut buffer)
                         .unwrap();
-
-                    write!(fmt, "{}", String::from_utf8(buffer).unwrap())
                 }
-            },
+
+                write!(fmt, "{}", String::from_utf8(buffer).unwrap())
+            }
         }
     }
 }
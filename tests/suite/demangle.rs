@@ -0,0 +1,28 @@
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+/// Snapshots every symbol in the assembly generated for `path`, followed by its
+/// demangled form, or `?` if it couldn't be demangled.
+#[test_generator::test_resources("tests/demangle/*.xi")]
+pub fn demangle(path: &str) -> anyhow::Result<()> {
+    let assembly = super::tile(path)?.to_string();
+
+    let symbols = assembly
+        .split(|character: char| !character.is_ascii_alphanumeric() && character != '_')
+        .filter(|word| word.starts_with("_I"))
+        .collect::<BTreeSet<_>>();
+
+    let mut snapshot = String::new();
+
+    for symbol in symbols {
+        let demangled = xic::api::demangle(symbol);
+        writeln!(snapshot, "{}", symbol)?;
+        writeln!(snapshot, "    {}", demangled.as_deref().unwrap_or("?"))?;
+    }
+
+    insta::assert_display_snapshot!(path, snapshot);
+
+    // Filtering should demangle every symbol in place
+    assert!(!xic::api::demangle_text(&assembly).contains("_I"));
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use xic::lint::Level;
+
+/// Snapshots the JSON form of every error and warning reported for `path`,
+/// one object per line, in the same order as `xic --error-format=json`.
+#[test_generator::test_resources("tests/diagnostic/*.xi")]
+pub fn diagnostic(path: &str) -> anyhow::Result<()> {
+    let path = Path::new(path);
+    let tokens = xic::api::lex(path)?;
+
+    // Lexical errors surface while parsing, since tokens are lexed lazily
+    let diagnostics = match xic::api::parse_recover(path, tokens) {
+        Err(error) => error.diagnostics(None),
+        Ok((program, syntax)) => match xic::api::check_warnings(None, path, program) {
+            Ok((_, _, warnings)) if syntax.is_empty() => warnings
+                .iter()
+                .map(|warning| warning.diagnostic(Level::Warn))
+                .collect(),
+            Ok(_) => xic::Error::Syntactic(syntax).diagnostics(None),
+            Err(xic::Error::Semantic(semantic)) => {
+                xic::Error::Recovered(syntax, semantic).diagnostics(None)
+            }
+            Err(error) => return Err(error.into()),
+        },
+    };
+
+    let mut snapshot = String::new();
+
+    for diagnostic in diagnostics {
+        writeln!(snapshot, "{}", diagnostic.json())?;
+    }
+
+    insta::assert_display_snapshot!(path.to_str().unwrap(), snapshot);
+    Ok(())
+}
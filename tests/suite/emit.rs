@@ -1,3 +1,8 @@
+use std::collections::HashSet;
+
+use xic::data::lir;
+use xic::data::ssa;
+
 #[test_generator::test_resources("tests/execute/*.xi")]
 pub fn interpret(path: &str) -> anyhow::Result<()> {
     let hir = super::emit_hir(path)?;
@@ -28,3 +33,49 @@ pub fn reorder(path: &str) -> anyhow::Result<()> {
     pretty_assertions::assert_eq!(expected_stdout, cleaned_stdout);
     Ok(())
 }
+
+#[test_generator::test_resources("tests/execute/*.xi")]
+pub fn ssa(path: &str) -> anyhow::Result<()> {
+    let expected_stdout = super::execute_expected(path)?;
+
+    let ssa = super::emit_lir(path)?
+        .map(xic::api::construct_cfg)
+        .map_mut(xic::api::clean_cfg)
+        .map(xic::api::construct_ssa);
+
+    for cfg in ssa.functions.values() {
+        let mut definitions = HashSet::new();
+
+        for (_, statements) in cfg.blocks() {
+            for statement in statements {
+                let defined = match statement {
+                    ssa::Statement::Phi {
+                        destination,
+                        sources: _,
+                    } => vec![*destination],
+                    ssa::Statement::Lir(lir::Statement::Move {
+                        destination: lir::Expression::Temporary(temporary),
+                        source: _,
+                    }) => vec![*temporary],
+                    ssa::Statement::Lir(lir::Statement::Call(_, _, returns)) => returns.clone(),
+                    ssa::Statement::Lir(_) => Vec::new(),
+                };
+
+                for temporary in defined {
+                    assert!(
+                        definitions.insert(temporary),
+                        "Multiple definitions of {} in {}",
+                        temporary,
+                        cfg.name(),
+                    );
+                }
+            }
+        }
+    }
+
+    let destructed = ssa.map(xic::api::destruct_ssa).map(xic::api::destruct_cfg);
+    let destructed_stdout = super::interpret_lir(&destructed)?;
+
+    pretty_assertions::assert_eq!(expected_stdout, destructed_stdout);
+    Ok(())
+}
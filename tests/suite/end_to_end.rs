@@ -56,4 +56,105 @@ mod separate {
     test!(shadow_class_2, shadow_class_2_1, shadow_class_2_2);
     test!(shadow_class_3, shadow_class_3_1, shadow_class_3_2);
     test!(shadow_class_4, shadow_class_4_1, shadow_class_4_2);
+    test!(interface_1, interface_1_1, interface_1_2);
+}
+
+mod interface {
+    use std::process::Command;
+
+    use anyhow::Context as _;
+
+    /// Checks `path` from `tests/separate` against its up-to-date interface file.
+    fn check(path: &str) -> anyhow::Result<()> {
+        let output = Command::new(env!("CARGO_BIN_EXE_xic"))
+            .arg("--sourcepath")
+            .arg("tests/separate")
+            .arg("--check-interface")
+            .arg(path)
+            .output()
+            .context("Checking interface with `xic`")?;
+
+        // Up-to-date interfaces produce no output, including warnings
+        assert!(
+            output.status.success(),
+            "Failed to check interface: {}",
+            String::from_utf8_lossy(&output.stderr),
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+        Ok(())
+    }
+
+    #[test]
+    fn cycle_function() -> anyhow::Result<()> {
+        check("cycle_function_1.xi")
+    }
+
+    #[test]
+    fn final_1() -> anyhow::Result<()> {
+        check("final_1_1.xi")
+    }
+}
+
+mod build {
+    use std::path::Path;
+    use std::process::Command;
+    use std::process::Output;
+
+    use anyhow::Context as _;
+    use tempfile::NamedTempFile;
+
+    /// Builds `paths` from `tests/separate` into an executable at `output`.
+    fn build(output: &Path, paths: &[&str]) -> anyhow::Result<Output> {
+        Command::new(env!("CARGO_BIN_EXE_xic"))
+            .arg("--sourcepath")
+            .arg("tests/separate")
+            .arg("build")
+            .env(
+                "XI_RUNTIME",
+                concat!(env!("CARGO_MANIFEST_DIR"), "/runtime"),
+            )
+            .arg("-o")
+            .arg(output)
+            .args(paths)
+            .output()
+            .context("Building with `xic`")
+    }
+
+    #[test]
+    fn smoke() -> anyhow::Result<()> {
+        let path = NamedTempFile::new()
+            .context("Creating temporary file")?
+            .into_temp_path();
+
+        let output = build(&path, &["smoke_1.xi", "smoke_2.xi"])?;
+        assert!(
+            output.status.success(),
+            "Failed to build: {}",
+            String::from_utf8_lossy(&output.stderr),
+        );
+
+        let stdout = super::super::stdout(Command::new(&path), None::<String>)
+            .context("Running built binary")?;
+        insta::assert_display_snapshot!(stdout);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_symbol() -> anyhow::Result<()> {
+        let path = NamedTempFile::new()
+            .context("Creating temporary file")?
+            .into_temp_path();
+
+        // Missing the implementation of `foo` in `smoke_2.xi`
+        let output = build(&path, &["smoke_1.xi"])?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(
+            stderr.contains("undefined reference to `foo(): int'"),
+            "Expected demangled symbol in linker error: {}",
+            stderr,
+        );
+        Ok(())
+    }
 }
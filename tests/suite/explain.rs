@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::anyhow;
+use anyhow::Context as _;
+
+/// Returns the codes of the errors reported for `path`, if any.
+fn codes(path: &Path) -> anyhow::Result<Vec<&'static str>> {
+    let tokens = xic::api::lex(path)?;
+    let program = match xic::api::parse(path, tokens) {
+        Ok(program) => program,
+        Err(error) => return Ok(codes_error(&error)),
+    };
+    match xic::api::check(None, path, program) {
+        Ok(_) => Ok(Vec::new()),
+        Err(error) => Ok(codes_error(&error)),
+    }
+}
+
+fn codes_error(error: &xic::Error) -> Vec<&'static str> {
+    error
+        .diagnostics(None)
+        .iter()
+        .map(|diagnostic| diagnostic.code)
+        .collect()
+}
+
+/// Checks that the first `xi` example in each explanation reports its code,
+/// and that the second example, which shows the fix, reports no errors.
+#[test]
+fn explain() -> anyhow::Result<()> {
+    let directory = tempfile::tempdir()?;
+
+    for code in xic::Code::ALL {
+        let examples = code
+            .explanation()
+            .split("```xi\n")
+            .skip(1)
+            .map(|example| example.split_once("```").unwrap().0)
+            .collect::<Vec<_>>();
+
+        for (index, example) in examples.iter().enumerate() {
+            let path = directory.path().join(format!("{}_{}.xi", code, index));
+            fs::write(&path, example)?;
+
+            let mut codes =
+                codes(&path).with_context(|| anyhow!("Checking example for {}", code))?;
+            codes.dedup();
+
+            let expected = match (examples.len(), index) {
+                (2, 0) => vec![code.to_static_str()],
+                _ => Vec::new(),
+            };
+
+            assert_eq!(codes, expected, "Example {} for {}", index, code);
+        }
+    }
+
+    Ok(())
+}
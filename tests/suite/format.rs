@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::anyhow;
+use anyhow::Context as _;
+use tempfile::NamedTempFile;
+
+fn format(path: &Path) -> anyhow::Result<String> {
+    let tokens = xic::api::lex(path).with_context(|| anyhow!("Lexing file: {}", path.display()))?;
+    xic::api::format(path, tokens).with_context(|| anyhow!("Formatting file: {}", path.display()))
+}
+
+/// Formats `path` and checks that formatting the output again changes nothing.
+fn format_idempotent(path: &str, extension: &str) -> anyhow::Result<(String, NamedTempFile)> {
+    let formatted = format(Path::new(path))?;
+
+    let file = tempfile::Builder::new()
+        .suffix(extension)
+        .tempfile()
+        .context("Creating temporary file")?;
+
+    fs::write(file.path(), &formatted).context("Writing formatted source")?;
+
+    pretty_assertions::assert_eq!(formatted, format(file.path())?);
+    Ok((formatted, file))
+}
+
+#[test_generator::test_resources("tests/format/*.xi")]
+pub fn format_program(path: &str) -> anyhow::Result<()> {
+    let (formatted, file) = format_idempotent(path, ".xi")?;
+
+    pretty_assertions::assert_eq!(
+        super::parse(path)?.to_string(),
+        super::parse(file.path().to_str().unwrap())?.to_string(),
+    );
+
+    insta::assert_display_snapshot!(path, formatted);
+    Ok(())
+}
+
+#[test_generator::test_resources("tests/format/*.ixi")]
+pub fn format_interface(path: &str) -> anyhow::Result<()> {
+    let (formatted, _) = format_idempotent(path, ".ixi")?;
+    insta::assert_display_snapshot!(path, formatted);
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Snapshots the interface derived from `path`, or the errors deriving it, followed
+/// by its differences from the existing interface file with the same name, if there is one.
+#[test_generator::test_resources("tests/interface/*.xi")]
+pub fn interface(path: &str) -> anyhow::Result<()> {
+    let path = Path::new(path);
+    let program = crate::parse(path.to_str().unwrap())?;
+    let (_, _, warnings) = xic::api::check_without_interface(None, path, program.clone())?;
+
+    // Every function is exported by the derived interface, so none are unused
+    assert!(warnings
+        .iter()
+        .all(|warning| warning.lint() != xic::lint::Lint::UnusedFunction));
+
+    // Report interfaces that can't be derived the same way as other semantic errors
+    let mut snapshot = match xic::api::emit_interface(path, &program) {
+        Ok(interface) => interface,
+        Err(error) => super::check::Snapshot::<()>(Err(error)).to_string(),
+    };
+    let existing = path.with_extension("ixi");
+
+    if existing.exists() {
+        writeln!(snapshot, "---")?;
+        for line in xic::api::diff_interface(&existing, &program)? {
+            writeln!(snapshot, "{}", line)?;
+        }
+    }
+
+    insta::assert_display_snapshot!(path.to_str().unwrap(), snapshot);
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+use std::path::Path;
+
+use xic::lint::Level;
+use xic::lint::Warning;
+
+struct Snapshot(Vec<Warning>);
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(fmt, "No warnings");
+        }
+
+        let mut cache = xic::data::span::FileCache::default();
+        let mut buffer = Vec::new();
+
+        for warning in &self.0 {
+            warning
+                .report(Level::Warn)
+                .with_config(ariadne::Config::default().with_color(false))
+                .finish()
+                .write(&mut cache, &mut buffer)
+                .unwrap();
+        }
+
+        write!(fmt, "{}", String::from_utf8(buffer).unwrap())
+    }
+}
+
+#[test_generator::test_resources("tests/lint/*.xi")]
+pub fn lint(path: &str) -> anyhow::Result<()> {
+    let program = super::parse(path)?;
+    let (_, _, warnings) = xic::api::check_warnings(None, Path::new(path), program)?;
+
+    insta::assert_display_snapshot!(path, Snapshot(warnings));
+    Ok(())
+}
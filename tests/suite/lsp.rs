@@ -0,0 +1,136 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Cursor;
+
+use anyhow::anyhow;
+use anyhow::Context as _;
+use serde_json::json;
+use serde_json::Value;
+
+fn frame(message: Value) -> Vec<u8> {
+    let body = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes()
+}
+
+fn unframe(mut output: &str) -> Vec<Value> {
+    let mut messages = Vec::new();
+
+    while let Some((header, rest)) = output.split_once("\r\n\r\n") {
+        let length = header
+            .trim_start_matches("Content-Length: ")
+            .parse::<usize>()
+            .unwrap();
+        messages.push(serde_json::from_str(&rest[..length]).unwrap());
+        output = &rest[length..];
+    }
+
+    messages
+}
+
+/// Opens `path` in a language server session, then sends a request for each marker
+/// comment of the form `// ^ hover`, where `^` points at a position on the closest
+/// preceding line that isn't a marker. Supports `hover`, `definition`, and `completion`.
+#[test_generator::test_resources("tests/lsp/*.xi")]
+pub fn lsp(path: &str) -> anyhow::Result<()> {
+    let absolute = fs::canonicalize(path).with_context(|| anyhow!("Resolving path: {}", path))?;
+    let text = fs::read_to_string(&absolute)?;
+    let uri = format!("file://{}", absolute.display());
+
+    let mut requests = vec![(
+        String::from("symbols"),
+        "textDocument/documentSymbol",
+        json!({}),
+    )];
+
+    let mut line = 0;
+
+    for (index, source) in text.lines().enumerate() {
+        let marker = source
+            .trim_start()
+            .strip_prefix("//")
+            .filter(|comment| comment.trim_start().starts_with('^'));
+
+        let marker = match marker {
+            Some(marker) => marker.trim_start(),
+            None => {
+                line = index;
+                continue;
+            }
+        };
+
+        let character = source.find('^').unwrap();
+        let method = match marker.trim_start_matches('^').trim() {
+            "hover" => "textDocument/hover",
+            "definition" => "textDocument/definition",
+            "completion" => "textDocument/completion",
+            method => return Err(anyhow!("Unknown marker: {}", method)),
+        };
+
+        requests.push((
+            format!(
+                "{}:{} {}",
+                line + 1,
+                character + 1,
+                marker.trim_start_matches('^').trim()
+            ),
+            method,
+            json!({ "line": line, "character": character }),
+        ));
+    }
+
+    let mut input = frame(json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {},
+    }));
+
+    input.extend(frame(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": { "uri": uri, "languageId": "xi", "version": 1, "text": text },
+        },
+    })));
+
+    for (id, (_, method, position)) in requests.iter().enumerate() {
+        input.extend(frame(json!({
+            "jsonrpc": "2.0",
+            "id": id + 1,
+            "method": method,
+            "params": {
+                "textDocument": { "uri": uri },
+                "position": position,
+            },
+        })));
+    }
+
+    input.extend(frame(json!({ "jsonrpc": "2.0", "method": "exit" })));
+
+    let mut output = Vec::new();
+    xic::api::serve(None, Cursor::new(input), &mut output)?;
+
+    let mut snapshot = String::new();
+
+    for message in unframe(&String::from_utf8(output)?) {
+        match message["id"].as_u64() {
+            None => writeln!(
+                snapshot,
+                "diagnostics: {}",
+                message["params"]["diagnostics"]
+            )?,
+            Some(0) => (),
+            Some(id) => writeln!(
+                snapshot,
+                "{}: {}",
+                requests[id as usize - 1].0,
+                message["result"],
+            )?,
+        }
+    }
+
+    // Strip absolute paths from URIs
+    let directory = absolute.parent().unwrap().display().to_string();
+    insta::assert_display_snapshot!(path, snapshot.replace(&directory, ""));
+    Ok(())
+}
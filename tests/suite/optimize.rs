@@ -2,6 +2,7 @@ use std::path::Path;
 
 use xic::analyze::analyze_default;
 use xic::analyze::LiveVariables;
+use xic::data::lir;
 use xic::optimize;
 
 #[test_generator::test_resources("tests/execute/*.xi")]
@@ -11,7 +12,13 @@ pub fn invert_loops_ast(path: &str) -> anyhow::Result<()> {
     let program = super::parse(path)?;
     let (mut program, mut context) = xic::api::check(None, Path::new(path), program).unwrap();
     optimize::invert_loops_ast(Path::new(path), &mut program);
-    let optimized = xic::api::emit_hir(&mut context, Path::new(path), xic::Abi::Xi, &program);
+    let optimized = xic::api::emit_hir(
+        &mut context,
+        Path::new(path),
+        xic::Abi::Xi,
+        xic::DebugInfo::Omit,
+        &program,
+    );
     let optimized_stdout = super::interpret_hir(&optimized)?;
 
     pretty_assertions::assert_eq!(expected_stdout, optimized_stdout);
@@ -24,7 +31,13 @@ pub fn abi_final_class(path: &str) -> anyhow::Result<()> {
 
     let program = super::parse(path)?;
     let (program, mut context) = xic::api::check(None, Path::new(path), program).unwrap();
-    let optimized = xic::api::emit_hir(&mut context, Path::new(path), xic::Abi::XiFinal, &program);
+    let optimized = xic::api::emit_hir(
+        &mut context,
+        Path::new(path),
+        xic::Abi::XiFinal,
+        xic::DebugInfo::Omit,
+        &program,
+    );
     let optimized_stdout = super::interpret_hir(&optimized)?;
 
     pretty_assertions::assert_eq!(expected_stdout, optimized_stdout);
@@ -144,6 +157,20 @@ pub fn propagate_constants_assembly(path: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test_generator::test_resources("tests/execute/*.xi")]
+pub fn propagate_conditional_constants_lir(path: &str) -> anyhow::Result<()> {
+    let expected_stdout = super::execute_expected(path)?;
+
+    let optimized = super::emit_lir(path)?
+        .map(xic::api::construct_cfg)
+        .map_mut(optimize::propagate_conditional_constants_lir)
+        .map(xic::api::destruct_cfg);
+    let optimized_stdout = super::interpret_lir(&optimized)?;
+
+    pretty_assertions::assert_eq!(expected_stdout, optimized_stdout);
+    Ok(())
+}
+
 #[test_generator::test_resources("tests/execute/*.xi")]
 pub fn inline_functions_lir(path: &str) -> anyhow::Result<()> {
     let expected_stdout = super::execute_expected(path)?;
@@ -169,3 +196,103 @@ pub fn eliminate_partial_redundancy_lir(path: &str) -> anyhow::Result<()> {
     pretty_assertions::assert_eq!(expected_stdout, optimized_stdout);
     Ok(())
 }
+
+#[test_generator::test_resources("tests/execute/*.xi")]
+pub fn hoist_invariants_lir(path: &str) -> anyhow::Result<()> {
+    let expected_stdout = super::execute_expected(path)?;
+
+    let optimized = super::emit_lir(path)?
+        .map(xic::api::construct_cfg)
+        .map_mut(optimize::hoist_invariants_lir)
+        .map(xic::api::destruct_cfg);
+    let optimized_stdout = super::interpret_lir(&optimized)?;
+
+    pretty_assertions::assert_eq!(expected_stdout, optimized_stdout);
+    Ok(())
+}
+
+#[test_generator::test_resources("tests/execute/*.xi")]
+pub fn reduce_strength_lir(path: &str) -> anyhow::Result<()> {
+    let expected_stdout = super::execute_expected(path)?;
+
+    let optimized = super::emit_lir(path)?
+        .map(xic::api::construct_cfg)
+        .map_mut(optimize::reduce_strength_lir)
+        .map(xic::api::destruct_cfg);
+    let optimized_stdout = super::interpret_lir(&optimized)?;
+
+    pretty_assertions::assert_eq!(expected_stdout, optimized_stdout);
+    Ok(())
+}
+
+#[test]
+pub fn hoist_invariants_lir_array_sum() -> anyhow::Result<()> {
+    let optimized = super::emit_lir(ARRAY_SUM)?
+        .map(xic::api::construct_cfg)
+        .map_mut(optimize::hoist_invariants_lir)
+        .map(xic::api::destruct_cfg);
+    let sum = render_sum(&optimized);
+
+    // `scale + offset` is computed once, before the loop
+    assert!(sum.contains("(MOVE (TEMP _invariant) (ADD (TEMP _scale) (TEMP _offset)))"));
+    assert!(sum.contains("(TEMP _invariant))))"));
+    insta::assert_snapshot!(sum);
+    Ok(())
+}
+
+#[test]
+pub fn reduce_strength_lir_array_sum() -> anyhow::Result<()> {
+    let optimized = super::emit_lir(ARRAY_SUM)?
+        .map(xic::api::construct_cfg)
+        .map_mut(optimize::propagate_copies_lir)
+        .map_mut(optimize::reduce_strength_lir)
+        .map(xic::api::destruct_cfg);
+    let sum = render_sum(&optimized);
+
+    // `i * 8` is computed once, before the loop, and then incremented alongside `i`
+    assert!(sum.contains("(MOVE (TEMP _induction) (MUL (TEMP _i) (CONST 8)))"));
+    assert!(sum.contains("(MOVE (TEMP _induction) (ADD (TEMP _induction) (CONST 8)))"));
+    assert_eq!(sum.matches("(MUL (TEMP _i) (CONST 8))").count(), 1);
+    insta::assert_snapshot!(sum);
+    Ok(())
+}
+
+const ARRAY_SUM: &str = "tests/execute/loop_array_sum.xi";
+
+/// Renders the statements of `sum`, one per line, with numeric suffixes
+/// stripped from temporaries and labels, since they depend on global counters.
+fn render_sum<T: lir::Target>(unit: &lir::Unit<T>) -> String {
+    let (_, sum) = unit
+        .functions
+        .iter()
+        .find(|(name, _)| name.to_string() == "_Isum_iaiii")
+        .unwrap();
+
+    sum.statements
+        .iter()
+        .map(|statement| {
+            let mut rendered = String::new();
+            let mut identifier = false;
+
+            for char in statement
+                .to_string()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+            {
+                if !(identifier && char.is_ascii_digit()) {
+                    rendered.push(char);
+                }
+                identifier = match char {
+                    '_' => true,
+                    _ if char.is_ascii_alphabetic() => true,
+                    _ if char.is_ascii_digit() => identifier,
+                    _ => false,
+                };
+            }
+
+            rendered + "\n"
+        })
+        .collect()
+}
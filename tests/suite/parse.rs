@@ -9,21 +9,26 @@ impl fmt::Display for Snapshot {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match &self.0 {
             Ok(program) => write!(fmt, "{}", program),
-            Err(error) => match error.report() {
-                None => write!(fmt, "{}", error),
-                Some(report) => {
-                    let cache = xic::data::span::FileCache::default();
-                    let mut buffer = Vec::new();
+            Err(error) => {
+                let reports = error.report(None);
 
+                if reports.is_empty() {
+                    return write!(fmt, "{}", error);
+                }
+
+                let mut cache = xic::data::span::FileCache::default();
+                let mut buffer = Vec::new();
+
+                for report in reports {
                     report
                         .with_config(ariadne::Config::default().with_color(false))
                         .finish()
-                        .write(cache, &mut buffer)
+                        .write(&mut cache, &mut buffer)
                         .map_err(|_| fmt::Error)?;
-
-                    write!(fmt, "{}", String::from_utf8(buffer).unwrap())
                 }
-            },
+
+                write!(fmt, "{}", String::from_utf8(buffer).unwrap())
+            }
         }
     }
 }